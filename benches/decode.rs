@@ -0,0 +1,61 @@
+//! Compares `serde_json` and `simd-json` on a typical Verify search response.
+//!
+//! Run with `cargo bench --features simd-json`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use vonage::verify::VerifyInfo;
+
+const SEARCH_RESULT: &str = r#"{
+    "request_id": "abcdef0123456789abcdef0123456789",
+    "account_id": "abcdef01",
+    "status": "SUCCESS",
+    "number": "14155550100",
+    "price": "0.10000000",
+    "currency": "EUR",
+    "sender_id": "verify",
+    "date_submitted": "2020-01-01 12:00:00",
+    "date_finalized": "2020-01-01 12:00:30",
+    "first_event_date": "2020-01-01 12:00:00",
+    "last_event_date": "2020-01-01 12:00:20",
+    "checks": [
+        {
+            "date_received": "2020-01-01 12:00:10",
+            "code": "987654",
+            "status": "INVALID",
+            "ip_address": "203.0.113.7"
+        },
+        {
+            "date_received": "2020-01-01 12:00:30",
+            "code": "123456",
+            "status": "VALID",
+            "ip_address": null
+        }
+    ],
+    "events": [["sms", "0A00000012345678"], ["tts", "0A00000012345679"]],
+    "estimated_price_messages_sent": "0.03330000"
+}"#;
+
+fn decode(c: &mut Criterion) {
+    let body = SEARCH_RESULT.as_bytes();
+    let mut group = c.benchmark_group("decode_verify_info");
+    group.throughput(Throughput::Bytes(body.len() as u64));
+
+    group.bench_function("serde_json", |b| {
+        b.iter(|| serde_json::from_slice::<VerifyInfo>(black_box(body)).unwrap())
+    });
+
+    // Includes the copy made by the client, since `simd-json` parses in place.
+    group.bench_function("simd_json", |b| {
+        b.iter(|| {
+            let mut body = black_box(body).to_vec();
+            simd_json::serde::from_slice::<VerifyInfo>(&mut body).unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
@@ -0,0 +1,17 @@
+use std::env;
+use std::process::Command;
+
+fn main() {
+    // Embed the compiler version so it can be reported in the `User-Agent` header.
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .and_then(|stdout| stdout.split_whitespace().nth(1).map(str::to_owned))
+        .unwrap_or_else(|| "unknown".into());
+
+    println!("cargo:rustc-env=VONAGE_RUSTC_VERSION={}", version);
+    println!("cargo:rerun-if-env-changed=RUSTC");
+}
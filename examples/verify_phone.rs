@@ -13,7 +13,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     let client = Client::new(api_key, api_secret);
     let mut pending = client
-        .verify(phone_to_verify.parse()?, "vonage-rs")?
+        .verify()
+        .request(phone_to_verify.parse()?, "vonage-rs")
         .code_length(CodeLength::Six)
         .pin_expiry(Duration::from_secs(5 * 60))
         .send()
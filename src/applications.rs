@@ -0,0 +1,678 @@
+//! Managing Vonage applications with the
+//! [Applications API](https://developer.nexmo.com/api/application.v2).
+//!
+//! An application holds the webhooks which Vonage sends events of each product to, and the public
+//! key which verifies the JWTs signed by [`ClientBuilder::jwt()`]. The webhooks are configured
+//! with a typed [`Capabilities`] builder.
+//!
+//! [`ClientBuilder::jwt()`]: ../struct.ClientBuilder.html#method.jwt
+//! [`Capabilities`]: ./struct.Capabilities.html
+
+use std::fmt::{self, Debug, Formatter};
+
+use bytes::Bytes;
+use http::header::{ACCEPT, CONTENT_TYPE};
+use http::{Method, Request, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::auth::Auth;
+use crate::http_client::HttpClient;
+use crate::pagination::{Page, Paginated};
+use crate::transport::HttpTransport;
+use crate::validation::{is_http_url, Validator};
+use crate::{DefaultTransport, Error, ErrorKind, Product, RequestPolicy, Result};
+
+/// A handle to the [Applications](https://developer.nexmo.com/api/application.v2) API.
+///
+/// This is returned from [`Client::applications()`](../struct.Client.html#method.applications).
+/// It holds the API key and API secret pair of the client, and is cheap to clone.
+pub struct ApplicationsApi<C = DefaultTransport> {
+    http_client: HttpClient<C>,
+    auth: Auth,
+}
+
+impl<C> ApplicationsApi<C> {
+    pub(crate) fn new(http_client: HttpClient<C>, auth: Auth) -> Self {
+        ApplicationsApi { http_client, auth }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`RequestPolicy`](../struct.RequestPolicy.html) for details.
+    pub fn policy(mut self, policy: RequestPolicy) -> Self {
+        self.http_client = self.http_client.with_policy(policy);
+        self
+    }
+
+    /// Creates a new application called `name`.
+    ///
+    /// Unless a public key is given, Vonage generates a key pair, and returns the private key
+    /// once in [`Application::private_key()`](./struct.Application.html#method.private_key).
+    pub fn create(&self, name: impl Into<String>) -> ConfigureApplication<C> {
+        self.configure(None, name.into())
+    }
+
+    /// Replaces the name, capabilities and public key of the application `id`.
+    ///
+    /// Capabilities which are not set are removed from the application.
+    pub fn update(&self, id: &str, name: impl Into<String>) -> ConfigureApplication<C> {
+        self.configure(Some(id.to_owned()), name.into())
+    }
+
+    fn configure(&self, id: Option<String>, name: String) -> ConfigureApplication<C> {
+        ConfigureApplication {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+            id,
+            body: ApplicationBody {
+                name,
+                capabilities: Capabilities::new(),
+                keys: None,
+            },
+        }
+    }
+}
+
+impl<C: HttpTransport> ApplicationsApi<C> {
+    /// Retrieves the application `id`.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequest`] without sending anything if `id` is
+    /// empty, or a `.` or `..` path segment.
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../enum.ErrorKind.html#variant.InvalidRequest
+    pub async fn get(&self, id: &str) -> Result<Application> {
+        let path = application_path(id)?;
+        let request = encode_request(&self.auth, Method::GET, &path, Bytes::new())?;
+        self.http_client
+            .send(Product::Applications, request, |response| {
+                match response.status() {
+                    StatusCode::OK => crate::http_client::decode_json(response.body()),
+                    _ => Err(Error::new_status(&response)),
+                }
+            })
+            .await
+    }
+
+    /// Deletes the application `id`.
+    ///
+    /// Numbers linked to the application are unlinked, and JWTs signed for it stop working.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequest`] without sending anything if `id` is
+    /// empty, or a `.` or `..` path segment.
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../enum.ErrorKind.html#variant.InvalidRequest
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let path = application_path(id)?;
+        let request = encode_request(&self.auth, Method::DELETE, &path, Bytes::new())?;
+        self.http_client
+            .send(Product::Applications, request, |response| {
+                match response.status() {
+                    StatusCode::OK | StatusCode::NO_CONTENT => Ok(()),
+                    _ => Err(Error::new_status(&response)),
+                }
+            })
+            .await
+    }
+}
+
+impl<C: HttpTransport + Send + Sync + 'static> ApplicationsApi<C> {
+    /// Returns a stream over every application of the account, fetching pages of them as it is
+    /// polled.
+    pub fn list(&self) -> Paginated<Application> {
+        #[derive(Deserialize)]
+        struct ResponseBody {
+            total_items: u64,
+            #[serde(rename = "_embedded")]
+            embedded: Embedded,
+        }
+
+        #[derive(Deserialize)]
+        struct Embedded {
+            #[serde(default)]
+            applications: Vec<Application>,
+        }
+
+        let http_client = self.http_client.clone();
+        let auth = self.auth.clone();
+        Paginated::new(move |page| {
+            let http_client = http_client.clone();
+            let path = format!(
+                "v2/applications?page={}&page_size={}",
+                page.index, page.size
+            );
+            let request = encode_request(&auth, Method::GET, &path, Bytes::new());
+
+            async move {
+                http_client
+                    .send(Product::Applications, request?, |response| {
+                        match response.status() {
+                            StatusCode::OK => {}
+                            _ => return Err(Error::new_status(&response)),
+                        }
+
+                        let body: ResponseBody = crate::http_client::decode_json(response.body())?;
+                        Ok(Page {
+                            items: body.embedded.applications,
+                            total_count: Some(body.total_items),
+                        })
+                    })
+                    .await
+            }
+        })
+    }
+}
+
+impl<C> Clone for ApplicationsApi<C> {
+    fn clone(&self) -> Self {
+        ApplicationsApi {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+        }
+    }
+}
+
+impl<C> Debug for ApplicationsApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(ApplicationsApi))
+            .field("http_client", &self.http_client)
+            .field("auth", &self.auth)
+            .finish()
+    }
+}
+
+fn application_path(id: &str) -> Result<String> {
+    let id = crate::encode_path_segment("id", id)?;
+    Ok(format!("v2/applications/{}", id))
+}
+
+fn encode_request(auth: &Auth, method: Method, path: &str, body: Bytes) -> Result<Request<Bytes>> {
+    let (name, value) = auth.to_auth_header()?;
+    Request::builder()
+        .method(method)
+        .uri(crate::resolve_url(path))
+        .header(CONTENT_TYPE, "application/json")
+        .header(ACCEPT, "application/json")
+        .header(name, value)
+        .body(body)
+        .map_err(|e| Error::with_cause(ErrorKind::Http, e))
+}
+
+/// A builder to create or update an application.
+///
+/// This is returned from [`ApplicationsApi::create()`] and [`ApplicationsApi::update()`].
+///
+/// [`ApplicationsApi::create()`]: ./struct.ApplicationsApi.html#method.create
+/// [`ApplicationsApi::update()`]: ./struct.ApplicationsApi.html#method.update
+pub struct ConfigureApplication<C> {
+    http_client: HttpClient<C>,
+    auth: Auth,
+    id: Option<String>,
+    body: ApplicationBody,
+}
+
+#[derive(Debug, Serialize)]
+struct ApplicationBody {
+    name: String,
+    capabilities: Capabilities,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keys: Option<Keys>,
+}
+
+impl<C> ConfigureApplication<C> {
+    /// Sets the webhooks of each product the application is used for.
+    pub fn capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.body.capabilities = capabilities;
+        self
+    }
+
+    /// Sets the PEM-encoded public key which verifies the JWTs signed for the application.
+    pub fn public_key(mut self, key: impl Into<String>) -> Self {
+        self.body.keys = Some(Keys {
+            public_key: Some(key.into()),
+            private_key: None,
+        });
+        self
+    }
+}
+
+impl<C: HttpTransport> ConfigureApplication<C> {
+    /// Sends the request, returning the application as stored by Vonage.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequest`] without sending anything if the
+    /// name is empty, a webhook address is not an absolute `http` or `https` URL, or the ID of an
+    /// updated application is empty or a `.` or `..` path segment.
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../enum.ErrorKind.html#variant.InvalidRequest
+    pub async fn send(self) -> Result<Application> {
+        let mut validator = Validator::new();
+        validator.check(!self.body.name.is_empty(), "name", "must not be empty");
+        for (field, webhook) in self.body.capabilities.webhooks() {
+            validator.check(
+                is_http_url(&webhook.address),
+                field,
+                "must be an absolute `http` or `https` URL",
+            );
+        }
+        validator.finish()?;
+
+        let (method, path) = match self.id.as_deref() {
+            Some(id) => (Method::PUT, application_path(id)?),
+            None => (Method::POST, "v2/applications".to_owned()),
+        };
+        let body =
+            serde_json::to_vec(&self.body).map_err(|e| Error::with_cause(ErrorKind::Decode, e))?;
+        let request = encode_request(&self.auth, method, &path, body.into())?;
+        self.http_client
+            .send(Product::Applications, request, |response| {
+                match response.status() {
+                    StatusCode::OK | StatusCode::CREATED => {
+                        crate::http_client::decode_json(response.body())
+                    }
+                    _ => Err(Error::new_status(&response)),
+                }
+            })
+            .await
+    }
+}
+
+impl<C> Debug for ConfigureApplication<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(ConfigureApplication))
+            .field("id", &self.id)
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+/// A Vonage application.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Application {
+    /// The unique ID of the application, used as the application ID of JWTs.
+    pub id: String,
+    /// The name of the application.
+    pub name: String,
+    /// The webhooks of each product the application is used for.
+    #[serde(default)]
+    pub capabilities: Capabilities,
+    #[serde(default)]
+    keys: Keys,
+}
+
+impl Application {
+    /// Returns the PEM-encoded public key of the application.
+    pub fn public_key(&self) -> Option<&str> {
+        self.keys.public_key.as_deref()
+    }
+
+    /// Returns the PEM-encoded private key generated for the application.
+    ///
+    /// This is only returned once, when the application is created without a public key. Store
+    /// it securely to sign JWTs with [`ClientBuilder::jwt()`](../struct.ClientBuilder.html#method.jwt).
+    pub fn private_key(&self) -> Option<&str> {
+        self.keys.private_key.as_deref()
+    }
+}
+
+#[derive(Clone, Default, Deserialize, PartialEq, Serialize)]
+struct Keys {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    public_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    private_key: Option<String>,
+}
+
+impl Debug for Keys {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Keys))
+            .field("public_key", &self.public_key)
+            .field(
+                "private_key",
+                &self.private_key.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
+}
+
+/// The webhooks of each product an application is used for.
+///
+/// # Example
+///
+/// ```
+/// use vonage::applications::{Capabilities, MessagesCapability, VoiceCapability, Webhook};
+///
+/// let capabilities = Capabilities::new()
+///     .voice(VoiceCapability::new(
+///         Webhook::get("https://example.com/answer"),
+///         Webhook::post("https://example.com/event"),
+///     ))
+///     .messages(MessagesCapability::new(
+///         Webhook::post("https://example.com/inbound"),
+///         Webhook::post("https://example.com/status"),
+///     ));
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// The webhooks of the Voice API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice: Option<VoiceCapability>,
+    /// The webhooks of the Messages API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages: Option<MessagesCapability>,
+    /// The webhooks of the Conversation API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rtc: Option<RtcCapability>,
+    /// Whether the application is used with Vonage Business Communications.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vbc: Option<VbcCapability>,
+}
+
+impl Capabilities {
+    /// Creates an empty set of capabilities.
+    pub fn new() -> Self {
+        Capabilities::default()
+    }
+
+    /// Enables the Voice API with the given webhooks.
+    pub fn voice(mut self, voice: VoiceCapability) -> Self {
+        self.voice = Some(voice);
+        self
+    }
+
+    /// Enables the Messages API with the given webhooks.
+    pub fn messages(mut self, messages: MessagesCapability) -> Self {
+        self.messages = Some(messages);
+        self
+    }
+
+    /// Enables the Conversation API with the given webhooks.
+    pub fn rtc(mut self, rtc: RtcCapability) -> Self {
+        self.rtc = Some(rtc);
+        self
+    }
+
+    /// Enables Vonage Business Communications.
+    pub fn vbc(mut self) -> Self {
+        self.vbc = Some(VbcCapability::default());
+        self
+    }
+
+    /// Returns every webhook which is set, with the name of its field.
+    fn webhooks(&self) -> Vec<(&'static str, &Webhook)> {
+        let voice = self.voice.as_ref().map(|v| &v.webhooks);
+        let messages = self.messages.as_ref().map(|m| &m.webhooks);
+        let rtc = self.rtc.as_ref().map(|r| &r.webhooks);
+
+        let webhooks = [
+            ("answer_url", voice.and_then(|v| v.answer_url.as_ref())),
+            (
+                "fallback_answer_url",
+                voice.and_then(|v| v.fallback_answer_url.as_ref()),
+            ),
+            ("event_url", voice.and_then(|v| v.event_url.as_ref())),
+            ("inbound_url", messages.and_then(|m| m.inbound_url.as_ref())),
+            ("status_url", messages.and_then(|m| m.status_url.as_ref())),
+            ("event_url", rtc.and_then(|r| r.event_url.as_ref())),
+        ];
+
+        let set = webhooks.iter().filter_map(|(f, w)| Some((*f, (*w)?)));
+        set.collect()
+    }
+}
+
+/// A webhook which Vonage sends requests to.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Webhook {
+    /// The URL of the webhook.
+    pub address: String,
+    /// The HTTP method of the requests, either `GET` or `POST`.
+    pub http_method: String,
+}
+
+impl Webhook {
+    /// Creates a webhook which receives `GET` requests at `address`.
+    pub fn get(address: impl Into<String>) -> Self {
+        Webhook {
+            address: address.into(),
+            http_method: "GET".to_owned(),
+        }
+    }
+
+    /// Creates a webhook which receives `POST` requests at `address`.
+    pub fn post(address: impl Into<String>) -> Self {
+        Webhook {
+            address: address.into(),
+            http_method: "POST".to_owned(),
+        }
+    }
+}
+
+/// The webhooks of the Voice API.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct VoiceCapability {
+    /// The webhooks themselves.
+    #[serde(default)]
+    pub webhooks: VoiceWebhooks,
+    /// Whether requests to the webhooks are signed with the signature secret of the account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_callbacks: Option<bool>,
+}
+
+impl VoiceCapability {
+    /// Creates a capability which fetches the NCCO of inbound calls from `answer_url`, and sends
+    /// call events to `event_url`.
+    pub fn new(answer_url: Webhook, event_url: Webhook) -> Self {
+        VoiceCapability {
+            webhooks: VoiceWebhooks {
+                answer_url: Some(answer_url),
+                fallback_answer_url: None,
+                event_url: Some(event_url),
+            },
+            signed_callbacks: None,
+        }
+    }
+
+    /// Sets the webhook which the NCCO is fetched from if `answer_url` is unavailable.
+    pub fn fallback_answer_url(mut self, url: Webhook) -> Self {
+        self.webhooks.fallback_answer_url = Some(url);
+        self
+    }
+
+    /// Signs requests to the webhooks with the signature secret of the account.
+    pub fn signed_callbacks(mut self, signed: bool) -> Self {
+        self.signed_callbacks = Some(signed);
+        self
+    }
+}
+
+/// The webhooks configured in a [`VoiceCapability`](./struct.VoiceCapability.html).
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct VoiceWebhooks {
+    /// The webhook which the NCCO of inbound calls is fetched from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer_url: Option<Webhook>,
+    /// The webhook which the NCCO is fetched from if `answer_url` is unavailable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_answer_url: Option<Webhook>,
+    /// The webhook which call events are sent to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_url: Option<Webhook>,
+}
+
+/// The webhooks of the Messages API.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MessagesCapability {
+    /// The webhooks themselves.
+    #[serde(default)]
+    pub webhooks: MessagesWebhooks,
+}
+
+impl MessagesCapability {
+    /// Creates a capability which sends inbound messages to `inbound_url`, and the status of
+    /// outbound messages to `status_url`.
+    pub fn new(inbound_url: Webhook, status_url: Webhook) -> Self {
+        MessagesCapability {
+            webhooks: MessagesWebhooks {
+                inbound_url: Some(inbound_url),
+                status_url: Some(status_url),
+            },
+        }
+    }
+}
+
+/// The webhooks configured in a [`MessagesCapability`](./struct.MessagesCapability.html).
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct MessagesWebhooks {
+    /// The webhook which inbound messages are sent to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inbound_url: Option<Webhook>,
+    /// The webhook which the status of outbound messages is sent to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_url: Option<Webhook>,
+}
+
+/// The webhooks of the Conversation API.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RtcCapability {
+    /// The webhooks themselves.
+    #[serde(default)]
+    pub webhooks: RtcWebhooks,
+}
+
+impl RtcCapability {
+    /// Creates a capability which sends conversation events to `event_url`.
+    pub fn new(event_url: Webhook) -> Self {
+        RtcCapability {
+            webhooks: RtcWebhooks {
+                event_url: Some(event_url),
+            },
+        }
+    }
+}
+
+/// The webhooks configured in an [`RtcCapability`](./struct.RtcCapability.html).
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct RtcWebhooks {
+    /// The webhook which conversation events are sent to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_url: Option<Webhook>,
+}
+
+/// The Vonage Business Communications capability, which has no settings.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct VbcCapability {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use http_body_util::{BodyExt, Full};
+    use serde_json::{json, Value};
+    use tower::service_fn;
+
+    use crate::Client;
+
+    #[tokio::test]
+    async fn creates_applications() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.method(), Method::POST);
+            assert_eq!(request.uri().path(), "/v2/applications");
+            let body = request.into_body().collect().await.unwrap().to_bytes();
+            let body: Value = serde_json::from_slice(&body).unwrap();
+            let expected = json!({
+                "name": "My App",
+                "capabilities": {
+                    "voice": {
+                        "webhooks": {
+                            "answer_url": {"address": "https://example.com/answer", "http_method": "GET"},
+                            "event_url": {"address": "https://example.com/event", "http_method": "POST"}
+                        }
+                    },
+                    "vbc": {}
+                }
+            });
+            assert_eq!(body, expected);
+
+            let body = r#"{"id":"78d335fa-323d-0114-9c3d-d6f0d48968cf","name":"My App",
+                "capabilities":{"voice":{"webhooks":{"answer_url":{"address":"https://example.com/answer",
+                "http_method":"GET"},"event_url":{"address":"https://example.com/event",
+                "http_method":"POST"}}},"vbc":{}},
+                "keys":{"public_key":"PUBLIC","private_key":"PRIVATE"}}"#;
+            let response = http::Response::builder()
+                .status(StatusCode::CREATED)
+                .body(Full::new(Bytes::from(body)))
+                .unwrap();
+            Ok::<_, Infallible>(response)
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let capabilities = Capabilities::new()
+            .voice(VoiceCapability::new(
+                Webhook::get("https://example.com/answer"),
+                Webhook::post("https://example.com/event"),
+            ))
+            .vbc();
+
+        let app = client
+            .applications()
+            .create("My App")
+            .capabilities(capabilities.clone())
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(app.capabilities, capabilities);
+        assert_eq!(app.private_key(), Some("PRIVATE"));
+        assert!(!format!("{:?}", app).contains("PRIVATE"));
+
+        let error = client
+            .applications()
+            .create("")
+            .capabilities(Capabilities::new().rtc(RtcCapability::new(Webhook::post("/event"))))
+            .send()
+            .await
+            .unwrap_err();
+        let invalid = error.as_invalid_request().unwrap().invalid_parameters();
+        let names: Vec<_> = invalid.iter().map(|param| param.name()).collect();
+        assert_eq!(names, ["name", "event_url"]);
+    }
+
+    #[tokio::test]
+    async fn escapes_application_ids() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.method(), Method::DELETE);
+            assert_eq!(request.uri().path(), "/v2/applications/a%2F..%2Fb%3Fc%23d");
+            assert_eq!(request.uri().query(), None);
+            let response = http::Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .body(Full::new(Bytes::new()))
+                .unwrap();
+            Ok::<_, Infallible>(response)
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+        let applications = client.applications();
+        applications.delete("a/../b?c#d").await.unwrap();
+
+        let error = applications.delete("..").await.unwrap_err();
+        assert_eq!(
+            error.as_invalid_request().unwrap().invalid_parameters()[0].name(),
+            "id"
+        );
+        let error = applications.update("", "My App").send().await.unwrap_err();
+        assert_eq!(
+            error.as_invalid_request().unwrap().invalid_parameters()[0].name(),
+            "id"
+        );
+    }
+}
@@ -1,13 +1,17 @@
 //! Authentication storage for connecting to Vonage APIs.
 
 use std::fmt::{self, Debug, Formatter};
-use std::time::SystemTime;
+use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::anyhow;
-use hyper::header::{HeaderName, AUTHORIZATION};
+use http::header::{HeaderName, AUTHORIZATION};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
 use serde::Serialize;
 
-use crate::{Error, Result};
+use crate::jwt::{self, JwtCache, JwtHealth};
+use crate::rt::SystemTime;
+use crate::{Error, ErrorKind, Result, SignatureSecret};
 
 static CLOCK_SEQUENCE: uuid::v1::Context = uuid::v1::Context::new(0);
 
@@ -25,16 +29,23 @@ impl Debug for ApiSecret {
     }
 }
 
-#[derive(Default)]
+/// An RSA private key, for signing JWTs in unit tests.
+#[cfg(test)]
+pub(crate) const TEST_PRIVATE_KEY: &str = include_str!("../tests/fixtures/private_key.pem");
+
+#[derive(Clone, Default)]
 pub struct Auth {
     api_key: Option<(ApiKey, ApiSecret)>,
-    jwt: Option<(String, String)>,
+    jwt: Option<(String, EncodingKey)>,
+    jwt_cache: Option<Arc<JwtCache>>,
 }
 
 impl Auth {
     pub fn builder() -> AuthBuilder {
         AuthBuilder {
             inner: Auth::default(),
+            jwt: None,
+            jwt_ttl: jwt::DEFAULT_TTL,
         }
     }
 
@@ -53,7 +64,6 @@ impl Auth {
     #[rustfmt::skip]
     pub fn generate_jwt<T: Serialize>(&self, claims: T) -> Result<String> {
         use chrono::Utc;
-        use jsonwebtoken::{EncodingKey, Header};
         use serde_json::json;
 
         if let Some((application_id, private_key)) = self.jwt.as_ref() {
@@ -67,8 +77,7 @@ impl Auth {
                 map.entry("jti").or_insert_with(|| json!(gen_uuid_v1_str()));
             }
 
-            let private_key = EncodingKey::from_secret(private_key.as_bytes());
-            let token = jsonwebtoken::encode(&Header::default(), &claims, &private_key)?;
+            let token = jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, private_key)?;
             Ok(token)
         } else {
             Err(Error::new_auth(anyhow!(
@@ -76,6 +85,52 @@ impl Auth {
             )))
         }
     }
+
+    pub fn has_jwt(&self) -> bool {
+        self.jwt.is_some()
+    }
+
+    /// Returns the `Authorization` header for endpoints accepting either credential, preferring
+    /// a JWT over the API key and API secret pair.
+    pub fn to_preferred_auth_header(&self) -> Result<(HeaderName, String)> {
+        match self.jwt_cache.as_ref() {
+            Some(cache) => {
+                let token = cache.get_or_sign(|ttl| self.generate_expiring_jwt(ttl))?;
+                Ok((AUTHORIZATION, format!("Bearer {}", token)))
+            }
+            None => self.to_auth_header(),
+        }
+    }
+
+    /// Signs a new JWT for the cache, returning how long until it is due for renewal.
+    pub fn refresh_jwt(&self) -> Result<Duration> {
+        match self.jwt_cache.as_ref() {
+            Some(cache) => cache.refresh(|ttl| self.generate_expiring_jwt(ttl)),
+            None => Err(Error::new_auth(anyhow!(
+                "product requires an application ID and private key to generate JWTs"
+            ))),
+        }
+    }
+
+    pub fn jwt_health(&self) -> Option<JwtHealth> {
+        self.jwt_cache.as_ref().map(|cache| cache.health())
+    }
+
+    fn generate_expiring_jwt(&self, ttl: Duration) -> Result<String> {
+        let exp = chrono::Utc::now().timestamp() + ttl.as_secs() as i64;
+        self.generate_jwt(serde_json::json!({ "exp": exp }))
+    }
+
+    /// Appends the API key, the current timestamp, and their `sig` to the encoded `query`.
+    pub fn sign_query(&self, secret: &SignatureSecret, query: &str) -> Result<String> {
+        let (ApiKey(key), _) = self.api_key_pair()?;
+        let mut params: Vec<(String, String)> = serde_urlencoded::from_str(query)
+            .map_err(|e| Error::with_cause(ErrorKind::UrlEncode, e))?;
+        params.push(("api_key".into(), key.clone()));
+        secret.sign_params(&mut params);
+
+        Ok(serde_urlencoded::to_string(params)?)
+    }
 }
 
 impl Debug for Auth {
@@ -92,7 +147,7 @@ fn gen_uuid_v1_str() -> String {
 
     // Source: https://github.com/uuidjs/uuid/blob/0e6c10ba1bf9517796ff23c052fc0468eedfd5f4/src/v1.js#L32-L40
     let mut node_id: [u8; 6] = rand::random();
-    node_id[0] = node_id[0] | 0x01;
+    node_id[0] |= 0x01;
 
     let now = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
@@ -107,6 +162,8 @@ fn gen_uuid_v1_str() -> String {
 #[derive(Debug)]
 pub struct AuthBuilder {
     inner: Auth,
+    jwt: Option<(String, String)>,
+    jwt_ttl: Duration,
 }
 
 impl AuthBuilder {
@@ -116,11 +173,28 @@ impl AuthBuilder {
     }
 
     pub fn jwt(&mut self, app_id: impl Into<String>, private_key: impl Into<String>) -> &mut Self {
-        self.inner.jwt = Some((app_id.into(), private_key.into()));
+        self.jwt = Some((app_id.into(), private_key.into()));
+        self
+    }
+
+    pub fn jwt_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.jwt_ttl = ttl;
         self
     }
 
-    pub fn build(self) -> Result<Auth> {
+    pub fn build(mut self) -> Result<Auth> {
+        if let Some((app_id, private_key)) = self.jwt {
+            let key = EncodingKey::from_rsa_pem(private_key.as_bytes()).map_err(|e| {
+                let message = format!(
+                    "private key of application `{}` is not a valid RSA PEM key",
+                    app_id
+                );
+                Error::new_auth(anyhow::Error::new(e).context(message))
+            })?;
+            self.inner.jwt = Some((app_id, key));
+            self.inner.jwt_cache = Some(Arc::new(JwtCache::new(self.jwt_ttl)));
+        }
+
         if self.inner.api_key.is_some() || self.inner.jwt.is_some() {
             Ok(self.inner)
         } else {
@@ -136,7 +210,28 @@ mod tests {
     #[test]
     fn creates_auth() {
         let mut builder = Auth::builder();
-        builder.api_key("hi", "there").jwt("hi", "there");
+        builder.api_key("hi", "there").jwt("hi", TEST_PRIVATE_KEY);
         let _ = builder.build().unwrap();
     }
+
+    #[test]
+    fn signs_rs256_jwts() {
+        let mut builder = Auth::builder();
+        builder.jwt("app_id", TEST_PRIVATE_KEY);
+        let token = builder
+            .build()
+            .unwrap()
+            .generate_jwt(serde_json::json!({}))
+            .unwrap();
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        assert_eq!(header.alg, Algorithm::RS256);
+
+        let mut builder = Auth::builder();
+        builder.jwt("app_id", "not a key");
+        let error = builder.build().unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Auth));
+        assert!(error.to_string().contains("authentication error"));
+        let source = std::error::Error::source(&error).unwrap().to_string();
+        assert!(source.contains("not a valid RSA PEM key"), "{}", source);
+    }
 }
@@ -0,0 +1,353 @@
+//! A blocking (synchronous) interface to the Vonage APIs.
+//!
+//! This mirrors the asynchronous API exposed at the crate root, except that all methods which
+//! would normally return a `Future` instead block the current thread until completion. This is
+//! convenient for CLI tools and non-async codebases which just need to fire off an OTP.
+//!
+//! Each [`Client`](./struct.Client.html) drives its requests on a small, dedicated Tokio runtime.
+//! As such, the types in this module must not be used from within an asynchronous context, or
+//! they will panic.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use vonage::blocking::{verify::Code, Client};
+//! use vonage::verify::CodeLength;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::new("<api_key>", "<api_secret>");
+//! let pending = client
+//!     .verify()
+//!     .request("+1 555-555-555".parse()?, "vonage-rs")
+//!     .code_length(CodeLength::Six)
+//!     .pin_expiry(Duration::from_secs(5 * 60))
+//!     .send()?;
+//!
+//! match pending.check("123456")? {
+//!     Code::Match(verified) => println!("Code matches! {:?}", verified),
+//!     Code::Mismatch(_) => println!("Code does not match."),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::io::Write;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use bytes::Bytes;
+use futures::io::AllowStdIo;
+use futures::StreamExt;
+use http::{Method, Response};
+use tokio::runtime::{Builder, Runtime};
+
+use crate::credentials::{ApiKeyAuth, Credentials, HasApiKey, HasJwt};
+use crate::transport::HttpTransport;
+use crate::{ClientBuilder, DefaultTransport, Error, ErrorKind, Progress, RequestPolicy, Result};
+
+pub mod applications;
+pub mod number_insight;
+pub mod numbers;
+pub mod sms;
+pub mod usage;
+pub mod verify;
+pub mod voice;
+
+/// A blocking client to interface with the Vonage APIs.
+///
+/// This is the synchronous equivalent of [`vonage::Client`](../struct.Client.html).
+pub struct Client<C = DefaultTransport, A = ApiKeyAuth> {
+    inner: crate::Client<C, A>,
+    runtime: Arc<Runtime>,
+}
+
+impl Client {
+    /// Creates a new `Client` using the given API key and API secret pair.
+    ///
+    /// See [`vonage::Client::new()`](../struct.Client.html#method.new) for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the underlying Tokio runtime cannot be started.
+    pub fn new(api_key: impl Into<String>, secret: impl Into<String>) -> Self {
+        Client::from_builder(crate::Client::builder().api_key(api_key, secret)).unwrap()
+    }
+}
+
+impl<C, A: Credentials> Client<C, A> {
+    /// Constructs a blocking `Client` from a configured [`ClientBuilder`].
+    ///
+    /// This allows for the same configuration options as the asynchronous client.
+    ///
+    /// Returns `Err` if the underlying Tokio runtime cannot be started.
+    ///
+    /// [`ClientBuilder`]: ../struct.ClientBuilder.html
+    pub fn from_builder(builder: ClientBuilder<C, A>) -> Result<Self> {
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("vonage-blocking")
+            .enable_all()
+            .build()
+            .map_err(|e| Error::with_cause(ErrorKind::Http, anyhow!(e)))?;
+
+        Ok(Client {
+            inner: builder.build()?,
+            runtime: Arc::new(runtime),
+        })
+    }
+}
+
+impl<C: HttpTransport, A: Credentials> Client<C, A> {
+    /// Sends a request to an endpoint which this library does not model yet, returning the raw
+    /// response.
+    ///
+    /// See [`vonage::Client::request_raw()`](../struct.Client.html#method.request_raw) for details.
+    pub fn request_raw(
+        &self,
+        method: Method,
+        path: &str,
+        body: impl Into<Bytes>,
+    ) -> Result<Response<Bytes>> {
+        block_on(&self.runtime, self.inner.request_raw(method, path, body))
+    }
+
+    /// Downloads a potentially large file, such as a call recording, streaming it chunk-by-chunk
+    /// instead of buffering it in memory.
+    ///
+    /// See [`vonage::Client::download()`](../struct.Client.html#method.download) for details.
+    pub fn download(&self, url: &str) -> Download<C> {
+        Download {
+            inner: self.inner.download(url),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Returns a handle to the [Verify v2][verify] API.
+    ///
+    /// [verify]: https://developer.vonage.com/en/api/verify.v2
+    ///
+    /// See [`vonage::Client::verify_v2()`](../struct.Client.html#method.verify_v2) for details.
+    pub fn verify_v2(&self) -> verify::v2::VerifyApi<C> {
+        verify::v2::VerifyApi::new(self.inner.verify_v2(), self.runtime.clone())
+    }
+}
+
+impl<C, A> Client<C, A>
+where
+    C: HttpTransport,
+    A: HasApiKey,
+{
+    /// Returns a handle to the [Verify (2FA)][verify] API.
+    ///
+    /// [verify]: https://developer.nexmo.com/api/verify
+    ///
+    /// This method is only available if this client was configured with an API key and API
+    /// secret.
+    pub fn verify(&self) -> verify::VerifyApi<C> {
+        verify::VerifyApi::new(self.inner.verify(), self.runtime.clone())
+    }
+
+    /// Returns a handle to the [Numbers][numbers] API.
+    ///
+    /// [numbers]: https://developer.nexmo.com/api/numbers
+    ///
+    /// This method is only available if this client was configured with an API key and API
+    /// secret.
+    pub fn numbers(&self) -> numbers::NumbersApi<C> {
+        numbers::NumbersApi::new(self.inner.numbers(), self.runtime.clone())
+    }
+
+    /// Returns a handle to the [Number Insight][ni] API.
+    ///
+    /// [ni]: https://developer.nexmo.com/api/number-insight
+    ///
+    /// This method is only available if this client was configured with an API key and API
+    /// secret.
+    pub fn number_insight(&self) -> number_insight::NumberInsightApi<C> {
+        number_insight::NumberInsightApi::new(self.inner.number_insight(), self.runtime.clone())
+    }
+
+    /// Returns a handle to the [SMS][sms] API.
+    ///
+    /// [sms]: https://developer.nexmo.com/api/sms
+    ///
+    /// This method is only available if this client was configured with an API key and API
+    /// secret.
+    pub fn sms(&self) -> sms::SmsApi<C> {
+        sms::SmsApi::new(self.inner.sms(), self.runtime.clone())
+    }
+
+    /// Returns a handle to the [Reports][reports] API, which summarizes account usage.
+    ///
+    /// [reports]: https://developer.nexmo.com/api/reports
+    ///
+    /// This method is only available if this client was configured with an API key and API
+    /// secret.
+    pub fn usage(&self) -> usage::UsageApi<C> {
+        usage::UsageApi::new(self.inner.usage(), self.runtime.clone())
+    }
+
+    /// Returns a handle to the [Applications][applications] API.
+    ///
+    /// [applications]: https://developer.nexmo.com/api/application.v2
+    ///
+    /// This method is only available if this client was configured with an API key and API
+    /// secret.
+    pub fn applications(&self) -> applications::ApplicationsApi<C> {
+        applications::ApplicationsApi::new(self.inner.applications(), self.runtime.clone())
+    }
+}
+
+impl<C, A> Client<C, A>
+where
+    C: HttpTransport,
+    A: HasJwt,
+{
+    /// Returns a handle to the [Voice][voice] API.
+    ///
+    /// [voice]: https://developer.nexmo.com/api/voice
+    ///
+    /// This method is only available if this client was configured with an application ID and
+    /// private key.
+    pub fn voice(&self) -> voice::VoiceApi<C> {
+        voice::VoiceApi::new(self.inner.voice(), self.runtime.clone())
+    }
+
+    /// Spawns a [`JwtRefresher`](../struct.JwtRefresher.html) onto the runtime of this client,
+    /// returning a handle to check its health.
+    ///
+    /// The refresher runs until the client is
+    /// [cancelled](../struct.ClientBuilder.html#method.cancellation_token) or its runtime is
+    /// dropped together with the last clone of this client.
+    ///
+    /// This method is only available if this client was configured with an application ID and
+    /// private key.
+    pub fn spawn_jwt_refresher(&self) -> crate::JwtRefresher<C>
+    where
+        C: Send + Sync + 'static,
+    {
+        let refresher = self.inner.jwt_refresher();
+        self.runtime.spawn(refresher.clone().run());
+        refresher
+    }
+}
+
+impl<C, A> Clone for Client<C, A> {
+    fn clone(&self) -> Self {
+        Client {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// An iterator over every item returned by a page-based list endpoint.
+///
+/// This is the blocking equivalent of [`vonage::Paginated`](../struct.Paginated.html). Pages are
+/// fetched lazily, one at a time, as the iterator advances.
+pub struct Paginated<T> {
+    inner: crate::Paginated<T>,
+    runtime: Arc<Runtime>,
+}
+
+impl<T> Paginated<T> {
+    fn new(inner: crate::Paginated<T>, runtime: Arc<Runtime>) -> Self {
+        Paginated { inner, runtime }
+    }
+
+    /// Sets the number of items requested per page.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn page_size(self, size: u32) -> Self {
+        Paginated {
+            inner: self.inner.page_size(size),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Returns the total number of items across all pages, if reported by the API.
+    pub fn total_count(&self) -> Option<u64> {
+        self.inner.total_count()
+    }
+}
+
+impl<T> Iterator for Paginated<T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        block_on(&self.runtime, self.inner.next())
+    }
+}
+
+impl<T> Debug for Paginated<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Paginated))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A pending download of a potentially large file.
+///
+/// This is the blocking equivalent of [`vonage::Download`](../struct.Download.html).
+pub struct Download<C = DefaultTransport, P = fn(Progress)> {
+    inner: crate::Download<C, P>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C, P: FnMut(Progress)> Download<C, P> {
+    /// Sends the request with the given timeout and retry policy.
+    ///
+    /// See [`vonage::Download::policy()`](../struct.Download.html#method.policy) for details.
+    pub fn policy(self, policy: RequestPolicy) -> Self {
+        Download {
+            inner: self.inner.policy(policy),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Calls `f` after every chunk is written, e.g. to render a progress bar.
+    pub fn on_progress<F: FnMut(Progress)>(self, f: F) -> Download<C, F> {
+        Download {
+            inner: self.inner.on_progress(f),
+            runtime: self.runtime,
+        }
+    }
+}
+
+impl<C: HttpTransport, P: FnMut(Progress)> Download<C, P> {
+    /// Streams the response body into `writer` chunk-by-chunk, returning the number of bytes
+    /// written.
+    ///
+    /// See [`vonage::Download::write_to()`](../struct.Download.html#method.write_to) for details.
+    pub fn write_to<W: Write + ?Sized>(self, writer: &mut W) -> Result<u64> {
+        let mut writer = AllowStdIo::new(writer);
+        block_on(&self.runtime, self.inner.write_to(&mut writer))
+    }
+}
+
+impl<C, P> Debug for Download<C, P> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Download))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<C, A> Debug for Client<C, A> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Client))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+fn block_on<F: Future>(runtime: &Runtime, future: F) -> F::Output {
+    runtime.block_on(future)
+}
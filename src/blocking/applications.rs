@@ -0,0 +1,137 @@
+//! Blocking interface to the Applications API.
+//!
+//! See [`vonage::applications`](../../applications/index.html) for the asynchronous equivalent.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use tokio::runtime::Runtime;
+
+use crate::applications::{self as inner, Application, Capabilities};
+use crate::transport::HttpTransport;
+use crate::{DefaultTransport, RequestPolicy, Result};
+
+/// A handle to the Applications API.
+///
+/// This is the blocking equivalent of
+/// [`vonage::applications::ApplicationsApi`](../../applications/struct.ApplicationsApi.html).
+pub struct ApplicationsApi<C = DefaultTransport> {
+    inner: inner::ApplicationsApi<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> ApplicationsApi<C> {
+    pub(super) fn new(inner: inner::ApplicationsApi<C>, runtime: Arc<Runtime>) -> Self {
+        ApplicationsApi { inner, runtime }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`vonage::RequestPolicy`](../../struct.RequestPolicy.html) for details.
+    pub fn policy(self, policy: RequestPolicy) -> Self {
+        ApplicationsApi {
+            inner: self.inner.policy(policy),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Creates a new application called `name`.
+    pub fn create(&self, name: impl Into<String>) -> ConfigureApplication<C> {
+        ConfigureApplication {
+            inner: self.inner.create(name),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Replaces the name, capabilities and public key of the application `id`.
+    pub fn update(&self, id: &str, name: impl Into<String>) -> ConfigureApplication<C> {
+        ConfigureApplication {
+            inner: self.inner.update(id, name),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C: HttpTransport> ApplicationsApi<C> {
+    /// Retrieves the application `id`.
+    pub fn get(&self, id: &str) -> Result<Application> {
+        super::block_on(&self.runtime, self.inner.get(id))
+    }
+
+    /// Deletes the application `id`.
+    pub fn delete(&self, id: &str) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.delete(id))
+    }
+}
+
+impl<C: HttpTransport + Send + Sync + 'static> ApplicationsApi<C> {
+    /// Returns an iterator over every application of the account, fetching pages of them as it
+    /// advances.
+    pub fn list(&self) -> super::Paginated<Application> {
+        super::Paginated::new(self.inner.list(), self.runtime.clone())
+    }
+}
+
+impl<C> Clone for ApplicationsApi<C> {
+    fn clone(&self) -> Self {
+        ApplicationsApi {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Debug for ApplicationsApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(ApplicationsApi))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A builder to create or update an application.
+///
+/// This is the blocking equivalent of
+/// [`vonage::applications::ConfigureApplication`](../../applications/struct.ConfigureApplication.html).
+pub struct ConfigureApplication<C> {
+    inner: inner::ConfigureApplication<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> ConfigureApplication<C> {
+    /// Sets the webhooks of each product the application is used for.
+    pub fn capabilities(self, capabilities: Capabilities) -> Self {
+        self.map(|c| c.capabilities(capabilities))
+    }
+
+    /// Sets the PEM-encoded public key which verifies the JWTs signed for the application.
+    pub fn public_key(self, key: impl Into<String>) -> Self {
+        self.map(|c| c.public_key(key))
+    }
+
+    fn map<F>(self, f: F) -> Self
+    where
+        F: FnOnce(inner::ConfigureApplication<C>) -> inner::ConfigureApplication<C>,
+    {
+        ConfigureApplication {
+            inner: f(self.inner),
+            runtime: self.runtime,
+        }
+    }
+}
+
+impl<C: HttpTransport> ConfigureApplication<C> {
+    /// Sends the request, returning the application as stored by Vonage.
+    pub fn send(self) -> Result<Application> {
+        super::block_on(&self.runtime, self.inner.send())
+    }
+}
+
+impl<C> Debug for ConfigureApplication<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(ConfigureApplication))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
@@ -0,0 +1,121 @@
+//! Blocking interface to the Number Insight API.
+//!
+//! See [`vonage::number_insight`](../../number_insight/index.html) for the asynchronous
+//! equivalent.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use phonenumber::country::Id;
+use phonenumber::PhoneNumber;
+use serde::de::DeserializeOwned;
+use tokio::runtime::Runtime;
+
+use crate::number_insight::{self as inner, AdvancedInsight, BasicInsight, StandardInsight};
+use crate::transport::HttpTransport;
+use crate::{DefaultTransport, RequestPolicy, Result};
+
+/// A handle to the Number Insight API.
+///
+/// This is the blocking equivalent of
+/// [`vonage::number_insight::NumberInsightApi`](../../number_insight/struct.NumberInsightApi.html).
+pub struct NumberInsightApi<C = DefaultTransport> {
+    inner: inner::NumberInsightApi<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> NumberInsightApi<C> {
+    pub(super) fn new(inner: inner::NumberInsightApi<C>, runtime: Arc<Runtime>) -> Self {
+        NumberInsightApi { inner, runtime }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`vonage::RequestPolicy`](../../struct.RequestPolicy.html) for details.
+    pub fn policy(self, policy: RequestPolicy) -> Self {
+        NumberInsightApi {
+            inner: self.inner.policy(policy),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Looks up the country and formats of `number`, free of charge.
+    pub fn basic(&self, number: &PhoneNumber) -> LookupNumber<C, BasicInsight> {
+        LookupNumber {
+            inner: self.inner.basic(number),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Looks up the carrier and ported status of `number`, in addition to a basic lookup.
+    pub fn standard(&self, number: &PhoneNumber) -> LookupNumber<C, StandardInsight> {
+        LookupNumber {
+            inner: self.inner.standard(number),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Looks up the validity, reachability and roaming status of `number`, in addition to a
+    /// standard lookup.
+    pub fn advanced(&self, number: &PhoneNumber) -> LookupNumber<C, AdvancedInsight> {
+        LookupNumber {
+            inner: self.inner.advanced(number),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Clone for NumberInsightApi<C> {
+    fn clone(&self) -> Self {
+        NumberInsightApi {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Debug for NumberInsightApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(NumberInsightApi))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A builder for a Number Insight lookup.
+///
+/// This is the blocking equivalent of
+/// [`vonage::number_insight::LookupNumber`](../../number_insight/struct.LookupNumber.html).
+pub struct LookupNumber<C, T> {
+    inner: inner::LookupNumber<C, T>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C, T> LookupNumber<C, T> {
+    /// Sets the country the number is expected to be in.
+    ///
+    /// Numbers from other countries are still looked up, but this determines the national format
+    /// returned for them.
+    pub fn country(self, country: Id) -> Self {
+        LookupNumber {
+            inner: self.inner.country(country),
+            runtime: self.runtime,
+        }
+    }
+}
+
+impl<C: HttpTransport, T: DeserializeOwned> LookupNumber<C, T> {
+    /// Sends the request.
+    pub fn send(self) -> Result<T> {
+        super::block_on(&self.runtime, self.inner.send())
+    }
+}
+
+impl<C, T> Debug for LookupNumber<C, T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(LookupNumber))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
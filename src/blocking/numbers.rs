@@ -0,0 +1,305 @@
+//! Blocking interface to the Numbers API.
+//!
+//! See [`vonage::numbers`](../../numbers/index.html) for the asynchronous equivalent.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use phonenumber::country::Id;
+use tokio::runtime::Runtime;
+
+use crate::messages::Sender;
+use crate::numbers::{
+    self as inner, Feature, NumberSearch, NumberType, OwnedNumber, SearchPattern,
+};
+use crate::transport::HttpTransport;
+use crate::{DefaultTransport, RequestPolicy, Result};
+
+/// A handle to the Numbers API.
+///
+/// This is the blocking equivalent of
+/// [`vonage::numbers::NumbersApi`](../../numbers/struct.NumbersApi.html).
+pub struct NumbersApi<C = DefaultTransport> {
+    inner: inner::NumbersApi<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> NumbersApi<C> {
+    pub(super) fn new(inner: inner::NumbersApi<C>, runtime: Arc<Runtime>) -> Self {
+        NumbersApi { inner, runtime }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`vonage::RequestPolicy`](../../struct.RequestPolicy.html) for details.
+    pub fn policy(self, policy: RequestPolicy) -> Self {
+        NumbersApi {
+            inner: self.inner.policy(policy),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Searches for numbers in `country` which are available to buy.
+    pub fn search(&self, country: Id) -> SearchNumbers<C> {
+        SearchNumbers {
+            inner: self.inner.search(country),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Lists the numbers owned by the account.
+    pub fn list(&self) -> ListNumbers<C> {
+        ListNumbers {
+            inner: self.inner.list(),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Changes the webhooks and application linked to a number owned by the account.
+    pub fn update(&self, country: Id, msisdn: impl Into<String>) -> UpdateNumber<C> {
+        UpdateNumber {
+            inner: self.inner.update(country, msisdn),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Looks up the messaging capabilities of a number owned by the account.
+    pub fn capabilities(&self, msisdn: impl Into<String>) -> QueryCapabilities<C> {
+        QueryCapabilities {
+            inner: self.inner.capabilities(msisdn),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C: HttpTransport> NumbersApi<C> {
+    /// Buys the available number `msisdn` in `country`.
+    pub fn buy(&self, country: Id, msisdn: impl Into<String>) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.buy(country, msisdn))
+    }
+
+    /// Cancels the rental of the number `msisdn` in `country`, releasing it from the account.
+    pub fn cancel(&self, country: Id, msisdn: impl Into<String>) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.cancel(country, msisdn))
+    }
+}
+
+impl<C> Clone for NumbersApi<C> {
+    fn clone(&self) -> Self {
+        NumbersApi {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Debug for NumbersApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(NumbersApi))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A builder to configure a search for available numbers.
+///
+/// This is the blocking equivalent of
+/// [`vonage::numbers::SearchNumbers`](../../numbers/struct.SearchNumbers.html).
+pub struct SearchNumbers<C> {
+    inner: inner::SearchNumbers<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> SearchNumbers<C> {
+    /// Only includes numbers of the given type.
+    pub fn number_type(self, number_type: NumberType) -> Self {
+        self.map(|s| s.number_type(number_type))
+    }
+
+    /// Only includes toll-free numbers.
+    pub fn toll_free(self) -> Self {
+        self.map(|s| s.toll_free())
+    }
+
+    /// Only includes numbers which support all of the given features.
+    pub fn features(self, features: &[Feature]) -> Self {
+        self.map(|s| s.features(features))
+    }
+
+    /// Only includes numbers which match `pattern` at the given position.
+    pub fn pattern(self, pattern: impl Into<String>, position: SearchPattern) -> Self {
+        self.map(|s| s.pattern(pattern, position))
+    }
+
+    /// Sets the number of results per page.
+    pub fn size(self, size: u64) -> Self {
+        self.map(|s| s.size(size))
+    }
+
+    /// Sets the page of results to return, starting at 1.
+    pub fn index(self, index: u64) -> Self {
+        self.map(|s| s.index(index))
+    }
+
+    fn map<F>(self, f: F) -> Self
+    where
+        F: FnOnce(inner::SearchNumbers<C>) -> inner::SearchNumbers<C>,
+    {
+        SearchNumbers {
+            inner: f(self.inner),
+            runtime: self.runtime,
+        }
+    }
+}
+
+impl<C: HttpTransport> SearchNumbers<C> {
+    /// Sends the search request.
+    pub fn send(self) -> Result<NumberSearch> {
+        super::block_on(&self.runtime, self.inner.send())
+    }
+}
+
+impl<C> Debug for SearchNumbers<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(SearchNumbers))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A builder to change the configuration of an owned number.
+///
+/// This is the blocking equivalent of
+/// [`vonage::numbers::UpdateNumber`](../../numbers/struct.UpdateNumber.html).
+pub struct UpdateNumber<C> {
+    inner: inner::UpdateNumber<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> UpdateNumber<C> {
+    /// Links the number to the Vonage application with the given ID.
+    pub fn app_id(self, id: impl Into<String>) -> Self {
+        self.map(|u| u.app_id(id))
+    }
+
+    /// Sets the webhook which inbound SMS messages to this number are sent to.
+    pub fn inbound_sms_url(self, url: impl Into<String>) -> Self {
+        self.map(|u| u.inbound_sms_url(url))
+    }
+
+    /// Sets the webhook which events about voice calls to this number are sent to.
+    pub fn voice_status_url(self, url: impl Into<String>) -> Self {
+        self.map(|u| u.voice_status_url(url))
+    }
+
+    fn map<F>(self, f: F) -> Self
+    where
+        F: FnOnce(inner::UpdateNumber<C>) -> inner::UpdateNumber<C>,
+    {
+        UpdateNumber {
+            inner: f(self.inner),
+            runtime: self.runtime,
+        }
+    }
+}
+
+impl<C: HttpTransport> UpdateNumber<C> {
+    /// Sends the update request.
+    pub fn send(self) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.send())
+    }
+}
+
+impl<C> Debug for UpdateNumber<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(UpdateNumber))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A builder to filter the numbers owned by the account.
+///
+/// This is the blocking equivalent of
+/// [`vonage::numbers::ListNumbers`](../../numbers/struct.ListNumbers.html).
+pub struct ListNumbers<C> {
+    inner: inner::ListNumbers<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> ListNumbers<C> {
+    /// Only includes numbers in `country`.
+    pub fn country(self, country: Id) -> Self {
+        self.map(|l| l.country(country))
+    }
+
+    /// Only includes numbers which match `pattern` at the given position.
+    pub fn pattern(self, pattern: impl Into<String>, position: SearchPattern) -> Self {
+        self.map(|l| l.pattern(pattern, position))
+    }
+
+    /// Only includes numbers linked to the Vonage application with the given ID.
+    pub fn app_id(self, id: impl Into<String>) -> Self {
+        self.map(|l| l.app_id(id))
+    }
+
+    /// Only includes numbers which are, or are not, linked to any application.
+    pub fn has_app(self, has_app: bool) -> Self {
+        self.map(|l| l.has_app(has_app))
+    }
+
+    fn map<F>(self, f: F) -> Self
+    where
+        F: FnOnce(inner::ListNumbers<C>) -> inner::ListNumbers<C>,
+    {
+        ListNumbers {
+            inner: f(self.inner),
+            runtime: self.runtime,
+        }
+    }
+}
+
+/// Iterates over every matching number, fetching pages of them as it advances.
+impl<C: HttpTransport + Send + Sync + 'static> IntoIterator for ListNumbers<C> {
+    type Item = Result<OwnedNumber>;
+    type IntoIter = super::Paginated<OwnedNumber>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        super::Paginated::new(self.inner.into_stream(), self.runtime)
+    }
+}
+
+impl<C> Debug for ListNumbers<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(ListNumbers))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A request for the messaging capabilities of an owned number.
+///
+/// This is the blocking equivalent of
+/// [`vonage::numbers::QueryCapabilities`](../../numbers/struct.QueryCapabilities.html).
+pub struct QueryCapabilities<C> {
+    inner: inner::QueryCapabilities<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C: HttpTransport> QueryCapabilities<C> {
+    /// Sends the request.
+    ///
+    /// Returns `None` if the number is not owned by the account.
+    pub fn send(self) -> Result<Option<Sender>> {
+        super::block_on(&self.runtime, self.inner.send())
+    }
+}
+
+impl<C> Debug for QueryCapabilities<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(QueryCapabilities))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
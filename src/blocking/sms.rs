@@ -0,0 +1,117 @@
+//! Blocking interface to the SMS API.
+//!
+//! See [`vonage::sms`](../../sms/index.html) for the asynchronous equivalent.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use phonenumber::PhoneNumber;
+use tokio::runtime::Runtime;
+
+use crate::sms::{self as inner, SentSms};
+use crate::transport::HttpTransport;
+use crate::{DefaultTransport, RequestPolicy, Result};
+
+/// A handle to the SMS API.
+///
+/// This is the blocking equivalent of [`vonage::sms::SmsApi`](../../sms/struct.SmsApi.html).
+pub struct SmsApi<C = DefaultTransport> {
+    inner: inner::SmsApi<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> SmsApi<C> {
+    pub(super) fn new(inner: inner::SmsApi<C>, runtime: Arc<Runtime>) -> Self {
+        SmsApi { inner, runtime }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`vonage::RequestPolicy`](../../struct.RequestPolicy.html) for details.
+    pub fn policy(self, policy: RequestPolicy) -> Self {
+        SmsApi {
+            inner: self.inner.policy(policy),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Sends `text` to `to` from `from`, which is either a virtual number in E.164 format without
+    /// a leading `+`, or an alphanumeric sender ID.
+    pub fn send(
+        &self,
+        to: &PhoneNumber,
+        from: impl Into<String>,
+        text: impl Into<String>,
+    ) -> SendSms<C> {
+        SendSms {
+            inner: self.inner.send(to, from, text),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Clone for SmsApi<C> {
+    fn clone(&self) -> Self {
+        SmsApi {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Debug for SmsApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(SmsApi))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A builder for an outgoing SMS.
+///
+/// This is the blocking equivalent of [`vonage::sms::SendSms`](../../sms/struct.SendSms.html).
+pub struct SendSms<C> {
+    inner: inner::SendSms<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> SendSms<C> {
+    fn map(self, f: impl FnOnce(inner::SendSms<C>) -> inner::SendSms<C>) -> Self {
+        SendSms {
+            inner: f(self.inner),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Sends the text as Unicode, which is required for characters outside of the GSM-7
+    /// alphabet, such as emoji.
+    pub fn unicode(self) -> Self {
+        self.map(inner::SendSms::unicode)
+    }
+
+    /// Overrides the URL which Vonage sends delivery receipts for this message to.
+    pub fn callback_url(self, url: impl Into<String>) -> Self {
+        self.map(|s| s.callback_url(url))
+    }
+
+    /// Attaches a reference which is echoed back in the delivery receipts of the message.
+    pub fn client_ref(self, reference: impl Into<String>) -> Self {
+        self.map(|s| s.client_ref(reference))
+    }
+}
+
+impl<C: HttpTransport> SendSms<C> {
+    /// Sends the message.
+    pub fn send(self) -> Result<SentSms> {
+        super::block_on(&self.runtime, self.inner.send())
+    }
+}
+
+impl<C> Debug for SendSms<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(SendSms))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
@@ -0,0 +1,114 @@
+//! Blocking interface to the Reports API.
+//!
+//! See [`vonage::usage`](../../usage/index.html) for the asynchronous equivalent.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use tokio::runtime::Runtime;
+
+use crate::transport::HttpTransport;
+use crate::usage::{self as inner, ReportProduct, UsageSummary};
+use crate::{DefaultTransport, RequestPolicy, Result, Timestamp};
+
+/// A handle to the Reports API.
+///
+/// This is the blocking equivalent of
+/// [`vonage::usage::UsageApi`](../../usage/struct.UsageApi.html).
+pub struct UsageApi<C = DefaultTransport> {
+    inner: inner::UsageApi<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> UsageApi<C> {
+    pub(super) fn new(inner: inner::UsageApi<C>, runtime: Arc<Runtime>) -> Self {
+        UsageApi { inner, runtime }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`vonage::RequestPolicy`](../../struct.RequestPolicy.html) for details.
+    pub fn policy(self, policy: RequestPolicy) -> Self {
+        UsageApi {
+            inner: self.inner.policy(policy),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Summarizes the usage of the account between `start` and `end`.
+    ///
+    /// See [`vonage::usage::UsageApi::summary()`](../../usage/struct.UsageApi.html#method.summary)
+    /// for details.
+    pub fn summary(&self, start: Timestamp, end: Timestamp) -> SummarizeUsage<C> {
+        SummarizeUsage {
+            inner: self.inner.summary(start, end),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Clone for UsageApi<C> {
+    fn clone(&self) -> Self {
+        UsageApi {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Debug for UsageApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(UsageApi))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A builder for a summary of account usage.
+///
+/// This is the blocking equivalent of
+/// [`vonage::usage::SummarizeUsage`](../../usage/struct.SummarizeUsage.html).
+pub struct SummarizeUsage<C> {
+    inner: inner::SummarizeUsage<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> SummarizeUsage<C> {
+    /// Includes `product` in the summary.
+    ///
+    /// Once called, only the products passed to this method are included.
+    pub fn product(self, product: ReportProduct) -> Self {
+        self.map(|s| s.product(product))
+    }
+
+    /// Includes the usage of all subaccounts of the account. Defaults to `false`.
+    pub fn include_subaccounts(self, include: bool) -> Self {
+        self.map(|s| s.include_subaccounts(include))
+    }
+
+    fn map<F>(self, f: F) -> Self
+    where
+        F: FnOnce(inner::SummarizeUsage<C>) -> inner::SummarizeUsage<C>,
+    {
+        SummarizeUsage {
+            inner: f(self.inner),
+            runtime: self.runtime,
+        }
+    }
+}
+
+impl<C: HttpTransport> SummarizeUsage<C> {
+    /// Loads the records of each product and aggregates them.
+    pub fn send(self) -> Result<UsageSummary> {
+        super::block_on(&self.runtime, self.inner.send())
+    }
+}
+
+impl<C> Debug for SummarizeUsage<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(SummarizeUsage))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
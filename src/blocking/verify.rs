@@ -0,0 +1,417 @@
+//! Blocking interface to the Verify (2FA) API.
+//!
+//! See [`vonage::verify`](../../verify/index.html) for the asynchronous equivalent.
+
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+use phonenumber::country::Id;
+use phonenumber::PhoneNumber;
+use tokio::runtime::Runtime;
+
+use crate::transport::HttpTransport;
+use crate::verify::{
+    self as inner, CodeLength, Language, Lookup, Normal, Psd2, Psd2Language, RequestId,
+    Verification, Verified, VerifyInfo, Workflow,
+};
+use crate::{Amount, DefaultTransport, RequestPolicy, ResponseMeta, Result};
+
+pub mod v2;
+
+/// A handle to the Verify (2FA) API.
+///
+/// This is the blocking equivalent of
+/// [`vonage::verify::VerifyApi`](../../verify/struct.VerifyApi.html).
+pub struct VerifyApi<C = DefaultTransport> {
+    inner: inner::VerifyApi<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> VerifyApi<C> {
+    pub(super) fn new(inner: inner::VerifyApi<C>, runtime: Arc<Runtime>) -> Self {
+        VerifyApi { inner, runtime }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`vonage::RequestPolicy`](../../struct.RequestPolicy.html) for details.
+    pub fn policy(self, policy: RequestPolicy) -> Self {
+        VerifyApi {
+            inner: self.inner.policy(policy),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Initiates a new [verify (2FA) request][verify] for the given phone number.
+    ///
+    /// [verify]: https://developer.nexmo.com/api/verify
+    pub fn request(&self, phone: PhoneNumber, brand: impl Into<String>) -> Verify<C> {
+        Verify {
+            inner: self.inner.request(phone, brand),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Retrieves details of past or current verify requests by ID.
+    ///
+    /// This is the blocking equivalent of
+    /// [`vonage::verify::VerifyApi::search()`](../../verify/struct.VerifyApi.html#method.search).
+    pub fn search<I>(&self, request_ids: I) -> Search<C>
+    where
+        I: IntoIterator<Item = RequestId>,
+    {
+        Search {
+            inner: self.inner.search(request_ids),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Clone for VerifyApi<C> {
+    fn clone(&self) -> Self {
+        VerifyApi {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Debug for VerifyApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(VerifyApi))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A builder to configure a new verify request.
+///
+/// This is the blocking equivalent of [`vonage::verify::Verify`](../../verify/struct.Verify.html).
+pub struct Verify<C, V: Verification = Normal> {
+    inner: inner::Verify<C, V>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> Verify<C> {
+    /// Sets the 11-character alphanumeric string that represents the identity of the sender of the
+    /// request.
+    pub fn sender_id(self, id: impl Into<String>) -> Self {
+        self.map(|v| v.sender_id(id))
+    }
+
+    /// Sets the language used for the SMS or TTS message to be sent.
+    pub fn language(self, lang: Language) -> Self {
+        self.map(|v| v.language(lang))
+    }
+
+    /// Changes this builder to construct a [Payment Services Directive 2 (PSD2)] request.
+    ///
+    /// [Payment Services Directive 2 (PSD2)]: https://developer.nexmo.com/api/verify#verifyRequestWithPSD2
+    pub fn psd2(self, payee: impl Into<String>, amount_eur: Amount) -> Verify<C, Psd2> {
+        self.map(|v| v.psd2(payee, amount_eur))
+    }
+}
+
+impl<C> Verify<C, Psd2> {
+    /// Sets the language used for the SMS or TTS message to be sent.
+    pub fn language(self, lang: Psd2Language) -> Self {
+        self.map(|v| v.language(lang))
+    }
+}
+
+impl<C, V: Verification> Verify<C, V> {
+    fn map<W, F>(self, f: F) -> Verify<C, W>
+    where
+        W: Verification,
+        F: FnOnce(inner::Verify<C, V>) -> inner::Verify<C, W>,
+    {
+        Verify {
+            inner: f(self.inner),
+            runtime: self.runtime,
+        }
+    }
+}
+
+impl<C, V> Verify<C, V>
+where
+    C: HttpTransport,
+    V: Verification,
+{
+    /// Overrides the country code of the phone number.
+    pub fn country(self, country: Id) -> Self {
+        self.map(|v| v.country(country))
+    }
+
+    /// Sets the verification code length.
+    pub fn code_length(self, len: CodeLength) -> Self {
+        self.map(|v| v.code_length(len))
+    }
+
+    /// Sets how long the generated verification code is valid for.
+    pub fn pin_expiry(self, valid_for: Duration) -> Self {
+        self.map(|v| v.pin_expiry(valid_for))
+    }
+
+    /// Sets the wait time in between attempts to deliver the verification code.
+    pub fn next_event_wait(self, wait: Duration) -> Self {
+        self.map(|v| v.next_event_wait(wait))
+    }
+
+    /// Sets the predefined sequence of SMS and TTS (text-to-speech) actions to use in order to
+    /// convey the PIN to your user.
+    pub fn workflow(self, w: Workflow) -> Self {
+        self.map(|v| v.workflow(w))
+    }
+
+    /// Overrides the URL which Vonage sends events about this verify request to.
+    pub fn callback_url(self, url: impl Into<String>) -> Self {
+        self.map(|v| v.callback_url(url))
+    }
+
+    /// Sets how many incorrect PIN codes may be submitted before `PendingVerify::check()` gives
+    /// up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `attempts` is zero.
+    pub fn check_attempts(self, attempts: usize) -> Self {
+        self.map(|v| v.check_attempts(attempts))
+    }
+
+    /// Screens the phone number with a Number Insight lookup before sending the verify request.
+    pub fn lookup(self, lookup: Lookup) -> Self {
+        self.map(|v| v.lookup(lookup))
+    }
+
+    /// Submits the verify request and returns a `PendingVerify` to control its state.
+    pub fn send(self) -> Result<PendingVerify<C>> {
+        let inner = super::block_on(&self.runtime, self.inner.send())?;
+        Ok(PendingVerify {
+            inner,
+            runtime: self.runtime,
+        })
+    }
+}
+
+impl<C, V: Debug + Verification> Debug for Verify<C, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Verify))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A handle to a pending verify request.
+///
+/// This is the blocking equivalent of
+/// [`vonage::verify::PendingVerify`](../../verify/struct.PendingVerify.html). Like its
+/// asynchronous counterpart, it is cheap to clone and can be shared between threads.
+pub struct PendingVerify<C = DefaultTransport> {
+    inner: inner::PendingVerify<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> PendingVerify<C>
+where
+    C: HttpTransport,
+{
+    /// Attempts to cancel this pending verify request.
+    pub fn cancel(&mut self) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.cancel())
+    }
+
+    /// Attempts to trigger the next phase of the request [`Workflow`].
+    ///
+    /// [`Workflow`]: ../../verify/enum.Workflow.html
+    pub fn trigger_next_event(&mut self) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.trigger_next_event())
+    }
+
+    /// Checks whether the user-provided PIN code matches the expected value.
+    ///
+    /// Returns `Ok(Code::Match(_))` if the given PIN code is correct. Returns
+    /// `Ok(Code::Mismatch(_))` if the given PIN code is incorrect, as long as
+    /// [`attempts_remaining()`](#method.attempts_remaining) is non-zero.
+    /// Returns `Err` if the code expired, the request was canceled, or some other error occurred.
+    pub fn check(self, code: &str) -> Result<Code<C>> {
+        let runtime = self.runtime;
+        match super::block_on(&runtime, self.inner.check(code))? {
+            inner::Code::Match(verified) => Ok(Code::Match(verified)),
+            inner::Code::Mismatch(inner) => Ok(Code::Mismatch(PendingVerify { inner, runtime })),
+        }
+    }
+
+    /// Returns the number of check attempts remaining.
+    #[inline]
+    pub fn attempts_remaining(&self) -> usize {
+        self.inner.attempts_remaining()
+    }
+
+    /// Returns the unique request ID.
+    #[inline]
+    pub fn request_id(&self) -> &RequestId {
+        self.inner.request_id()
+    }
+
+    /// Returns the metadata of the most recent response received for this request.
+    #[inline]
+    pub fn response_meta(&self) -> &ResponseMeta {
+        self.inner.response_meta()
+    }
+}
+
+impl<C> Clone for PendingVerify<C> {
+    fn clone(&self) -> Self {
+        PendingVerify {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Debug for PendingVerify<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(PendingVerify))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<C> Eq for PendingVerify<C> {}
+
+impl<C> PartialEq for PendingVerify<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<C> Hash for PendingVerify<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner.hash(state);
+    }
+}
+
+/// The result of a PIN code check.
+#[derive(Debug)]
+pub enum Code<C> {
+    /// The user-provided code matched the expected value.
+    Match(Verified),
+    /// The user-provided code didn't match the expected value.
+    Mismatch(PendingVerify<C>),
+}
+
+/// A search for many verify requests at once.
+///
+/// This is the blocking equivalent of [`vonage::verify::Search`](../../verify/struct.Search.html).
+pub struct Search<C = DefaultTransport, P = fn(usize, usize)> {
+    inner: inner::Search<C, P>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C, P: FnMut(usize, usize)> Search<C, P> {
+    /// Sets the maximum number of chunks which may be in flight at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `concurrency` is zero.
+    pub fn concurrency(self, concurrency: usize) -> Self {
+        Search {
+            inner: self.inner.concurrency(concurrency),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Calls `f` with the number of request IDs searched so far and the total number of request
+    /// IDs after every chunk completes.
+    pub fn on_progress<F: FnMut(usize, usize)>(self, f: F) -> Search<C, F> {
+        Search {
+            inner: self.inner.on_progress(f),
+            runtime: self.runtime,
+        }
+    }
+}
+
+impl<C: HttpTransport + 'static, P: FnMut(usize, usize)> Search<C, P> {
+    /// Sends the search, returning one entry per request ID in the order they were given.
+    pub fn send(self) -> Result<Vec<Option<VerifyInfo>>> {
+        super::block_on(&self.runtime, self.inner.send())
+    }
+}
+
+impl<C, P> Debug for Search<C, P> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Search))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// Retrieves details of past or current verify requests.
+///
+/// This is the blocking equivalent of [`vonage::verify::search()`](../../verify/fn.search.html).
+pub fn search<'a, I, C>(iter: I) -> Result<Vec<Option<VerifyInfo>>>
+where
+    I: IntoIterator<Item = &'a PendingVerify<C>>,
+    C: HttpTransport + 'static,
+{
+    let pending: Vec<_> = iter.into_iter().collect();
+    match pending.first() {
+        Some(first) => {
+            let runtime = first.runtime.clone();
+            super::block_on(&runtime, inner::search(pending.iter().map(|p| &p.inner)))
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use bytes::Bytes;
+    use http::{Request, Response};
+    use http_body_util::Full;
+    use tower::service_fn;
+
+    use crate::blocking::Client;
+
+    #[test]
+    fn sends_and_checks_synchronously() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            let body = match request.uri().path() {
+                "/verify/json" => {
+                    r#"{"request_id":"abcdef0123456789abcdef0123456789","status":"0"}"#
+                }
+                "/verify/check/json" => {
+                    r#"{"status":"16","error_text":"The code provided does not match the expected value"}"#
+                }
+                other => panic!("unexpected path: {}", other),
+            };
+            Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let builder = crate::Client::from_service(service).api_key("key", "secret");
+        let client = Client::from_builder(builder).unwrap();
+        let pending = client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .code_length(CodeLength::Six)
+            .send()
+            .unwrap();
+
+        assert_eq!(
+            pending.request_id().to_string(),
+            "abcdef0123456789abcdef0123456789"
+        );
+
+        match pending.check("0000").unwrap() {
+            Code::Mismatch(pending) => assert_eq!(pending.attempts_remaining(), 2),
+            Code::Match(_) => panic!("code should not have matched"),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Blocking interface to the Verify v2 API.
+//!
+//! See [`vonage::verify::v2`](../../../verify/v2/index.html) for the asynchronous equivalent.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::runtime::Runtime;
+
+use crate::transport::HttpTransport;
+use crate::verify::v2::{self as inner, Channel, RequestId};
+use crate::{DefaultTransport, RequestPolicy, Result};
+
+/// A handle to the Verify v2 API.
+///
+/// This is the blocking equivalent of
+/// [`vonage::verify::v2::VerifyApi`](../../../verify/v2/struct.VerifyApi.html).
+pub struct VerifyApi<C = DefaultTransport> {
+    inner: inner::VerifyApi<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> VerifyApi<C> {
+    pub(in crate::blocking) fn new(inner: inner::VerifyApi<C>, runtime: Arc<Runtime>) -> Self {
+        VerifyApi { inner, runtime }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`vonage::RequestPolicy`](../../../struct.RequestPolicy.html) for details.
+    pub fn policy(self, policy: RequestPolicy) -> Self {
+        VerifyApi {
+            inner: self.inner.policy(policy),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Starts building a verify request on behalf of `brand`.
+    pub fn request(&self, brand: impl Into<String>) -> Verify<C> {
+        Verify {
+            inner: self.inner.request(brand),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Returns a handle to the existing request `request_id`.
+    pub fn pending(&self, request_id: RequestId) -> PendingVerify<C> {
+        PendingVerify {
+            inner: self.inner.pending(request_id),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Clone for VerifyApi<C> {
+    fn clone(&self) -> Self {
+        VerifyApi {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Debug for VerifyApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(VerifyApi))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A builder to configure a new Verify v2 request.
+///
+/// This is the blocking equivalent of
+/// [`vonage::verify::v2::Verify`](../../../verify/v2/struct.Verify.html).
+pub struct Verify<C> {
+    inner: inner::Verify<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> Verify<C> {
+    /// Appends `channel` to the workflow.
+    pub fn workflow(self, channel: Channel) -> Self {
+        self.map(|v| v.workflow(channel))
+    }
+
+    /// Sets the locale of the messages sent to the user, e.g. `en-us`.
+    pub fn locale(self, locale: impl Into<String>) -> Self {
+        self.map(|v| v.locale(locale))
+    }
+
+    /// Sets how long to wait for a correct code before moving to the next channel.
+    pub fn channel_timeout(self, timeout: Duration) -> Self {
+        self.map(|v| v.channel_timeout(timeout))
+    }
+
+    /// Sets a reference which is included in webhooks about the request.
+    pub fn client_ref(self, client_ref: impl Into<String>) -> Self {
+        self.map(|v| v.client_ref(client_ref))
+    }
+
+    /// Sets the number of digits of the generated code.
+    pub fn code_length(self, len: u8) -> Self {
+        self.map(|v| v.code_length(len))
+    }
+
+    fn map<F>(self, f: F) -> Self
+    where
+        F: FnOnce(inner::Verify<C>) -> inner::Verify<C>,
+    {
+        Verify {
+            inner: f(self.inner),
+            runtime: self.runtime,
+        }
+    }
+}
+
+impl<C: HttpTransport> Verify<C> {
+    /// Sends the request, returning a handle to check the code the user enters.
+    pub fn send(self) -> Result<PendingVerify<C>> {
+        let inner = crate::blocking::block_on(&self.runtime, self.inner.send())?;
+        Ok(PendingVerify {
+            inner,
+            runtime: self.runtime,
+        })
+    }
+}
+
+impl<C> Debug for Verify<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Verify))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A handle to a pending Verify v2 request.
+///
+/// This is the blocking equivalent of
+/// [`vonage::verify::v2::PendingVerify`](../../../verify/v2/struct.PendingVerify.html).
+pub struct PendingVerify<C = DefaultTransport> {
+    inner: inner::PendingVerify<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> PendingVerify<C> {
+    /// Returns the unique request ID.
+    #[inline]
+    pub fn request_id(&self) -> &RequestId {
+        self.inner.request_id()
+    }
+
+    /// Returns the URL which the user's device must open to complete a silent authentication
+    /// check.
+    #[inline]
+    pub fn check_url(&self) -> Option<&str> {
+        self.inner.check_url()
+    }
+}
+
+impl<C: HttpTransport> PendingVerify<C> {
+    /// Checks whether the code entered by the user is correct.
+    pub fn check(&self, code: &str) -> Result<bool> {
+        crate::blocking::block_on(&self.runtime, self.inner.check(code))
+    }
+
+    /// Cancels the request, unless it has already completed.
+    pub fn cancel(&self) -> Result<()> {
+        crate::blocking::block_on(&self.runtime, self.inner.cancel())
+    }
+
+    /// Moves to the next channel of the workflow without waiting for the current one to time
+    /// out.
+    pub fn next_workflow(&self) -> Result<()> {
+        crate::blocking::block_on(&self.runtime, self.inner.next_workflow())
+    }
+}
+
+impl<C> Clone for PendingVerify<C> {
+    fn clone(&self) -> Self {
+        PendingVerify {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Debug for PendingVerify<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(PendingVerify))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
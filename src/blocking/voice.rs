@@ -0,0 +1,541 @@
+//! Blocking interface to the Voice API.
+//!
+//! See [`vonage::voice`](../../voice/index.html) for the asynchronous equivalent.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use phonenumber::PhoneNumber;
+use tokio::runtime::Runtime;
+
+use crate::transport::HttpTransport;
+use crate::voice::ncco::Conversation;
+use crate::voice::{self as inner, CallStatus, Direction, Endpoint, MachineDetection, Transcript};
+use crate::webhooks::VoiceEvent;
+use crate::{DefaultTransport, RequestPolicy, Result};
+
+/// A handle to the Voice API.
+///
+/// This is the blocking equivalent of
+/// [`vonage::voice::VoiceApi`](../../voice/struct.VoiceApi.html).
+pub struct VoiceApi<C = DefaultTransport> {
+    inner: inner::VoiceApi<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> VoiceApi<C> {
+    pub(super) fn new(inner: inner::VoiceApi<C>, runtime: Arc<Runtime>) -> Self {
+        VoiceApi { inner, runtime }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`vonage::RequestPolicy`](../../struct.RequestPolicy.html) for details.
+    pub fn policy(self, policy: RequestPolicy) -> Self {
+        VoiceApi {
+            inner: self.inner.policy(policy),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Places an outbound call from the virtual number `from` to `to`.
+    ///
+    /// See [`vonage::voice::VoiceApi::create_call()`](../../voice/struct.VoiceApi.html#method.create_call)
+    /// for details.
+    pub fn create_call(&self, to: Endpoint, from: &PhoneNumber) -> CreateCall<C> {
+        CreateCall {
+            inner: self.inner.create_call(to, from),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Returns a handle to the conference called `name`, which is created once the first call
+    /// joins it.
+    ///
+    /// See [`vonage::voice::Conference`](../../voice/struct.Conference.html) for details.
+    pub fn conference(&self, name: impl Into<String>) -> Conference<C> {
+        Conference {
+            inner: self.inner.conference(name),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Starts an attended transfer of the caller on the leg `caller_uuid` from the agent on the
+    /// leg `agent_uuid` to a third party.
+    ///
+    /// See [`vonage::voice::AttendedTransfer`](../../voice/struct.AttendedTransfer.html) for
+    /// details.
+    pub fn attended_transfer(&self, caller_uuid: &str, agent_uuid: &str) -> AttendedTransfer<C> {
+        AttendedTransfer {
+            inner: self.inner.attended_transfer(caller_uuid, agent_uuid),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Retrieves the transcript of a recording.
+    ///
+    /// See [`vonage::voice::VoiceApi::transcript()`](../../voice/struct.VoiceApi.html#method.transcript)
+    /// for details.
+    pub fn transcript(&self, url: &str) -> FetchTranscript<C> {
+        FetchTranscript {
+            inner: self.inner.transcript(url),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Leaves the MP3 or WAV file at `audio_url` as a message on answering machines.
+    ///
+    /// See [`vonage::voice::VoicemailDrop`](../../voice/struct.VoicemailDrop.html) for details.
+    pub fn voicemail_drop(&self, audio_url: impl Into<String>) -> VoicemailDrop<C> {
+        VoicemailDrop {
+            inner: self.inner.voicemail_drop(audio_url),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Clone for VoiceApi<C> {
+    fn clone(&self) -> Self {
+        VoiceApi {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Debug for VoiceApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(VoiceApi))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A request for the transcript of a recording.
+///
+/// This is the blocking equivalent of
+/// [`vonage::voice::FetchTranscript`](../../voice/struct.FetchTranscript.html).
+pub struct FetchTranscript<C> {
+    inner: inner::FetchTranscript<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C: HttpTransport> FetchTranscript<C> {
+    /// Sends the request.
+    pub fn send(self) -> Result<Transcript> {
+        super::block_on(&self.runtime, self.inner.send())
+    }
+}
+
+impl<C> Debug for FetchTranscript<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(FetchTranscript))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A request to place an outbound call.
+///
+/// This is the blocking equivalent of
+/// [`vonage::voice::CreateCall`](../../voice/struct.CreateCall.html).
+pub struct CreateCall<C> {
+    inner: inner::CreateCall<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> CreateCall<C> {
+    fn map<F>(self, f: F) -> Self
+    where
+        F: FnOnce(inner::CreateCall<C>) -> inner::CreateCall<C>,
+    {
+        CreateCall {
+            inner: f(self.inner),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Requests the NCCO which controls the call from the given URL once it is answered.
+    pub fn answer_url(self, url: impl Into<String>) -> Self {
+        self.map(|inner| inner.answer_url(url))
+    }
+
+    /// Controls the call with the given NCCO once it is answered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ncco` cannot be serialized to JSON.
+    pub fn ncco<T: serde::Serialize + ?Sized>(self, ncco: &T) -> Self {
+        self.map(|inner| inner.ncco(ncco))
+    }
+
+    /// Sends status updates of the call to the given URL.
+    pub fn event_url(self, url: impl Into<String>) -> Self {
+        self.map(|inner| inner.event_url(url))
+    }
+
+    /// Sets how long the call may ring before it is abandoned, from 1 to 120 seconds.
+    pub fn ringing_timer(self, seconds: u64) -> Self {
+        self.map(|inner| inner.ringing_timer(seconds))
+    }
+
+    /// Sets the maximum length of the call, from 1 to 86400 seconds.
+    pub fn length_timer(self, seconds: u64) -> Self {
+        self.map(|inner| inner.length_timer(seconds))
+    }
+
+    /// Detects whether the call was answered by an answering machine, and applies `behavior` if
+    /// so.
+    pub fn machine_detection(self, behavior: MachineDetection) -> Self {
+        self.map(|inner| inner.machine_detection(behavior))
+    }
+
+    /// Detects answering machines, and waits up to `beep_timeout` seconds, from 45 to 120, for
+    /// the beep which starts the recording.
+    pub fn advanced_machine_detection(self, behavior: MachineDetection, beep_timeout: u64) -> Self {
+        self.map(|inner| inner.advanced_machine_detection(behavior, beep_timeout))
+    }
+}
+
+impl<C: HttpTransport> CreateCall<C> {
+    /// Sends the request, returning a handle to the call once it has been started.
+    pub fn send(self) -> Result<Call<C>> {
+        let inner = super::block_on(&self.runtime, self.inner.send())?;
+        Ok(Call {
+            inner,
+            runtime: self.runtime,
+        })
+    }
+}
+
+impl<C> Debug for CreateCall<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(CreateCall))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A handle to a call.
+///
+/// This is the blocking equivalent of [`vonage::voice::Call`](../../voice/struct.Call.html).
+pub struct Call<C = DefaultTransport> {
+    inner: inner::Call<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> Call<C> {
+    /// Returns the UUID of the call.
+    pub fn uuid(&self) -> &str {
+        self.inner.uuid()
+    }
+
+    /// Returns the UUID of the conversation the call belongs to.
+    pub fn conversation_uuid(&self) -> &str {
+        self.inner.conversation_uuid()
+    }
+
+    /// Returns the status of the call when it was created.
+    pub fn status(&self) -> CallStatus {
+        self.inner.status()
+    }
+
+    /// Returns the direction of the call.
+    pub fn direction(&self) -> Direction {
+        self.inner.direction()
+    }
+}
+
+impl<C: HttpTransport> Call<C> {
+    /// Hangs up the call.
+    pub fn hangup(&self) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.hangup())
+    }
+
+    /// Mutes the call, so that the other parties can no longer hear it.
+    pub fn mute(&self) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.mute())
+    }
+
+    /// Unmutes a call muted with [`mute()`](#method.mute).
+    pub fn unmute(&self) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.unmute())
+    }
+
+    /// Replaces the NCCO controlling the call with `ncco`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ncco` cannot be serialized to JSON.
+    pub fn transfer_ncco<T: serde::Serialize + ?Sized>(&self, ncco: &T) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.transfer_ncco(ncco))
+    }
+}
+
+impl<C> Clone for Call<C> {
+    fn clone(&self) -> Self {
+        Call {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Debug for Call<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Call))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// Places calls which play a prerecorded message once an answering machine starts recording.
+///
+/// This is the blocking equivalent of
+/// [`vonage::voice::VoicemailDrop`](../../voice/struct.VoicemailDrop.html).
+pub struct VoicemailDrop<C = DefaultTransport> {
+    inner: inner::VoicemailDrop<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> VoicemailDrop<C> {
+    fn map<F>(self, f: F) -> Self
+    where
+        F: FnOnce(inner::VoicemailDrop<C>) -> inner::VoicemailDrop<C>,
+    {
+        VoicemailDrop {
+            inner: f(self.inner),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Controls calls answered by a person with the given NCCO. By default, it streams the same
+    /// message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ncco` cannot be serialized to JSON.
+    pub fn human_ncco<T: serde::Serialize + ?Sized>(self, ncco: &T) -> Self {
+        self.map(|inner| inner.human_ncco(ncco))
+    }
+
+    /// Sets how long to wait for the beep of an answering machine before playing the message
+    /// anyway, from 45 to 120 seconds. Defaults to 45 seconds.
+    pub fn beep_timeout(self, seconds: u64) -> Self {
+        self.map(|inner| inner.beep_timeout(seconds))
+    }
+}
+
+impl<C: HttpTransport> VoicemailDrop<C> {
+    /// Places a call from the virtual number `from` to `to`, which sends status updates to
+    /// `event_url`.
+    pub fn place(
+        &self,
+        to: &PhoneNumber,
+        from: &PhoneNumber,
+        event_url: impl Into<String>,
+    ) -> Result<Call<C>> {
+        let inner = super::block_on(&self.runtime, self.inner.place(to, from, event_url))?;
+        Ok(Call {
+            inner,
+            runtime: self.runtime.clone(),
+        })
+    }
+
+    /// Plays the message to the call which sent `event` if it reports that an answering machine
+    /// is ready to record, returning whether it did.
+    pub fn handle_event(&self, event: &VoiceEvent) -> Result<bool> {
+        super::block_on(&self.runtime, self.inner.handle_event(event))
+    }
+}
+
+impl<C> Clone for VoicemailDrop<C> {
+    fn clone(&self) -> Self {
+        VoicemailDrop {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Debug for VoicemailDrop<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(VoicemailDrop))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A conference, and the participants who were dialed into it.
+///
+/// This is the blocking equivalent of
+/// [`vonage::voice::Conference`](../../voice/struct.Conference.html).
+pub struct Conference<C = DefaultTransport> {
+    inner: inner::Conference<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> Conference<C> {
+    /// Returns the name of the conference.
+    pub fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    /// Returns the action which joins a call to the conference.
+    pub fn ncco(&self) -> Conversation {
+        self.inner.ncco()
+    }
+
+    /// Returns the participants which were dialed out and not removed yet, oldest first.
+    pub fn participants(&self) -> Vec<Call<C>> {
+        self.inner
+            .participants()
+            .into_iter()
+            .map(|inner| Call {
+                inner,
+                runtime: self.runtime.clone(),
+            })
+            .collect()
+    }
+}
+
+impl<C: HttpTransport> Conference<C> {
+    /// Dials out from the virtual number `from` to `to`, and joins the call to the conference
+    /// once it is answered.
+    pub fn add_participant(&self, to: Endpoint, from: &PhoneNumber) -> Result<Call<C>> {
+        let inner = super::block_on(&self.runtime, self.inner.add_participant(to, from))?;
+        Ok(Call {
+            inner,
+            runtime: self.runtime.clone(),
+        })
+    }
+
+    /// Hangs up the call `uuid`, removing it from the conference.
+    pub fn remove_participant(&self, uuid: &str) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.remove_participant(uuid))
+    }
+
+    /// Mutes the call `uuid`, so that the other participants can no longer hear it.
+    pub fn mute(&self, uuid: &str) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.mute(uuid))
+    }
+
+    /// Unmutes a call muted with [`mute()`](#method.mute).
+    pub fn unmute(&self, uuid: &str) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.unmute(uuid))
+    }
+
+    /// Joins the supervisor on the leg `supervisor_leg` to the conference without letting anyone
+    /// hear them.
+    pub fn monitor(&self, supervisor_leg: &str) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.monitor(supervisor_leg))
+    }
+
+    /// Joins the supervisor on the leg `supervisor_leg` to the conference so that only the agent
+    /// on the leg `agent_leg` hears them.
+    pub fn whisper(&self, supervisor_leg: &str, agent_leg: &str) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.whisper(supervisor_leg, agent_leg))
+    }
+
+    /// Joins the supervisor on the leg `supervisor_leg` to the conference so that everyone hears
+    /// them.
+    pub fn barge(&self, supervisor_leg: &str) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.barge(supervisor_leg))
+    }
+
+    /// Starts recording the conference, sending the recording to `event_url` once it stops.
+    pub fn start_recording(&self, event_url: &str) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.start_recording(event_url))
+    }
+
+    /// Stops a recording started with [`start_recording()`](#method.start_recording).
+    pub fn stop_recording(&self) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.stop_recording())
+    }
+}
+
+impl<C> Clone for Conference<C> {
+    fn clone(&self) -> Self {
+        Conference {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Debug for Conference<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Conference))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// An attended transfer of a caller from an agent to a third party.
+///
+/// This is the blocking equivalent of
+/// [`vonage::voice::AttendedTransfer`](../../voice/struct.AttendedTransfer.html).
+pub struct AttendedTransfer<C = DefaultTransport> {
+    inner: inner::AttendedTransfer<C>,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> AttendedTransfer<C> {
+    /// Plays the audio file at `url` to the caller while on hold.
+    pub fn hold_music(self, url: impl Into<String>) -> Self {
+        AttendedTransfer {
+            inner: self.inner.hold_music(url),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Returns the UUID of the leg of the third party, once [consulted](#method.consult).
+    pub fn consult_leg(&self) -> Option<String> {
+        self.inner.consult_leg()
+    }
+}
+
+impl<C: HttpTransport> AttendedTransfer<C> {
+    /// Puts the caller on hold, and connects the agent to a new call from the virtual number
+    /// `from` to `to`.
+    pub fn consult(&self, to: Endpoint, from: &PhoneNumber) -> Result<Call<C>> {
+        let inner = super::block_on(&self.runtime, self.inner.consult(to, from))?;
+        Ok(Call {
+            inner,
+            runtime: self.runtime.clone(),
+        })
+    }
+
+    /// Connects the caller to the third party, and hangs up the agent.
+    pub fn complete(&self) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.complete())
+    }
+
+    /// Connects the caller to both the agent and the third party.
+    pub fn bridge(&self) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.bridge())
+    }
+
+    /// Hangs up the third party, and reconnects the caller to the agent.
+    pub fn cancel(&self) -> Result<()> {
+        super::block_on(&self.runtime, self.inner.cancel())
+    }
+}
+
+impl<C> Clone for AttendedTransfer<C> {
+    fn clone(&self) -> Self {
+        AttendedTransfer {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<C> Debug for AttendedTransfer<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(AttendedTransfer))
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
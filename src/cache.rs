@@ -0,0 +1,398 @@
+//! Client-side caching of responses from slow-changing endpoints.
+
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::{HeaderMap, Method, Request, Response, StatusCode, Uri};
+
+use crate::rt::Instant;
+
+/// A storage backend for cached responses.
+///
+/// The default [`MemoryStore`](./struct.MemoryStore.html) keeps responses in process memory.
+/// Implement this trait to share cached responses between processes instead, e.g. with Redis.
+///
+/// Stores must never return entries whose TTL has elapsed. Cache keys consist of the host, path and
+/// query of a request, without the `api_key`, `api_secret`, `sig` and `timestamp` parameters, so
+/// they never contain credentials. For the same reason, a store should not be shared between
+/// clients of different accounts.
+pub trait CacheStore: Send + Sync {
+    /// Returns the response cached under `key`, if it has not expired yet.
+    fn get(&self, key: &str) -> Option<Response<Bytes>>;
+
+    /// Caches `response` under `key` for the given `ttl`, replacing any previous entry.
+    fn insert(&self, key: String, response: Response<Bytes>, ttl: Duration);
+}
+
+impl<S: CacheStore + ?Sized> CacheStore for Arc<S> {
+    fn get(&self, key: &str) -> Option<Response<Bytes>> {
+        (**self).get(key)
+    }
+
+    fn insert(&self, key: String, response: Response<Bytes>, ttl: Duration) {
+        (**self).insert(key, response, ttl)
+    }
+}
+
+/// A [`CacheStore`](./trait.CacheStore.html) which keeps responses in process memory.
+///
+/// Expired entries are evicted whenever a new response is inserted.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    expires_at: Instant,
+}
+
+impl MemoryStore {
+    /// Creates a new, empty `MemoryStore`.
+    pub fn new() -> Self {
+        MemoryStore::default()
+    }
+
+    /// Removes all cached responses.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+impl CacheStore for MemoryStore {
+    fn get(&self, key: &str) -> Option<Response<Bytes>> {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(key).filter(|e| e.expires_at > Instant::now())?;
+
+        let mut response = Response::new(entry.body.clone());
+        *response.status_mut() = entry.status;
+        *response.headers_mut() = entry.headers.clone();
+        Some(response)
+    }
+
+    fn insert(&self, key: String, response: Response<Bytes>, ttl: Duration) {
+        let now = Instant::now();
+        let (parts, body) = response.into_parts();
+        let entry = Entry {
+            status: parts.status,
+            headers: parts.headers,
+            body,
+            expires_at: now + ttl,
+        };
+
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, e| e.expires_at > now);
+        entries.insert(key, entry);
+    }
+}
+
+/// A TTL-based cache for `GET` requests to slow-changing endpoints.
+///
+/// Successful responses from matching endpoints are stored and replayed until their TTL elapses,
+/// so that dashboards polling e.g. the account balance don't exhaust their API quota. Cached
+/// responses skip rate limits, circuit breakers, interceptors and metrics entirely, since no
+/// request is actually sent. Requests with any other method are never cached, and nothing is
+/// invalidated when a resource is modified; use a short TTL for data which must stay fresh.
+///
+/// By default, the following endpoints are cached:
+///
+/// Endpoint                         | TTL
+/// ---------------------------------|-----------
+/// `/account/get-balance`           | 30 seconds
+/// `/account/get-pricing/`          | 1 hour
+/// `/account/get-full-pricing/`     | 1 hour
+/// `/account/get-prefix-pricing/`   | 1 hour
+/// `/account/numbers`               | 5 minutes
+/// `/v2/applications`               | 5 minutes
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use vonage::{Client, ResponseCache};
+///
+/// let cache = ResponseCache::new().ttl("/account/get-balance", Duration::from_secs(5));
+///
+/// let client = Client::builder()
+///     .api_key("<api_key>", "<api_secret>")
+///     .response_cache(cache)
+///     .build();
+/// ```
+#[derive(Clone)]
+pub struct ResponseCache {
+    rules: Vec<(String, Duration)>,
+    store: Arc<dyn CacheStore>,
+}
+
+impl ResponseCache {
+    /// Creates a new `ResponseCache` with the default TTLs, backed by a
+    /// [`MemoryStore`](./struct.MemoryStore.html).
+    pub fn new() -> Self {
+        const MINUTE: u64 = 60;
+        const HOUR: u64 = 60 * MINUTE;
+
+        let rules = vec![
+            ("/account/get-balance", 30),
+            ("/account/get-pricing/", HOUR),
+            ("/account/get-full-pricing/", HOUR),
+            ("/account/get-prefix-pricing/", HOUR),
+            ("/account/numbers", 5 * MINUTE),
+            ("/v2/applications", 5 * MINUTE),
+        ];
+
+        ResponseCache {
+            rules: rules
+                .into_iter()
+                .map(|(prefix, secs)| (prefix.to_owned(), Duration::from_secs(secs)))
+                .collect(),
+            store: Arc::new(MemoryStore::new()),
+        }
+    }
+
+    /// Caches responses from endpoints whose path starts with `path_prefix` for `ttl`.
+    ///
+    /// Calling this method again for the same prefix replaces its TTL, and a zero `ttl` disables
+    /// caching for it. When several prefixes match a path, the longest one wins.
+    pub fn ttl(mut self, path_prefix: impl Into<String>, ttl: Duration) -> Self {
+        let path_prefix = path_prefix.into();
+        self.rules.retain(|(prefix, _)| *prefix != path_prefix);
+        self.rules.push((path_prefix, ttl));
+        self
+    }
+
+    /// Stores cached responses in `store` instead of process memory.
+    pub fn store<S: CacheStore + 'static>(mut self, store: S) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Returns the cache key and TTL for `request`, if its response may be cached.
+    pub(crate) fn policy<B>(&self, request: &Request<B>) -> Option<(String, Duration)> {
+        if request.method() != Method::GET {
+            return None;
+        }
+
+        let path = request.uri().path();
+        let (_, ttl) = self
+            .rules
+            .iter()
+            .filter(|(prefix, _)| path.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())?;
+
+        match *ttl {
+            ttl if ttl > Duration::from_secs(0) => Some((cache_key(request.uri())?, ttl)),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<Response<Bytes>> {
+        self.store.get(key)
+    }
+
+    /// Stores a copy of `response` if it was successful.
+    pub(crate) fn insert(&self, key: String, ttl: Duration, response: &Response<Bytes>) {
+        if response.status() == StatusCode::OK {
+            let mut copy = Response::new(response.body().clone());
+            *copy.status_mut() = response.status();
+            *copy.headers_mut() = response.headers().clone();
+            self.store.insert(key, copy, ttl);
+        }
+    }
+}
+
+/// Returns the host, path and query of `uri` without credentials, nor the signature parameters
+/// which change with every request, or `None` if the query is malformed.
+fn cache_key(uri: &Uri) -> Option<String> {
+    const EXCLUDED: &[&str] = &["api_key", "api_secret", "sig", "timestamp"];
+
+    let query = uri.query().unwrap_or_default();
+    let params: Vec<(String, String)> = serde_urlencoded::from_str(query).ok()?;
+    let params: Vec<_> = params
+        .into_iter()
+        .filter(|(name, _)| !EXCLUDED.contains(&name.as_str()))
+        .collect();
+
+    let mut key = uri.host().unwrap_or_default().to_ascii_lowercase();
+    if let Some(port) = uri.port_u16() {
+        key.push_str(&format!(":{}", port));
+    }
+    key.push_str(uri.path());
+
+    match serde_urlencoded::to_string(params).ok()? {
+        query if query.is_empty() => Some(key),
+        query => Some(format!("{}?{}", key, query)),
+    }
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        ResponseCache::new()
+    }
+}
+
+impl Debug for ResponseCache {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(ResponseCache))
+            .field("rules", &self.rules)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use http_body_util::Full;
+    use tower::service_fn;
+
+    use crate::Client;
+
+    #[test]
+    fn matches_longest_prefix() {
+        let cache = ResponseCache::new()
+            .ttl("/account/", Duration::from_secs(1))
+            .ttl("/account/numbers", Duration::from_secs(0));
+
+        let get = |uri: &str| Request::get(uri).body(()).unwrap();
+        let ttl = |uri: &str| cache.policy(&get(uri)).map(|(_, ttl)| ttl);
+
+        assert_eq!(ttl("/account/get-balance"), Some(Duration::from_secs(30)));
+        assert_eq!(ttl("/account/settings"), Some(Duration::from_secs(1)));
+        assert_eq!(ttl("/account/numbers"), None);
+        assert_eq!(ttl("/verify/json"), None);
+
+        let post = Request::post("/account/get-balance").body(()).unwrap();
+        assert_eq!(cache.policy(&post), None);
+    }
+
+    #[tokio::test]
+    async fn replays_successful_responses() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let service = service_fn(move |request: Request<Full<Bytes>>| {
+            let call = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                let status = match request.uri().path() {
+                    "/account/get-balance" => StatusCode::OK,
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                let body = Full::new(Bytes::from(call.to_string()));
+                let response = Response::builder().status(status).body(body).unwrap();
+                Ok::<_, Infallible>(response)
+            }
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .response_cache(ResponseCache::new().ttl("/v2/applications", Duration::from_secs(60)))
+            .build()
+            .unwrap();
+
+        for _ in 0..3 {
+            let response = client
+                .request_raw(Method::GET, "/account/get-balance", "")
+                .await
+                .unwrap();
+            assert_eq!(response.body(), "0");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let response = client
+            .request_raw(Method::GET, "/account/get-balance?autoReload=true", "")
+            .await
+            .unwrap();
+        assert_eq!(response.body(), "1");
+
+        for expected in ["2", "3"].iter() {
+            let response = client
+                .request_raw(Method::GET, "/v2/applications", "")
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+            assert_eq!(response.body(), expected);
+        }
+    }
+
+    #[derive(Default)]
+    struct KeyLog(Mutex<Vec<String>>, MemoryStore);
+
+    impl CacheStore for KeyLog {
+        fn get(&self, key: &str) -> Option<Response<Bytes>> {
+            self.1.get(key)
+        }
+
+        fn insert(&self, key: String, response: Response<Bytes>, ttl: Duration) {
+            self.0.lock().unwrap().push(key.clone());
+            self.1.insert(key, response, ttl);
+        }
+    }
+
+    #[tokio::test]
+    async fn keys_exclude_credentials_and_signatures() {
+        use futures::TryStreamExt;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let service = service_fn(move |request: Request<Full<Bytes>>| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            let body = match request.uri().path() {
+                "/account/numbers" => r#"{"count":0,"numbers":[]}"#,
+                _ => "{}",
+            };
+            async move { Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(body)))) }
+        });
+
+        let store = Arc::new(KeyLog::default());
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .sms_signature(crate::SignatureSecret::new("signature-secret"))
+            .response_cache(ResponseCache::new().store(store.clone()))
+            .build()
+            .unwrap();
+
+        for _ in 0..2 {
+            client
+                .request_raw(Method::GET, "/account/get-balance", "")
+                .await
+                .unwrap();
+            let numbers: Vec<_> = client
+                .numbers()
+                .list()
+                .into_stream()
+                .try_collect()
+                .await
+                .unwrap();
+            assert!(numbers.is_empty());
+            client
+                .request_raw(
+                    Method::GET,
+                    "https://rest.nexmo.com/account/get-balance",
+                    "",
+                )
+                .await
+                .unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let keys = store.0.lock().unwrap();
+        assert_eq!(keys.len(), 3);
+        assert_eq!(keys[0], "api.nexmo.com/account/get-balance");
+        assert!(keys[1].starts_with("rest.nexmo.com/account/numbers?"));
+        assert_eq!(keys[2], "rest.nexmo.com/account/get-balance");
+        for key in keys.iter() {
+            for param in &["key", "secret", "sig=", "timestamp="] {
+                assert!(!key.contains(param), "{} leaked into {}", param, key);
+            }
+        }
+    }
+}
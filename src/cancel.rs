@@ -0,0 +1,127 @@
+//! Cooperative cancellation of in-flight requests.
+
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use futures::channel::oneshot;
+use futures::future::{self, Either, FutureExt, Shared};
+
+use crate::{Error, Result};
+
+/// A token which aborts in-flight requests when cancelled.
+///
+/// Tokens are cheap to clone, and all clones share the same state. A token can be registered with
+/// [`ClientBuilder::cancellation_token()`] to abort every request made by a client, which is
+/// useful for graceful shutdown. It can also guard an arbitrary sequence of calls, such as a
+/// polling loop, with [`run()`](#method.run).
+///
+/// Once cancelled, a token stays cancelled. Requests started afterwards fail immediately.
+///
+/// [`ClientBuilder::cancellation_token()`]: ./struct.ClientBuilder.html#method.cancellation_token
+///
+/// # Example
+///
+/// ```no_run
+/// use vonage::{CancellationToken, Client};
+///
+/// # async fn run() -> vonage::Result<()> {
+/// let shutdown = CancellationToken::new();
+/// let client = Client::builder()
+///     .api_key("<api_key>", "<api_secret>")
+///     .cancellation_token(shutdown.clone())
+///     .build()?;
+///
+/// // Somewhere in the graceful shutdown logic:
+/// shutdown.cancel();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CancellationToken {
+    sender: Arc<Mutex<Option<oneshot::Sender<()>>>>,
+    cancelled: Shared<oneshot::Receiver<()>>,
+}
+
+impl CancellationToken {
+    /// Creates a new token which has not been cancelled yet.
+    pub fn new() -> Self {
+        let (sender, receiver) = oneshot::channel();
+        CancellationToken {
+            sender: Arc::new(Mutex::new(Some(sender))),
+            cancelled: receiver.shared(),
+        }
+    }
+
+    /// Cancels this token, aborting all requests guarded by it.
+    pub fn cancel(&self) {
+        // Dropping the sender resolves every clone of the shared receiver.
+        self.sender.lock().unwrap().take();
+    }
+
+    /// Returns whether this token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.sender.lock().unwrap().is_none()
+    }
+
+    /// Runs the given future to completion unless this token is cancelled first.
+    ///
+    /// Returns an error of kind [`ErrorKind::Cancelled`] if the token was cancelled before `fut`
+    /// completed, in which case `fut` is dropped.
+    ///
+    /// [`ErrorKind::Cancelled`]: ./enum.ErrorKind.html#variant.Cancelled
+    pub async fn run<F, T>(&self, fut: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        if self.is_cancelled() {
+            return Err(Error::new_cancelled());
+        }
+
+        futures::pin_mut!(fut);
+        match future::select(fut, self.cancelled.clone()).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => Err(Error::new_cancelled()),
+        }
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        CancellationToken::new()
+    }
+}
+
+impl Debug for CancellationToken {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(CancellationToken))
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::future::pending;
+
+    use crate::ErrorKind;
+
+    #[tokio::test]
+    async fn aborts_pending_futures() {
+        let token = CancellationToken::new();
+        assert_eq!(token.run(async { Ok(1) }).await.unwrap(), 1);
+
+        let guarded = token.clone();
+        let task = tokio::spawn(async move { guarded.run(pending::<Result<()>>()).await });
+        token.cancel();
+
+        let error = task.await.unwrap().unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Cancelled));
+        assert!(token.is_cancelled());
+
+        let error = token.run(async { Ok(()) }).await.unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Cancelled));
+    }
+}
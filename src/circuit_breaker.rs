@@ -0,0 +1,206 @@
+//! Fail-fast protection against repeatedly failing endpoints.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::anyhow;
+
+use crate::rt::Instant;
+use crate::{Error, ErrorKind, Result};
+
+/// A circuit breaker policy applied to every Vonage endpoint individually.
+///
+/// When an endpoint fails `failure_threshold` times in a row, either with a `5xx` status code or
+/// an HTTP transport error, the circuit "trips" and further requests to that endpoint immediately
+/// fail with [`ErrorKind::CircuitOpen`] until the `cooldown` period has elapsed. Afterwards, a
+/// single trial request is let through: if it succeeds, the circuit closes again, and if it fails,
+/// another cool-down period begins.
+///
+/// This protects latency-sensitive code paths (e.g. sending a login OTP) from waiting on an
+/// endpoint which is known to be degraded.
+///
+/// [`ErrorKind::CircuitOpen`]: ./enum.ErrorKind.html#variant.CircuitOpen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl CircuitBreaker {
+    /// Creates a new `CircuitBreaker` which trips after `failure_threshold` consecutive failures
+    /// and stays open for `cooldown`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `failure_threshold` is zero.
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        assert!(failure_threshold > 0, "failure threshold must be non-zero");
+        CircuitBreaker {
+            failure_threshold,
+            cooldown,
+        }
+    }
+}
+
+/// Tracks the circuit state of each endpoint that has been called.
+#[derive(Debug, Default)]
+pub(crate) struct Circuits {
+    policy: Option<CircuitBreaker>,
+    endpoints: Mutex<HashMap<String, State>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen { until: Instant },
+}
+
+impl Circuits {
+    pub fn new(policy: Option<CircuitBreaker>) -> Self {
+        Circuits {
+            policy,
+            endpoints: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns `Err` if the circuit for `endpoint` is currently open.
+    pub fn check(&self, endpoint: &str) -> Result<()> {
+        self.check_at(endpoint, Instant::now())
+    }
+
+    /// Records the outcome of a request sent to `endpoint`.
+    pub fn record(&self, endpoint: &str, success: bool) {
+        self.record_at(endpoint, success, Instant::now())
+    }
+
+    fn check_at(&self, endpoint: &str, now: Instant) -> Result<()> {
+        let policy = match self.policy {
+            Some(policy) => policy,
+            None => return Ok(()),
+        };
+
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        let state = match endpoints.get_mut(endpoint) {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+
+        match *state {
+            State::Closed { .. } => Ok(()),
+            State::Open { until } | State::HalfOpen { until } if now >= until => {
+                // Let a single trial request through. If it never reports back (e.g. the future
+                // was dropped), another trial is allowed after the next cool-down period.
+                *state = State::HalfOpen {
+                    until: now + policy.cooldown,
+                };
+                Ok(())
+            }
+            State::Open { until } | State::HalfOpen { until } => Err(Error::with_cause(
+                ErrorKind::CircuitOpen,
+                anyhow!(
+                    "too many failures from `{}`, retry in {:?}",
+                    endpoint,
+                    until - now
+                ),
+            )),
+        }
+    }
+
+    fn record_at(&self, endpoint: &str, success: bool, now: Instant) {
+        let policy = match self.policy {
+            Some(policy) => policy,
+            None => return,
+        };
+
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        if success {
+            endpoints.remove(endpoint);
+            return;
+        }
+
+        let state = endpoints
+            .entry(endpoint.to_owned())
+            .or_insert(State::Closed { failures: 0 });
+
+        *state = match *state {
+            State::Closed { failures } if failures + 1 < policy.failure_threshold => {
+                State::Closed {
+                    failures: failures + 1,
+                }
+            }
+            _ => State::Open {
+                until: now + policy.cooldown,
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENDPOINT: &str = "/verify/json";
+
+    fn circuits() -> Circuits {
+        Circuits::new(Some(CircuitBreaker::new(2, Duration::from_secs(10))))
+    }
+
+    #[test]
+    fn trips_after_consecutive_failures() {
+        let circuits = circuits();
+        let now = Instant::now();
+
+        circuits.record_at(ENDPOINT, false, now);
+        assert!(circuits.check_at(ENDPOINT, now).is_ok());
+
+        circuits.record_at(ENDPOINT, false, now);
+        let err = circuits.check_at(ENDPOINT, now).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::CircuitOpen));
+
+        assert!(circuits.check_at("/verify/check/json", now).is_ok());
+    }
+
+    #[test]
+    fn success_resets_failure_count() {
+        let circuits = circuits();
+        let now = Instant::now();
+
+        circuits.record_at(ENDPOINT, false, now);
+        circuits.record_at(ENDPOINT, true, now);
+        circuits.record_at(ENDPOINT, false, now);
+        assert!(circuits.check_at(ENDPOINT, now).is_ok());
+    }
+
+    #[test]
+    fn allows_single_trial_after_cooldown() {
+        let circuits = circuits();
+        let now = Instant::now();
+        circuits.record_at(ENDPOINT, false, now);
+        circuits.record_at(ENDPOINT, false, now);
+
+        let later = now + Duration::from_secs(10);
+        assert!(circuits.check_at(ENDPOINT, later).is_ok());
+        assert!(circuits.check_at(ENDPOINT, later).is_err());
+
+        circuits.record_at(ENDPOINT, false, later);
+        assert!(circuits.check_at(ENDPOINT, later).is_err());
+
+        let even_later = later + Duration::from_secs(10);
+        assert!(circuits.check_at(ENDPOINT, even_later).is_ok());
+        circuits.record_at(ENDPOINT, true, even_later);
+        assert!(circuits.check_at(ENDPOINT, even_later).is_ok());
+        assert!(circuits.check_at(ENDPOINT, even_later).is_ok());
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let circuits = Circuits::new(None);
+        let now = Instant::now();
+        for _ in 0..10 {
+            circuits.record_at(ENDPOINT, false, now);
+        }
+        assert!(circuits.check_at(ENDPOINT, now).is_ok());
+    }
+}
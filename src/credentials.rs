@@ -0,0 +1,118 @@
+//! Type-level markers tracking which credentials a client was configured with.
+//!
+//! According to the official [authentication guide], each Vonage product supports exactly _one_
+//! authentication method. The credentials passed to a [`ClientBuilder`] are recorded in the type
+//! of the resulting [`Client`], so calling a product which requires credentials that were never
+//! configured is a compile-time error instead of a runtime one:
+//!
+//! ```compile_fail
+//! use vonage::Client;
+//!
+//! let client = Client::builder().jwt("<app_id>", "<private_key>").build().unwrap();
+//! // Verify requires an API key and API secret pair.
+//! let verify = client.verify();
+//! ```
+//!
+//! The markers in this module are only ever used as type parameters and never need to be
+//! constructed.
+//!
+//! [authentication guide]: https://developer.nexmo.com/concepts/guides/authentication
+//! [`ClientBuilder`]: ../struct.ClientBuilder.html
+//! [`Client`]: ../struct.Client.html
+
+mod private {
+    pub trait Sealed {}
+}
+
+use self::private::Sealed;
+
+/// No credentials have been configured yet.
+///
+/// A [`ClientBuilder`](../struct.ClientBuilder.html) in this state cannot be built.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct NoCredentials;
+
+/// Only an API key and API secret pair has been configured.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ApiKeyAuth;
+
+/// Only an application ID and private JWT signing key have been configured.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct JwtAuth;
+
+/// Both an API key and API secret pair and a JWT application ID and private key have been
+/// configured.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FullAuth;
+
+/// A set of credentials which a client can be built with.
+pub trait Credentials: Sealed + Send + Sync + 'static {}
+
+/// A set of credentials which includes an API key and API secret pair.
+///
+/// This is required by the SMS and Verify (2FA) products.
+pub trait HasApiKey: Credentials {}
+
+/// A set of credentials which includes an application ID and private JWT signing key.
+///
+/// This is required by the Voice product.
+pub trait HasJwt: Credentials {}
+
+/// The credentials resulting from adding an API key and API secret pair.
+pub trait AddApiKey: Sealed {
+    /// The resulting set of credentials.
+    type Output: HasApiKey;
+}
+
+/// The credentials resulting from adding an application ID and private JWT signing key.
+pub trait AddJwt: Sealed {
+    /// The resulting set of credentials.
+    type Output: HasJwt;
+}
+
+impl Sealed for NoCredentials {}
+impl Sealed for ApiKeyAuth {}
+impl Sealed for JwtAuth {}
+impl Sealed for FullAuth {}
+
+impl Credentials for ApiKeyAuth {}
+impl Credentials for JwtAuth {}
+impl Credentials for FullAuth {}
+
+impl HasApiKey for ApiKeyAuth {}
+impl HasApiKey for FullAuth {}
+
+impl HasJwt for JwtAuth {}
+impl HasJwt for FullAuth {}
+
+impl AddApiKey for NoCredentials {
+    type Output = ApiKeyAuth;
+}
+
+impl AddApiKey for ApiKeyAuth {
+    type Output = ApiKeyAuth;
+}
+
+impl AddApiKey for JwtAuth {
+    type Output = FullAuth;
+}
+
+impl AddApiKey for FullAuth {
+    type Output = FullAuth;
+}
+
+impl AddJwt for NoCredentials {
+    type Output = JwtAuth;
+}
+
+impl AddJwt for ApiKeyAuth {
+    type Output = FullAuth;
+}
+
+impl AddJwt for JwtAuth {
+    type Output = JwtAuth;
+}
+
+impl AddJwt for FullAuth {
+    type Output = FullAuth;
+}
@@ -0,0 +1,143 @@
+//! Timestamps reported by the Vonage APIs.
+
+use std::borrow::Cow;
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Error as _, Serializer};
+
+/// The format of timestamps returned by the older Vonage REST APIs, which are always in UTC.
+#[cfg(not(feature = "time"))]
+const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// A point in time reported by the Vonage APIs, in UTC.
+///
+/// By default, this is a [`chrono::NaiveDateTime`](https://docs.rs/chrono). Enable the `time`
+/// feature to use a [`time::OffsetDateTime`](https://docs.rs/time) instead.
+#[cfg(not(feature = "time"))]
+pub type Timestamp = chrono::NaiveDateTime;
+
+/// A point in time reported by the Vonage APIs, in UTC.
+#[cfg(feature = "time")]
+pub type Timestamp = time::OffsetDateTime;
+
+/// Parses a `YYYY-MM-DD HH:MM:SS` timestamp in UTC.
+pub(crate) fn parse(s: &str) -> Result<Timestamp, String> {
+    #[cfg(not(feature = "time"))]
+    let result = chrono::NaiveDateTime::parse_from_str(s, FORMAT).map_err(|e| e.to_string());
+
+    #[cfg(feature = "time")]
+    let result = {
+        let format =
+            time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+        time::PrimitiveDateTime::parse(s, format)
+            .map(time::PrimitiveDateTime::assume_utc)
+            .map_err(|e| e.to_string())
+    };
+
+    result
+}
+
+/// Formats `timestamp` as a `YYYY-MM-DD HH:MM:SS` timestamp in UTC.
+pub(crate) fn format(timestamp: &Timestamp) -> Result<String, String> {
+    #[cfg(not(feature = "time"))]
+    let result = Ok(timestamp.format(FORMAT).to_string());
+
+    #[cfg(feature = "time")]
+    let result = {
+        let format =
+            time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+        let utc = timestamp.to_offset(time::UtcOffset::UTC);
+        utc.format(format).map_err(|e| e.to_string())
+    };
+
+    result
+}
+
+/// Serializes a `YYYY-MM-DD HH:MM:SS` timestamp in UTC, for use with `#[serde(with)]`.
+pub(crate) fn serialize<S>(timestamp: &Timestamp, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let s = format(timestamp).map_err(S::Error::custom)?;
+    serializer.serialize_str(&s)
+}
+
+/// Deserializes a `YYYY-MM-DD HH:MM:SS` timestamp in UTC, for use with `#[serde(with)]`.
+pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Timestamp, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = Cow::<'de, str>::deserialize(deserializer)?;
+    parse(&s).map_err(de::Error::custom)
+}
+
+/// Timestamps in the ISO 8601 format used by newer Vonage APIs, e.g. `2020-01-01T12:34:56Z`.
+pub(crate) mod iso8601 {
+    use serde::ser::{Error as _, Serializer};
+
+    use super::Timestamp;
+
+    #[cfg(not(feature = "time"))]
+    const FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+    /// Formats `timestamp` as an ISO 8601 timestamp in UTC.
+    pub(crate) fn format(timestamp: &Timestamp) -> Result<String, String> {
+        #[cfg(not(feature = "time"))]
+        let result = Ok(timestamp.format(FORMAT).to_string());
+
+        #[cfg(feature = "time")]
+        let result = {
+            let format =
+                time::macros::format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]Z");
+            let utc = timestamp.to_offset(time::UtcOffset::UTC);
+            utc.format(format).map_err(|e| e.to_string())
+        };
+
+        result
+    }
+
+    /// Serializes an ISO 8601 timestamp, for use with `#[serde(serialize_with)]`.
+    pub(crate) fn serialize<S>(timestamp: &Timestamp, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let s = format(timestamp).map_err(S::Error::custom)?;
+        serializer.serialize_str(&s)
+    }
+
+    /// Serializes an optional ISO 8601 timestamp, for use with `#[serde(serialize_with)]`.
+    pub(crate) fn serialize_option<S>(
+        timestamp: &Option<Timestamp>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match timestamp {
+            Some(timestamp) => {
+                let s = format(timestamp).map_err(S::Error::custom)?;
+                serializer.serialize_str(&s)
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_api_timestamps() {
+        let timestamp = parse("2020-01-01 12:34:56").unwrap();
+
+        #[cfg(not(feature = "time"))]
+        assert_eq!(timestamp.and_utc().timestamp(), 1_577_882_096);
+        #[cfg(feature = "time")]
+        assert_eq!(timestamp.unix_timestamp(), 1_577_882_096);
+
+        assert_eq!(format(&timestamp).unwrap(), "2020-01-01 12:34:56");
+        assert_eq!(iso8601::format(&timestamp).unwrap(), "2020-01-01T12:34:56Z");
+        assert!(parse("2020-01-01T12:34:56Z").is_err());
+    }
+}
@@ -0,0 +1,249 @@
+//! Streaming downloads of large response bodies, such as call recordings.
+
+use std::fmt::{self, Debug, Formatter};
+
+use bytes::Bytes;
+use futures::io::{AsyncWrite, AsyncWriteExt};
+use futures::TryStreamExt;
+use http::header::CONTENT_LENGTH;
+use http::Request;
+
+use crate::http_client::HttpClient;
+use crate::transport::HttpTransport;
+use crate::{DefaultTransport, Error, ErrorKind, Product, RequestPolicy, Result};
+
+/// The progress of a [`Download`](./struct.Download.html), reported after every chunk.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Progress {
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+impl Progress {
+    /// Returns the number of bytes written so far.
+    #[inline]
+    pub fn downloaded(&self) -> u64 {
+        self.downloaded
+    }
+
+    /// Returns the total size of the download in bytes, if reported by the server.
+    #[inline]
+    pub fn total(&self) -> Option<u64> {
+        self.total
+    }
+}
+
+/// A pending download of a potentially large file.
+///
+/// This is returned from [`Client::download()`](./struct.Client.html#method.download). Nothing
+/// is sent until [`write_to()`](#method.write_to) is called.
+pub struct Download<C = DefaultTransport, P = fn(Progress)> {
+    http_client: HttpClient<C>,
+    request: Result<Request<Bytes>>,
+    on_progress: P,
+}
+
+impl<C> Download<C> {
+    pub(crate) fn new(http_client: HttpClient<C>, request: Result<Request<Bytes>>) -> Self {
+        Download {
+            http_client,
+            request,
+            on_progress: |_| {},
+        }
+    }
+}
+
+impl<C, P: FnMut(Progress)> Download<C, P> {
+    /// Sends the request with the given timeout and retry policy.
+    ///
+    /// The timeout only applies until the response headers are received, so it does not limit
+    /// how long the body may take to download. See [`RequestPolicy`](./struct.RequestPolicy.html)
+    /// for details.
+    pub fn policy(mut self, policy: RequestPolicy) -> Self {
+        self.http_client = self.http_client.with_policy(policy);
+        self
+    }
+
+    /// Calls `f` after every chunk is written, e.g. to render a progress bar.
+    pub fn on_progress<F: FnMut(Progress)>(self, f: F) -> Download<C, F> {
+        Download {
+            http_client: self.http_client,
+            request: self.request,
+            on_progress: f,
+        }
+    }
+}
+
+impl<C: HttpTransport, P: FnMut(Progress)> Download<C, P> {
+    /// Streams the response body into `writer` chunk-by-chunk, returning the number of bytes
+    /// written.
+    ///
+    /// Returns `Err` if the server responds with a non-successful status code, the connection
+    /// fails midway, or `writer` returns an I/O error. In the latter two cases, `writer` may have
+    /// received part of the body already.
+    pub async fn write_to<W>(self, writer: &mut W) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let Download {
+            http_client,
+            request,
+            mut on_progress,
+        } = self;
+
+        let request = request?;
+        let method = request.method().clone();
+        let endpoint = request.uri().path().to_owned();
+
+        let response = http_client.send_streaming(Product::Other, request).await?;
+        let total = response
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok()?.parse().ok());
+
+        let mut body = response.into_body();
+        let copy = async {
+            let mut downloaded = 0;
+            while let Some(chunk) = body.try_next().await.map_err(Error::new_http)? {
+                writer.write_all(&chunk).await.map_err(new_io)?;
+                downloaded += chunk.len() as u64;
+                on_progress(Progress { downloaded, total });
+            }
+
+            writer.flush().await.map_err(new_io)?;
+            Ok(downloaded)
+        };
+
+        let result = http_client.cancellable(copy).await;
+        result.map_err(|e| http_client.report(Product::Other, e.with_request(&method, &endpoint)))
+    }
+}
+
+impl<C, P> Debug for Download<C, P> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Download))
+            .field("http_client", &self.http_client)
+            .field("request", &self.request)
+            .finish()
+    }
+}
+
+fn new_io(e: std::io::Error) -> Error {
+    Error::with_cause(ErrorKind::Io, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use futures::stream;
+    use http::{Response, StatusCode};
+    use http_body::Frame;
+    use http_body_util::{Full, StreamBody};
+    use tower::service_fn;
+
+    use crate::Client;
+
+    #[tokio::test]
+    async fn streams_body_into_writer() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.uri().path(), "/v1/files/abc");
+            assert_eq!(request.headers()["accept-encoding"], "identity");
+
+            let chunks = vec!["hello", ", ", "world"]
+                .into_iter()
+                .map(|chunk| Ok::<_, Infallible>(Frame::data(Bytes::from(chunk))));
+            let response = Response::builder()
+                .header(CONTENT_LENGTH, "12")
+                .body(StreamBody::new(stream::iter(chunks)))
+                .unwrap();
+            Ok::<_, Infallible>(response)
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let mut progress = Vec::new();
+        let mut buffer = Vec::new();
+        let written = client
+            .download("/v1/files/abc")
+            .on_progress(|p| progress.push((p.downloaded(), p.total())))
+            .write_to(&mut buffer)
+            .await
+            .unwrap();
+
+        assert_eq!(written, 12);
+        assert_eq!(buffer, b"hello, world");
+        assert_eq!(progress, [(5, Some(12)), (7, Some(12)), (12, Some(12))]);
+    }
+
+    #[tokio::test]
+    async fn reports_unsuccessful_status_codes() {
+        let service = service_fn(|_: Request<Full<Bytes>>| async move {
+            let response = Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Full::new(Bytes::from_static(b"no such file")))
+                .unwrap();
+            Ok::<_, Infallible>(response)
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let mut buffer = Vec::new();
+        let error = client
+            .download("/v1/files/abc")
+            .write_to(&mut buffer)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error.kind(),
+            ErrorKind::Status(StatusCode::NOT_FOUND)
+        ));
+        assert_eq!(error.body(), Some("no such file"));
+        assert!(buffer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_urls_outside_vonage() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let sent = Arc::new(AtomicUsize::new(0));
+        let service = service_fn({
+            let sent = sent.clone();
+            move |_: Request<Full<Bytes>>| {
+                sent.fetch_add(1, Ordering::SeqCst);
+                async move { Ok::<_, Infallible>(Response::new(Full::new(Bytes::new()))) }
+            }
+        });
+
+        let client = Client::from_service(service)
+            .jwt("app_id", crate::auth::TEST_PRIVATE_KEY)
+            .build()
+            .unwrap();
+
+        for url in &[
+            "https://attacker.example.com/recording.mp3",
+            "http://api.nexmo.com/v1/files/abc",
+        ] {
+            let mut buffer = Vec::new();
+            let error = client
+                .download(url)
+                .write_to(&mut buffer)
+                .await
+                .unwrap_err();
+            let invalid = error.as_invalid_request().unwrap().invalid_parameters();
+            assert_eq!(invalid[0].name(), "path", "{}", url);
+        }
+
+        assert_eq!(sent.load(Ordering::SeqCst), 0);
+    }
+}
@@ -1,17 +1,87 @@
 //! Error types used throughout the library.
 
+use std::io;
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::{Method, Response};
+
+use crate::problem::Problem;
+use crate::validation::InvalidRequest;
+use crate::verify::{Undeliverable, VerifyError};
+
+/// Maximum number of bytes of a response body kept in an error.
+const MAX_BODY_SNIPPET: usize = 1024;
+
 /// A list specifying general categories of Vonage API errors.
+///
+/// New variants may be added as more products are supported, so matches against this enum must
+/// include a wildcard arm.
 #[derive(Clone, Copy, Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum ErrorKind {
     /// An authentication error occurred.
     #[error("authentication error")]
     Auth,
+    /// The request was aborted by a [`CancellationToken`](./struct.CancellationToken.html).
+    #[error("request was cancelled")]
+    Cancelled,
+    /// The endpoint failed too many times in a row and is temporarily being avoided.
+    #[error("circuit breaker is open")]
+    CircuitOpen,
+    /// The response body could not be decoded, e.g. because its schema changed unexpectedly.
+    #[error("error decoding response body")]
+    Decode,
+    /// A string could not be parsed as a [`NetworkCode`](./phone/struct.NetworkCode.html).
+    #[error("invalid network code")]
+    InvalidNetworkCode,
+    /// A phone number could not be parsed or is not valid.
+    #[error("invalid phone number")]
+    InvalidPhoneNumber,
+    /// A request was rejected before being sent, because some of its parameters are invalid.
+    ///
+    /// Details can be obtained with [`Error::as_invalid_request()`].
+    ///
+    /// [`Error::as_invalid_request()`]: ./struct.Error.html#method.as_invalid_request
+    #[error("invalid request")]
+    InvalidRequest,
+    /// A string could not be parsed as a request ID.
+    #[error("invalid request ID")]
+    InvalidRequestId,
+    /// A webhook payload could not be parsed or was not recognized.
+    #[error("invalid webhook payload")]
+    InvalidWebhook,
     /// An HTTP error occurred.
     #[error("HTTP error")]
     Http,
+    /// A downloaded response body could not be written to its destination.
+    #[error("error writing download")]
+    Io,
+    /// Vonage rejected the request with `429 Too Many Requests`.
+    ///
+    /// The delay requested by the server, if any, is available from
+    /// [`Error::retry_after()`](./struct.Error.html#method.retry_after).
+    #[error("rate limited by the server")]
+    RateLimited,
     /// Received an unexpected HTTP status code.
     #[error("received unexpected status code: {0}")]
-    Status(hyper::StatusCode),
+    Status(http::StatusCode),
+    /// The client is in [test mode](./struct.ClientBuilder.html#method.test_mode), and the
+    /// request was refused because its endpoint has no sandbox equivalent.
+    #[error("endpoint is not available in test mode")]
+    TestMode,
+    /// The request timed out before a response was received.
+    #[error("request timed out")]
+    Timeout,
+    /// A [`Lookup`](./verify/enum.Lookup.html) found that the destination of a verify request
+    /// cannot receive the verification, so it was not sent.
+    ///
+    /// The reason can be obtained with [`Error::as_undeliverable()`].
+    ///
+    /// [`Error::as_undeliverable()`]: ./struct.Error.html#method.as_undeliverable
+    #[error("destination cannot receive the verification")]
+    Undeliverable,
+    /// The request parameters could not be URL-encoded.
     #[error("error URL-encoding request body")]
     UrlEncode,
     /// An error occurred in the [Verify (2FA)](https://developer.nexmo.com/api/verify) API.
@@ -31,18 +101,96 @@ impl ErrorKind {
 /// The error type for Vonage API operations.
 ///
 /// It is used with the [`ErrorKind`](./enum.ErrorKind.html) enum.
+///
+/// Errors returned by API calls also record which request failed, and the identifiers assigned to
+/// it by Vonage, if any. Quoting these when contacting Vonage support helps them locate the
+/// request in their logs.
 #[derive(Debug, thiserror::Error)]
 #[error("{kind}")]
 pub struct Error {
     kind: ErrorKind,
+    body: Option<String>,
+    context: Option<Box<Context>>,
     source: Option<anyhow::Error>,
 }
 
+/// Details of the request which caused an error.
+#[derive(Debug, Default)]
+struct Context {
+    method: Option<Method>,
+    endpoint: Option<String>,
+    request_id: Option<String>,
+    retry_after: Option<Duration>,
+    trace_id: Option<String>,
+    step: Option<String>,
+}
+
 impl Error {
     pub(crate) fn new_auth(src: impl Into<anyhow::Error>) -> Self {
         Error::with_cause(ErrorKind::Auth, src)
     }
 
+    pub(crate) fn new_cancelled() -> Self {
+        Error {
+            kind: ErrorKind::Cancelled,
+            body: None,
+            context: None,
+            source: None,
+        }
+    }
+
+    /// Creates an error for a response body which failed to deserialize, keeping a bounded
+    /// snippet of the body and the path to the offending field for diagnostics.
+    pub(crate) fn new_decode(
+        body: &[u8],
+        src: serde_path_to_error::Error<serde_json::Error>,
+    ) -> Self {
+        let mut error = Error::with_cause(ErrorKind::Decode, src);
+        error.body = body_snippet(body);
+        error
+    }
+
+    pub(crate) fn new_http(src: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        let root: &(dyn std::error::Error + 'static) = &*src;
+        let timed_out = std::iter::successors(Some(root), |e| e.source()).any(|e| {
+            e.downcast_ref::<io::Error>()
+                .is_some_and(|e| e.kind() == io::ErrorKind::TimedOut)
+        });
+
+        let kind = if timed_out {
+            ErrorKind::Timeout
+        } else {
+            ErrorKind::Http
+        };
+        Error::with_cause(kind, anyhow::Error::from_boxed(src))
+    }
+
+    /// Creates an error for a response with an unexpected status code, keeping a bounded snippet
+    /// of its body for diagnostics.
+    pub(crate) fn new_status(response: &Response<Bytes>) -> Self {
+        let mut error = match response.status() {
+            http::StatusCode::TOO_MANY_REQUESTS => Error {
+                kind: ErrorKind::RateLimited,
+                body: None,
+                context: None,
+                source: None,
+            },
+            status => Error::from(status),
+        };
+        if let Some(delay) = crate::response::retry_after(response.headers()) {
+            let context = error.context.get_or_insert_with(Box::default);
+            context.retry_after = Some(delay);
+        }
+        if let Some(snippet) = body_snippet(response.body()) {
+            error.source = Some(match Problem::from_response(response) {
+                Some(problem) => problem.into(),
+                None => anyhow::Error::msg(format!("response body: {}", snippet)),
+            });
+            error.body = Some(snippet);
+        }
+        error
+    }
+
     pub(crate) fn new_verify(src: impl Into<anyhow::Error>) -> Self {
         Error::with_cause(
             ErrorKind::Verify {
@@ -64,6 +212,8 @@ impl Error {
     pub(crate) fn with_cause(kind: ErrorKind, src: impl Into<anyhow::Error>) -> Self {
         Error {
             kind,
+            body: None,
+            context: None,
             source: Some(src.into()),
         }
     }
@@ -73,18 +223,154 @@ impl Error {
     pub fn kind(&self) -> ErrorKind {
         self.kind
     }
+
+    /// Returns whether the failed operation may succeed if it is retried after a short delay.
+    ///
+    /// This is the case for connection errors, `429 Too Many Requests` and `5xx` status codes, and
+    /// API errors which indicate throttling or a temporary failure on Vonage's side. Errors caused
+    /// by invalid input, bad credentials, cancellation, or an open circuit breaker are not
+    /// retryable.
+    ///
+    /// Note that a request which failed with a connection error may still have reached Vonage, so
+    /// retrying requests which are not idempotent, such as starting a verification, can cause them
+    /// to be processed twice.
+    pub fn is_retryable(&self) -> bool {
+        match self.kind {
+            ErrorKind::Http | ErrorKind::RateLimited | ErrorKind::Timeout => true,
+            ErrorKind::Status(code) => {
+                code == http::StatusCode::TOO_MANY_REQUESTS || code.is_server_error()
+            }
+            ErrorKind::Verify { .. } => self.as_verify().is_some_and(VerifyError::is_retryable),
+            ErrorKind::Auth
+            | ErrorKind::Cancelled
+            | ErrorKind::CircuitOpen
+            | ErrorKind::Decode
+            | ErrorKind::InvalidNetworkCode
+            | ErrorKind::InvalidPhoneNumber
+            | ErrorKind::InvalidRequest
+            | ErrorKind::InvalidRequestId
+            | ErrorKind::InvalidWebhook
+            | ErrorKind::Io
+            | ErrorKind::TestMode
+            | ErrorKind::Undeliverable
+            | ErrorKind::UrlEncode => false,
+        }
+    }
+
+    /// Returns the status code and error text reported by the
+    /// [Verify (2FA)](https://developer.nexmo.com/api/verify) API, if this error originated there.
+    pub fn as_verify(&self) -> Option<&VerifyError> {
+        self.source.as_ref()?.downcast_ref()
+    }
+
+    /// Returns why a verify request was rejected by a [`Lookup`](./verify/enum.Lookup.html), if
+    /// this error is of kind [`ErrorKind::Undeliverable`](./enum.ErrorKind.html#variant.Undeliverable).
+    pub fn as_undeliverable(&self) -> Option<&Undeliverable> {
+        self.source.as_ref()?.downcast_ref()
+    }
+
+    /// Returns the HTTP method of the request which failed, if known.
+    pub fn method(&self) -> Option<&Method> {
+        self.context.as_ref()?.method.as_ref()
+    }
+
+    /// Returns the path of the endpoint which the failed request was sent to, if known.
+    ///
+    /// Query parameters are not included, since they may contain credentials.
+    pub fn endpoint(&self) -> Option<&str> {
+        self.context.as_ref()?.endpoint.as_deref()
+    }
+
+    /// Returns the ID of the API request returned by Vonage alongside the error, if any.
+    pub fn request_id(&self) -> Option<&str> {
+        self.context.as_ref()?.request_id.as_deref()
+    }
+
+    /// Returns how long Vonage asked the client to wait before retrying, if specified.
+    ///
+    /// This is parsed from the `Retry-After` header of the response, which usually accompanies
+    /// errors of kind [`ErrorKind::RateLimited`](./enum.ErrorKind.html#variant.RateLimited).
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.context.as_ref()?.retry_after
+    }
+
+    /// Returns the trace ID which Vonage assigned to the failed request, if any.
+    pub fn trace_id(&self) -> Option<&str> {
+        self.context.as_ref()?.trace_id.as_deref()
+    }
+
+    /// Returns the name of the [`Pipeline`](./pipeline/struct.Pipeline.html) step which failed, if
+    /// this error was returned from one.
+    pub fn step(&self) -> Option<&str> {
+        self.context.as_ref()?.step.as_deref()
+    }
+
+    /// Records the request which caused this error, unless already known.
+    pub(crate) fn with_request(mut self, method: &Method, endpoint: &str) -> Self {
+        let context = self.context.get_or_insert_with(Box::default);
+        context.method.get_or_insert_with(|| method.clone());
+        context.endpoint.get_or_insert_with(|| endpoint.to_owned());
+        self
+    }
+
+    pub(crate) fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        let context = self.context.get_or_insert_with(Box::default);
+        context.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Records the pipeline step which caused this error, unless already known.
+    pub(crate) fn with_step(mut self, step: &str) -> Self {
+        let context = self.context.get_or_insert_with(Box::default);
+        context.step.get_or_insert_with(|| step.to_owned());
+        self
+    }
+
+    pub(crate) fn with_trace_id(mut self, trace_id: impl Into<String>) -> Self {
+        let context = self.context.get_or_insert_with(Box::default);
+        context.trace_id = Some(trace_id.into());
+        self
+    }
+
+    /// Returns the parameters which failed client-side validation, if this error is of kind
+    /// [`ErrorKind::InvalidRequest`](./enum.ErrorKind.html#variant.InvalidRequest).
+    pub fn as_invalid_request(&self) -> Option<&InvalidRequest> {
+        self.source.as_ref()?.downcast_ref()
+    }
+
+    /// Returns the [RFC 7807](https://tools.ietf.org/html/rfc7807) problem details reported by the
+    /// API, if the response was an `application/problem+json` document.
+    pub fn as_problem(&self) -> Option<&Problem> {
+        self.source.as_ref()?.downcast_ref()
+    }
+
+    /// Returns the beginning of the response body, if this error was caused by a response with
+    /// an unexpected status code or a body which could not be decoded.
+    ///
+    /// This usually contains the actual explanation for the failure, such as a JSON error message
+    /// or an HTML page returned by a proxy. Long bodies are truncated to about 1 KiB.
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
 }
 
-impl From<hyper::Error> for Error {
-    fn from(e: hyper::Error) -> Self {
-        Error::with_cause(ErrorKind::Http, e)
+/// Returns the beginning of `body` as text, or `None` if it is empty.
+fn body_snippet(body: &[u8]) -> Option<String> {
+    let snippet = String::from_utf8_lossy(&body[..body.len().min(MAX_BODY_SNIPPET)]);
+    let mut snippet = snippet.trim_end_matches('\u{FFFD}').trim().to_owned();
+    if body.len() > MAX_BODY_SNIPPET {
+        snippet.push_str("...");
     }
+
+    Some(snippet).filter(|s| !s.is_empty())
 }
 
-impl From<hyper::StatusCode> for Error {
-    fn from(code: hyper::StatusCode) -> Self {
+impl From<http::StatusCode> for Error {
+    fn from(code: http::StatusCode) -> Self {
         Error {
             kind: ErrorKind::Status(code),
+            body: None,
+            context: None,
             source: None,
         }
     }
@@ -101,3 +387,75 @@ impl From<serde_urlencoded::ser::Error> for Error {
         Error::with_cause(ErrorKind::UrlEncode, e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http::StatusCode;
+
+    #[test]
+    fn keeps_bounded_body_snippet() {
+        let response = Response::builder()
+            .status(StatusCode::BAD_GATEWAY)
+            .body(Bytes::from("<html>Bad Gateway</html>\n"))
+            .unwrap();
+
+        let error = Error::new_status(&response);
+        assert!(matches!(
+            error.kind(),
+            ErrorKind::Status(StatusCode::BAD_GATEWAY)
+        ));
+        assert_eq!(error.body(), Some("<html>Bad Gateway</html>"));
+        let source = std::error::Error::source(&error).unwrap();
+        assert_eq!(
+            source.to_string(),
+            "response body: <html>Bad Gateway</html>"
+        );
+
+        let response = Response::new(Bytes::from("é".repeat(MAX_BODY_SNIPPET)));
+        let body = Error::new_status(&response).body().unwrap().to_owned();
+        assert!(body.ends_with("é..."));
+        assert!(body.len() <= MAX_BODY_SNIPPET + 3);
+
+        assert_eq!(Error::new_status(&Response::new(Bytes::new())).body(), None);
+    }
+
+    #[test]
+    fn classifies_rate_limits_and_timeouts() {
+        let response = Response::builder()
+            .status(StatusCode::TOO_MANY_REQUESTS)
+            .body(Bytes::new())
+            .unwrap();
+        let error = Error::new_status(&response);
+        assert!(matches!(error.kind(), ErrorKind::RateLimited));
+        assert!(error.is_retryable());
+        assert_eq!(error.retry_after(), None);
+
+        let response = Response::builder()
+            .status(StatusCode::TOO_MANY_REQUESTS)
+            .header("Retry-After", "3")
+            .body(Bytes::new())
+            .unwrap();
+        let error = Error::new_status(&response);
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(3)));
+
+        let timeout = io::Error::new(io::ErrorKind::TimedOut, "connect timed out");
+        let error = Error::new_http(Box::new(timeout));
+        assert!(matches!(error.kind(), ErrorKind::Timeout));
+
+        let error = Error::new_http("connection reset".into());
+        assert!(matches!(error.kind(), ErrorKind::Http));
+    }
+
+    #[test]
+    fn classifies_retryable_errors() {
+        assert!(Error::from(StatusCode::TOO_MANY_REQUESTS).is_retryable());
+        assert!(Error::from(StatusCode::SERVICE_UNAVAILABLE).is_retryable());
+        assert!(Error::new_http("connection reset".into()).is_retryable());
+
+        assert!(!Error::from(StatusCode::UNAUTHORIZED).is_retryable());
+        assert!(!Error::new_cancelled().is_retryable());
+        assert!(!Error::new_verify(anyhow::Error::msg("unrelated")).is_retryable());
+    }
+}
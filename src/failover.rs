@@ -0,0 +1,223 @@
+//! Failover across regional API hosts.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::uri::{Authority, Uri};
+use http::Request;
+
+use crate::rt::Instant;
+use crate::Product;
+
+/// A list of regional hosts to fail over between when one of them keeps returning errors.
+///
+/// Requests are sent to the first healthy host in the list. When a host fails
+/// `failure_threshold` times in a row, either with a `5xx` status code or an HTTP transport error,
+/// it is considered unhealthy and skipped for the `cooldown` period, after which it is tried again.
+/// If every host is unhealthy, the one which will recover soonest is used.
+///
+/// Only products which are served from multiple regions, such as Voice and Messages, support
+/// failover. This setting is ignored for all other products.
+///
+/// # Example
+///
+/// ```
+/// use vonage::Failover;
+///
+/// let failover = Failover::new(vec!["api-us.vonage.com", "api-eu.vonage.com"])
+///     .failure_threshold(3);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Failover {
+    hosts: Vec<Authority>,
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl Failover {
+    /// Creates a new `Failover` between the given `hosts`, in order of preference.
+    ///
+    /// By default, a host is considered unhealthy after 5 consecutive failures and is skipped for
+    /// 30 seconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hosts` is empty or any of the hosts is not a valid URI authority.
+    pub fn new<I, S>(hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let hosts: Vec<Authority> = hosts
+            .into_iter()
+            .map(|host| {
+                let host = host.as_ref();
+                host.parse()
+                    .unwrap_or_else(|_| panic!("invalid failover host: `{}`", host))
+            })
+            .collect();
+
+        assert!(!hosts.is_empty(), "failover requires at least one host");
+        Failover {
+            hosts,
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+
+    /// Sets the number of consecutive failures after which a host is considered unhealthy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` is zero.
+    pub fn failure_threshold(mut self, threshold: u32) -> Self {
+        assert!(threshold > 0, "failure threshold must be non-zero");
+        self.failure_threshold = threshold;
+        self
+    }
+
+    /// Sets how long an unhealthy host is skipped before it is tried again.
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+}
+
+/// Tracks the health of every configured host, per product.
+#[derive(Debug, Default)]
+pub(crate) struct Regions {
+    pools: HashMap<Product, HostPool>,
+}
+
+#[derive(Debug)]
+struct HostPool {
+    policy: Failover,
+    health: Mutex<Vec<Health>>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Health {
+    failures: u32,
+    unhealthy_until: Option<Instant>,
+}
+
+impl Regions {
+    pub fn new(policies: HashMap<Product, Failover>) -> Self {
+        let pools = policies
+            .into_iter()
+            .filter(|(product, _)| product.supports_failover())
+            .map(|(product, policy)| {
+                let health = Mutex::new(vec![Health::default(); policy.hosts.len()]);
+                (product, HostPool { policy, health })
+            })
+            .collect();
+
+        Regions { pools }
+    }
+
+    /// Points `request` at the preferred host for `product`, returning its index.
+    ///
+    /// Returns `None` and leaves the request untouched if no failover is configured.
+    pub fn route(&self, product: Product, request: &mut Request<Bytes>) -> Option<usize> {
+        let pool = self.pools.get(&product)?;
+        let index = pool.select_at(Instant::now());
+
+        let mut parts = request.uri().clone().into_parts();
+        parts.authority = Some(pool.policy.hosts[index].clone());
+        *request.uri_mut() = Uri::from_parts(parts).expect("replacing authority cannot fail");
+        Some(index)
+    }
+
+    /// Records the outcome of a request sent to the host at `index`.
+    pub fn record(&self, product: Product, index: usize, success: bool) {
+        if let Some(pool) = self.pools.get(&product) {
+            pool.record_at(index, success, Instant::now());
+        }
+    }
+}
+
+impl HostPool {
+    fn select_at(&self, now: Instant) -> usize {
+        let health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+        let healthy = health
+            .iter()
+            .position(|h| h.unhealthy_until.is_none_or(|until| now >= until));
+
+        healthy.unwrap_or_else(|| {
+            let soonest = health
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, h)| h.unhealthy_until);
+            soonest.map_or(0, |(i, _)| i)
+        })
+    }
+
+    fn record_at(&self, index: usize, success: bool, now: Instant) {
+        let mut health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+        let host = &mut health[index];
+        if success {
+            *host = Health::default();
+        } else if host.failures + 1 < self.policy.failure_threshold {
+            host.failures += 1;
+        } else {
+            *host = Health {
+                failures: 0,
+                unhealthy_until: Some(now + self.policy.cooldown),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool() -> HostPool {
+        let policy = Failover::new(vec!["api-us.vonage.com", "api-eu.vonage.com"])
+            .failure_threshold(2)
+            .cooldown(Duration::from_secs(10));
+        HostPool {
+            health: Mutex::new(vec![Health::default(); 2]),
+            policy,
+        }
+    }
+
+    #[test]
+    fn fails_over_after_sustained_errors() {
+        let pool = pool();
+        let now = Instant::now();
+        assert_eq!(pool.select_at(now), 0);
+
+        pool.record_at(0, false, now);
+        assert_eq!(pool.select_at(now), 0);
+        pool.record_at(0, false, now);
+        assert_eq!(pool.select_at(now), 1);
+
+        let later = now + Duration::from_secs(10);
+        assert_eq!(pool.select_at(later), 0);
+    }
+
+    #[test]
+    fn prefers_soonest_recovery_when_all_hosts_are_down() {
+        let pool = pool();
+        let now = Instant::now();
+        for _ in 0..2 {
+            pool.record_at(1, false, now);
+        }
+
+        let later = now + Duration::from_secs(1);
+        for _ in 0..2 {
+            pool.record_at(0, false, later);
+        }
+
+        assert_eq!(pool.select_at(later), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid failover host")]
+    fn rejects_invalid_hosts() {
+        Failover::new(vec!["not a host"]);
+    }
+}
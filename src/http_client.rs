@@ -0,0 +1,801 @@
+//! Shared HTTP plumbing used by all product modules.
+
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::io::Read;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use flate2::read::GzDecoder;
+use futures::TryStreamExt;
+use http::header::{
+    HeaderMap, HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, USER_AGENT,
+};
+use http::{Request, Response, StatusCode};
+use serde::de::DeserializeOwned;
+
+use crate::cache::ResponseCache;
+use crate::cancel::CancellationToken;
+use crate::circuit_breaker::Circuits;
+use crate::failover::Regions;
+use crate::interceptor::Interceptor;
+use crate::metrics::{MetricsSink, RequestMetrics};
+use crate::rate_limit::RateLimiter;
+use crate::response::{ResponseMeta, TRACE_ID};
+use crate::rt::Instant;
+use crate::sender::{SenderIdCheck, SenderPool};
+use crate::transport::{BodyStream, BoxError, HttpTransport};
+use crate::{Error, ErrorKind, Product, RequestPolicy, Result};
+
+/// A callback invoked with every error returned by the client.
+pub(crate) type ErrorHook = dyn Fn(Product, &Error) + Send + Sync;
+
+/// Client-wide settings shared by every clone of an `HttpClient`.
+pub(crate) struct Middleware {
+    pub user_agent: HeaderValue,
+    pub cancellation: Option<CancellationToken>,
+    pub default_headers: HeaderMap,
+    pub rate_limiter: RateLimiter,
+    pub circuits: Circuits,
+    pub regions: Regions,
+    pub interceptors: Vec<Arc<dyn Interceptor>>,
+    pub metrics: Option<Arc<dyn MetricsSink>>,
+    pub error_hooks: Vec<Arc<ErrorHook>>,
+    pub cache: Option<ResponseCache>,
+    pub sender_pool: Option<SenderPool>,
+    pub sender_id_check: SenderIdCheck,
+    pub test_mode: bool,
+}
+
+impl Middleware {
+    fn record(
+        &self,
+        product: Product,
+        endpoint: &str,
+        latency: Duration,
+        status: Option<StatusCode>,
+        error: Option<&Error>,
+    ) {
+        if let Some(sink) = self.metrics.as_ref() {
+            sink.record(&RequestMetrics {
+                product,
+                endpoint,
+                latency,
+                status,
+                error: error.map(Error::kind),
+            });
+        }
+    }
+}
+
+impl Debug for Middleware {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Middleware))
+            .field("user_agent", &self.user_agent)
+            .field("cancellation", &self.cancellation)
+            .field("default_headers", &self.default_headers)
+            .field("rate_limiter", &self.rate_limiter)
+            .field("circuits", &self.circuits)
+            .field("regions", &self.regions)
+            .field("interceptors", &self.interceptors.len())
+            .field("metrics", &self.metrics.is_some())
+            .field("error_hooks", &self.error_hooks.len())
+            .field("cache", &self.cache)
+            .field("sender_pool", &self.sender_pool)
+            .field("sender_id_check", &self.sender_id_check)
+            .field("test_mode", &self.test_mode)
+            .finish()
+    }
+}
+
+/// Wraps the user-provided HTTP transport with client-wide settings.
+pub(crate) struct HttpClient<C> {
+    transport: Arc<C>,
+    middleware: Arc<Middleware>,
+    policy: RequestPolicy,
+}
+
+impl<C> HttpClient<C> {
+    pub fn new(transport: C, middleware: Middleware) -> Self {
+        HttpClient {
+            transport: Arc::new(transport),
+            middleware: Arc::new(middleware),
+            policy: RequestPolicy::new(),
+        }
+    }
+
+    /// Returns a copy of this client which sends requests with the given policy.
+    pub fn with_policy(&self, policy: RequestPolicy) -> Self {
+        HttpClient {
+            policy,
+            ..self.clone()
+        }
+    }
+}
+
+impl<C> HttpClient<C> {
+    /// Returns the sender IDs configured for outgoing messages, if any.
+    pub fn sender_pool(&self) -> Option<&SenderPool> {
+        self.middleware.sender_pool.as_ref()
+    }
+
+    /// Returns how strictly sender IDs are checked before sending.
+    pub fn sender_id_check(&self) -> SenderIdCheck {
+        self.middleware.sender_id_check
+    }
+}
+
+impl<C: HttpTransport> HttpClient<C> {
+    /// Sends the request to the given Vonage `product` and decodes the response with `decode`,
+    /// applying all configured middleware.
+    pub async fn send<T, F>(
+        &self,
+        product: Product,
+        request: Request<Bytes>,
+        decode: F,
+    ) -> Result<T>
+    where
+        F: FnOnce(Response<Bytes>) -> Result<T>,
+    {
+        let method = request.method().clone();
+        let endpoint = request.uri().path().to_owned();
+
+        let response = self.send_uncancellable(product, request, decode);
+        let result = self.cancellable(response).await;
+        result.map_err(|e| self.report(product, e.with_request(&method, &endpoint)))
+    }
+
+    /// Like `send()`, but returns the response as soon as its headers are received, with its body
+    /// streamed in chunks.
+    ///
+    /// Responses with a non-successful status code are buffered and returned as `Err`.
+    pub async fn send_streaming(
+        &self,
+        product: Product,
+        request: Request<Bytes>,
+    ) -> Result<Response<BodyStream>> {
+        let method = request.method().clone();
+        let endpoint = request.uri().path().to_owned();
+
+        let response = self.send_streaming_uncancellable(product, request);
+        let result = self.cancellable(response).await;
+        result.map_err(|e| self.report(product, e.with_request(&method, &endpoint)))
+    }
+
+    /// Drives `future` to completion, unless the client is cancelled first.
+    pub async fn cancellable<T>(&self, future: impl Future<Output = Result<T>>) -> Result<T> {
+        match self.middleware.cancellation.as_ref() {
+            Some(token) => token.run(future).await,
+            None => future.await,
+        }
+    }
+
+    /// Passes `error` to every registered error hook before returning it.
+    pub fn report(&self, product: Product, error: Error) -> Error {
+        for hook in &self.middleware.error_hooks {
+            hook(product, &error);
+        }
+        error
+    }
+
+    async fn send_uncancellable<T, F>(
+        &self,
+        product: Product,
+        request: Request<Bytes>,
+        decode: F,
+    ) -> Result<T>
+    where
+        F: FnOnce(Response<Bytes>) -> Result<T>,
+    {
+        let middleware = &*self.middleware;
+        let endpoint = request.uri().path().to_owned();
+
+        let cache = middleware.cache.as_ref();
+        let cache = cache.and_then(|cache| Some((cache, cache.policy(&request)?)));
+        if let Some(response) = cache.as_ref().and_then(|(cache, (key, _))| cache.get(key)) {
+            let annotate = annotate_trace_id(response.headers());
+            return decode(response).map_err(annotate);
+        }
+
+        let send = |transport: &C, request| transport.send(request);
+        let (response, start) = self.dispatch(product, &endpoint, request, send).await?;
+        let status = response.status();
+        let annotate = annotate_trace_id(response.headers());
+
+        let response = match decompress(response).map_err(&annotate) {
+            Ok(response) => response,
+            Err(e) => {
+                middleware.record(product, &endpoint, start.elapsed(), Some(status), Some(&e));
+                return Err(e);
+            }
+        };
+
+        for interceptor in &middleware.interceptors {
+            interceptor.on_response(&response);
+        }
+
+        if let Some((cache, (key, ttl))) = cache {
+            cache.insert(key, ttl, &response);
+        }
+
+        let result = decode(response).map_err(annotate);
+        let error = result.as_ref().err();
+        middleware.record(product, &endpoint, start.elapsed(), Some(status), error);
+        result
+    }
+
+    async fn send_streaming_uncancellable(
+        &self,
+        product: Product,
+        request: Request<Bytes>,
+    ) -> Result<Response<BodyStream>> {
+        let middleware = &*self.middleware;
+        let endpoint = request.uri().path().to_owned();
+
+        let send = |transport: &C, request| transport.send_streaming(request);
+        let (response, start) = self.dispatch(product, &endpoint, request, send).await?;
+        let status = response.status();
+        let annotate = annotate_trace_id(response.headers());
+
+        // Interceptors only get to see the head of streamed responses.
+        let (parts, body) = response.into_parts();
+        let head = Response::from_parts(parts, Bytes::new());
+        for interceptor in &middleware.interceptors {
+            interceptor.on_response(&head);
+        }
+
+        let (parts, _) = head.into_parts();
+        let result = if status.is_success() {
+            Ok(Response::from_parts(parts, body))
+        } else {
+            let body = body.map_ok(Vec::from).try_concat().await;
+            match body {
+                Ok(body) => Err(Error::new_status(&Response::from_parts(parts, body.into()))),
+                Err(e) => Err(Error::new_http(e)),
+            }
+        };
+
+        let result = result.map_err(annotate);
+        let error = result.as_ref().err();
+        middleware.record(product, &endpoint, start.elapsed(), Some(status), error);
+        result
+    }
+
+    /// Sends `request` with `send`, applying the timeout and retries of the policy of this handle.
+    ///
+    /// Returns the response to the last attempt, and when that attempt was started.
+    async fn dispatch<B, F, Fut>(
+        &self,
+        product: Product,
+        endpoint: &str,
+        request: Request<Bytes>,
+        send: F,
+    ) -> Result<(Response<B>, Instant)>
+    where
+        F: Fn(&C, Request<Bytes>) -> Fut,
+        Fut: Future<Output = std::result::Result<Response<B>, BoxError>>,
+    {
+        let middleware = &*self.middleware;
+        let mut retries = 0;
+
+        loop {
+            let mut attempt = clone_request(&request);
+            let host = self.prepare(product, endpoint, &mut attempt).await?;
+            let record_host = |success| {
+                if let Some(index) = host {
+                    middleware.regions.record(product, index, success);
+                }
+            };
+
+            let start = Instant::now();
+            let sent = match self.policy.timeout_duration() {
+                Some(timeout) => crate::rt::timeout(timeout, send(&self.transport, attempt))
+                    .await
+                    .ok_or_else(|| {
+                        let message = format!("no response received within {:?}", timeout);
+                        Error::with_cause(ErrorKind::Timeout, anyhow::Error::msg(message))
+                    }),
+                None => Ok(send(&self.transport, attempt).await),
+            };
+
+            let (error, retry_after) = match sent.and_then(|sent| sent.map_err(Error::new_http)) {
+                Ok(response) => {
+                    let status = response.status();
+                    middleware
+                        .circuits
+                        .record(endpoint, !status.is_server_error());
+                    record_host(!status.is_server_error());
+
+                    let retryable = self.policy.may_retry(request.method(), Some(status));
+                    if !retryable || retries >= self.policy.retries() {
+                        return Ok((response, start));
+                    }
+
+                    let mut head = Response::new(Bytes::new());
+                    *head.status_mut() = status;
+                    let retry_after = crate::response::retry_after(response.headers());
+                    (Error::new_status(&head), retry_after)
+                }
+                Err(e) => {
+                    middleware.circuits.record(endpoint, false);
+                    record_host(false);
+                    (e, None)
+                }
+            };
+
+            let status = match error.kind() {
+                ErrorKind::Status(status) => Some(status),
+                ErrorKind::RateLimited => Some(StatusCode::TOO_MANY_REQUESTS),
+                _ => None,
+            };
+            middleware.record(product, endpoint, start.elapsed(), status, Some(&error));
+            let retryable = status.is_some() || self.policy.may_retry(request.method(), None);
+            if !retryable || retries >= self.policy.retries() {
+                return Err(error);
+            }
+
+            crate::rt::sleep(retry_after.unwrap_or_else(|| self.policy.delay(retries))).await;
+            retries += 1;
+        }
+    }
+
+    /// Applies client-wide settings to `request`, returning the index of the regional host it was
+    /// routed to, if any.
+    async fn prepare(
+        &self,
+        product: Product,
+        endpoint: &str,
+        request: &mut Request<Bytes>,
+    ) -> Result<Option<usize>> {
+        let middleware = &*self.middleware;
+
+        if middleware.test_mode {
+            if let Err(e) = crate::sandbox::route(request) {
+                middleware.record(product, endpoint, Duration::default(), None, Some(&e));
+                return Err(e);
+            }
+        }
+
+        if let Err(e) = middleware.circuits.check(endpoint) {
+            middleware.record(product, endpoint, Duration::default(), None, Some(&e));
+            return Err(e);
+        }
+
+        middleware.rate_limiter.acquire(product).await;
+
+        let headers = request.headers_mut();
+        for name in middleware.default_headers.keys() {
+            if !headers.contains_key(name) {
+                for value in middleware.default_headers.get_all(name) {
+                    headers.append(name, value.clone());
+                }
+            }
+        }
+        headers
+            .entry(USER_AGENT)
+            .or_insert_with(|| middleware.user_agent.clone());
+        headers
+            .entry(ACCEPT_ENCODING)
+            .or_insert_with(|| HeaderValue::from_static("gzip"));
+
+        let host = if middleware.test_mode {
+            None
+        } else {
+            middleware.regions.route(product, request)
+        };
+        for interceptor in &middleware.interceptors {
+            interceptor.on_request(request);
+        }
+
+        Ok(host)
+    }
+
+    /// Like `send()`, but also returns the metadata of the response if one was received, even
+    /// when decoding it failed.
+    pub async fn send_with_meta<T, F>(
+        &self,
+        product: Product,
+        request: Request<Bytes>,
+        decode: F,
+    ) -> (Result<T>, Option<ResponseMeta>)
+    where
+        F: FnOnce(Response<Bytes>) -> Result<T>,
+    {
+        let mut meta = None;
+        let result = self
+            .send(product, request, |response| {
+                meta = Some(ResponseMeta::new(&response));
+                decode(response)
+            })
+            .await;
+        (result, meta)
+    }
+}
+
+/// Deserializes a JSON response body, reporting the path to the offending field on failure.
+///
+/// With the `simd-json` feature enabled, the body is parsed with `simd-json` first, and is only
+/// parsed again with `serde_json` if that fails, in order to produce a detailed error.
+pub(crate) fn decode_json<T: DeserializeOwned>(body: &[u8]) -> Result<T> {
+    #[cfg(feature = "simd-json")]
+    {
+        // `simd-json` parses in place, so it needs a mutable copy of the body.
+        if let Ok(value) = simd_json::serde::from_slice(&mut body.to_vec()) {
+            return Ok(value);
+        }
+    }
+
+    let deserializer = &mut serde_json::Deserializer::from_slice(body);
+    serde_path_to_error::deserialize(deserializer).map_err(|e| Error::new_decode(body, e))
+}
+
+/// Returns a closure which attaches the Vonage trace ID in `headers`, if any, to an error.
+fn annotate_trace_id(headers: &HeaderMap) -> impl Fn(Error) -> Error {
+    let trace_id = headers
+        .get(TRACE_ID)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+
+    move |e: Error| match trace_id {
+        Some(ref trace_id) => e.with_trace_id(trace_id.clone()),
+        None => e,
+    }
+}
+
+/// The largest body, in bytes, which a gzip-compressed response may inflate to.
+const MAX_DECOMPRESSED_LEN: u64 = 16 * 1024 * 1024;
+
+/// Transparently decodes gzip-compressed response bodies.
+///
+/// Some transports (e.g. `fetch()` in browsers) already do this on their own, in which case the
+/// `Content-Encoding` header is absent and the response is returned unchanged. Bodies which are
+/// corrupt or inflate to more than [`MAX_DECOMPRESSED_LEN`] bytes fail with [`ErrorKind::Decode`].
+fn decompress(mut response: Response<Bytes>) -> Result<Response<Bytes>> {
+    let is_gzip = response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .is_some_and(|encoding| encoding.as_bytes().eq_ignore_ascii_case(b"gzip"));
+
+    if is_gzip {
+        let mut decoded = Vec::new();
+        GzDecoder::new(&response.body()[..])
+            .take(MAX_DECOMPRESSED_LEN + 1)
+            .read_to_end(&mut decoded)
+            .map_err(|e| Error::with_cause(ErrorKind::Decode, e))?;
+
+        if decoded.len() as u64 > MAX_DECOMPRESSED_LEN {
+            let message = format!(
+                "decompressed response body exceeds {} bytes",
+                MAX_DECOMPRESSED_LEN
+            );
+            return Err(Error::with_cause(
+                ErrorKind::Decode,
+                anyhow::Error::msg(message),
+            ));
+        }
+
+        let headers = response.headers_mut();
+        headers.remove(CONTENT_ENCODING);
+        headers.insert(CONTENT_LENGTH, decoded.len().into());
+        *response.body_mut() = decoded.into();
+    }
+
+    Ok(response)
+}
+
+/// Copies `request`, so that it can be sent again.
+fn clone_request(request: &Request<Bytes>) -> Request<Bytes> {
+    let mut copy = Request::new(request.body().clone());
+    *copy.method_mut() = request.method().clone();
+    *copy.uri_mut() = request.uri().clone();
+    *copy.version_mut() = request.version();
+    *copy.headers_mut() = request.headers().clone();
+    copy
+}
+
+impl<C> Clone for HttpClient<C> {
+    fn clone(&self) -> Self {
+        HttpClient {
+            transport: self.transport.clone(),
+            middleware: self.middleware.clone(),
+            policy: self.policy,
+        }
+    }
+}
+
+impl<C> Debug for HttpClient<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(HttpClient))
+            .field("middleware", &self.middleware)
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::convert::Infallible;
+
+    use http_body_util::Full;
+    use tower::service_fn;
+
+    use crate::Client;
+
+    #[tokio::test]
+    async fn decompresses_gzip_responses() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.headers()[ACCEPT_ENCODING], "gzip");
+
+            let body = r#"{"request_id":"abcdef0123456789abcdef0123456789","status":"0"}"#;
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(body.as_bytes()).unwrap();
+
+            let response = Response::builder()
+                .header(CONTENT_ENCODING, "gzip")
+                .body(Full::new(Bytes::from(encoder.finish().unwrap())))
+                .unwrap();
+            Ok::<_, Infallible>(response)
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let pending = client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            pending.request_id().to_string(),
+            "abcdef0123456789abcdef0123456789"
+        );
+    }
+
+    #[test]
+    fn rejects_corrupt_and_oversized_gzip_bodies() {
+        let gzip = |body: &[u8]| {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(body).unwrap();
+            let body = Bytes::from(encoder.finish().unwrap());
+            Response::builder()
+                .header(CONTENT_ENCODING, "gzip")
+                .body(body)
+                .unwrap()
+        };
+
+        let limit = vec![0; MAX_DECOMPRESSED_LEN as usize];
+        let response = decompress(gzip(&limit)).unwrap();
+        assert_eq!(response.body().len() as u64, MAX_DECOMPRESSED_LEN);
+
+        let oversized = vec![0; MAX_DECOMPRESSED_LEN as usize + 1];
+        let error = decompress(gzip(&oversized)).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Decode));
+        assert!(!error.is_retryable());
+
+        let mut corrupt = gzip(b"{}");
+        *corrupt.body_mut() = Bytes::from_static(b"not gzip");
+        let error = decompress(corrupt).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Decode));
+        assert!(!error.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn applies_policies_per_handle() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let service = service_fn({
+            let attempts = attempts.clone();
+            move |request: Request<Full<Bytes>>| {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if request.uri().path() == "/verify/check/json" {
+                        tokio::time::sleep(Duration::from_millis(200)).await;
+                    }
+
+                    let response = if attempt < 2 {
+                        Response::builder()
+                            .status(StatusCode::SERVICE_UNAVAILABLE)
+                            .body(Full::new(Bytes::new()))
+                    } else {
+                        let body =
+                            r#"{"request_id":"abcdef0123456789abcdef0123456789","status":"0"}"#;
+                        Response::builder().body(Full::new(Bytes::from(body)))
+                    };
+                    Ok::<_, Infallible>(response.unwrap())
+                }
+            }
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let result = client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .send()
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.swap(0, Ordering::SeqCst), 1);
+
+        let verify = client.verify().policy(
+            RequestPolicy::new()
+                .max_retries(2)
+                .backoff(Duration::from_millis(1))
+                .timeout(Duration::from_millis(20)),
+        );
+        let pending = verify
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(attempts.swap(0, Ordering::SeqCst), 3);
+
+        let error = pending.check("1234").await.unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Timeout));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn reports_decode_error_context() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Body {
+            #[allow(dead_code)]
+            items: Vec<Item>,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        struct Item {
+            #[allow(dead_code)]
+            price: f64,
+        }
+
+        let body = br#"{"items":[{"price":1.0},{"price":"free"}]}"#;
+        let error = decode_json::<Body>(body).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Decode));
+        assert_eq!(
+            error.body(),
+            Some(r#"{"items":[{"price":1.0},{"price":"free"}]}"#)
+        );
+
+        let source = std::error::Error::source(&error).unwrap().to_string();
+        assert!(
+            source.starts_with("items[1].price: invalid type"),
+            "{}",
+            source
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_only_idempotent_requests_with_exponential_policy() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use http::Method;
+
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let service = service_fn({
+            let attempts = attempts.clone();
+            move |request: Request<Full<Bytes>>| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    let status = match request.uri().path() {
+                        "/limited" => StatusCode::TOO_MANY_REQUESTS,
+                        _ => StatusCode::SERVICE_UNAVAILABLE,
+                    };
+                    let response = Response::builder()
+                        .status(status)
+                        .header("Retry-After", "0")
+                        .body(Full::new(Bytes::new()));
+                    Ok::<_, Infallible>(response.unwrap())
+                }
+            }
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .policy(RequestPolicy::exponential(2).backoff(Duration::from_millis(1)))
+            .build()
+            .unwrap();
+
+        let cases = [
+            (Method::GET, "/unavailable", 3),
+            (Method::POST, "/unavailable", 1),
+            (Method::POST, "/limited", 3),
+        ];
+        for (method, path, expected) in cases.iter() {
+            let response = client.request_raw(method.clone(), path, "").await.unwrap();
+            assert!(response.status().is_server_error() || response.status().as_u16() == 429);
+            assert_eq!(
+                attempts.swap(0, Ordering::SeqCst),
+                *expected,
+                "{} {}",
+                method,
+                path
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_errors_to_hooks() {
+        use std::sync::Mutex;
+
+        let service = service_fn(|_: Request<Full<Bytes>>| async {
+            let response = Response::builder()
+                .status(StatusCode::BAD_GATEWAY)
+                .body(Full::new(Bytes::from("upstream unavailable")))
+                .unwrap();
+            Ok::<_, Infallible>(response)
+        });
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .on_error({
+                let seen = seen.clone();
+                move |product, error| {
+                    let endpoint = error.endpoint().map(str::to_owned);
+                    seen.lock().unwrap().push((product, endpoint, error.kind()));
+                }
+            })
+            .build()
+            .unwrap();
+
+        let result = client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .send()
+            .await;
+        assert!(result.is_err());
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].0, Product::Verify);
+        assert_eq!(seen[0].1.as_deref(), Some("/verify/json"));
+        assert!(matches!(
+            seen[0].2,
+            ErrorKind::Status(StatusCode::BAD_GATEWAY)
+        ));
+    }
+
+    #[tokio::test]
+    async fn annotates_errors_with_request_details() {
+        let service = service_fn(|_: Request<Full<Bytes>>| async {
+            let body = r#"{"request_id":"abcdef0123456789abcdef0123456789","status":"10","error_text":"Concurrent verifications to the same number are not allowed"}"#;
+            let response = Response::builder()
+                .header("X-Nexmo-Trace-Id", "trace")
+                .body(Full::new(Bytes::from(body)))
+                .unwrap();
+            Ok::<_, Infallible>(response)
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let error = client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .send()
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.method(), Some(&http::Method::POST));
+        assert_eq!(error.endpoint(), Some("/verify/json"));
+        assert_eq!(error.request_id(), Some("abcdef0123456789abcdef0123456789"));
+        assert_eq!(error.trace_id(), Some("trace"));
+    }
+}
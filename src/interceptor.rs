@@ -0,0 +1,105 @@
+//! Hooks for observing and modifying requests sent by the client.
+
+use bytes::Bytes;
+use http::{Request, Response};
+
+/// A hook invoked around every HTTP request sent to the Vonage APIs.
+///
+/// Interceptors are registered with [`ClientBuilder::interceptor()`] and run in the order they
+/// were added. They are useful for adding custom headers, audit logging, or collecting metrics
+/// without implementing a custom [`HttpTransport`].
+///
+/// Both methods do nothing by default, so implementors only need to override the ones they need.
+///
+/// [`ClientBuilder::interceptor()`]: ./struct.ClientBuilder.html#method.interceptor
+/// [`HttpTransport`]: ./transport/trait.HttpTransport.html
+///
+/// # Example
+///
+/// ```
+/// use http::header::HeaderValue;
+/// use vonage::transport::{Bytes, Request};
+/// use vonage::Interceptor;
+///
+/// struct CorrelationId(HeaderValue);
+///
+/// impl Interceptor for CorrelationId {
+///     fn on_request(&self, request: &mut Request<Bytes>) {
+///         request.headers_mut().insert("x-correlation-id", self.0.clone());
+///     }
+/// }
+/// ```
+pub trait Interceptor: Send + Sync {
+    /// Called immediately before `request` is sent.
+    fn on_request(&self, request: &mut Request<Bytes>) {
+        let _ = request;
+    }
+
+    /// Called after `response` is received, before it is decoded.
+    fn on_response(&self, response: &Response<Bytes>) {
+        let _ = response;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use std::convert::Infallible;
+
+    use http_body_util::Full;
+    use tower::service_fn;
+
+    use crate::Client;
+
+    struct AddHeader;
+
+    impl Interceptor for AddHeader {
+        fn on_request(&self, request: &mut Request<Bytes>) {
+            request
+                .headers_mut()
+                .insert("x-test", "intercepted".parse().unwrap());
+        }
+    }
+
+    struct CountResponses(Arc<AtomicUsize>);
+
+    impl Interceptor for CountResponses {
+        fn on_response(&self, _: &Response<Bytes>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_hooks_around_requests() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.headers()["x-test"], "intercepted");
+            let body = r#"{"request_id":"abcdef0123456789abcdef0123456789","status":"0"}"#;
+            Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let responses = Arc::new(AtomicUsize::new(0));
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .interceptor(AddHeader)
+            .interceptor(CountResponses(responses.clone()))
+            .build()
+            .unwrap();
+
+        let pending = client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            pending.request_id().to_string(),
+            "abcdef0123456789abcdef0123456789"
+        );
+        assert_eq!(responses.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,258 @@
+//! Caching and background renewal of the JWTs used to authenticate with application credentials.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::auth::Auth;
+use crate::http_client::HttpClient;
+use crate::rt::{Instant, SystemTime};
+use crate::transport::HttpTransport;
+use crate::{DefaultTransport, Product, Result};
+
+/// How long signed JWTs are valid for, unless configured otherwise.
+pub(crate) const DEFAULT_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// How long the refresher waits before trying again after failing to sign a JWT.
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// A signed JWT, and when it is due for renewal.
+struct Token {
+    value: String,
+    /// When the refresher renews the token.
+    refresh_at: Instant,
+    /// When requests stop using the token and sign a new one themselves.
+    stale_at: Instant,
+}
+
+/// A JWT shared by all clones of a client, which is re-signed shortly before it expires.
+pub(crate) struct JwtCache {
+    ttl: Duration,
+    token: Mutex<Option<Token>>,
+    health: Mutex<JwtHealth>,
+}
+
+impl JwtCache {
+    pub fn new(ttl: Duration) -> Self {
+        JwtCache {
+            ttl,
+            token: Mutex::new(None),
+            health: Mutex::new(JwtHealth::default()),
+        }
+    }
+
+    /// Returns the cached token, signing a new one with `sign` if it is missing or stale.
+    pub fn get_or_sign(&self, sign: impl FnOnce(Duration) -> Result<String>) -> Result<String> {
+        let mut token = self.token.lock().unwrap();
+        match token.as_ref() {
+            Some(token) if Instant::now() < token.stale_at => Ok(token.value.clone()),
+            _ => self.sign(&mut token, sign),
+        }
+    }
+
+    /// Signs a new token with `sign`, returning how long until it is due for renewal.
+    pub fn refresh(&self, sign: impl FnOnce(Duration) -> Result<String>) -> Result<Duration> {
+        let mut token = self.token.lock().unwrap();
+        self.sign(&mut token, sign)?;
+        let refresh_at = token.as_ref().map(|token| token.refresh_at);
+        Ok(refresh_at.map_or(Duration::default(), |at| {
+            at.saturating_duration_since(Instant::now())
+        }))
+    }
+
+    pub fn health(&self) -> JwtHealth {
+        self.health.lock().unwrap().clone()
+    }
+
+    fn sign(
+        &self,
+        token: &mut Option<Token>,
+        sign: impl FnOnce(Duration) -> Result<String>,
+    ) -> Result<String> {
+        let result = sign(self.ttl);
+        let mut health = self.health.lock().unwrap();
+        match result {
+            Ok(value) => {
+                let now = Instant::now();
+                *token = Some(Token {
+                    value: value.clone(),
+                    refresh_at: now + self.ttl * 3 / 4,
+                    stale_at: now + self.ttl * 4 / 5,
+                });
+
+                let now = SystemTime::now();
+                health.refreshed_at = Some(now);
+                health.expires_at = Some(now + self.ttl);
+                health.last_error = None;
+                health.consecutive_failures = 0;
+                Ok(value)
+            }
+            Err(e) => {
+                health.last_error = Some(match std::error::Error::source(&e) {
+                    Some(source) => format!("{}: {}", e, source),
+                    None => e.to_string(),
+                });
+                health.consecutive_failures += 1;
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Debug for JwtCache {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(JwtCache))
+            .field("ttl", &self.ttl)
+            .field("health", &self.health())
+            .finish()
+    }
+}
+
+/// The state of the JWT cache of a client, as reported by
+/// [`JwtRefresher::health()`](./struct.JwtRefresher.html#method.health).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct JwtHealth {
+    refreshed_at: Option<SystemTime>,
+    expires_at: Option<SystemTime>,
+    last_error: Option<String>,
+    consecutive_failures: u32,
+}
+
+impl JwtHealth {
+    /// Returns whether a valid JWT is cached, and the last attempt to sign one succeeded.
+    pub fn is_healthy(&self) -> bool {
+        self.consecutive_failures == 0 && self.expires_at.is_some_and(|at| SystemTime::now() < at)
+    }
+
+    /// Returns when the cached JWT was signed, if one was signed at all.
+    pub fn refreshed_at(&self) -> Option<SystemTime> {
+        self.refreshed_at
+    }
+
+    /// Returns when the cached JWT expires, if one was signed at all.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.expires_at
+    }
+
+    /// Returns why signing a JWT failed, if the last attempt failed.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    /// Returns how many attempts to sign a JWT have failed in a row.
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+}
+
+/// A background task which renews the JWT of a client before it expires.
+///
+/// Clients with application credentials sign a JWT once and reuse it until shortly before it
+/// expires, at which point the next request signs a new one. Running the refresher instead renews
+/// the JWT ahead of time, so that requests never wait for signing, and a failure to sign is
+/// reported to the [error hooks](./struct.ClientBuilder.html#method.on_error) of the client and
+/// its [`health()`](#method.health) as soon as it happens, rather than by the next API call.
+///
+/// This is returned from [`Client::jwt_refresher()`](./struct.Client.html#method.jwt_refresher).
+///
+/// # Example
+///
+/// ```no_run
+/// use vonage::Client;
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = Client::builder().jwt("<app_id>", "<private_key>").build()?;
+///
+/// let refresher = client.jwt_refresher();
+/// tokio::spawn(refresher.clone().run());
+///
+/// // E.g. in a readiness probe:
+/// assert!(refresher.health().is_healthy());
+/// # Ok(())
+/// # }
+/// ```
+pub struct JwtRefresher<C = DefaultTransport> {
+    http_client: HttpClient<C>,
+    auth: Auth,
+}
+
+impl<C> JwtRefresher<C> {
+    pub(crate) fn new(http_client: HttpClient<C>, auth: Auth) -> Self {
+        JwtRefresher { http_client, auth }
+    }
+
+    /// Returns the state of the JWT cache.
+    pub fn health(&self) -> JwtHealth {
+        self.auth.jwt_health().unwrap_or_default()
+    }
+}
+
+impl<C: HttpTransport> JwtRefresher<C> {
+    /// Renews the JWT whenever three quarters of its lifetime have passed, until the client is
+    /// [cancelled](./struct.ClientBuilder.html#method.cancellation_token).
+    ///
+    /// Failures are retried every 10 seconds.
+    pub async fn run(self) {
+        let refresh = async {
+            loop {
+                let delay = match self.auth.refresh_jwt() {
+                    Ok(delay) => delay,
+                    Err(e) => {
+                        self.http_client.report(Product::Other, e);
+                        RETRY_DELAY
+                    }
+                };
+                crate::rt::sleep(delay).await;
+            }
+        };
+
+        let _: Result<()> = self.http_client.cancellable(refresh).await;
+    }
+}
+
+impl<C> Clone for JwtRefresher<C> {
+    fn clone(&self) -> Self {
+        JwtRefresher {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+        }
+    }
+}
+
+impl<C> Debug for JwtRefresher<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(JwtRefresher))
+            .field("auth", &self.auth)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_tokens_until_stale() {
+        let cache = JwtCache::new(Duration::from_secs(60));
+        assert!(!cache.health().is_healthy());
+
+        let first = cache.get_or_sign(|_| Ok("first".into())).unwrap();
+        let second = cache.get_or_sign(|_| Ok("second".into())).unwrap();
+        assert_eq!(first, second);
+        assert!(cache.health().is_healthy());
+
+        let delay = cache.refresh(|_| Ok("third".into())).unwrap();
+        assert!(delay <= Duration::from_secs(45) && delay > Duration::from_secs(40));
+        assert_eq!(cache.get_or_sign(|_| unreachable!()).unwrap(), "third");
+
+        let error = crate::Error::new_auth(anyhow::anyhow!("key revoked"));
+        assert!(cache.refresh(|_| Err(error)).is_err());
+        let health = cache.health();
+        assert!(!health.is_healthy());
+        assert_eq!(health.consecutive_failures(), 1);
+        assert_eq!(
+            health.last_error(),
+            Some("authentication error: key revoked")
+        );
+    }
+}
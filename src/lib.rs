@@ -13,46 +13,200 @@
 //! [sign-up]: https://dashboard.nexmo.com/sign-up?utm_source=DEV_REL&utm_medium=github
 //!
 //! See [developer.nexmo.com](https://developer.nexmo.com/) for upstream documentation.
+//!
+//! # TLS backends
+//!
+//! By default, the HTTP client returned by [`Client::builder()`] uses the platform's native TLS
+//! implementation (OpenSSL on Linux) via the `native-tls` feature. For static binaries and
+//! environments without OpenSSL, disable the default features and enable the `rustls` feature
+//! instead. These two features are mutually exclusive.
+//!
+//! [`Client::builder()`]: ./struct.Client.html#method.builder
+//!
+//! # WebAssembly
+//!
+//! When targeting `wasm32-unknown-unknown` (e.g. for edge runtimes like Cloudflare Workers), the
+//! `reqwest` feature must be enabled. In this configuration, the default client sends requests
+//! using the `fetch()` API via [`ReqwestTransport`](./transport/struct.ReqwestTransport.html), and
+//! the TLS features have no effect.
+//!
+//! # Receiving webhooks
+//!
+//! The [`webhooks`](./webhooks/index.html) module parses and verifies callbacks sent by Vonage
+//! from within any web framework. For prototypes, the `server` feature additionally provides a
+//! minimal built-in webhook server.
+//!
+//! # Custom transports
+//!
+//! The client is not tied to any particular HTTP stack or async runtime. See the
+//! [`transport`](./transport/index.html) module for how to plug in your own.
 
 #![deny(missing_debug_implementations)]
 #![forbid(unsafe_code)]
 
+#[cfg(all(feature = "native-tls", feature = "rustls"))]
+compile_error!("features `native-tls` and `rustls` are mutually exclusive");
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    not(any(feature = "native-tls", feature = "rustls"))
+))]
+compile_error!("either the `native-tls` or `rustls` feature must be enabled");
+#[cfg(all(target_arch = "wasm32", not(feature = "reqwest")))]
+compile_error!("the `reqwest` feature must be enabled when targeting wasm32");
+#[cfg(all(target_arch = "wasm32", feature = "blocking"))]
+compile_error!("the `blocking` feature is not supported when targeting wasm32");
+#[cfg(all(target_arch = "wasm32", feature = "server"))]
+compile_error!("the `server` feature is not supported when targeting wasm32");
+
+pub use self::cache::{CacheStore, MemoryStore, ResponseCache};
+pub use self::cancel::CancellationToken;
+pub use self::circuit_breaker::CircuitBreaker;
+pub use self::datetime::Timestamp;
+pub use self::download::{Download, Progress};
 pub use self::error::{Error, ErrorKind};
+pub use self::failover::Failover;
+pub use self::interceptor::Interceptor;
+pub use self::jwt::{JwtHealth, JwtRefresher};
+#[cfg(feature = "log")]
+pub use self::logging::RequestLogger;
+pub use self::metadata::{Metadata, MAX_CLIENT_REF_LEN};
+#[cfg(feature = "prometheus")]
+pub use self::metrics::PrometheusMetrics;
+pub use self::metrics::{MetricsSink, RequestMetrics};
+pub use self::money::{Amount, CurrencyConverter, ExchangeRates, Price};
+pub use self::pagination::{ListParams, Order, Paginated};
+pub use self::policy::{RequestPolicy, RetryPolicy};
+pub use self::problem::{InvalidParameter, Problem};
+pub use self::product::Product;
+pub use self::rate_limit::{RateLimit, SharedRateLimiter};
+pub use self::response::ResponseMeta;
+pub use self::sender::{SenderIdCheck, SenderIdProblem, SenderIdRule, SenderPool};
 pub use self::sig::{Signature, SignatureMethod, SignatureSecret};
+pub use self::validation::InvalidRequest;
 
+use std::collections::HashMap;
 use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
 
-use hyper::body::Body;
-use hyper::client::HttpConnector;
-use hyper::service::Service;
-use hyper::{Request, Response};
-use hyper_tls::HttpsConnector;
-use phonenumber::PhoneNumber;
+use bytes::Bytes;
+use http::header::{HeaderMap, HeaderValue};
+use http::{Method, Request, Response};
+#[cfg(not(target_arch = "wasm32"))]
+use http_body_util::Full;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
 use serde::Serialize;
 
+use self::applications::ApplicationsApi;
 use self::auth::{Auth, AuthBuilder};
-use self::verify::Verify;
+use self::circuit_breaker::Circuits;
+use self::credentials::{
+    AddApiKey, AddJwt, ApiKeyAuth, Credentials, HasApiKey, HasJwt, NoCredentials,
+};
+use self::failover::Regions;
+use self::http_client::{ErrorHook, HttpClient, Middleware};
+use self::number_insight::NumberInsightApi;
+use self::numbers::NumbersApi;
+use self::rate_limit::RateLimiter;
+use self::sms::SmsApi;
+use self::transport::HttpTransport;
+#[cfg(target_arch = "wasm32")]
+use self::transport::ReqwestTransport;
+#[cfg(not(target_arch = "wasm32"))]
+use self::transport::{BoxError, HyperTransport};
+use self::usage::UsageApi;
+use self::validation::Validator;
+use self::verify::VerifyApi;
+use self::voice::VoiceApi;
 
+pub mod applications;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod credentials;
+pub mod messages;
+pub mod number_insight;
+pub mod numbers;
+pub mod phone;
+pub mod pipeline;
+pub mod sms;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod transport;
+pub mod usage;
 pub mod verify;
+pub mod voice;
+pub mod webhooks;
 
 mod auth;
+mod cache;
+mod cancel;
+mod circuit_breaker;
+mod datetime;
+mod download;
 mod error;
+mod failover;
+mod http_client;
+mod interceptor;
+mod jwt;
+#[cfg(feature = "log")]
+mod logging;
+mod metadata;
+mod metrics;
+mod money;
+mod pagination;
+mod policy;
+mod problem;
+mod product;
+mod rate_limit;
+#[cfg(any(feature = "log", feature = "testing"))]
+mod redact;
+mod response;
+mod rt;
+mod sandbox;
+mod sender;
 mod sig;
+mod validation;
 
 const VONAGE_URL_BASE: &str = "https://api.nexmo.com";
+const VONAGE_REST_URL_BASE: &str = "https://rest.nexmo.com";
+const USER_AGENT: &str = concat!(
+    "vonage-rs/",
+    env!("CARGO_PKG_VERSION"),
+    " rust/",
+    env!("VONAGE_RUSTC_VERSION")
+);
 
 /// A specialized [`Result`] error type for convenience.
 ///
 /// [`Result`]: enum@std::result::Result
 pub type Result<T> = std::result::Result<T, Error>;
 
-type HyperClient = hyper::Client<HttpsConnector<HttpConnector>>;
+#[cfg(not(target_arch = "wasm32"))]
+type DefaultTransport = HyperTransport<transport::DefaultClient>;
+
+/// A [`Client`](./struct.Client.html) whose default HTTP client is wrapped in the `tower` layer
+/// `L`, as returned by [`Client::layered()`](./struct.Client.html#method.layered).
+#[cfg(not(target_arch = "wasm32"))]
+pub type LayeredClient<L, A = ApiKeyAuth> =
+    Client<HyperTransport<<L as tower_layer::Layer<transport::DefaultClient>>::Service>, A>;
+#[cfg(target_arch = "wasm32")]
+type DefaultTransport = ReqwestTransport;
 
 /// A client to interface with the Vonage APIs.
-pub struct Client<C = HyperClient> {
-    http_client: C,
+///
+/// `Client` is cheap to clone, and is `Send` and `Sync` whenever its transport is. Clones share
+/// the same connection pool, credentials, and middleware, such as rate limits and circuit
+/// breakers, so a single client can be shared between tasks.
+///
+/// The `A` type parameter records which credentials the client was configured with, which
+/// determines the products it can be used for. See the [`credentials`](./credentials/index.html)
+/// module for details.
+pub struct Client<C = DefaultTransport, A = ApiKeyAuth> {
+    http_client: HttpClient<C>,
     authentication: Auth,
     sms_signature: Option<SignatureSecret>,
+    credentials: PhantomData<A>,
 }
 
 impl Client {
@@ -74,49 +228,414 @@ impl Client {
     ///
     /// This option allows for configuration of all available API authentication options.
     pub fn builder() -> ClientBuilder {
-        let client = hyper::Client::builder().build(HttpsConnector::new());
-        Client::from_service(client)
+        #[cfg(not(target_arch = "wasm32"))]
+        let transport = HyperTransport::new(transport::build_default_client(
+            &transport::default_client_builder(),
+        ));
+        #[cfg(target_arch = "wasm32")]
+        let transport = ReqwestTransport::default();
+
+        ClientBuilder::new(transport)
     }
-}
 
-impl<C> Client<C>
-where
-    C: Service<Request<Body>, Response = Response<Body>, Error = hyper::Error> + Clone,
-{
-    /// Creates a builder to configure a new `Client` built on the given `http_client`.
+    /// Creates a builder to configure a new `Client` built on the given `tower` service.
     ///
     /// Similar to [`Client::builder()`](#method.builder) except it allows for specifying a custom
-    /// HTTP client instead of the default [`hyper::Client`]. This option allows for configuration
-    /// of all available API authentication options.
+    /// HTTP client instead of the default [`hyper_util`] client, such as one wrapped in `tower`
+    /// middleware. This option allows for configuration of all available API authentication
+    /// options.
+    ///
+    /// This is a shorthand for wrapping the service in a
+    /// [`HyperTransport`](./transport/struct.HyperTransport.html) and passing it to
+    /// [`Client::from_transport()`](#method.from_transport).
     ///
-    /// [`hyper::Client`]: https://docs.rs/hyper/0.13/hyper/client/struct.Client.html
+    /// [`hyper_util`]: https://docs.rs/hyper-util/0.1/hyper_util/client/legacy/struct.Client.html
+    #[cfg(not(target_arch = "wasm32"))]
     #[inline]
-    pub fn from_service(http_client: C) -> ClientBuilder<C> {
-        ClientBuilder::new(http_client)
+    pub fn from_service<S, B>(service: S) -> ClientBuilder<HyperTransport<S>>
+    where
+        S: tower_service::Service<Request<Full<Bytes>>, Response = http::Response<B>>
+            + Clone
+            + Send
+            + 'static,
+        S::Future: Send,
+        S::Error: Into<BoxError>,
+        B: http_body::Body + Send + 'static,
+        B::Data: Send,
+        B::Error: Into<BoxError>,
+    {
+        ClientBuilder::new(HyperTransport::new(service))
     }
 
-    /// Initiates a new [verify (2FA) request][verify] for the given phone number.
+    /// Creates a builder to configure a new `Client` whose default HTTP client is wrapped in the
+    /// given `tower` layer, such as a [`ServiceBuilder`] stack of `tower` or `tower-http`
+    /// middleware.
+    ///
+    /// This allows standard middleware, e.g. for tracing, timeouts, or request signing by a
+    /// proxy, to be applied to every request sent by the client, in addition to the middleware
+    /// configured on the builder. The resulting client is a
+    /// [`LayeredClient`](./type.LayeredClient.html).
+    ///
+    /// To wrap a different HTTP client, or to tune the connection pool of the default one, build
+    /// the service yourself and pass it to [`Client::from_service()`](#method.from_service)
+    /// instead.
+    ///
+    /// [`ServiceBuilder`]: https://docs.rs/tower/0.5/tower/struct.ServiceBuilder.html
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use http::header::{HeaderValue, USER_AGENT};
+    /// use tower::ServiceBuilder;
+    /// use vonage::Client;
+    ///
+    /// # fn run() -> vonage::Result<()> {
+    /// let middleware = ServiceBuilder::new().map_request(|mut request: http::Request<_>| {
+    ///     let value = HeaderValue::from_static("my-app/1.0");
+    ///     request.headers_mut().insert(USER_AGENT, value);
+    ///     request
+    /// });
+    ///
+    /// let client = Client::layered(middleware)
+    ///     .api_key("<api_key>", "<api_secret>")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn layered<L, B>(layer: L) -> ClientBuilder<HyperTransport<L::Service>>
+    where
+        L: tower_layer::Layer<transport::DefaultClient>,
+        L::Service: tower_service::Service<Request<Full<Bytes>>, Response = http::Response<B>>
+            + Clone
+            + Send
+            + 'static,
+        <L::Service as tower_service::Service<Request<Full<Bytes>>>>::Future: Send,
+        <L::Service as tower_service::Service<Request<Full<Bytes>>>>::Error: Into<BoxError>,
+        B: http_body::Body + Send + 'static,
+        B::Data: Send,
+        B::Error: Into<BoxError>,
+    {
+        let client = transport::build_default_client(&transport::default_client_builder());
+        ClientBuilder::new(HyperTransport::new(layer.layer(client)))
+    }
+}
+
+impl<C: HttpTransport> Client<C> {
+    /// Creates a builder to configure a new `Client` which sends requests over the given
+    /// [`HttpTransport`](./transport/trait.HttpTransport.html).
+    ///
+    /// This allows the client to run on any HTTP stack or async runtime. With the `reqwest`
+    /// feature enabled, a [`reqwest::Client`] can be used by wrapping it in a
+    /// [`ReqwestTransport`](./transport/struct.ReqwestTransport.html).
+    ///
+    /// [`reqwest::Client`]: https://docs.rs/reqwest/0.12/reqwest/struct.Client.html
+    #[inline]
+    pub fn from_transport(transport: C) -> ClientBuilder<C> {
+        ClientBuilder::new(transport)
+    }
+}
+
+impl<C: HttpTransport, A: Credentials> Client<C, A> {
+    /// Sends a request to an endpoint which this library does not model yet, returning the raw
+    /// response.
+    ///
+    /// Relative paths are resolved against `https://api.nexmo.com`, while absolute URLs, such as
+    /// `https://rest.nexmo.com/sms/json`, are used as-is. A non-empty `body` is sent as JSON.
+    ///
+    /// The configured credentials are applied the same way as for modeled products:
+    ///
+    /// * If a JWT application ID and private key were configured, a signed JWT is sent as a bearer
+    ///   token.
+    /// * Otherwise, if an [SMS signature secret](./struct.ClientBuilder.html#method.sms_signature)
+    ///   was configured, the API key, a timestamp, and the resulting `sig` are appended to the
+    ///   query string.
+    /// * Otherwise, the API key and API secret pair are sent with HTTP Basic authentication.
+    ///
+    /// Absolute URLs must use `https` and point to a Vonage host, i.e. `nexmo.com`, `vonage.com`, or
+    /// one of their subdomains, so that credentials are never sent elsewhere.
+    ///
+    /// The response is returned regardless of its status code, so only transport failures and
+    /// client-side middleware, such as rate limits and circuit breakers, produce an `Err`, besides
+    /// an error of kind [`ErrorKind::InvalidRequest`] if `path` is not a valid path or Vonage URL.
+    /// Requests
+    /// to `/sms/` and `/v1/messages` endpoints are reported to middleware as [`Product::Sms`] and
+    /// [`Product::Messages`] respectively, and all others as [`Product::Other`].
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ./enum.ErrorKind.html#variant.InvalidRequest
+    /// [`Product::Sms`]: ./enum.Product.html#variant.Sms
+    /// [`Product::Messages`]: ./enum.Product.html#variant.Messages
+    /// [`Product::Other`]: ./enum.Product.html#variant.Other
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use http::Method;
+    /// use vonage::Client;
+    ///
+    /// # async fn run() -> vonage::Result<()> {
+    /// let client = Client::new("<api_key>", "<api_secret>");
+    /// let response = client
+    ///     .request_raw(Method::GET, "/account/get-balance", "")
+    ///     .await?;
+    /// println!("{}", String::from_utf8_lossy(response.body()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn request_raw(
+        &self,
+        method: Method,
+        path: &str,
+        body: impl Into<Bytes>,
+    ) -> Result<Response<Bytes>> {
+        use http::header::{ACCEPT, CONTENT_TYPE};
+
+        let body = body.into();
+        let mut builder = Request::builder()
+            .method(method)
+            .header(ACCEPT, "application/json");
+        if !body.is_empty() {
+            builder = builder.header(CONTENT_TYPE, "application/json");
+        }
+
+        let request = self.encode_request_raw(builder, path, body)?;
+        let product = Product::for_raw_path(request.uri().path());
+        self.http_client.send(product, request, Ok).await
+    }
+
+    /// Downloads a potentially large file, such as a call recording, streaming it chunk-by-chunk
+    /// instead of buffering it in memory.
+    ///
+    /// The `url` is resolved and authenticated the same way as in
+    /// [`request_raw()`](#method.request_raw), so URLs received in webhooks, e.g. the
+    /// `recording_url` of a call, are rejected unless they use `https` and point to a Vonage host.
+    /// The returned [`Download`](./struct.Download.html)
+    /// can be written to any [`AsyncWrite`], optionally reporting its progress along the way.
+    ///
+    /// [`AsyncWrite`]: https://docs.rs/futures/0.3/futures/io/trait.AsyncWrite.html
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures::io::AllowStdIo;
+    /// use vonage::Client;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::builder().jwt("<app_id>", "<private_key>").build()?;
+    /// let mut file = AllowStdIo::new(std::fs::File::create("recording.mp3")?);
+    /// let written = client
+    ///     .download("/v1/files/aaaaaaaa-bbbb-cccc-dddd-0123456789ab")
+    ///     .on_progress(|progress| println!("{} bytes so far", progress.downloaded()))
+    ///     .write_to(&mut file)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn download(&self, url: &str) -> Download<C> {
+        use http::header::{ACCEPT, ACCEPT_ENCODING};
+
+        // Media is rarely worth compressing, and uncompressed bodies can be streamed as-is.
+        let builder = Request::builder()
+            .method(Method::GET)
+            .header(ACCEPT, "*/*")
+            .header(ACCEPT_ENCODING, "identity");
+
+        let request = self.encode_request_raw(builder, url, Bytes::new());
+        Download::new(self.http_client.clone(), request)
+    }
+
+    /// Returns a handle to the [Verify v2][verify] API.
+    ///
+    /// [verify]: https://developer.vonage.com/en/api/verify.v2
+    ///
+    /// Requests are authenticated with a JWT if an application ID and private key were
+    /// configured, and with the API key and API secret otherwise.
+    pub fn verify_v2(&self) -> verify::v2::VerifyApi<C> {
+        verify::v2::VerifyApi::new(self.http_client.clone(), self.authentication.clone())
+    }
+
+    /// Resolves `path` against the API base URL and applies the configured credentials.
+    fn encode_request_raw(
+        &self,
+        mut builder: http::request::Builder,
+        path: &str,
+        body: Bytes,
+    ) -> Result<Request<Bytes>> {
+        let mut uri = resolve_url(path);
+
+        let parsed = uri.parse::<http::Uri>();
+        let mut validator = Validator::new();
+        validator.check(
+            parsed.is_ok(),
+            "path",
+            "must be a valid path or absolute URL",
+        );
+        validator.check(
+            parsed.as_ref().map_or(true, is_vonage_url),
+            "path",
+            "must be an https URL on a Vonage host",
+        );
+        validator.finish()?;
+
+        match self.sms_signature.as_ref() {
+            Some(secret) if !self.authentication.has_jwt() => {
+                let (path, query) = uri.split_once('?').unwrap_or((&uri, ""));
+                uri = format!(
+                    "{}?{}",
+                    path,
+                    self.authentication.sign_query(secret, query)?
+                );
+            }
+            _ => {
+                let (name, value) = self.authentication.to_preferred_auth_header()?;
+                builder = builder.header(name, value);
+            }
+        }
+
+        let request = builder.uri(uri).body(body);
+        Ok(request.expect("http::RequestBuilder cannot fail"))
+    }
+}
+
+impl<C: HttpTransport, A: HasApiKey> Client<C, A> {
+    /// Returns a handle to the [Verify (2FA)][verify] API.
     ///
     /// [verify]: https://developer.nexmo.com/api/verify
     ///
-    /// Returns `Err` if this client was not configured with an API key and API secret, and returns
-    /// `Ok` otherwise.
-    pub fn verify(&self, phone: PhoneNumber, brand: impl Into<String>) -> Result<Verify<C>> {
-        // FIXME: While "brand" is a required field in regular verify requests, it is not present
-        // at all in PSD2 verify requests. Currently, we discard the `brand` parameter if `.psd2()`
-        // is called anywhere in the method chain. There might be a better way to do this.
-        Verify::new(
+    /// This method is only available if this client was configured with an API key and API
+    /// secret.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vonage::Client;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::new("<api_key>", "<api_secret>");
+    /// let pending = client
+    ///     .verify()
+    ///     .request("+14155550100".parse()?, "vonage-rs")
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn verify(&self) -> VerifyApi<C> {
+        let key_pair = self
+            .authentication
+            .api_key_pair()
+            .expect("`HasApiKey` clients always have an API key pair");
+        VerifyApi::new(self.http_client.clone(), key_pair.clone())
+    }
+
+    /// Returns a handle to the [Numbers][numbers] API.
+    ///
+    /// [numbers]: https://developer.nexmo.com/api/numbers
+    ///
+    /// This method is only available if this client was configured with an API key and API
+    /// secret.
+    pub fn numbers(&self) -> NumbersApi<C> {
+        let key_pair = self
+            .authentication
+            .api_key_pair()
+            .expect("`HasApiKey` clients always have an API key pair");
+        NumbersApi::new(self.http_client.clone(), key_pair.clone())
+    }
+
+    /// Returns a handle to the [Number Insight][ni] API.
+    ///
+    /// [ni]: https://developer.nexmo.com/api/number-insight
+    ///
+    /// This method is only available if this client was configured with an API key and API
+    /// secret.
+    pub fn number_insight(&self) -> NumberInsightApi<C> {
+        let key_pair = self
+            .authentication
+            .api_key_pair()
+            .expect("`HasApiKey` clients always have an API key pair");
+        NumberInsightApi::new(self.http_client.clone(), key_pair.clone())
+    }
+
+    /// Returns a handle to the [SMS][sms] API.
+    ///
+    /// [sms]: https://developer.nexmo.com/api/sms
+    /// [SMS signature secret]: ./struct.ClientBuilder.html#method.sms_signature
+    ///
+    /// Messages are signed with the [SMS signature secret] if one was configured, and
+    /// authenticated with the API secret otherwise.
+    ///
+    /// This method is only available if this client was configured with an API key and API
+    /// secret.
+    pub fn sms(&self) -> SmsApi<C> {
+        let key_pair = self
+            .authentication
+            .api_key_pair()
+            .expect("`HasApiKey` clients always have an API key pair");
+        SmsApi::new(
             self.http_client.clone(),
-            &self.authentication,
-            phone,
-            brand.into(),
+            key_pair.clone(),
+            self.sms_signature.clone(),
         )
     }
+
+    /// Returns a handle to the [Reports][reports] API, which summarizes account usage.
+    ///
+    /// [reports]: https://developer.nexmo.com/api/reports
+    ///
+    /// This method is only available if this client was configured with an API key and API
+    /// secret.
+    pub fn usage(&self) -> UsageApi<C> {
+        UsageApi::new(self.http_client.clone(), self.authentication.clone())
+    }
+
+    /// Returns a handle to the [Applications][applications] API.
+    ///
+    /// [applications]: https://developer.nexmo.com/api/application.v2
+    ///
+    /// This method is only available if this client was configured with an API key and API
+    /// secret.
+    pub fn applications(&self) -> ApplicationsApi<C> {
+        ApplicationsApi::new(self.http_client.clone(), self.authentication.clone())
+    }
 }
 
-impl<C> Debug for Client<C> {
+impl<C: HttpTransport, A: HasJwt> Client<C, A> {
+    /// Returns a handle to the [Voice][voice] API.
+    ///
+    /// [voice]: https://developer.nexmo.com/api/voice
+    ///
+    /// This method is only available if this client was configured with an application ID and
+    /// private key.
+    pub fn voice(&self) -> VoiceApi<C> {
+        VoiceApi::new(self.http_client.clone(), self.authentication.clone())
+    }
+
+    /// Returns a background task which renews the JWT of this client before it expires.
+    ///
+    /// See [`JwtRefresher`](./struct.JwtRefresher.html) for details.
+    ///
+    /// This method is only available if this client was configured with an application ID and
+    /// private key.
+    pub fn jwt_refresher(&self) -> JwtRefresher<C> {
+        JwtRefresher::new(self.http_client.clone(), self.authentication.clone())
+    }
+}
+
+impl<C, A> Clone for Client<C, A> {
+    fn clone(&self) -> Self {
+        Client {
+            http_client: self.http_client.clone(),
+            authentication: self.authentication.clone(),
+            sms_signature: self.sms_signature.clone(),
+            credentials: PhantomData,
+        }
+    }
+}
+
+impl<C, A> Debug for Client<C, A> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.debug_struct(stringify!(Client))
+            .field("http_client", &self.http_client)
             .field("authentication", &self.authentication)
             .field("sms_signature", &self.sms_signature)
             .finish()
@@ -139,22 +658,62 @@ impl<C> Debug for Client<C> {
 /// [jwt]: https://jwt.io/
 ///
 /// This builder lets you to specify one or both authentication methods when constructing a new
-/// `Client`, depending on which Vonage products are to be used at runtime.
-pub struct ClientBuilder<C = HyperClient> {
-    http_client: C,
+/// `Client`, depending on which Vonage products are to be used at runtime. The configured methods
+/// are tracked in the `A` type parameter, so the builder can only be built once at least one of
+/// them has been specified. See the [`credentials`](./credentials/index.html) module for details.
+pub struct ClientBuilder<C = DefaultTransport, A = NoCredentials> {
+    transport: C,
     auth_builder: AuthBuilder,
     sms_signature: Option<SignatureSecret>,
+    rate_limits: HashMap<Product, RateLimit>,
+    shared_rate_limiter: Option<SharedRateLimiter>,
+    circuit_breaker: Option<CircuitBreaker>,
+    failover: HashMap<Product, Failover>,
+    policy: RequestPolicy,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    metrics: Option<Arc<dyn MetricsSink>>,
+    error_hooks: Vec<Arc<ErrorHook>>,
+    app_info: Option<(String, String)>,
+    cancellation: Option<CancellationToken>,
+    default_headers: HeaderMap,
+    response_cache: Option<ResponseCache>,
+    sender_pool: Option<SenderPool>,
+    sender_id_check: SenderIdCheck,
+    test_mode: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    hyper_builder: hyper_util::client::legacy::Builder,
+    credentials: PhantomData<A>,
 }
 
 impl<C> ClientBuilder<C> {
-    fn new(http_client: C) -> Self {
+    fn new(transport: C) -> Self {
         ClientBuilder {
-            http_client,
+            transport,
             auth_builder: Auth::builder(),
             sms_signature: None,
+            rate_limits: HashMap::new(),
+            shared_rate_limiter: None,
+            circuit_breaker: None,
+            failover: HashMap::new(),
+            policy: RequestPolicy::new(),
+            interceptors: Vec::new(),
+            metrics: None,
+            error_hooks: Vec::new(),
+            app_info: None,
+            cancellation: None,
+            default_headers: HeaderMap::new(),
+            response_cache: None,
+            sender_pool: None,
+            sender_id_check: SenderIdCheck::default(),
+            test_mode: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            hyper_builder: transport::default_client_builder(),
+            credentials: PhantomData,
         }
     }
+}
 
+impl<C, A> ClientBuilder<C, A> {
     /// Configures the API key and API secret pair for products that require this form of
     /// authentication.
     ///
@@ -162,9 +721,16 @@ impl<C> ClientBuilder<C> {
     ///
     /// This authentication method is required for the [SMS](https://developer.nexmo.com/api/sms)
     /// and [Verify (2FA)](https://developer.nexmo.com/api/verify) products.
-    pub fn api_key(mut self, api_key: impl Into<String>, secret: impl Into<String>) -> Self {
+    pub fn api_key(
+        mut self,
+        api_key: impl Into<String>,
+        secret: impl Into<String>,
+    ) -> ClientBuilder<C, A::Output>
+    where
+        A: AddApiKey,
+    {
         self.auth_builder.api_key(api_key, secret);
-        self
+        self.with_credentials()
     }
 
     /// Configures the application ID and private [JWT] signing key for products that require this
@@ -172,12 +738,35 @@ impl<C> ClientBuilder<C> {
     ///
     /// [JWT]: https://jwt.io/
     ///
+    /// `private_key` is the PEM-encoded RSA private key of the application, as downloaded from the
+    /// Vonage dashboard, which signs the JWTs with RS256. [`build()`](#method.build) returns an
+    /// error of kind [`ErrorKind::Auth`] if it is malformed.
+    ///
+    /// [`ErrorKind::Auth`]: ./enum.ErrorKind.html#variant.Auth
+    ///
     /// # Product support
     ///
     /// This authentication method is required for the
     /// [Voice](https://developer.nexmo.com/api/voice) product.
-    pub fn jwt(mut self, app_id: impl Into<String>, private_key: impl Into<String>) -> Self {
+    pub fn jwt(
+        mut self,
+        app_id: impl Into<String>,
+        private_key: impl Into<String>,
+    ) -> ClientBuilder<C, A::Output>
+    where
+        A: AddJwt,
+    {
         self.auth_builder.jwt(app_id, private_key);
+        self.with_credentials()
+    }
+
+    /// Sets how long the JWTs signed with the [application credentials](#method.jwt) are valid.
+    ///
+    /// A JWT is signed once and reused by all requests until four fifths of this time have
+    /// passed, or renewed ahead of time by a [`JwtRefresher`](./struct.JwtRefresher.html). By
+    /// default, this is 15 minutes.
+    pub fn jwt_ttl(mut self, ttl: Duration) -> Self {
+        self.auth_builder.jwt_ttl(ttl);
         self
     }
 
@@ -192,38 +781,478 @@ impl<C> ClientBuilder<C> {
         self
     }
 
+    /// Limits the rate at which requests are sent to the given Vonage `product`.
+    ///
+    /// Requests exceeding the limit are delayed on the client side until they can be sent, which
+    /// smooths out bursty traffic instead of tripping Vonage's own throttling errors. Calling this
+    /// method again for the same product replaces the previous limit.
+    ///
+    /// By default, no rate limits are applied.
+    pub fn rate_limit(mut self, product: Product, limit: RateLimit) -> Self {
+        self.rate_limits.insert(product, limit);
+        self
+    }
+
+    /// Enforces the limits of `limiter` jointly with every other client it is passed to.
+    ///
+    /// Clones of a `Client` already share its rate limits. This is only needed to share them
+    /// between separately built clients. The limits of `limiter` apply in addition to those set
+    /// with [`rate_limit()`](#method.rate_limit).
+    pub fn shared_rate_limiter(mut self, limiter: SharedRateLimiter) -> Self {
+        self.shared_rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Enables a circuit breaker which fails fast when an endpoint keeps returning errors.
+    ///
+    /// While the circuit for an endpoint is open, requests to it return an error of kind
+    /// [`ErrorKind::CircuitOpen`](./enum.ErrorKind.html#variant.CircuitOpen) without being sent.
+    ///
+    /// By default, no circuit breaker is used.
+    pub fn circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// Fails over between the given regional hosts when requests to the given Vonage `product`
+    /// keep failing.
+    ///
+    /// Each host's health is tracked separately, and requests are sent to the first healthy host
+    /// in the list. Calling this method again for the same product replaces the previous hosts.
+    /// Products which are not served from multiple regions ignore this setting; see
+    /// [`Failover`](./struct.Failover.html) for details.
+    ///
+    /// By default, all requests are sent to the product's global endpoint.
+    pub fn failover(mut self, product: Product, failover: Failover) -> Self {
+        self.failover.insert(product, failover);
+        self
+    }
+
+    /// Sets the timeout and retry policy of all product handles, e.g.
+    /// [`RequestPolicy::exponential(3)`](./struct.RequestPolicy.html#method.exponential).
+    ///
+    /// Handles can override it with their own `policy()` method. By default, requests have no
+    /// timeout and are not retried.
+    pub fn policy(mut self, policy: RequestPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Retries failed requests of all product handles according to `policy`.
+    ///
+    /// This is an alias of [`policy()`](#method.policy), for when only retries are of interest.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vonage::{Client, RetryPolicy};
+    ///
+    /// # fn main() -> vonage::Result<()> {
+    /// let client = Client::builder()
+    ///     .api_key("<key>", "<secret>")
+    ///     .retry(RetryPolicy::exponential(3))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn retry(self, policy: RetryPolicy) -> Self {
+        self.policy(policy)
+    }
+
+    /// Registers an [`Interceptor`](./trait.Interceptor.html) to be invoked around every request.
+    ///
+    /// This method may be called multiple times. Interceptors run in the order they were added.
+    pub fn interceptor<I>(mut self, interceptor: I) -> Self
+    where
+        I: Interceptor + 'static,
+    {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    /// Registers a [`MetricsSink`](./trait.MetricsSink.html) to be notified of the outcome of
+    /// every request.
+    ///
+    /// Calling this method again replaces the previous sink.
+    pub fn metrics<M>(mut self, sink: M) -> Self
+    where
+        M: MetricsSink + 'static,
+    {
+        self.metrics = Some(Arc::new(sink));
+        self
+    }
+
+    /// Registers a callback to be invoked with every error returned by an API call.
+    ///
+    /// The callback receives the product which the failed request was sent to and the error
+    /// itself, which records the [endpoint](./struct.Error.html#method.endpoint) and
+    /// [kind](./struct.Error.html#method.kind) of the failure. This is useful for centralized
+    /// alerting, regardless of where in the application the call was made.
+    ///
+    /// This method may be called multiple times. Callbacks run in the order they were added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vonage::Client;
+    ///
+    /// let client = Client::builder()
+    ///     .api_key("<api_key>", "<api_secret>")
+    ///     .on_error(|product, error| {
+    ///         eprintln!("{:?} request to {:?} failed: {}", product, error.endpoint(), error);
+    ///     })
+    ///     .build();
+    /// ```
+    pub fn on_error<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(Product, &Error) + Send + Sync + 'static,
+    {
+        self.error_hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Appends the name and version of your application to the `User-Agent` header.
+    ///
+    /// By default, requests are sent with `User-Agent: vonage-rs/<version> rust/<rustc version>`.
+    /// Setting this adds an `<app_name>/<app_version>` suffix, which helps Vonage support identify
+    /// your traffic. It is ignored if either value contains characters not allowed in HTTP headers.
+    pub fn app_info(mut self, name: impl Into<String>, version: impl Into<String>) -> Self {
+        self.app_info = Some((name.into(), version.into()));
+        self
+    }
+
+    /// Adds headers to be sent with every request, such as corporate correlation IDs.
+    ///
+    /// Default headers never replace headers set by the request itself, e.g. `Content-Type`, but
+    /// they do take precedence over the `User-Agent` set by this library. Calling this method
+    /// multiple times accumulates headers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use http::header::{HeaderMap, HeaderValue};
+    /// use vonage::Client;
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("x-correlation-id", HeaderValue::from_static("checkout-service"));
+    ///
+    /// let client = Client::builder()
+    ///     .api_key("<api_key>", "<api_secret>")
+    ///     .default_headers(headers)
+    ///     .build();
+    /// ```
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers.extend(headers);
+        self
+    }
+
+    /// Registers a [`CancellationToken`](./struct.CancellationToken.html) which aborts all
+    /// in-flight and future requests made by this client once cancelled.
+    ///
+    /// Aborted requests return an error of kind
+    /// [`ErrorKind::Cancelled`](./enum.ErrorKind.html#variant.Cancelled).
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Caches responses from slow-changing endpoints, such as pricing and the account balance.
+    ///
+    /// See [`ResponseCache`](./struct.ResponseCache.html) for which endpoints are cached and for
+    /// how long. Calling this method again replaces the previous cache.
+    ///
+    /// By default, no responses are cached.
+    pub fn response_cache(mut self, cache: ResponseCache) -> Self {
+        self.response_cache = Some(cache);
+        self
+    }
+
+    /// Assigns sender IDs from `pool` to outgoing messages which don't set one explicitly.
+    ///
+    /// See [`SenderPool`](./struct.SenderPool.html) for how sender IDs are selected. Calling this
+    /// method again replaces the previous pool.
+    ///
+    /// # Product support
+    ///
+    /// This setting is currently used by the [Verify (2FA)](https://developer.nexmo.com/api/verify)
+    /// product only, and is ignored by PSD2 verify requests, which don't accept a sender ID.
+    pub fn sender_pool(mut self, pool: SenderPool) -> Self {
+        self.sender_pool = Some(pool);
+        self
+    }
+
+    /// Sets how strictly sender IDs are checked against the rules of the destination country
+    /// before a request is sent.
+    ///
+    /// With [`SenderIdCheck::Deny`](./enum.SenderIdCheck.html#variant.Deny), alphanumeric sender
+    /// IDs are rejected for countries which only accept phone numbers, and for countries which
+    /// require registration unless listed in the [`SenderPool`](./struct.SenderPool.html). See
+    /// [`SenderIdProblem`](./enum.SenderIdProblem.html) for all checks.
+    ///
+    /// By default, this is [`SenderIdCheck::Warn`](./enum.SenderIdCheck.html#variant.Warn).
+    pub fn sender_id_check(mut self, check: SenderIdCheck) -> Self {
+        self.sender_id_check = check;
+        self
+    }
+
+    /// Puts the client in test mode, so that it can never send real traffic.
+    ///
+    /// In test mode, requests to endpoints which have a sandbox equivalent are sent to the
+    /// sandbox instead, which currently only applies to sending messages through the
+    /// [Messages API sandbox](https://developer.vonage.com/en/messages/concepts/messages-api-sandbox).
+    /// Requests to all other endpoints, including verify requests, calls and
+    /// [raw requests](./struct.Client.html#method.request_raw), fail with an error of kind
+    /// [`ErrorKind::TestMode`](./enum.ErrorKind.html#variant.TestMode) without being sent.
+    ///
+    /// Failover hosts are ignored in test mode.
+    pub fn test_mode(mut self) -> Self {
+        self.test_mode = true;
+        self
+    }
+
     /// Constructs the configured `Client`.
     ///
-    /// Returns `Ok` if at least one authentication method has been specified, and returns `Err`
-    /// otherwise.
-    pub fn build(self) -> Result<Client<C>> {
+    /// This method is only available once at least one authentication method has been specified:
+    ///
+    /// ```compile_fail
+    /// use vonage::Client;
+    ///
+    /// let client = Client::builder().build();
+    /// ```
+    pub fn build(self) -> Result<Client<C, A>>
+    where
+        A: Credentials,
+    {
+        let user_agent = self
+            .app_info
+            .and_then(|(name, version)| {
+                let value = format!("{} {}/{}", USER_AGENT, name, version);
+                HeaderValue::from_str(&value).ok()
+            })
+            .unwrap_or_else(|| HeaderValue::from_static(USER_AGENT));
+
         Ok(Client {
-            http_client: self.http_client,
+            http_client: HttpClient::new(
+                self.transport,
+                Middleware {
+                    user_agent,
+                    cancellation: self.cancellation,
+                    default_headers: self.default_headers,
+                    rate_limiter: RateLimiter::new(self.rate_limits, self.shared_rate_limiter),
+                    circuits: Circuits::new(self.circuit_breaker),
+                    regions: Regions::new(self.failover),
+                    interceptors: self.interceptors,
+                    metrics: self.metrics,
+                    error_hooks: self.error_hooks,
+                    cache: self.response_cache,
+                    sender_pool: self.sender_pool,
+                    sender_id_check: self.sender_id_check,
+                    test_mode: self.test_mode,
+                },
+            )
+            .with_policy(self.policy),
             authentication: self.auth_builder.build()?,
             sms_signature: self.sms_signature,
+            credentials: PhantomData,
         })
     }
+
+    fn with_credentials<B>(self) -> ClientBuilder<C, B> {
+        ClientBuilder {
+            transport: self.transport,
+            auth_builder: self.auth_builder,
+            sms_signature: self.sms_signature,
+            rate_limits: self.rate_limits,
+            shared_rate_limiter: self.shared_rate_limiter,
+            circuit_breaker: self.circuit_breaker,
+            failover: self.failover,
+            policy: self.policy,
+            interceptors: self.interceptors,
+            metrics: self.metrics,
+            error_hooks: self.error_hooks,
+            app_info: self.app_info,
+            cancellation: self.cancellation,
+            default_headers: self.default_headers,
+            response_cache: self.response_cache,
+            sender_pool: self.sender_pool,
+            sender_id_check: self.sender_id_check,
+            test_mode: self.test_mode,
+            #[cfg(not(target_arch = "wasm32"))]
+            hyper_builder: self.hyper_builder,
+            credentials: PhantomData,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<A> ClientBuilder<DefaultTransport, A> {
+    /// Sets the maximum number of idle connections kept open per host by the default HTTP client.
+    ///
+    /// By default, there is no limit.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.hyper_builder.pool_max_idle_per_host(max);
+        self.rebuild_transport()
+    }
+
+    /// Sets how long idle connections are kept open by the default HTTP client.
+    ///
+    /// Pass `None` to keep idle connections open indefinitely. By default, this is 90 seconds.
+    pub fn pool_idle_timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.hyper_builder.pool_idle_timeout(timeout);
+        self.rebuild_transport()
+    }
+
+    /// Makes the default HTTP client speak HTTP/2 exclusively, multiplexing concurrent requests
+    /// over a single connection instead of opening one connection per in-flight request.
+    ///
+    /// By default, only HTTP/1.1 is used.
+    pub fn http2_only(mut self, enabled: bool) -> Self {
+        self.hyper_builder.http2_only(enabled);
+        self.rebuild_transport()
+    }
+
+    fn rebuild_transport(mut self) -> Self {
+        self.transport = HyperTransport::new(transport::build_default_client(&self.hyper_builder));
+        self
+    }
 }
 
-impl<C> Debug for ClientBuilder<C> {
+impl<C, A> Debug for ClientBuilder<C, A> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.debug_struct(stringify!(ClientBuilder))
             .field("auth_builder", &self.auth_builder)
             .field("sms_signature", &self.sms_signature)
+            .field("rate_limits", &self.rate_limits)
+            .field("shared_rate_limiter", &self.shared_rate_limiter)
+            .field("circuit_breaker", &self.circuit_breaker)
+            .field("failover", &self.failover)
+            .field("interceptors", &self.interceptors.len())
+            .field("metrics", &self.metrics.is_some())
+            .field("error_hooks", &self.error_hooks.len())
+            .field("app_info", &self.app_info)
+            .field("cancellation", &self.cancellation)
+            .field("default_headers", &self.default_headers)
+            .field("response_cache", &self.response_cache)
+            .field("sender_pool", &self.sender_pool)
+            .field("sender_id_check", &self.sender_id_check)
+            .field("test_mode", &self.test_mode)
             .finish()
     }
 }
 
-fn encode_request_post<T>(path: &str, form: T) -> Result<Request<Body>>
+/// Characters which must be escaped within a single URI path segment.
+///
+/// See the `segment` production in [RFC 3986](https://tools.ietf.org/html/rfc3986#section-3.3).
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// Room reserved for the query string or form body when encoding a request, which covers the
+/// parameters of most endpoints without reallocating.
+const PARAMS_CAPACITY: usize = 256;
+
+/// Builds the URI of a JSON API endpoint from its path segments, percent-encoding each one.
+///
+/// The returned string has spare capacity for a query string to be appended.
+fn encode_uri(path: &[&str]) -> String {
+    encode_uri_at(VONAGE_URL_BASE, path, "/json")
+}
+
+/// Resolves `path` against the API base URL, unless it is an absolute URL already.
+fn resolve_url(path: &str) -> String {
+    if path.starts_with("https://") || path.starts_with("http://") {
+        path.to_owned()
+    } else {
+        format!("{}/{}", VONAGE_URL_BASE, path.trim_start_matches('/'))
+    }
+}
+
+/// Returns whether credentials may be sent to `uri`, i.e. it uses HTTPS and is served by Vonage.
+fn is_vonage_url(uri: &http::Uri) -> bool {
+    let host = uri.host().unwrap_or_default().to_ascii_lowercase();
+    let is_vonage_host = ["nexmo.com", "vonage.com"].iter().any(|domain| {
+        let subdomain = host.strip_suffix(domain).and_then(|s| s.strip_suffix('.'));
+        host == *domain || subdomain.is_some_and(|s| !s.is_empty())
+    });
+    uri.scheme() == Some(&http::uri::Scheme::HTTPS) && is_vonage_host
+}
+
+/// Like `encode_uri()`, but for the older endpoints served from `rest.nexmo.com`, such as the
+/// Numbers API, which have no `/json` suffix.
+fn encode_rest_uri(path: &[&str]) -> String {
+    encode_uri_at(VONAGE_REST_URL_BASE, path, "")
+}
+
+/// Percent-encodes a caller-supplied `id` as a single path segment, so that it cannot address
+/// another resource.
+///
+/// Returns an error of kind `InvalidRequest` for the parameter `name` if `id` is empty, or is a
+/// `.` or `..` segment.
+pub(crate) fn encode_path_segment(name: &str, id: &str) -> Result<String> {
+    let mut validator = Validator::new();
+    validator.check(
+        !id.is_empty() && id != "." && id != "..",
+        name,
+        "must be a non-empty ID",
+    );
+    validator.finish()?;
+    Ok(utf8_percent_encode(id, PATH_SEGMENT).to_string())
+}
+
+fn encode_uri_at(base: &str, path: &[&str], suffix: &str) -> String {
+    let path_len: usize = path.iter().map(|segment| segment.len() + 1).sum();
+    let capacity = base.len() + path_len + suffix.len() + PARAMS_CAPACITY;
+    let mut uri = String::with_capacity(capacity);
+
+    uri.push_str(base);
+    for segment in path {
+        uri.push('/');
+        uri.extend(utf8_percent_encode(segment, PATH_SEGMENT));
+    }
+
+    uri.push_str(suffix);
+    uri
+}
+
+/// URL-encodes `params` directly onto the end of `target`, after the first `start` bytes.
+fn encode_params<T: Serialize>(target: String, start: usize, params: T) -> Result<String> {
+    let mut encoder = form_urlencoded::Serializer::for_suffix(target, start);
+    params.serialize(serde_urlencoded::Serializer::new(&mut encoder))?;
+    Ok(encoder.finish())
+}
+
+fn encode_request_post<T>(path: &[&str], form: T) -> Result<Request<Bytes>>
+where
+    T: Serialize,
+{
+    encode_post(encode_uri(path), form)
+}
+
+fn encode_post<T>(uri: String, form: T) -> Result<Request<Bytes>>
 where
     T: Serialize,
 {
-    use hyper::header::{ACCEPT, CONTENT_TYPE};
+    use http::header::{ACCEPT, CONTENT_TYPE};
 
-    let encoded = serde_urlencoded::to_string(form)?;
+    let encoded = encode_params(String::with_capacity(PARAMS_CAPACITY), 0, form)?;
     let request = Request::builder()
-        .method(hyper::Method::POST)
-        .uri(format!("{}{}/json", VONAGE_URL_BASE, path))
+        .method(http::Method::POST)
+        .uri(uri)
         .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
         .header(ACCEPT, "application/json")
         .body(encoded.into())
@@ -232,19 +1261,32 @@ where
     Ok(request)
 }
 
-fn encode_request_get<T>(path: &str, query_params: T) -> Result<Request<Body>>
+fn encode_request_get<T>(path: &[&str], query_params: T) -> Result<Request<Bytes>>
 where
     T: Serialize,
 {
-    use hyper::header::{ACCEPT, CONTENT_TYPE};
+    encode_get(encode_uri(path), query_params)
+}
+
+fn encode_get<T>(mut uri: String, query_params: T) -> Result<Request<Bytes>>
+where
+    T: Serialize,
+{
+    use http::header::{ACCEPT, CONTENT_TYPE};
+
+    uri.push('?');
+    let start = uri.len();
+    let mut uri = encode_params(uri, start, query_params)?;
+    if uri.len() == start {
+        uri.pop();
+    }
 
-    let encoded = serde_urlencoded::to_string(query_params)?;
     let request = Request::builder()
-        .method(hyper::Method::GET)
-        .uri(format!("{}{}/json?{}", VONAGE_URL_BASE, path, encoded))
+        .method(http::Method::GET)
+        .uri(uri)
         .header(CONTENT_TYPE, "application/json")
         .header(ACCEPT, "application/json")
-        .body(Body::empty())
+        .body(Bytes::new())
         .expect("http::RequestBuilder cannot fail");
 
     Ok(request)
@@ -257,20 +1299,415 @@ mod tests {
     #[test]
     fn creates_client() {
         // client with api key and secret by default.
-        let client = Client::new("api key", "private key");
+        let _client = Client::new("api key", "private key");
 
         // Different methods of creating signatures.
-        let signature = SignatureSecret::new("secret");
+        let _signature = SignatureSecret::new("secret");
         let signature = SignatureSecret::with_method(SignatureMethod::Md5Hash, "secret");
 
-        let client = Client::builder()
+        let _client = Client::builder()
             .api_key("api key", "private key")
             .sms_signature(signature.clone())
             .build();
 
-        let client = Client::builder()
+        let _client = Client::builder()
             .jwt("app id", "private key")
             .sms_signature(signature)
             .build();
+
+        let _client = Client::builder()
+            .api_key("api key", "private key")
+            .rate_limit(Product::Verify, RateLimit::per_second(30).burst(5))
+            .circuit_breaker(CircuitBreaker::new(5, Duration::from_secs(30)))
+            .failover(Product::Verify, Failover::new(vec!["api.nexmo.com"]))
+            .build();
+
+        let _client = Client::builder()
+            .api_key("api key", "private key")
+            .pool_max_idle_per_host(8)
+            .pool_idle_timeout(Duration::from_secs(30))
+            .http2_only(true)
+            .build();
+    }
+
+    #[test]
+    fn encodes_reserved_characters() {
+        let uri = encode_uri(&["numbers", "a/b?c#d e%"]);
+        assert_eq!(
+            uri,
+            "https://api.nexmo.com/numbers/a%2Fb%3Fc%23d%20e%25/json"
+        );
+
+        let request = encode_request_get(&["verify"], ());
+        assert_eq!(request.unwrap().uri(), "https://api.nexmo.com/verify/json");
+
+        let request = encode_request_get(&["verify", "search"], [("request_id", "a&b=c#d/e f+")]);
+        let uri = request.unwrap().uri().clone();
+        assert_eq!(uri.path(), "/verify/search/json");
+        let query: Vec<(String, String)> =
+            serde_urlencoded::from_str(uri.query().unwrap()).unwrap();
+        assert_eq!(query, [("request_id".into(), "a&b=c#d/e f+".into())]);
+    }
+
+    #[tokio::test]
+    async fn preserves_reserved_characters_across_endpoints() {
+        use std::collections::HashMap;
+        use std::convert::Infallible;
+
+        use http::Response;
+        use http_body_util::BodyExt;
+        use tower::service_fn;
+
+        const REQUEST_ID: &str = "a&b=c#d/e f+%";
+
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            let (parts, body) = request.into_parts();
+            let body = body.collect().await.unwrap().to_bytes();
+            let form: HashMap<String, String> = serde_urlencoded::from_bytes(&body).unwrap();
+
+            let response = match parts.uri.path() {
+                "/verify/json" => {
+                    assert_eq!(form["number"], "+14155550100");
+                    assert_eq!(form["brand"], "Q&A = #1 / 100%");
+                    format!(r#"{{"request_id":"{}","status":"0"}}"#, REQUEST_ID)
+                }
+                "/verify/control/json" => {
+                    assert_eq!(form["request_id"], REQUEST_ID);
+                    assert_eq!(form["cmd"], "trigger_next_event");
+                    r#"{"status":"0"}"#.to_owned()
+                }
+                "/verify/check/json" => {
+                    assert_eq!(form["request_id"], REQUEST_ID);
+                    assert_eq!(form["code"], "12&4");
+                    format!(
+                        r#"{{"request_id":"{}","event_id":"0A00000012345678","status":"0","price":"0.10000000","currency":"EUR","estimated_price_messages_sent":"0.03330000"}}"#,
+                        REQUEST_ID
+                    )
+                }
+                other => panic!("unexpected path {}", other),
+            };
+
+            Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(response))))
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let mut pending = client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "Q&A = #1 / 100%")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(pending.request_id().to_string(), REQUEST_ID);
+
+        pending.trigger_next_event().await.unwrap();
+        pending.check("12&4").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn sends_default_headers() {
+        use std::convert::Infallible;
+
+        use http::header::CONTENT_TYPE;
+        use http::Response;
+        use tower::service_fn;
+
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            let headers = request.headers();
+            assert_eq!(headers["x-correlation-id"], "abc");
+            assert_eq!(headers[CONTENT_TYPE], "application/x-www-form-urlencoded");
+            let body = r#"{"request_id":"abcdef0123456789abcdef0123456789","status":"0"}"#;
+            Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-correlation-id", HeaderValue::from_static("abc"));
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .default_headers(headers)
+            .build()
+            .unwrap();
+
+        client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .send()
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_requests_before_sending() {
+        use std::convert::Infallible;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use http::Response;
+        use tower::service_fn;
+
+        let sent = Arc::new(AtomicUsize::new(0));
+        let service = service_fn({
+            let sent = sent.clone();
+            move |_: Request<Full<Bytes>>| {
+                sent.fetch_add(1, Ordering::SeqCst);
+                async move { Ok::<_, Infallible>(Response::new(Full::new(Bytes::new()))) }
+            }
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let error = client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .sender_id("VeryLongSenderId")
+            .pin_expiry(Duration::from_secs(30))
+            .send()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error.kind(), ErrorKind::InvalidRequest));
+        let names: Vec<_> = error
+            .as_invalid_request()
+            .unwrap()
+            .invalid_parameters()
+            .iter()
+            .map(|p| p.name())
+            .collect();
+        assert_eq!(names, ["pin_expiry", "sender_id"]);
+
+        let error = client
+            .request_raw(Method::GET, "/account/get balance", "")
+            .await
+            .unwrap_err();
+        let invalid = error.as_invalid_request().unwrap().invalid_parameters();
+        assert_eq!(invalid[0].name(), "path");
+
+        for url in &[
+            "http://rest.nexmo.com/sms/json",
+            "https://example.com/sms/json",
+            "https://api.nexmo.com.example.com/account/get-balance",
+            "https://evilnexmo.com/account/get-balance",
+        ] {
+            let error = client.request_raw(Method::GET, url, "").await.unwrap_err();
+            let invalid = error.as_invalid_request().unwrap().invalid_parameters();
+            assert_eq!(invalid[0].name(), "path", "{}", url);
+        }
+
+        assert_eq!(sent.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn handles_are_clone_send_and_sync() {
+        fn assert_handle<T: Clone + Send + Sync + 'static>() {}
+
+        assert_handle::<Client>();
+        assert_handle::<Client<DefaultTransport, credentials::FullAuth>>();
+        assert_handle::<VerifyApi>();
+        assert_handle::<verify::PendingVerify>();
+        assert_handle::<verify::v2::VerifyApi>();
+        assert_handle::<verify::v2::PendingVerify>();
+        assert_handle::<NumbersApi>();
+        assert_handle::<NumberInsightApi>();
+        assert_handle::<SmsApi>();
+        assert_handle::<VoiceApi>();
+        assert_handle::<voice::Call>();
+        assert_handle::<voice::VoicemailDrop>();
+        assert_handle::<voice::Conference>();
+        assert_handle::<voice::AttendedTransfer>();
+        assert_handle::<UsageApi>();
+        assert_handle::<ApplicationsApi>();
+        assert_handle::<JwtRefresher>();
+
+        #[cfg(feature = "blocking")]
+        {
+            assert_handle::<blocking::Client>();
+            assert_handle::<blocking::verify::VerifyApi>();
+            assert_handle::<blocking::verify::PendingVerify>();
+            assert_handle::<blocking::verify::v2::VerifyApi>();
+            assert_handle::<blocking::verify::v2::PendingVerify>();
+            assert_handle::<blocking::numbers::NumbersApi>();
+            assert_handle::<blocking::number_insight::NumberInsightApi>();
+            assert_handle::<blocking::sms::SmsApi>();
+            assert_handle::<blocking::voice::VoiceApi>();
+            assert_handle::<blocking::voice::Call>();
+            assert_handle::<blocking::voice::VoicemailDrop>();
+            assert_handle::<blocking::voice::Conference>();
+            assert_handle::<blocking::voice::AttendedTransfer>();
+            assert_handle::<blocking::usage::UsageApi>();
+            assert_handle::<blocking::applications::ApplicationsApi>();
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_requests_through_layers() {
+        use std::convert::Infallible;
+
+        use http::header::USER_AGENT;
+        use http::Method;
+        use tower::layer::layer_fn;
+        use tower::{service_fn, ServiceBuilder};
+
+        // Replaces the default client, so that nothing is sent over the network.
+        let stub = layer_fn(|_: transport::DefaultClient| {
+            service_fn(|request: Request<Full<Bytes>>| async move {
+                let agent = request.headers().get(USER_AGENT).unwrap();
+                let body = Bytes::copy_from_slice(agent.as_bytes());
+                Ok::<_, Infallible>(Response::new(Full::new(body)))
+            })
+        });
+
+        let middleware = ServiceBuilder::new()
+            .map_request(|mut request: Request<Full<Bytes>>| {
+                let value = HeaderValue::from_static("layered");
+                request.headers_mut().insert(USER_AGENT, value);
+                request
+            })
+            .layer(stub);
+
+        let client = Client::layered(middleware)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+        let response = client
+            .request_raw(Method::GET, "/account/get-balance", "")
+            .await
+            .unwrap();
+        assert_eq!(response.body(), "layered");
+    }
+
+    #[tokio::test]
+    async fn sends_raw_requests() {
+        use std::convert::Infallible;
+        use std::sync::Mutex;
+
+        use http::header::AUTHORIZATION;
+        use http::{Method, Response, StatusCode};
+        use tower::service_fn;
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let service = {
+            let seen = seen.clone();
+            service_fn(move |request: Request<Full<Bytes>>| {
+                let seen = seen.clone();
+                async move {
+                    let auth = request.headers().get(AUTHORIZATION).cloned();
+                    seen.lock().unwrap().push((request.uri().clone(), auth));
+                    let response = Response::builder()
+                        .status(StatusCode::NOT_FOUND)
+                        .body(Full::new(Bytes::from_static(b"not found")))
+                        .unwrap();
+                    Ok::<_, Infallible>(response)
+                }
+            })
+        };
+
+        let client = Client::from_service(service.clone())
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+        let response = client
+            .request_raw(Method::GET, "/account/get-balance?a=b", "")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.body(), "not found");
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .sms_signature(SignatureSecret::new("sig secret"))
+            .build()
+            .unwrap();
+        client
+            .request_raw(Method::POST, "https://rest.nexmo.com/sms/json", "{}")
+            .await
+            .unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen[0].0, "https://api.nexmo.com/account/get-balance?a=b");
+        assert_eq!(seen[0].1.as_ref().unwrap(), "Basic a2V5OnNlY3JldA==");
+
+        assert_eq!(seen[1].0.host(), Some("rest.nexmo.com"));
+        assert!(seen[1].1.is_none());
+        let query: Vec<(String, String)> =
+            serde_urlencoded::from_str(seen[1].0.query().unwrap()).unwrap();
+        let names: Vec<_> = query.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["api_key", "timestamp", "sig"]);
+    }
+
+    #[tokio::test]
+    async fn routes_test_mode_to_sandbox() {
+        use std::convert::Infallible;
+
+        use http::{Method, Response};
+        use tower::service_fn;
+
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(
+                request.uri(),
+                "https://messages-sandbox.nexmo.com/v1/messages"
+            );
+            Ok::<_, Infallible>(Response::new(Full::new(Bytes::from_static(b"{}"))))
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .test_mode()
+            .build()
+            .unwrap();
+        client
+            .request_raw(Method::POST, "/v1/messages", "{}")
+            .await
+            .unwrap();
+
+        let error = client
+            .request_raw(Method::GET, "/account/get-balance", "")
+            .await
+            .unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::TestMode));
+
+        let phone: phonenumber::PhoneNumber = "+14155550100".parse().unwrap();
+        let error = client
+            .verify()
+            .request(phone, "vonage-rs")
+            .send()
+            .await
+            .unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::TestMode));
+    }
+
+    #[tokio::test]
+    async fn sends_user_agent() {
+        use std::convert::Infallible;
+
+        use http::Response;
+        use tower::service_fn;
+
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            let user_agent = request.headers()["user-agent"].to_str().unwrap();
+            assert!(user_agent.starts_with("vonage-rs/0.1.0 rust/"));
+            assert!(user_agent.ends_with(" my-app/1.2.3"));
+            let body = r#"{"request_id":"abcdef0123456789abcdef0123456789","status":"0"}"#;
+            Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .app_info("my-app", "1.2.3")
+            .build()
+            .unwrap();
+
+        client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .send()
+            .await
+            .unwrap();
     }
 }
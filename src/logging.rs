@@ -0,0 +1,86 @@
+//! Debug logging of requests and responses with secrets masked.
+
+use bytes::Bytes;
+use http::{Request, Response};
+
+use crate::interceptor::Interceptor;
+use crate::redact::{redact_body, redact_uri};
+
+const TARGET: &str = "vonage";
+
+/// An [`Interceptor`] which logs every request and response at the `DEBUG` level.
+///
+/// Messages are emitted through the [`log`] crate under the `vonage` target. API secrets,
+/// signatures, PIN codes, and private keys are masked in both URLs and bodies, and headers are
+/// not logged at all, so the output is safe to share when debugging.
+///
+/// [`Interceptor`]: ./trait.Interceptor.html
+/// [`log`]: https://docs.rs/log/0.4/log/
+///
+/// # Example
+///
+/// ```
+/// use vonage::{Client, RequestLogger};
+///
+/// let client = Client::builder()
+///     .api_key("<api_key>", "<api_secret>")
+///     .interceptor(RequestLogger)
+///     .build();
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RequestLogger;
+
+impl Interceptor for RequestLogger {
+    fn on_request(&self, request: &mut Request<Bytes>) {
+        if log::log_enabled!(target: TARGET, log::Level::Debug) {
+            log::debug!(target: TARGET, "{}", describe_request(request));
+        }
+    }
+
+    fn on_response(&self, response: &Response<Bytes>) {
+        if log::log_enabled!(target: TARGET, log::Level::Debug) {
+            log::debug!(target: TARGET, "{}", describe_response(response));
+        }
+    }
+}
+
+fn describe_request(request: &Request<Bytes>) -> String {
+    let mut line = format!("--> {} {}", request.method(), redact_uri(request.uri()));
+    if !request.body().is_empty() {
+        line.push(' ');
+        line.push_str(&redact_body(request.headers(), request.body()));
+    }
+    line
+}
+
+fn describe_response(response: &Response<Bytes>) -> String {
+    format!(
+        "<-- {} {}",
+        response.status(),
+        redact_body(response.headers(), response.body())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http::header::CONTENT_TYPE;
+
+    #[test]
+    fn describes_redacted_messages() {
+        let request = Request::post("https://api.nexmo.com/verify/check/json")
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Bytes::from_static(
+                b"api_key=key&api_secret=secret&request_id=abc&code=1234",
+            ))
+            .unwrap();
+        assert_eq!(
+            describe_request(&request),
+            "--> POST /verify/check/json api_key=REDACTED&api_secret=REDACTED&request_id=abc&code=REDACTED"
+        );
+
+        let response = Response::new(Bytes::from_static(br#"{"status":"0"}"#));
+        assert_eq!(describe_response(&response), r#"<-- 200 OK {"status":"0"}"#);
+    }
+}
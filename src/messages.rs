@@ -0,0 +1,198 @@
+//! Channel capabilities of senders for the [Messages API](https://developer.nexmo.com/api/messages-olympus).
+//!
+//! Not every sender can deliver every kind of message: most long codes only support SMS, MMS is
+//! limited to some North American numbers, and WhatsApp, RCS, Viber and Messenger each require an
+//! external account linked to the Vonage application. A [`Sender`] describes what a single number
+//! or external account supports, and [`route()`] picks the first capable sender for a message.
+//!
+//! The capabilities of owned numbers are reported by
+//! [`NumbersApi::capabilities()`](../numbers/struct.NumbersApi.html#method.capabilities). External
+//! accounts cannot be queried with an API key and API secret, so they are declared with
+//! [`Sender::external_account()`](./struct.Sender.html#method.external_account).
+//!
+//! [`Sender`]: ./struct.Sender.html
+//! [`route()`]: ./fn.route.html
+//!
+//! # Example
+//!
+//! ```
+//! use vonage::messages::{self, Channel, MessageType, Sender};
+//! use vonage::numbers::Feature;
+//!
+//! let senders = vec![
+//!     Sender::number("14155550100", &[Feature::Sms, Feature::Mms]),
+//!     Sender::external_account(Channel::WhatsApp, "14155550101"),
+//! ];
+//!
+//! let preference = [Channel::Rcs, Channel::WhatsApp, Channel::Mms, Channel::Sms];
+//! let (channel, sender) = messages::route(&senders, &preference, MessageType::Image).unwrap();
+//! assert_eq!(channel, Channel::WhatsApp);
+//! assert_eq!(sender.id(), "14155550101");
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::numbers::Feature;
+
+/// A channel which messages can be sent over.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum Channel {
+    /// Plain SMS.
+    Sms,
+    /// MMS, which is only available from some US and Canadian numbers.
+    Mms,
+    /// WhatsApp Business.
+    WhatsApp,
+    /// Rich Communication Services (RCS) business messaging.
+    Rcs,
+    /// Viber Business Messages.
+    #[serde(rename = "viber_service")]
+    Viber,
+    /// Facebook Messenger.
+    Messenger,
+}
+
+impl Channel {
+    /// Returns whether the channel can carry messages of the given type.
+    pub fn supports(self, message_type: MessageType) -> bool {
+        use MessageType::*;
+
+        match self {
+            Channel::Sms => message_type == Text,
+            Channel::Mms => matches!(message_type, Text | Image | Audio | Video | Vcard),
+            Channel::WhatsApp | Channel::Messenger => {
+                matches!(message_type, Text | Image | Audio | Video | File)
+            }
+            Channel::Rcs | Channel::Viber => matches!(message_type, Text | Image | Video | File),
+        }
+    }
+}
+
+/// The type of content carried by a message.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum MessageType {
+    /// A plain text message.
+    Text,
+    /// An image, e.g. a JPEG or PNG file.
+    Image,
+    /// An audio clip.
+    Audio,
+    /// A video clip.
+    Video,
+    /// An arbitrary file attachment, e.g. a PDF document.
+    File,
+    /// A contact card.
+    Vcard,
+}
+
+/// A number or external account which messages can be sent from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Sender {
+    id: String,
+    channels: Vec<Channel>,
+}
+
+impl Sender {
+    /// Creates a sender for a phone number with the given capabilities.
+    ///
+    /// `msisdn` is the number in E.164 format, without a leading `+`. Voice capabilities are
+    /// ignored, since they don't affect which messages can be sent.
+    pub fn number(msisdn: impl Into<String>, features: &[Feature]) -> Self {
+        let mut channels = Vec::with_capacity(2);
+        if features.contains(&Feature::Sms) {
+            channels.push(Channel::Sms);
+        }
+        if features.contains(&Feature::Mms) {
+            channels.push(Channel::Mms);
+        }
+
+        Sender {
+            id: msisdn.into(),
+            channels,
+        }
+    }
+
+    /// Creates a sender for an external account linked to the Vonage application.
+    ///
+    /// `id` is the identifier used as the `from` field of messages on that channel, e.g. the
+    /// WhatsApp number, the RCS agent ID, the Viber service message ID or the Facebook page ID.
+    pub fn external_account(channel: Channel, id: impl Into<String>) -> Self {
+        Sender {
+            id: id.into(),
+            channels: vec![channel],
+        }
+    }
+
+    /// Returns the identifier of this sender.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the channels this sender can send messages over.
+    pub fn channels(&self) -> &[Channel] {
+        &self.channels
+    }
+
+    /// Returns whether this sender can send messages of the given type over `channel`.
+    pub fn supports(&self, channel: Channel, message_type: MessageType) -> bool {
+        self.channels.contains(&channel) && channel.supports(message_type)
+    }
+}
+
+/// Picks a sender which can send a message of the given type.
+///
+/// `channels` are tried in order of preference, and the first sender supporting a channel wins.
+/// Returns the chosen channel along with the sender, or `None` if no sender is capable.
+pub fn route<'a>(
+    senders: &'a [Sender],
+    channels: &[Channel],
+    message_type: MessageType,
+) -> Option<(Channel, &'a Sender)> {
+    channels.iter().find_map(|&channel| {
+        senders
+            .iter()
+            .find(|sender| sender.supports(channel, message_type))
+            .map(|sender| (channel, sender))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_by_preference_and_capability() {
+        let senders = vec![
+            Sender::number("447700900000", &[Feature::Voice, Feature::Sms]),
+            Sender::number("14155550100", &[Feature::Sms, Feature::Mms]),
+            Sender::external_account(Channel::Rcs, "acme-agent"),
+        ];
+        assert_eq!(senders[0].channels(), [Channel::Sms]);
+
+        let preference = [Channel::Rcs, Channel::WhatsApp, Channel::Mms, Channel::Sms];
+        let pick = |message_type| {
+            route(&senders, &preference, message_type).map(|(channel, s)| (channel, s.id()))
+        };
+
+        assert_eq!(pick(MessageType::Text), Some((Channel::Rcs, "acme-agent")));
+        assert_eq!(
+            pick(MessageType::Audio),
+            Some((Channel::Mms, "14155550100"))
+        );
+        assert_eq!(
+            pick(MessageType::Vcard),
+            Some((Channel::Mms, "14155550100"))
+        );
+
+        let sms_only = [Channel::Sms];
+        assert_eq!(
+            route(&senders, &sms_only, MessageType::Text).map(|(_, s)| s.id()),
+            Some("447700900000")
+        );
+        assert!(route(&senders, &sms_only, MessageType::Image).is_none());
+    }
+}
@@ -0,0 +1,172 @@
+//! Custom metadata which is echoed back in webhooks.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::iter::FromIterator;
+use std::str::FromStr;
+
+use crate::validation::Validator;
+use crate::{Error, ErrorKind, Result};
+
+/// The maximum length of a `client_ref`, as accepted by the Messages API.
+pub const MAX_CLIENT_REF_LEN: usize = 100;
+
+/// Arbitrary key/value pairs attached to an outgoing call or message.
+///
+/// Vonage does not store metadata of its own, but echoes back a `client_ref` string in the
+/// webhooks of messages, and the query string of the event URL of calls. `Metadata` is encoded
+/// into either as `key=value` pairs, and parsed back with the `metadata()` methods of the
+/// payloads in the [`webhooks`](./webhooks/index.html) module, so webhook handlers can correlate
+/// events with e.g. the order or tenant they belong to.
+///
+/// Keys are kept in sorted order, so the same metadata always encodes to the same string.
+///
+/// # Example
+///
+/// ```
+/// use vonage::Metadata;
+///
+/// let metadata = Metadata::new().with("order", "42").with("tier", "gold & more");
+/// let client_ref = metadata.to_client_ref().unwrap();
+/// assert_eq!(client_ref, "order=42&tier=gold+%26+more");
+///
+/// let parsed: Metadata = client_ref.parse().unwrap();
+/// assert_eq!(parsed.get("tier"), Some("gold & more"));
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Metadata {
+    entries: BTreeMap<String, String>,
+}
+
+impl Metadata {
+    /// Creates a new, empty `Metadata`.
+    pub fn new() -> Self {
+        Metadata::default()
+    }
+
+    /// Adds the given key/value pair, replacing any previous value of `key`.
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// Inserts the given key/value pair, returning the previous value of `key`, if any.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        self.entries.insert(key.into(), value.into())
+    }
+
+    /// Returns the value of `key`, if present.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    /// Returns an iterator over all key/value pairs, sorted by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns the number of key/value pairs.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether there are no key/value pairs.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Encodes the metadata as a `client_ref`.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequest`] if the encoded string is longer
+    /// than [`MAX_CLIENT_REF_LEN`](./constant.MAX_CLIENT_REF_LEN.html).
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ./enum.ErrorKind.html#variant.InvalidRequest
+    pub fn to_client_ref(&self) -> Result<String> {
+        let encoded = self.to_string();
+        let mut validator = Validator::new();
+        validator.max_len(Some(&encoded), "client_ref", MAX_CLIENT_REF_LEN);
+        validator.finish()?;
+        Ok(encoded)
+    }
+
+    /// Appends the metadata to the query string of `url`, e.g. the event URL of a call.
+    pub fn append_to_url(&self, url: &str) -> String {
+        if self.is_empty() {
+            return url.to_owned();
+        }
+
+        let (base, fragment) = match url.find('#') {
+            Some(index) => url.split_at(index),
+            None => (url, ""),
+        };
+        let sep = match base.find('?') {
+            Some(index) if index + 1 < base.len() => "&",
+            Some(_) => "",
+            None => "?",
+        };
+        format!("{}{}{}{}", base, sep, self, fragment)
+    }
+}
+
+impl Display for Metadata {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let encoded = serde_urlencoded::to_string(&self.entries).map_err(|_| fmt::Error)?;
+        f.write_str(&encoded)
+    }
+}
+
+/// Parses metadata encoded with [`to_client_ref()`](#method.to_client_ref) or
+/// [`append_to_url()`](#method.append_to_url), the latter from the query string only.
+impl FromStr for Metadata {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let entries = serde_urlencoded::from_str(s)
+            .map_err(|e| Error::with_cause(ErrorKind::UrlEncode, e))?;
+        Ok(Metadata { entries })
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Metadata {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let entries = iter.into_iter().map(|(k, v)| (k.into(), v.into()));
+        Metadata {
+            entries: entries.collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_urls_and_client_refs() {
+        let metadata: Metadata = vec![("tenant", "acme"), ("order", "42")]
+            .into_iter()
+            .collect();
+
+        let url = "https://example.com/events?source=app#top";
+        let url = metadata.append_to_url(url);
+        assert_eq!(
+            url,
+            "https://example.com/events?source=app&order=42&tenant=acme#top"
+        );
+        assert_eq!(
+            metadata.append_to_url("https://example.com/events?"),
+            "https://example.com/events?order=42&tenant=acme"
+        );
+
+        let query: http::Uri = url.parse().unwrap();
+        let parsed: Metadata = query.query().unwrap().parse().unwrap();
+        assert_eq!(parsed.get("order"), Some("42"));
+        assert_eq!(parsed.get("source"), Some("app"));
+
+        let long = Metadata::new().with("note", "x".repeat(MAX_CLIENT_REF_LEN));
+        let error = long.to_client_ref().unwrap_err();
+        assert_eq!(
+            error.as_invalid_request().unwrap().invalid_parameters()[0].name(),
+            "client_ref"
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Hooks for collecting client-side request metrics.
+
+use std::time::Duration;
+
+use http::StatusCode;
+
+use crate::{ErrorKind, Product};
+
+/// Measurements taken for a single request sent to a Vonage API.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct RequestMetrics<'a> {
+    /// The Vonage product which was called.
+    pub product: Product,
+    /// The path of the endpoint which was called, e.g. `/verify/json`.
+    pub endpoint: &'a str,
+    /// The time elapsed between sending the request and decoding the response.
+    ///
+    /// Time spent waiting on client-side rate limits is not included.
+    pub latency: Duration,
+    /// The HTTP status code of the response, if one was received.
+    pub status: Option<StatusCode>,
+    /// The kind of error which occurred, if the request failed.
+    pub error: Option<ErrorKind>,
+}
+
+/// A receiver of client-side request metrics.
+///
+/// A sink can be registered with [`ClientBuilder::metrics()`], after which it is invoked once for
+/// every request sent by the client, including requests which failed. This is enough to derive
+/// request counts, error counts by kind, and latency histograms.
+///
+/// A ready-made implementation backed by the [`prometheus`] crate is available with the
+/// `prometheus` feature enabled.
+///
+/// [`ClientBuilder::metrics()`]: ./struct.ClientBuilder.html#method.metrics
+/// [`prometheus`]: https://docs.rs/prometheus/
+pub trait MetricsSink: Send + Sync {
+    /// Records the outcome of a single request.
+    fn record(&self, metrics: &RequestMetrics);
+}
+
+impl<M: MetricsSink + ?Sized> MetricsSink for std::sync::Arc<M> {
+    fn record(&self, metrics: &RequestMetrics) {
+        (**self).record(metrics)
+    }
+}
+
+/// A [`MetricsSink`](./trait.MetricsSink.html) which exports metrics to a Prometheus registry.
+///
+/// The following metrics are registered:
+///
+/// * `vonage_requests_total`, a counter labeled by `product`.
+/// * `vonage_errors_total`, a counter labeled by `product` and error `kind`.
+/// * `vonage_request_duration_seconds`, a histogram labeled by `product`.
+#[cfg(feature = "prometheus")]
+#[derive(Clone, Debug)]
+pub struct PrometheusMetrics {
+    requests: prometheus::IntCounterVec,
+    errors: prometheus::IntCounterVec,
+    latency: prometheus::HistogramVec,
+}
+
+#[cfg(feature = "prometheus")]
+impl PrometheusMetrics {
+    /// Creates a new `PrometheusMetrics` and registers its collectors with `registry`.
+    ///
+    /// Returns `Err` if any of the metrics are already registered.
+    pub fn new(registry: &prometheus::Registry) -> prometheus::Result<Self> {
+        use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts};
+
+        let requests = IntCounterVec::new(
+            Opts::new("vonage_requests_total", "Requests sent to Vonage APIs"),
+            &["product"],
+        )?;
+        let errors = IntCounterVec::new(
+            Opts::new("vonage_errors_total", "Failed requests sent to Vonage APIs"),
+            &["product", "kind"],
+        )?;
+        let latency = HistogramVec::new(
+            HistogramOpts::new(
+                "vonage_request_duration_seconds",
+                "Latency of requests sent to Vonage APIs",
+            ),
+            &["product"],
+        )?;
+
+        registry.register(Box::new(requests.clone()))?;
+        registry.register(Box::new(errors.clone()))?;
+        registry.register(Box::new(latency.clone()))?;
+
+        Ok(PrometheusMetrics {
+            requests,
+            errors,
+            latency,
+        })
+    }
+}
+
+#[cfg(feature = "prometheus")]
+impl MetricsSink for PrometheusMetrics {
+    fn record(&self, metrics: &RequestMetrics) {
+        let product = product_label(metrics.product);
+        self.requests.with_label_values(&[product]).inc();
+        self.latency
+            .with_label_values(&[product])
+            .observe(metrics.latency.as_secs_f64());
+
+        if let Some(kind) = metrics.error {
+            self.errors
+                .with_label_values(&[product, error_kind_label(kind)])
+                .inc();
+        }
+    }
+}
+
+#[cfg(feature = "prometheus")]
+fn product_label(product: Product) -> &'static str {
+    match product {
+        Product::Verify => "verify",
+        Product::Numbers => "numbers",
+        Product::NumberInsight => "number_insight",
+        Product::Voice => "voice",
+        Product::Reports => "reports",
+        Product::Applications => "applications",
+        Product::Sms => "sms",
+        Product::Messages => "messages",
+        Product::Other => "other",
+    }
+}
+
+#[cfg(feature = "prometheus")]
+fn error_kind_label(kind: ErrorKind) -> &'static str {
+    match kind {
+        ErrorKind::Auth => "auth",
+        ErrorKind::Cancelled => "cancelled",
+        ErrorKind::CircuitOpen => "circuit_open",
+        ErrorKind::Decode => "decode",
+        ErrorKind::Http => "http",
+        ErrorKind::InvalidNetworkCode => "invalid_network_code",
+        ErrorKind::InvalidPhoneNumber => "invalid_phone_number",
+        ErrorKind::InvalidRequest => "invalid_request",
+        ErrorKind::InvalidRequestId => "invalid_request_id",
+        ErrorKind::InvalidWebhook => "invalid_webhook",
+        ErrorKind::Io => "io",
+        ErrorKind::RateLimited => "rate_limited",
+        ErrorKind::Status(_) => "status",
+        ErrorKind::TestMode => "test_mode",
+        ErrorKind::Timeout => "timeout",
+        ErrorKind::Undeliverable => "undeliverable",
+        ErrorKind::UrlEncode => "url_encode",
+        ErrorKind::Verify { .. } => "verify",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use std::convert::Infallible;
+
+    use bytes::Bytes;
+    use http::{Request, Response};
+    use http_body_util::Full;
+    use tower::service_fn;
+
+    use crate::Client;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(String, Option<StatusCode>, bool)>>);
+
+    impl MetricsSink for Recorder {
+        fn record(&self, metrics: &RequestMetrics) {
+            let is_verify_error = matches!(metrics.error, Some(ErrorKind::Verify { .. }));
+            let entry = (metrics.endpoint.to_owned(), metrics.status, is_verify_error);
+            self.0.lock().unwrap().push(entry);
+        }
+    }
+
+    #[tokio::test]
+    async fn records_api_errors() {
+        let service = service_fn(|_: Request<Full<Bytes>>| async {
+            let body = r#"{"status":"3","error_text":"Invalid value for param: number"}"#;
+            Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let recorder = Arc::new(Recorder::default());
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .metrics(recorder.clone())
+            .build()
+            .unwrap();
+
+        let result = client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .send()
+            .await;
+
+        assert!(result.is_err());
+        let recorded = recorder.0.lock().unwrap();
+        assert_eq!(
+            *recorded,
+            vec![("/verify/json".to_owned(), Some(StatusCode::OK), true)]
+        );
+    }
+
+    #[cfg(feature = "prometheus")]
+    #[test]
+    fn exports_prometheus_metrics() {
+        let registry = prometheus::Registry::new();
+        let sink = PrometheusMetrics::new(&registry).unwrap();
+
+        sink.record(&RequestMetrics {
+            product: Product::Verify,
+            endpoint: "/verify/json",
+            latency: Duration::from_millis(120),
+            status: Some(StatusCode::OK),
+            error: None,
+        });
+        sink.record(&RequestMetrics {
+            product: Product::Verify,
+            endpoint: "/verify/check/json",
+            latency: Duration::from_millis(80),
+            status: Some(StatusCode::BAD_GATEWAY),
+            error: Some(ErrorKind::Status(StatusCode::BAD_GATEWAY)),
+        });
+
+        assert_eq!(sink.requests.with_label_values(&["verify"]).get(), 2);
+        assert_eq!(
+            sink.errors.with_label_values(&["verify", "status"]).get(),
+            1
+        );
+        assert_eq!(registry.gather().len(), 3);
+    }
+}
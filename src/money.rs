@@ -0,0 +1,117 @@
+//! Monetary amounts exchanged with the Vonage APIs.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A monetary amount reported by the Vonage APIs, such as a price or an account balance.
+///
+/// By default, this is the decimal string exactly as it was returned by the API, e.g.
+/// `"0.10000000"`. Enable the `decimal` feature to parse it into a lossless
+/// [`rust_decimal::Decimal`](https://docs.rs/rust_decimal) instead.
+#[cfg(not(feature = "decimal"))]
+pub type Price = String;
+
+/// A monetary amount reported by the Vonage APIs, such as a price or an account balance.
+#[cfg(feature = "decimal")]
+pub type Price = rust_decimal::Decimal;
+
+/// A monetary amount sent to the Vonage APIs, such as the value of a PSD2 payment.
+///
+/// By default, this is an `f64`. Enable the `decimal` feature to use a lossless
+/// [`rust_decimal::Decimal`](https://docs.rs/rust_decimal) instead.
+#[cfg(not(feature = "decimal"))]
+pub type Amount = f64;
+
+/// A monetary amount sent to the Vonage APIs, such as the value of a PSD2 payment.
+#[cfg(feature = "decimal")]
+pub type Amount = rust_decimal::Decimal;
+
+/// Converts monetary amounts between currencies.
+///
+/// Vonage bills most accounts in EUR. The cost helpers, such as
+/// [`Verified::cost_in()`](./verify/struct.Verified.html#method.cost_in) and
+/// [`UsageSummary::total_spend_in()`](./usage/struct.UsageSummary.html#method.total_spend_in),
+/// use a converter to report prices in the currency you account in instead. Implement this trait
+/// to use the rates of your own finance system, or use [`ExchangeRates`](./struct.ExchangeRates.html)
+/// for a fixed set of rates.
+pub trait CurrencyConverter: Send + Sync {
+    /// Converts `amount` from the ISO 4217 currency `from` into `to`, returning `None` if either
+    /// currency is not supported.
+    fn convert(&self, amount: Amount, from: &str, to: &str) -> Option<Amount>;
+}
+
+impl<C: CurrencyConverter + ?Sized> CurrencyConverter for Arc<C> {
+    fn convert(&self, amount: Amount, from: &str, to: &str) -> Option<Amount> {
+        (**self).convert(amount, from, to)
+    }
+}
+
+/// A [`CurrencyConverter`](./trait.CurrencyConverter.html) with fixed exchange rates.
+///
+/// Rates are given relative to a base currency, and conversions between two other currencies go
+/// through it.
+///
+/// # Example
+///
+/// ```
+/// use vonage::{Amount, CurrencyConverter, ExchangeRates};
+///
+/// let rates = ExchangeRates::new("EUR")
+///     .rate("USD", "1.25".parse().unwrap())
+///     .rate("GBP", "0.5".parse().unwrap());
+///
+/// let amount: Amount = "2".parse().unwrap();
+/// assert_eq!(rates.convert(amount, "EUR", "USD"), "2.5".parse().ok());
+/// assert_eq!(rates.convert(amount, "GBP", "USD"), "5".parse().ok());
+/// assert_eq!(rates.convert(amount, "EUR", "JPY"), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ExchangeRates {
+    base: String,
+    rates: HashMap<String, Amount>,
+}
+
+impl ExchangeRates {
+    /// Creates an empty set of exchange rates relative to the currency `base`.
+    pub fn new(base: impl Into<String>) -> Self {
+        ExchangeRates {
+            base: base.into(),
+            rates: HashMap::new(),
+        }
+    }
+
+    /// Sets the amount of `currency` which one unit of the base currency is worth.
+    pub fn rate(mut self, currency: impl Into<String>, rate: Amount) -> Self {
+        self.rates.insert(currency.into(), rate);
+        self
+    }
+
+    fn rate_of(&self, currency: &str) -> Option<Amount> {
+        if currency == self.base {
+            Some(Amount::from(1u8))
+        } else {
+            self.rates.get(currency).copied()
+        }
+    }
+}
+
+impl CurrencyConverter for ExchangeRates {
+    fn convert(&self, amount: Amount, from: &str, to: &str) -> Option<Amount> {
+        if from == to {
+            return Some(amount);
+        }
+
+        let from = self
+            .rate_of(from)
+            .filter(|rate| *rate != Amount::default())?;
+        Some(amount / from * self.rate_of(to)?)
+    }
+}
+
+/// Converts a `Price` reported by the API into an `Amount` which can be computed with.
+pub(crate) fn to_amount(price: &Price) -> Option<Amount> {
+    #[cfg(not(feature = "decimal"))]
+    return price.parse().ok();
+    #[cfg(feature = "decimal")]
+    return Some(*price);
+}
@@ -0,0 +1,513 @@
+//! Looking up the validity, carrier and reachability of phone numbers with the
+//! [Number Insight API](https://developer.nexmo.com/api/number-insight).
+//!
+//! Number Insight offers three levels of lookup, each returning everything the previous level
+//! does and more:
+//!
+//! * [`basic()`] is free, and returns the country and formats of a number.
+//! * [`standard()`] adds the current and original carrier of the number, and whether it was
+//!   ported between them.
+//! * [`advanced()`] adds whether the number is valid and reachable, and whether it is roaming.
+//!
+//! Screening numbers before sending them a verify request or an SMS avoids paying for traffic to
+//! numbers which cannot receive it. To reject undeliverable numbers as part of a verify request
+//! instead, see [`Verify::lookup()`](../verify/struct.Verify.html#method.lookup).
+//!
+//! [`basic()`]: ./struct.NumberInsightApi.html#method.basic
+//! [`standard()`]: ./struct.NumberInsightApi.html#method.standard
+//! [`advanced()`]: ./struct.NumberInsightApi.html#method.advanced
+
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+
+use http::StatusCode;
+use phonenumber::country::Id;
+use phonenumber::PhoneNumber;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::{ApiKey, ApiSecret};
+use crate::http_client::HttpClient;
+use crate::phone::NetworkCode;
+use crate::transport::HttpTransport;
+use crate::{DefaultTransport, Error, ErrorKind, Price, Product, RequestPolicy, Result};
+
+/// A handle to the [Number Insight](https://developer.nexmo.com/api/number-insight) API.
+///
+/// This is returned from [`Client::number_insight()`](../struct.Client.html#method.number_insight).
+/// It holds the API key and API secret pair of the client, and is cheap to clone.
+pub struct NumberInsightApi<C = DefaultTransport> {
+    http_client: HttpClient<C>,
+    key_pair: (ApiKey, ApiSecret),
+}
+
+impl<C> NumberInsightApi<C> {
+    pub(crate) fn new(http_client: HttpClient<C>, key_pair: (ApiKey, ApiSecret)) -> Self {
+        NumberInsightApi {
+            http_client,
+            key_pair,
+        }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`RequestPolicy`](../struct.RequestPolicy.html) for details.
+    pub fn policy(mut self, policy: RequestPolicy) -> Self {
+        self.http_client = self.http_client.with_policy(policy);
+        self
+    }
+
+    /// Looks up the country and formats of `number`, free of charge.
+    pub fn basic(&self, number: &PhoneNumber) -> LookupNumber<C, BasicInsight> {
+        self.lookup("basic", number)
+    }
+
+    /// Looks up the carrier and ported status of `number`, in addition to a basic lookup.
+    pub fn standard(&self, number: &PhoneNumber) -> LookupNumber<C, StandardInsight> {
+        self.lookup("standard", number)
+    }
+
+    /// Looks up the validity, reachability and roaming status of `number`, in addition to a
+    /// standard lookup.
+    pub fn advanced(&self, number: &PhoneNumber) -> LookupNumber<C, AdvancedInsight> {
+        self.lookup("advanced", number)
+    }
+
+    fn lookup<T>(&self, level: &'static str, number: &PhoneNumber) -> LookupNumber<C, T> {
+        LookupNumber {
+            http_client: self.http_client.clone(),
+            level,
+            query: LookupQuery {
+                api_key: self.key_pair.0.clone(),
+                api_secret: self.key_pair.1.clone(),
+                number: number.to_string().trim_start_matches('+').to_owned(),
+                country: None,
+            },
+            insight: PhantomData,
+        }
+    }
+}
+
+impl<C> Clone for NumberInsightApi<C> {
+    fn clone(&self) -> Self {
+        NumberInsightApi {
+            http_client: self.http_client.clone(),
+            key_pair: self.key_pair.clone(),
+        }
+    }
+}
+
+impl<C> Debug for NumberInsightApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(NumberInsightApi))
+            .field("http_client", &self.http_client)
+            .field("key_pair", &self.key_pair)
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct LookupQuery {
+    api_key: ApiKey,
+    api_secret: ApiSecret,
+    number: String,
+    country: Option<Id>,
+}
+
+/// A builder for a Number Insight lookup.
+///
+/// This is returned from [`NumberInsightApi::basic()`], [`NumberInsightApi::standard()`] and
+/// [`NumberInsightApi::advanced()`], and resolves to a [`BasicInsight`], [`StandardInsight`] or
+/// [`AdvancedInsight`] respectively.
+///
+/// [`NumberInsightApi::basic()`]: ./struct.NumberInsightApi.html#method.basic
+/// [`NumberInsightApi::standard()`]: ./struct.NumberInsightApi.html#method.standard
+/// [`NumberInsightApi::advanced()`]: ./struct.NumberInsightApi.html#method.advanced
+/// [`BasicInsight`]: ./struct.BasicInsight.html
+/// [`StandardInsight`]: ./struct.StandardInsight.html
+/// [`AdvancedInsight`]: ./struct.AdvancedInsight.html
+pub struct LookupNumber<C, T> {
+    http_client: HttpClient<C>,
+    level: &'static str,
+    query: LookupQuery,
+    insight: PhantomData<fn() -> T>,
+}
+
+impl<C, T> LookupNumber<C, T> {
+    /// Sets the country the number is expected to be in.
+    ///
+    /// Numbers from other countries are still looked up, but this determines the national format
+    /// returned for them.
+    pub fn country(mut self, country: Id) -> Self {
+        self.query.country = Some(country);
+        self
+    }
+}
+
+impl<C: HttpTransport, T: DeserializeOwned> LookupNumber<C, T> {
+    /// Sends the request.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequest`] if Number Insight rejected the
+    /// number, or [`ErrorKind::RateLimited`] if lookups are being throttled.
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../enum.ErrorKind.html#variant.InvalidRequest
+    /// [`ErrorKind::RateLimited`]: ../enum.ErrorKind.html#variant.RateLimited
+    pub async fn send(self) -> Result<T> {
+        #[derive(Deserialize)]
+        struct Status {
+            status: u8,
+            #[serde(default)]
+            status_message: String,
+        }
+
+        let request = crate::encode_request_get(&["ni", self.level], &self.query)?;
+        self.http_client
+            .send(Product::NumberInsight, request, |response| {
+                match response.status() {
+                    StatusCode::OK => {}
+                    _ => return Err(Error::new_status(&response)),
+                }
+
+                let status: Status = crate::http_client::decode_json(response.body())?;
+                match status.status {
+                    0 => crate::http_client::decode_json(response.body()),
+                    code => Err(status_error(code, &status.status_message)),
+                }
+            })
+            .await
+    }
+}
+
+impl<C, T> Debug for LookupNumber<C, T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(LookupNumber))
+            .field("level", &self.level)
+            .field("query", &self.query)
+            .finish()
+    }
+}
+
+/// Converts a non-zero Number Insight `status` into an error.
+pub(crate) fn status_error(status: u8, message: &str) -> Error {
+    let kind = match status {
+        1 => ErrorKind::RateLimited,
+        3 => ErrorKind::InvalidRequest,
+        4 => ErrorKind::Auth,
+        9 => ErrorKind::Status(StatusCode::PAYMENT_REQUIRED),
+        19 => ErrorKind::Status(StatusCode::FORBIDDEN),
+        _ => ErrorKind::Status(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let message = format!(
+        "Number Insight lookup failed with status {}: {}",
+        status, message
+    );
+    Error::with_cause(kind, anyhow::Error::msg(message))
+}
+
+/// The result of a [basic lookup](./struct.NumberInsightApi.html#method.basic).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BasicInsight {
+    /// The unique ID of the lookup.
+    pub request_id: String,
+    /// The number in international format, e.g. `447700900000`.
+    pub international_format_number: String,
+    /// The number in the national format of its country, e.g. `07700 900000`.
+    pub national_format_number: String,
+    /// The two-letter ISO 3166-1 code of the country of the number.
+    pub country_code: String,
+    /// The three-letter ISO 3166-1 code of the country of the number.
+    pub country_code_iso3: String,
+    /// The name of the country of the number.
+    pub country_name: String,
+    /// The international dialing prefix of the country of the number, e.g. `44`.
+    pub country_prefix: String,
+}
+
+/// The result of a [standard lookup](./struct.NumberInsightApi.html#method.standard).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StandardInsight {
+    /// The fields returned by a basic lookup.
+    #[serde(flatten)]
+    pub basic: BasicInsight,
+    /// The cost of the lookup, in EUR.
+    pub request_price: Option<Price>,
+    /// The balance of the account after the lookup, in EUR.
+    pub remaining_balance: Option<Price>,
+    /// The carrier currently serving the number.
+    #[serde(default)]
+    pub current_carrier: Option<Carrier>,
+    /// The carrier the number was originally assigned to.
+    #[serde(default)]
+    pub original_carrier: Option<Carrier>,
+    /// Whether the number was ported from its original carrier.
+    #[serde(default)]
+    pub ported: Ported,
+}
+
+impl StandardInsight {
+    /// Returns whether the number is a landline, according to its current carrier.
+    pub fn is_landline(&self) -> bool {
+        let carrier = self.current_carrier.as_ref();
+        carrier.is_some_and(|carrier| carrier.network_type.is_landline())
+    }
+}
+
+/// The result of an [advanced lookup](./struct.NumberInsightApi.html#method.advanced).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AdvancedInsight {
+    /// The fields returned by a standard lookup.
+    #[serde(flatten)]
+    pub standard: StandardInsight,
+    /// Whether the number exists.
+    #[serde(default, rename = "valid_number")]
+    pub validity: Validity,
+    /// Whether the number can be called or messaged.
+    #[serde(default, rename = "reachable")]
+    pub reachability: Reachability,
+    /// Whether the number is roaming, and where.
+    #[serde(default)]
+    pub roaming: Roaming,
+}
+
+/// A carrier serving a phone number.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Carrier {
+    /// The mobile country code and mobile network code of the carrier, e.g. `310090`.
+    #[serde(default)]
+    pub network_code: Option<String>,
+    /// The full name of the carrier.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The two-letter ISO 3166-1 code of the country of the carrier.
+    #[serde(default)]
+    pub country: Option<String>,
+    /// The type of network of the carrier.
+    #[serde(default)]
+    pub network_type: NetworkType,
+}
+
+impl Carrier {
+    /// Returns the [`network_code`](#structfield.network_code) of the carrier, if it is valid.
+    pub fn network(&self) -> Option<NetworkCode> {
+        self.network_code.as_ref()?.parse().ok()
+    }
+}
+
+/// The type of network a phone number belongs to.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum NetworkType {
+    /// A mobile network.
+    Mobile,
+    /// A fixed-line network.
+    Landline,
+    /// A fixed-line network for numbers which are free to call.
+    #[serde(rename = "landline_tollfree")]
+    LandlineTollFree,
+    /// A fixed-line network for numbers which charge a premium rate.
+    LandlinePremium,
+    /// A virtual network, e.g. for VoIP numbers.
+    Virtual,
+    /// A paging network.
+    Pager,
+    /// The type of network is not known.
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+impl NetworkType {
+    /// Returns whether this is any kind of fixed-line network, which cannot receive SMS.
+    pub fn is_landline(self) -> bool {
+        matches!(
+            self,
+            NetworkType::Landline | NetworkType::LandlineTollFree | NetworkType::LandlinePremium
+        )
+    }
+}
+
+/// Whether a phone number was ported from its original carrier.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Ported {
+    /// The number was ported.
+    Ported,
+    /// The number was not ported.
+    NotPorted,
+    /// The number is assumed to have been ported, as its carriers differ.
+    AssumedPorted,
+    /// The number is assumed not to have been ported.
+    AssumedNotPorted,
+    /// The ported status of the number is not known.
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+impl Ported {
+    /// Returns whether the number was, or is assumed to have been, ported.
+    pub fn is_ported(self) -> bool {
+        self == Ported::Ported || self == Ported::AssumedPorted
+    }
+}
+
+/// Whether a phone number exists.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Validity {
+    /// The number exists.
+    Valid,
+    /// The number does not exist.
+    NotValid,
+    /// The validity of the number is not known.
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+/// Whether a phone number can be called or messaged.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Reachability {
+    /// The number is reachable.
+    Reachable,
+    /// The number cannot receive calls or messages.
+    Undeliverable,
+    /// The number is temporarily unreachable, e.g. because the device is switched off.
+    Absent,
+    /// The number is not in service.
+    BadNumber,
+    /// The number is blacklisted by its carrier.
+    Blacklisted,
+    /// The reachability of the number is not known.
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+/// The roaming status of a phone number.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Roaming {
+    /// Whether the number is roaming.
+    #[serde(default)]
+    pub status: RoamingStatus,
+    /// The two-letter ISO 3166-1 code of the country the number is roaming in.
+    #[serde(default, rename = "roaming_country_code")]
+    pub country_code: Option<String>,
+    /// The mobile country code and mobile network code of the network the number is roaming on.
+    #[serde(default, rename = "roaming_network_code")]
+    pub network_code: Option<String>,
+    /// The name of the network the number is roaming on.
+    #[serde(default, rename = "roaming_network_name")]
+    pub network_name: Option<String>,
+}
+
+impl Roaming {
+    /// Returns whether the number is roaming.
+    pub fn is_roaming(&self) -> bool {
+        self.status == RoamingStatus::Roaming
+    }
+}
+
+/// Whether a phone number is roaming.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum RoamingStatus {
+    /// The number is roaming.
+    Roaming,
+    /// The number is not roaming.
+    NotRoaming,
+    /// The roaming status of the number is not known.
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use bytes::Bytes;
+    use http::Request;
+    use http_body_util::Full;
+    use tower::service_fn;
+
+    use crate::Client;
+
+    #[tokio::test]
+    async fn looks_up_advanced_insight() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.uri().path(), "/ni/advanced/json");
+            let query = request.uri().query().unwrap();
+            assert!(query.contains("number=14155550100"));
+            assert!(query.contains("country=US"));
+
+            let body = r#"{"status":0,"status_message":"Success","request_id":"aaaa",
+                "international_format_number":"14155550100","national_format_number":"(415) 555-0100",
+                "country_code":"US","country_code_iso3":"USA","country_name":"United States of America",
+                "country_prefix":"1","request_price":"0.03000000","remaining_balance":"10.00000000",
+                "current_carrier":{"network_code":"310410","name":"AT&T Mobility","country":"US",
+                "network_type":"mobile"},"original_carrier":{"network_code":"311480",
+                "name":"Verizon Wireless","country":"US","network_type":"mobile"},
+                "ported":"ported","roaming":{"status":"roaming","roaming_country_code":"GB",
+                "roaming_network_code":"23410","roaming_network_name":"O2"},
+                "valid_number":"valid","reachable":"reachable","lookup_outcome":0}"#;
+            Ok::<_, Infallible>(http::Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let number = crate::phone::parse("+14155550100", None).unwrap();
+        let insight = client
+            .number_insight()
+            .advanced(&number)
+            .country(Id::US)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(insight.standard.basic.country_code_iso3, "USA");
+        assert!(insight.standard.ported.is_ported());
+        assert!(!insight.standard.is_landline());
+        let carrier = insight.standard.current_carrier.as_ref().unwrap();
+        assert_eq!(carrier.network().unwrap().operator(), Some("AT&T"));
+        assert_eq!(insight.validity, Validity::Valid);
+        assert_eq!(insight.reachability, Reachability::Reachable);
+        assert!(insight.roaming.is_roaming());
+        assert_eq!(insight.roaming.country_code.as_deref(), Some("GB"));
+    }
+
+    #[tokio::test]
+    async fn reports_lookup_failures() {
+        let service = service_fn(|_: Request<Full<Bytes>>| async move {
+            let body = r#"{"status":3,"status_message":"Invalid request :: Invalid number"}"#;
+            Ok::<_, Infallible>(http::Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let number = crate::phone::parse("+14155550100", None).unwrap();
+        let error = client
+            .number_insight()
+            .basic(&number)
+            .send()
+            .await
+            .unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::InvalidRequest));
+    }
+}
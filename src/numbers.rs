@@ -0,0 +1,831 @@
+//! Searching for, buying, configuring and cancelling phone numbers with the
+//! [Numbers API](https://developer.nexmo.com/api/numbers).
+//!
+//! Toll-free numbers are found by searching with
+//! [`NumberType::TollFree`](./enum.NumberType.html#variant.TollFree). In North America, these
+//! differ from standard long codes: they accept SMS only once they were verified for messaging,
+//! so check [`AvailableNumber::supports()`](./struct.AvailableNumber.html#method.supports) before
+//! buying one for an SMS use case. Short codes cannot be searched for, as they are provisioned by
+//! Vonage support.
+
+use std::fmt::{self, Debug, Display, Formatter};
+
+use bytes::Bytes;
+use http::{Response, StatusCode};
+use phonenumber::country::Id;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::{ApiKey, ApiSecret};
+use crate::http_client::HttpClient;
+use crate::messages::Sender;
+use crate::pagination::{Page, Paginated};
+use crate::transport::HttpTransport;
+use crate::validation::Validator;
+use crate::{DefaultTransport, Error, Price, Product, RequestPolicy, Result};
+
+/// A handle to the [Numbers](https://developer.nexmo.com/api/numbers) API.
+///
+/// This is returned from [`Client::numbers()`](../struct.Client.html#method.numbers). It holds the
+/// API key and API secret pair of the client, and is cheap to clone.
+pub struct NumbersApi<C = DefaultTransport> {
+    http_client: HttpClient<C>,
+    key_pair: (ApiKey, ApiSecret),
+}
+
+impl<C> NumbersApi<C> {
+    pub(crate) fn new(http_client: HttpClient<C>, key_pair: (ApiKey, ApiSecret)) -> Self {
+        NumbersApi {
+            http_client,
+            key_pair,
+        }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`RequestPolicy`](../struct.RequestPolicy.html) for details.
+    pub fn policy(mut self, policy: RequestPolicy) -> Self {
+        self.http_client = self.http_client.with_policy(policy);
+        self
+    }
+
+    /// Searches for numbers in `country` which are available to buy.
+    pub fn search(&self, country: Id) -> SearchNumbers<C> {
+        SearchNumbers {
+            http_client: self.http_client.clone(),
+            query: SearchQuery {
+                api_key: self.key_pair.0.clone(),
+                api_secret: self.key_pair.1.clone(),
+                country,
+                number_type: None,
+                features: None,
+                pattern: None,
+                search_pattern: None,
+                size: None,
+                index: None,
+            },
+        }
+    }
+
+    /// Lists the numbers owned by the account.
+    pub fn list(&self) -> ListNumbers<C> {
+        ListNumbers {
+            http_client: self.http_client.clone(),
+            query: OwnedQuery::new(&self.key_pair),
+        }
+    }
+
+    /// Changes the webhooks and application linked to a number owned by the account.
+    ///
+    /// `msisdn` is the number in E.164 format, without a leading `+`, e.g. `18005550100`.
+    pub fn update(&self, country: Id, msisdn: impl Into<String>) -> UpdateNumber<C> {
+        UpdateNumber {
+            http_client: self.http_client.clone(),
+            form: UpdateForm {
+                api_key: self.key_pair.0.clone(),
+                api_secret: self.key_pair.1.clone(),
+                country,
+                msisdn: msisdn.into(),
+                app_id: None,
+                mo_http_url: None,
+                voice_status_callback: None,
+            },
+        }
+    }
+
+    /// Looks up the messaging capabilities of a number owned by the account.
+    ///
+    /// `msisdn` is the number in E.164 format, without a leading `+`. See the
+    /// [`messages`](../messages/index.html) module for routing messages based on the result.
+    pub fn capabilities(&self, msisdn: impl Into<String>) -> QueryCapabilities<C> {
+        QueryCapabilities {
+            http_client: self.http_client.clone(),
+            query: OwnedQuery {
+                pattern: Some(msisdn.into()),
+                search_pattern: Some(SearchPattern::StartsWith.code()),
+                ..OwnedQuery::new(&self.key_pair)
+            },
+        }
+    }
+}
+
+impl<C: HttpTransport> NumbersApi<C> {
+    /// Buys the available number `msisdn` in `country`, e.g. one returned by a
+    /// [search](#method.search).
+    ///
+    /// The monthly rental cost of the number is charged to the account immediately.
+    pub async fn buy(&self, country: Id, msisdn: impl Into<String>) -> Result<()> {
+        self.change_ownership("buy", country, msisdn.into()).await
+    }
+
+    /// Cancels the rental of the number `msisdn` in `country`, releasing it from the account.
+    pub async fn cancel(&self, country: Id, msisdn: impl Into<String>) -> Result<()> {
+        self.change_ownership("cancel", country, msisdn.into())
+            .await
+    }
+
+    async fn change_ownership(&self, action: &str, country: Id, msisdn: String) -> Result<()> {
+        #[derive(Serialize)]
+        struct OwnershipForm<'a> {
+            api_key: &'a ApiKey,
+            api_secret: &'a ApiSecret,
+            country: Id,
+            msisdn: String,
+        }
+
+        let form = OwnershipForm {
+            api_key: &self.key_pair.0,
+            api_secret: &self.key_pair.1,
+            country,
+            msisdn,
+        };
+
+        let uri = crate::encode_rest_uri(&["number", action]);
+        let request = crate::encode_post(uri, &form)?;
+        self.http_client
+            .send(Product::Numbers, request, decode_error_code)
+            .await
+    }
+}
+
+impl<C> Clone for NumbersApi<C> {
+    fn clone(&self) -> Self {
+        NumbersApi {
+            http_client: self.http_client.clone(),
+            key_pair: self.key_pair.clone(),
+        }
+    }
+}
+
+impl<C> Debug for NumbersApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(NumbersApi))
+            .field("http_client", &self.http_client)
+            .field("key_pair", &self.key_pair)
+            .finish()
+    }
+}
+
+/// The kind of a phone number.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum NumberType {
+    /// A geographic landline number.
+    #[serde(rename = "landline")]
+    Landline,
+    /// A mobile number, also known as a long virtual number.
+    #[serde(rename = "mobile-lvn")]
+    Mobile,
+    /// A toll-free number, which is free of charge for the caller.
+    #[serde(rename = "landline-toll-free")]
+    TollFree,
+    /// A number type which is not known to this version of the library.
+    #[serde(other)]
+    Unknown,
+}
+
+/// A capability of a phone number.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+#[non_exhaustive]
+pub enum Feature {
+    /// The number can send and receive SMS messages.
+    Sms,
+    /// The number can make and receive voice calls.
+    Voice,
+    /// The number can send and receive MMS messages.
+    Mms,
+    /// A capability which is not known to this version of the library.
+    #[serde(other)]
+    Unknown,
+}
+
+impl Display for Feature {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Feature::Sms => "SMS",
+            Feature::Voice => "VOICE",
+            Feature::Mms => "MMS",
+            Feature::Unknown => "UNKNOWN",
+        })
+    }
+}
+
+/// Where a pattern must occur in a number for it to match a search.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SearchPattern {
+    /// The number starts with the pattern, after its country code.
+    StartsWith,
+    /// The number contains the pattern anywhere.
+    Contains,
+    /// The number ends with the pattern.
+    EndsWith,
+}
+
+impl SearchPattern {
+    fn code(self) -> u8 {
+        match self {
+            SearchPattern::StartsWith => 0,
+            SearchPattern::Contains => 1,
+            SearchPattern::EndsWith => 2,
+        }
+    }
+}
+
+/// A builder to configure a search for available numbers.
+///
+/// This is returned from [`NumbersApi::search()`](./struct.NumbersApi.html#method.search).
+pub struct SearchNumbers<C> {
+    http_client: HttpClient<C>,
+    query: SearchQuery,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchQuery {
+    api_key: ApiKey,
+    api_secret: ApiSecret,
+    country: Id,
+    #[serde(rename = "type")]
+    number_type: Option<NumberType>,
+    features: Option<String>,
+    pattern: Option<String>,
+    search_pattern: Option<u8>,
+    size: Option<u64>,
+    index: Option<u64>,
+}
+
+impl<C> SearchNumbers<C> {
+    /// Only includes numbers of the given type.
+    pub fn number_type(mut self, number_type: NumberType) -> Self {
+        self.query.number_type = Some(number_type);
+        self
+    }
+
+    /// Only includes toll-free numbers.
+    ///
+    /// This is a shorthand for `.number_type(NumberType::TollFree)`.
+    pub fn toll_free(self) -> Self {
+        self.number_type(NumberType::TollFree)
+    }
+
+    /// Only includes numbers which support all of the given features.
+    pub fn features(mut self, features: &[Feature]) -> Self {
+        let features: Vec<_> = features.iter().map(Feature::to_string).collect();
+        self.query.features = Some(features.join(","));
+        self
+    }
+
+    /// Only includes numbers which match `pattern` at the given position, e.g. a vanity number
+    /// ending in `"1234"`.
+    pub fn pattern(mut self, pattern: impl Into<String>, position: SearchPattern) -> Self {
+        self.query.pattern = Some(pattern.into());
+        self.query.search_pattern = Some(position.code());
+        self
+    }
+
+    /// Sets the number of results per page.
+    ///
+    /// This can be set to 1, at minimum, and 100, at maximum. Defaults to 10.
+    pub fn size(mut self, size: u64) -> Self {
+        self.query.size = Some(size);
+        self
+    }
+
+    /// Sets the page of results to return, starting at 1.
+    pub fn index(mut self, index: u64) -> Self {
+        self.query.index = Some(index);
+        self
+    }
+}
+
+impl<C: HttpTransport> SearchNumbers<C> {
+    /// Sends the search request.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequest`] without sending anything if the
+    /// page size is out of range.
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../enum.ErrorKind.html#variant.InvalidRequest
+    pub async fn send(self) -> Result<NumberSearch> {
+        let mut validator = Validator::new();
+        validator.range(self.query.size, "size", 1, 100).range(
+            self.query.index,
+            "index",
+            1,
+            u64::MAX,
+        );
+        validator.finish()?;
+
+        let uri = crate::encode_rest_uri(&["number", "search"]);
+        let request = crate::encode_get(uri, &self.query)?;
+        self.http_client
+            .send(Product::Numbers, request, |response| {
+                match response.status() {
+                    StatusCode::OK => {}
+                    _ => return Err(Error::new_status(&response)),
+                }
+
+                crate::http_client::decode_json(response.body())
+            })
+            .await
+    }
+}
+
+impl<C> Debug for SearchNumbers<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(SearchNumbers))
+            .field("query", &self.query)
+            .finish()
+    }
+}
+
+/// A page of numbers returned from a [`SearchNumbers`](./struct.SearchNumbers.html) request.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct NumberSearch {
+    /// The total number of available numbers matching the search.
+    pub count: u64,
+    /// The available numbers on the requested page.
+    #[serde(default)]
+    pub numbers: Vec<AvailableNumber>,
+}
+
+/// A phone number which is available to buy.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct AvailableNumber {
+    /// The country the number is located in.
+    pub country: Id,
+    /// The number in E.164 format, without a leading `+`.
+    pub msisdn: String,
+    /// The kind of the number.
+    #[serde(rename = "type")]
+    pub number_type: NumberType,
+    /// The monthly rental cost of the number, in EUR.
+    pub cost: Price,
+    /// The capabilities of the number.
+    pub features: Vec<Feature>,
+}
+
+impl AvailableNumber {
+    /// Returns whether the number has the given capability.
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.features.contains(&feature)
+    }
+}
+
+/// A builder to change the configuration of an owned number.
+///
+/// This is returned from [`NumbersApi::update()`](./struct.NumbersApi.html#method.update).
+/// Settings which are left unset keep their current value.
+pub struct UpdateNumber<C> {
+    http_client: HttpClient<C>,
+    form: UpdateForm,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateForm {
+    api_key: ApiKey,
+    api_secret: ApiSecret,
+    country: Id,
+    msisdn: String,
+    #[serde(rename = "app_id")]
+    app_id: Option<String>,
+    mo_http_url: Option<String>,
+    voice_status_callback: Option<String>,
+}
+
+impl<C> UpdateNumber<C> {
+    /// Links the number to the Vonage application with the given ID, which then receives its
+    /// inbound calls and messages.
+    pub fn app_id(mut self, id: impl Into<String>) -> Self {
+        self.form.app_id = Some(id.into());
+        self
+    }
+
+    /// Sets the webhook which inbound SMS messages to this number are sent to.
+    ///
+    /// Setting this is the final step of enabling SMS on a number, e.g. a toll-free number which
+    /// was verified for messaging.
+    pub fn inbound_sms_url(mut self, url: impl Into<String>) -> Self {
+        self.form.mo_http_url = Some(url.into());
+        self
+    }
+
+    /// Sets the webhook which events about voice calls to this number are sent to.
+    pub fn voice_status_url(mut self, url: impl Into<String>) -> Self {
+        self.form.voice_status_callback = Some(url.into());
+        self
+    }
+}
+
+impl<C: HttpTransport> UpdateNumber<C> {
+    /// Sends the update request.
+    pub async fn send(self) -> Result<()> {
+        let uri = crate::encode_rest_uri(&["number", "update"]);
+        let request = crate::encode_post(uri, &self.form)?;
+        self.http_client
+            .send(Product::Numbers, request, decode_error_code)
+            .await
+    }
+}
+
+impl<C> Debug for UpdateNumber<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(UpdateNumber))
+            .field("form", &self.form)
+            .finish()
+    }
+}
+
+/// Decodes the `error-code` of a response which changes a number.
+fn decode_error_code(response: Response<Bytes>) -> Result<()> {
+    #[derive(Deserialize)]
+    struct ResponseBody {
+        #[serde(rename = "error-code")]
+        error_code: String,
+    }
+
+    match response.status() {
+        StatusCode::OK => {}
+        _ => return Err(Error::new_status(&response)),
+    }
+
+    let body: ResponseBody = crate::http_client::decode_json(response.body())?;
+    match StatusCode::from_bytes(body.error_code.as_bytes()) {
+        Ok(StatusCode::OK) => Ok(()),
+        code => {
+            // Older accounts report failures in the body of a `200 OK` response.
+            let (mut parts, body) = response.into_parts();
+            parts.status = code.unwrap_or(StatusCode::BAD_REQUEST);
+            Err(Error::new_status(&Response::from_parts(parts, body)))
+        }
+    }
+}
+
+/// A builder to filter the numbers owned by the account.
+///
+/// This is returned from [`NumbersApi::list()`](./struct.NumbersApi.html#method.list).
+pub struct ListNumbers<C> {
+    http_client: HttpClient<C>,
+    query: OwnedQuery,
+}
+
+impl<C> ListNumbers<C> {
+    /// Only includes numbers in `country`.
+    pub fn country(mut self, country: Id) -> Self {
+        self.query.country = Some(country);
+        self
+    }
+
+    /// Only includes numbers which match `pattern` at the given position.
+    pub fn pattern(mut self, pattern: impl Into<String>, position: SearchPattern) -> Self {
+        self.query.pattern = Some(pattern.into());
+        self.query.search_pattern = Some(position.code());
+        self
+    }
+
+    /// Only includes numbers linked to the Vonage application with the given ID.
+    pub fn app_id(mut self, id: impl Into<String>) -> Self {
+        self.query.application_id = Some(id.into());
+        self
+    }
+
+    /// Only includes numbers which are, or are not, linked to any application.
+    pub fn has_app(mut self, has_app: bool) -> Self {
+        self.query.has_application = Some(has_app);
+        self
+    }
+}
+
+impl<C: HttpTransport + Send + Sync + 'static> ListNumbers<C> {
+    /// Returns a stream over every matching number, fetching pages of them as it is polled.
+    pub fn into_stream(self) -> Paginated<OwnedNumber> {
+        #[derive(Deserialize)]
+        struct ResponseBody {
+            count: u64,
+            #[serde(default)]
+            numbers: Vec<OwnedNumber>,
+        }
+
+        let http_client = self.http_client;
+        let query = self.query;
+        Paginated::new(move |page| {
+            let http_client = http_client.clone();
+            let query = OwnedQuery {
+                index: Some(page.index),
+                size: Some(page.size),
+                ..query.clone()
+            };
+
+            async move {
+                let uri = crate::encode_rest_uri(&["account", "numbers"]);
+                let request = crate::encode_get(uri, &query)?;
+                http_client
+                    .send(Product::Numbers, request, |response| {
+                        match response.status() {
+                            StatusCode::OK => {}
+                            _ => return Err(Error::new_status(&response)),
+                        }
+
+                        let body: ResponseBody = crate::http_client::decode_json(response.body())?;
+                        Ok(Page {
+                            items: body.numbers,
+                            total_count: Some(body.count),
+                        })
+                    })
+                    .await
+            }
+        })
+    }
+}
+
+impl<C> Debug for ListNumbers<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(ListNumbers))
+            .field("query", &self.query)
+            .finish()
+    }
+}
+
+/// A phone number owned by the account.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct OwnedNumber {
+    /// The country the number is located in.
+    pub country: Id,
+    /// The number in E.164 format, without a leading `+`.
+    pub msisdn: String,
+    /// The kind of the number.
+    #[serde(rename = "type")]
+    pub number_type: NumberType,
+    /// The capabilities of the number.
+    #[serde(default)]
+    pub features: Vec<Feature>,
+    /// The ID of the Vonage application the number is linked to, if any.
+    #[serde(default, rename = "app_id")]
+    pub app_id: Option<String>,
+    /// The webhook which inbound SMS messages to the number are sent to, if any.
+    #[serde(default, rename = "moHttpUrl")]
+    pub inbound_sms_url: Option<String>,
+    /// The webhook which events about voice calls to the number are sent to, if any.
+    #[serde(default, rename = "voiceStatusCallback")]
+    pub voice_status_url: Option<String>,
+}
+
+impl OwnedNumber {
+    /// Returns whether the number has the given capability.
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.features.contains(&feature)
+    }
+}
+
+/// A request for the messaging capabilities of an owned number.
+///
+/// This is returned from
+/// [`NumbersApi::capabilities()`](./struct.NumbersApi.html#method.capabilities).
+pub struct QueryCapabilities<C> {
+    http_client: HttpClient<C>,
+    query: OwnedQuery,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct OwnedQuery {
+    api_key: ApiKey,
+    api_secret: ApiSecret,
+    country: Option<Id>,
+    pattern: Option<String>,
+    search_pattern: Option<u8>,
+    application_id: Option<String>,
+    has_application: Option<bool>,
+    size: Option<u32>,
+    index: Option<u32>,
+}
+
+impl OwnedQuery {
+    fn new((api_key, api_secret): &(ApiKey, ApiSecret)) -> Self {
+        OwnedQuery {
+            api_key: api_key.clone(),
+            api_secret: api_secret.clone(),
+            country: None,
+            pattern: None,
+            search_pattern: None,
+            application_id: None,
+            has_application: None,
+            size: None,
+            index: None,
+        }
+    }
+}
+
+impl<C: HttpTransport> QueryCapabilities<C> {
+    /// Sends the request.
+    ///
+    /// Returns `None` if the number is not owned by the account.
+    pub async fn send(self) -> Result<Option<Sender>> {
+        #[derive(Deserialize)]
+        struct OwnedNumber {
+            msisdn: String,
+            #[serde(default)]
+            features: Vec<Feature>,
+        }
+
+        #[derive(Deserialize)]
+        struct ResponseBody {
+            #[serde(default)]
+            numbers: Vec<OwnedNumber>,
+        }
+
+        let msisdn = self.query.pattern.clone().unwrap_or_default();
+        let uri = crate::encode_rest_uri(&["account", "numbers"]);
+        let request = crate::encode_get(uri, &self.query)?;
+        self.http_client
+            .send(Product::Numbers, request, |response| {
+                match response.status() {
+                    StatusCode::OK => {}
+                    _ => return Err(Error::new_status(&response)),
+                }
+
+                // The pattern matches by prefix, so longer numbers may be returned as well.
+                let body: ResponseBody = crate::http_client::decode_json(response.body())?;
+                let sender = body
+                    .numbers
+                    .into_iter()
+                    .find(|number| number.msisdn == msisdn)
+                    .map(|number| Sender::number(number.msisdn, &number.features));
+                Ok(sender)
+            })
+            .await
+    }
+}
+
+impl<C> Debug for QueryCapabilities<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(QueryCapabilities))
+            .field("query", &self.query)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use bytes::Bytes;
+    use http::Request;
+    use http_body_util::Full;
+    use tower::service_fn;
+
+    use crate::{Client, ErrorKind};
+
+    #[tokio::test]
+    async fn searches_toll_free_numbers() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.uri().host(), Some("rest.nexmo.com"));
+            assert_eq!(request.uri().path(), "/number/search");
+            let query = request.uri().query().unwrap();
+            assert!(query.contains("country=US"));
+            assert!(query.contains("type=landline-toll-free"));
+            assert!(query.contains("features=SMS%2CVOICE"));
+
+            let body = r#"{"count":1,"numbers":[{"country":"US","msisdn":"18005550100",
+                "type":"landline-toll-free","cost":"0.90","features":["VOICE","SMS"]}]}"#;
+            Ok::<_, Infallible>(http::Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let search = client
+            .numbers()
+            .search(Id::US)
+            .toll_free()
+            .features(&[Feature::Sms, Feature::Voice])
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(search.count, 1);
+        let number = &search.numbers[0];
+        assert_eq!(number.number_type, NumberType::TollFree);
+        assert!(number.supports(Feature::Sms));
+        assert!(!number.supports(Feature::Mms));
+    }
+
+    #[tokio::test]
+    async fn queries_messaging_capabilities() {
+        use crate::messages::Channel;
+
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.uri().path(), "/account/numbers");
+            assert!(request
+                .uri()
+                .query()
+                .unwrap()
+                .contains("pattern=14155550100"));
+
+            let body = r#"{"count":2,"numbers":[
+                {"country":"US","msisdn":"141555501001","type":"mobile-lvn","features":["SMS"]},
+                {"country":"US","msisdn":"14155550100","type":"mobile-lvn","features":["VOICE","SMS","MMS"]}
+            ]}"#;
+            Ok::<_, Infallible>(http::Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let sender = client.numbers().capabilities("14155550100").send().await;
+        let sender = sender.unwrap().unwrap();
+        assert_eq!(sender.id(), "14155550100");
+        assert_eq!(sender.channels(), [Channel::Sms, Channel::Mms]);
+    }
+
+    #[tokio::test]
+    async fn lists_owned_numbers_across_pages() {
+        use futures::TryStreamExt;
+
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.uri().path(), "/account/numbers");
+            let query = request.uri().query().unwrap();
+            assert!(query.contains("country=GB"));
+            assert!(query.contains("pattern=1234&search_pattern=2"));
+
+            let body = if query.contains("index=1") {
+                r#"{"count":3,"numbers":[
+                    {"country":"GB","msisdn":"447700901234","type":"mobile-lvn","features":["SMS"]},
+                    {"country":"GB","msisdn":"447700911234","type":"mobile-lvn","app_id":"app"}
+                ]}"#
+            } else {
+                assert!(query.contains("index=2"));
+                r#"{"count":3,"numbers":[
+                    {"country":"GB","msisdn":"447700921234","type":"landline","features":["VOICE"]}
+                ]}"#
+            };
+            Ok::<_, Infallible>(http::Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let numbers: Vec<_> = client
+            .numbers()
+            .list()
+            .country(Id::GB)
+            .pattern("1234", SearchPattern::EndsWith)
+            .into_stream()
+            .page_size(2)
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(numbers.len(), 3);
+        assert_eq!(numbers[1].app_id.as_deref(), Some("app"));
+        assert!(numbers[2].supports(Feature::Voice));
+    }
+
+    #[tokio::test]
+    async fn buys_numbers() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.uri().path(), "/number/buy");
+            let body = r#"{"error-code":"200","error-code-label":"success"}"#;
+            Ok::<_, Infallible>(http::Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        client.numbers().buy(Id::US, "18005550100").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reports_update_errors_in_body() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.uri().path(), "/number/update");
+            let body = r#"{"error-code":"420","error-code-label":"Number not found"}"#;
+            Ok::<_, Infallible>(http::Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let error = client
+            .numbers()
+            .update(Id::US, "18005550100")
+            .inbound_sms_url("https://example.com/inbound")
+            .send()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error.kind(), ErrorKind::Status(code) if code.as_u16() == 420));
+        assert!(error.body().unwrap().contains("Number not found"));
+    }
+}
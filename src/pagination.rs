@@ -0,0 +1,359 @@
+//! Streaming over page-based list endpoints and the query parameters they share.
+
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+
+use crate::datetime::{self, Timestamp};
+use crate::rt::MaybeSend;
+use crate::Result;
+
+const DEFAULT_PAGE_SIZE: u32 = 100;
+
+#[cfg(not(target_arch = "wasm32"))]
+type PageFuture<T> = Pin<Box<dyn Future<Output = Result<Page<T>>> + Send>>;
+#[cfg(target_arch = "wasm32")]
+type PageFuture<T> = Pin<Box<dyn Future<Output = Result<Page<T>>>>>;
+
+#[cfg(not(target_arch = "wasm32"))]
+type FetchPage<T> = Box<dyn FnMut(PageRequest) -> PageFuture<T> + Send>;
+#[cfg(target_arch = "wasm32")]
+type FetchPage<T> = Box<dyn FnMut(PageRequest) -> PageFuture<T>>;
+
+/// Filtering and ordering parameters accepted by every list endpoint.
+///
+/// Each list endpoint accepts a `ListParams`, so that date ranges, page sizes, ordering, and
+/// cursors behave identically across products. Parameters which are left unset fall back to the
+/// defaults of the respective endpoint.
+///
+/// # Example
+///
+/// ```
+/// use vonage::{ListParams, Order};
+///
+/// let params = ListParams::new().page_size(50).order(Order::Descending);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ListParams {
+    #[serde(
+        serialize_with = "datetime::iso8601::serialize_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    date_start: Option<Timestamp>,
+    #[serde(
+        serialize_with = "datetime::iso8601::serialize_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    date_end: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order: Option<Order>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+}
+
+impl ListParams {
+    /// Creates a new `ListParams` with every parameter unset.
+    pub fn new() -> Self {
+        ListParams::default()
+    }
+
+    /// Only includes items created at or after `start`.
+    pub fn since(mut self, start: Timestamp) -> Self {
+        self.date_start = Some(start);
+        self
+    }
+
+    /// Only includes items created at or before `end`.
+    pub fn until(mut self, end: Timestamp) -> Self {
+        self.date_end = Some(end);
+        self
+    }
+
+    /// Only includes items created between `start` and `end`, inclusive.
+    ///
+    /// This is a shorthand for calling [`since()`](#method.since) and [`until()`](#method.until).
+    pub fn date_range(self, start: Timestamp, end: Timestamp) -> Self {
+        self.since(start).until(end)
+    }
+
+    /// Sets the maximum number of items returned per page.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn page_size(mut self, size: u32) -> Self {
+        assert!(size > 0, "page size must be non-zero");
+        self.page_size = Some(size);
+        self
+    }
+
+    /// Sets the order in which items are returned, by creation date.
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Resumes listing from a cursor returned by an earlier request.
+    ///
+    /// This is only supported by cursor-based endpoints, and is ignored by all others.
+    pub fn cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+}
+
+/// The order in which a list endpoint returns its items.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Order {
+    /// Oldest items first.
+    #[serde(rename = "asc")]
+    Ascending,
+    /// Newest items first.
+    #[serde(rename = "desc")]
+    Descending,
+}
+
+/// The page to request from a list endpoint.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct PageRequest {
+    /// The 1-based index of the page.
+    pub index: u32,
+    /// The maximum number of items on the page.
+    pub size: u32,
+}
+
+/// A single page of results returned by a list endpoint.
+#[derive(Debug)]
+pub(crate) struct Page<T> {
+    pub items: Vec<T>,
+    pub total_count: Option<u64>,
+}
+
+/// A [`Stream`] over every item returned by a page-based list endpoint.
+///
+/// Pages are fetched lazily, one at a time, as the stream is polled. The stream ends after a page
+/// which is shorter than the page size, or once the total number of items reported by the API
+/// has been returned. If a page cannot be fetched, its error is yielded and the stream ends.
+///
+/// [`Stream`]: https://docs.rs/futures/0.3/futures/stream/trait.Stream.html
+///
+/// # Example
+///
+/// ```no_run
+/// use futures::TryStreamExt;
+/// use vonage::Paginated;
+///
+/// # async fn run<T>(items: Paginated<T>) -> vonage::Result<()> {
+/// let all: Vec<T> = items.page_size(50).try_collect().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Paginated<T> {
+    fetch: FetchPage<T>,
+    page_size: u32,
+    next_index: Option<u32>,
+    pending: Option<PageFuture<T>>,
+    buffered: VecDeque<T>,
+    fetched: u64,
+    total_count: Option<u64>,
+}
+
+impl<T> Paginated<T> {
+    /// Creates a new `Paginated` which fetches each page by calling `fetch`.
+    pub(crate) fn new<F, Fut>(mut fetch: F) -> Self
+    where
+        F: FnMut(PageRequest) -> Fut + MaybeSend + 'static,
+        Fut: Future<Output = Result<Page<T>>> + MaybeSend + 'static,
+    {
+        Paginated {
+            fetch: Box::new(move |request| Box::pin(fetch(request))),
+            page_size: DEFAULT_PAGE_SIZE,
+            next_index: Some(1),
+            pending: None,
+            buffered: VecDeque::new(),
+            fetched: 0,
+            total_count: None,
+        }
+    }
+
+    /// Sets the number of items requested per page.
+    ///
+    /// Larger pages require fewer requests, but each endpoint enforces its own maximum. By default,
+    /// 100 items are requested per page.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn page_size(mut self, size: u32) -> Self {
+        assert!(size > 0, "page size must be non-zero");
+        self.page_size = size;
+        self
+    }
+
+    /// Returns the total number of items across all pages, if reported by the API.
+    ///
+    /// This is only known once the first page has been fetched.
+    pub fn total_count(&self) -> Option<u64> {
+        self.total_count
+    }
+
+    fn is_last(&self, page: &Page<T>) -> bool {
+        let short = page.items.len() < self.page_size as usize;
+        let complete = page.total_count.is_some_and(|t| self.fetched >= t);
+        short || complete
+    }
+}
+
+impl<T> Unpin for Paginated<T> {}
+
+impl<T> Stream for Paginated<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(item) = self.buffered.pop_front() {
+                return Poll::Ready(Some(Ok(item)));
+            }
+
+            if let Some(pending) = self.pending.as_mut() {
+                let result = match pending.as_mut().poll(cx) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => return Poll::Pending,
+                };
+
+                self.pending = None;
+                match result {
+                    Ok(page) => {
+                        self.fetched += page.items.len() as u64;
+                        self.total_count = page.total_count.or(self.total_count);
+                        self.next_index = match self.next_index {
+                            Some(_) if self.is_last(&page) => None,
+                            index => index.map(|i| i + 1),
+                        };
+                        self.buffered.extend(page.items);
+                        continue;
+                    }
+                    Err(e) => {
+                        self.next_index = None;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                }
+            }
+
+            match self.next_index {
+                Some(index) => {
+                    let request = PageRequest {
+                        index,
+                        size: self.page_size,
+                    };
+                    self.pending = Some((self.fetch)(request));
+                }
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl<T> Debug for Paginated<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Paginated))
+            .field("page_size", &self.page_size)
+            .field("next_index", &self.next_index)
+            .field("buffered", &self.buffered.len())
+            .field("fetched", &self.fetched)
+            .field("total_count", &self.total_count)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use futures::{future, StreamExt, TryStreamExt};
+
+    use crate::{Error, ErrorKind};
+
+    fn numbers(total: u32, requests: Arc<Mutex<Vec<PageRequest>>>) -> Paginated<u32> {
+        Paginated::new(move |request: PageRequest| {
+            requests.lock().unwrap().push(request);
+            let start = (request.index - 1) * request.size;
+            let end = (start + request.size).min(total);
+            future::ready(Ok(Page {
+                items: (start..end).collect(),
+                total_count: Some(total.into()),
+            }))
+        })
+    }
+
+    #[test]
+    fn encodes_list_params() {
+        let params = ListParams::new();
+        assert_eq!(serde_urlencoded::to_string(&params).unwrap(), "");
+
+        let params = params
+            .date_range(
+                datetime::parse("2020-01-01 00:00:00").unwrap(),
+                datetime::parse("2020-01-31 23:59:59").unwrap(),
+            )
+            .page_size(10)
+            .order(Order::Descending)
+            .cursor("abc");
+        assert_eq!(
+            serde_urlencoded::to_string(&params).unwrap(),
+            "date_start=2020-01-01T00%3A00%3A00Z&date_end=2020-01-31T23%3A59%3A59Z\
+             &page_size=10&order=desc&cursor=abc"
+        );
+    }
+
+    #[tokio::test]
+    async fn streams_items_across_pages() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let mut stream = numbers(5, requests.clone()).page_size(2);
+        assert_eq!(stream.total_count(), None);
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), 0);
+        assert_eq!(stream.total_count(), Some(5));
+
+        let rest: Vec<u32> = stream.try_collect().await.unwrap();
+        assert_eq!(rest, vec![1, 2, 3, 4]);
+
+        let indices: Vec<u32> = requests.lock().unwrap().iter().map(|r| r.index).collect();
+        assert_eq!(indices, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn stops_when_total_count_is_reached() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let items: Vec<u32> = numbers(4, requests.clone())
+            .page_size(2)
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(items, vec![0, 1, 2, 3]);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn ends_after_an_error() {
+        let mut stream = Paginated::<u32>::new(|_| {
+            future::ready(Err(Error::with_cause(
+                ErrorKind::Http,
+                anyhow::anyhow!("connection reset"),
+            )))
+        });
+
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -0,0 +1,104 @@
+//! Helpers for handling user-supplied phone numbers.
+//!
+//! All Vonage APIs identify phone numbers in [E.164](https://en.wikipedia.org/wiki/E.164) format,
+//! which is represented by the [`PhoneNumber`] type re-exported from the [`phonenumber`] crate.
+//!
+//! [`PhoneNumber`]: ./struct.PhoneNumber.html
+//! [`phonenumber`]: https://docs.rs/phonenumber/
+
+pub use phonenumber::country::Id as Country;
+pub use phonenumber::PhoneNumber;
+
+pub use self::network::NetworkCode;
+
+use crate::{Error, ErrorKind, Result};
+
+mod network;
+
+/// Parses and validates a phone number entered by a user.
+///
+/// Numbers without a leading `+` and country calling code are interpreted as national numbers in
+/// the `default_region`, if one is given. The parsed number must be a valid, assigned number,
+/// which guarantees that it can be represented in E.164 format.
+///
+/// Returns an error of kind [`ErrorKind::InvalidPhoneNumber`] if the number cannot be parsed or
+/// is not valid.
+///
+/// [`ErrorKind::InvalidPhoneNumber`]: ../enum.ErrorKind.html#variant.InvalidPhoneNumber
+///
+/// # Example
+///
+/// ```
+/// use vonage::phone::{self, Country};
+///
+/// let number = phone::parse("(415) 555-0100", Country::US).unwrap();
+/// assert_eq!(number.to_string(), "+14155550100");
+///
+/// assert!(phone::parse("12345", None).is_err());
+/// ```
+pub fn parse(number: &str, default_region: impl Into<Option<Country>>) -> Result<PhoneNumber> {
+    let parsed = phonenumber::parse(default_region.into(), number)
+        .map_err(|e| Error::with_cause(ErrorKind::InvalidPhoneNumber, anyhow::Error::msg(e)))?;
+
+    if parsed.is_valid() {
+        Ok(parsed)
+    } else {
+        let message = format!("`{}` is not a valid phone number", number);
+        Err(Error::with_cause(
+            ErrorKind::InvalidPhoneNumber,
+            anyhow::Error::msg(message),
+        ))
+    }
+}
+
+/// (De)serializes a `PhoneNumber` as an E.164 string, as used by the Vonage APIs.
+///
+/// Responses usually omit the leading `+`, so it is optional when deserializing.
+pub(crate) mod e164 {
+    use serde::de::{self, Deserialize, Deserializer};
+    use serde::Serializer;
+
+    use super::PhoneNumber;
+
+    pub fn serialize<S: Serializer>(phone: &PhoneNumber, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(phone)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<PhoneNumber, D::Error> {
+        let number = String::deserialize(deserializer)?;
+        let international = format!("+{}", number.trim_start_matches('+'));
+        phonenumber::parse(None, &international).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_national_and_international_numbers() {
+        let number = parse("+44 20 7946 0958", None).unwrap();
+        assert_eq!(number.to_string(), "+442079460958");
+
+        let number = parse("020 7946 0958", Country::GB).unwrap();
+        assert_eq!(number.to_string(), "+442079460958");
+
+        let error = parse("020 7946 0958", None).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::InvalidPhoneNumber));
+
+        let error = parse("+1 555", None).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::InvalidPhoneNumber));
+    }
+
+    #[test]
+    fn deserializes_numbers_without_plus_sign() {
+        let mut de = serde_json::Deserializer::from_str(r#""14155550100""#);
+        let number = e164::deserialize(&mut de).unwrap();
+        assert_eq!(number.to_string(), "+14155550100");
+
+        let mut de = serde_json::Deserializer::from_str(r#""+14155550100""#);
+        assert_eq!(e164::deserialize(&mut de).unwrap(), number);
+    }
+}
@@ -0,0 +1,416 @@
+//! Mobile network identifiers.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::{Serialize, Serializer};
+
+use super::Country;
+use crate::{Error, ErrorKind, Result};
+
+/// A mobile network, identified by its MCCMNC code.
+///
+/// Delivery receipts, Number Insight lookups and Verify searches report the network of a phone
+/// number as a string of 5 or 6 digits: a 3-digit mobile country code (MCC), followed by a 2- or
+/// 3-digit mobile network code (MNC). `NetworkCode` validates such a string once, and resolves the
+/// country and, for well-known networks, the operator it belongs to.
+///
+/// # Example
+///
+/// ```
+/// use vonage::phone::{Country, NetworkCode};
+///
+/// let network: NetworkCode = "310260".parse().unwrap();
+/// assert_eq!(network.mcc(), "310");
+/// assert_eq!(network.mnc(), "260");
+/// assert_eq!(network.country(), Some(Country::US));
+/// assert_eq!(network.operator(), Some("T-Mobile"));
+///
+/// assert!("3102".parse::<NetworkCode>().is_err());
+/// ```
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct NetworkCode(String);
+
+impl NetworkCode {
+    /// Returns the 3-digit mobile country code.
+    pub fn mcc(&self) -> &str {
+        &self.0[..3]
+    }
+
+    /// Returns the 2- or 3-digit mobile network code.
+    pub fn mnc(&self) -> &str {
+        &self.0[3..]
+    }
+
+    /// Returns the country the network belongs to.
+    ///
+    /// Some mobile country codes are shared by several territories, e.g. `340` by the French
+    /// Antilles. The largest of them is returned in that case. Returns `None` for codes which are
+    /// not assigned to a country, such as international networks.
+    pub fn country(&self) -> Option<Country> {
+        let mcc: u16 = self.mcc().parse().ok()?;
+        let index = COUNTRIES.binary_search_by_key(&mcc, |&(m, _)| m).ok()?;
+        Some(COUNTRIES[index].1)
+    }
+
+    /// Returns the brand name of the network operator, if it is well-known.
+    ///
+    /// Only the largest networks of major markets are known, so `None` does not mean that the
+    /// code is invalid.
+    pub fn operator(&self) -> Option<&'static str> {
+        let index = OPERATORS.binary_search_by_key(&self.0.as_str(), |&(c, _)| c);
+        index.ok().map(|i| OPERATORS[i].1)
+    }
+}
+
+impl AsRef<str> for NetworkCode {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for NetworkCode {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl FromStr for NetworkCode {
+    type Err = Error;
+
+    /// Parses a network code, which must consist of 5 or 6 ASCII digits.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidNetworkCode`] if `s` is not a valid network
+    /// code.
+    ///
+    /// [`ErrorKind::InvalidNetworkCode`]: ../enum.ErrorKind.html#variant.InvalidNetworkCode
+    fn from_str(s: &str) -> Result<Self> {
+        let is_valid = matches!(s.len(), 5 | 6) && s.bytes().all(|b| b.is_ascii_digit());
+
+        if is_valid {
+            Ok(NetworkCode(s.to_owned()))
+        } else {
+            let message = format!("`{}` is not a valid network code", s);
+            Err(Error::with_cause(
+                ErrorKind::InvalidNetworkCode,
+                anyhow::Error::msg(message),
+            ))
+        }
+    }
+}
+
+impl Serialize for NetworkCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for NetworkCode {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Mobile country codes and the countries they are assigned to, sorted by code.
+static COUNTRIES: &[(u16, Country)] = &[
+    (202, Country::GR),
+    (204, Country::NL),
+    (206, Country::BE),
+    (208, Country::FR),
+    (212, Country::MC),
+    (213, Country::AD),
+    (214, Country::ES),
+    (216, Country::HU),
+    (218, Country::BA),
+    (219, Country::HR),
+    (220, Country::RS),
+    (222, Country::IT),
+    (225, Country::VA),
+    (226, Country::RO),
+    (228, Country::CH),
+    (230, Country::CZ),
+    (231, Country::SK),
+    (232, Country::AT),
+    (234, Country::GB),
+    (235, Country::GB),
+    (238, Country::DK),
+    (240, Country::SE),
+    (242, Country::NO),
+    (244, Country::FI),
+    (246, Country::LT),
+    (247, Country::LV),
+    (248, Country::EE),
+    (250, Country::RU),
+    (255, Country::UA),
+    (257, Country::BY),
+    (259, Country::MD),
+    (260, Country::PL),
+    (262, Country::DE),
+    (266, Country::GI),
+    (268, Country::PT),
+    (270, Country::LU),
+    (272, Country::IE),
+    (274, Country::IS),
+    (276, Country::AL),
+    (278, Country::MT),
+    (280, Country::CY),
+    (282, Country::GE),
+    (283, Country::AM),
+    (284, Country::BG),
+    (286, Country::TR),
+    (288, Country::FO),
+    (290, Country::GL),
+    (292, Country::SM),
+    (293, Country::SI),
+    (294, Country::MK),
+    (295, Country::LI),
+    (297, Country::ME),
+    (302, Country::CA),
+    (308, Country::PM),
+    (310, Country::US),
+    (311, Country::US),
+    (312, Country::US),
+    (313, Country::US),
+    (314, Country::US),
+    (315, Country::US),
+    (316, Country::US),
+    (330, Country::PR),
+    (334, Country::MX),
+    (338, Country::JM),
+    (340, Country::GP),
+    (342, Country::BB),
+    (344, Country::AG),
+    (346, Country::KY),
+    (348, Country::VG),
+    (350, Country::BM),
+    (352, Country::GD),
+    (354, Country::MS),
+    (356, Country::KN),
+    (358, Country::LC),
+    (360, Country::VC),
+    (362, Country::CW),
+    (363, Country::AW),
+    (364, Country::BS),
+    (365, Country::AI),
+    (366, Country::DM),
+    (368, Country::CU),
+    (370, Country::DO),
+    (372, Country::HT),
+    (374, Country::TT),
+    (376, Country::TC),
+    (400, Country::AZ),
+    (401, Country::KZ),
+    (402, Country::BT),
+    (404, Country::IN),
+    (405, Country::IN),
+    (406, Country::IN),
+    (410, Country::PK),
+    (412, Country::AF),
+    (413, Country::LK),
+    (414, Country::MM),
+    (415, Country::LB),
+    (416, Country::JO),
+    (417, Country::SY),
+    (418, Country::IQ),
+    (419, Country::KW),
+    (420, Country::SA),
+    (421, Country::YE),
+    (422, Country::OM),
+    (424, Country::AE),
+    (425, Country::IL),
+    (426, Country::BH),
+    (427, Country::QA),
+    (428, Country::MN),
+    (429, Country::NP),
+    (430, Country::AE),
+    (431, Country::AE),
+    (432, Country::IR),
+    (434, Country::UZ),
+    (436, Country::TJ),
+    (437, Country::KG),
+    (438, Country::TM),
+    (440, Country::JP),
+    (441, Country::JP),
+    (450, Country::KR),
+    (452, Country::VN),
+    (454, Country::HK),
+    (455, Country::MO),
+    (456, Country::KH),
+    (457, Country::LA),
+    (460, Country::CN),
+    (461, Country::CN),
+    (466, Country::TW),
+    (467, Country::KP),
+    (470, Country::BD),
+    (472, Country::MV),
+    (502, Country::MY),
+    (505, Country::AU),
+    (510, Country::ID),
+    (514, Country::TL),
+    (515, Country::PH),
+    (520, Country::TH),
+    (525, Country::SG),
+    (528, Country::BN),
+    (530, Country::NZ),
+    (536, Country::NR),
+    (537, Country::PG),
+    (539, Country::TO),
+    (540, Country::SB),
+    (541, Country::VU),
+    (542, Country::FJ),
+    (544, Country::AS),
+    (545, Country::KI),
+    (546, Country::NC),
+    (547, Country::PF),
+    (548, Country::CK),
+    (549, Country::WS),
+    (550, Country::FM),
+    (551, Country::MH),
+    (552, Country::PW),
+    (602, Country::EG),
+    (603, Country::DZ),
+    (604, Country::MA),
+    (605, Country::TN),
+    (606, Country::LY),
+    (607, Country::GM),
+    (608, Country::SN),
+    (609, Country::MR),
+    (610, Country::ML),
+    (611, Country::GN),
+    (612, Country::CI),
+    (613, Country::BF),
+    (614, Country::NE),
+    (615, Country::TG),
+    (616, Country::BJ),
+    (617, Country::MU),
+    (618, Country::LR),
+    (619, Country::SL),
+    (620, Country::GH),
+    (621, Country::NG),
+    (622, Country::TD),
+    (623, Country::CF),
+    (624, Country::CM),
+    (625, Country::CV),
+    (626, Country::ST),
+    (627, Country::GQ),
+    (628, Country::GA),
+    (629, Country::CG),
+    (630, Country::CD),
+    (631, Country::AO),
+    (632, Country::GW),
+    (633, Country::SC),
+    (634, Country::SD),
+    (635, Country::RW),
+    (636, Country::ET),
+    (637, Country::SO),
+    (638, Country::DJ),
+    (639, Country::KE),
+    (640, Country::TZ),
+    (641, Country::UG),
+    (642, Country::BI),
+    (643, Country::MZ),
+    (645, Country::ZM),
+    (646, Country::MG),
+    (647, Country::RE),
+    (648, Country::ZW),
+    (649, Country::NA),
+    (650, Country::MW),
+    (651, Country::LS),
+    (652, Country::BW),
+    (653, Country::SZ),
+    (654, Country::KM),
+    (655, Country::ZA),
+    (657, Country::ER),
+    (659, Country::SS),
+    (702, Country::BZ),
+    (704, Country::GT),
+    (706, Country::SV),
+    (708, Country::HN),
+    (710, Country::NI),
+    (712, Country::CR),
+    (714, Country::PA),
+    (716, Country::PE),
+    (722, Country::AR),
+    (724, Country::BR),
+    (730, Country::CL),
+    (732, Country::CO),
+    (734, Country::VE),
+    (736, Country::BO),
+    (738, Country::GY),
+    (740, Country::EC),
+    (744, Country::PY),
+    (746, Country::SR),
+    (748, Country::UY),
+    (750, Country::FK),
+];
+
+/// Network codes of well-known operators and their brand names, sorted by code.
+static OPERATORS: &[(&str, &str)] = &[
+    ("20404", "Vodafone"),
+    ("20408", "KPN"),
+    ("20416", "Odido"),
+    ("20801", "Orange"),
+    ("20810", "SFR"),
+    ("20815", "Free Mobile"),
+    ("20820", "Bouygues Telecom"),
+    ("21401", "Vodafone"),
+    ("21403", "Orange"),
+    ("21407", "Movistar"),
+    ("22201", "TIM"),
+    ("22210", "Vodafone"),
+    ("22288", "WindTre"),
+    ("23410", "O2"),
+    ("23415", "Vodafone"),
+    ("23420", "Three"),
+    ("23430", "EE"),
+    ("26201", "Telekom"),
+    ("26202", "Vodafone"),
+    ("26203", "O2"),
+    ("302220", "Telus"),
+    ("302610", "Bell"),
+    ("302720", "Rogers"),
+    ("310260", "T-Mobile"),
+    ("310410", "AT&T"),
+    ("311480", "Verizon"),
+    ("40410", "Airtel"),
+    ("40445", "Airtel"),
+    ("44010", "NTT Docomo"),
+    ("44020", "SoftBank"),
+    ("50501", "Telstra"),
+    ("50502", "Optus"),
+    ("50503", "Vodafone"),
+    ("72405", "Claro"),
+    ("72406", "Vivo"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_country_and_operator() {
+        assert!(COUNTRIES.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(OPERATORS.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(OPERATORS
+            .iter()
+            .all(|(code, _)| code.parse::<NetworkCode>().is_ok()));
+
+        let network: NetworkCode = "23415".parse().unwrap();
+        assert_eq!(network.mnc(), "15");
+        assert_eq!(network.country(), Some(Country::GB));
+        assert_eq!(network.operator(), Some("Vodafone"));
+
+        let unknown: NetworkCode = "90101".parse().unwrap();
+        assert_eq!(unknown.country(), None);
+        assert_eq!(unknown.operator(), None);
+        assert!(matches!(
+            "2341a".parse::<NetworkCode>().unwrap_err().kind(),
+            ErrorKind::InvalidNetworkCode
+        ));
+    }
+}
@@ -0,0 +1,254 @@
+//! Orchestration of workflows which chain several Vonage products.
+//!
+//! Onboarding flows commonly look up a number with Number Insight, start a verification, and fall
+//! back to a plain SMS if the verification cannot be delivered. A [`Pipeline`] runs each of these
+//! steps with its own timeout, aborts the whole flow when cancelled, and tags errors with the name
+//! of the step which failed.
+//!
+//! [`Pipeline`]: ./struct.Pipeline.html
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use futures::TryFutureExt;
+//! use http::Method;
+//! use vonage::pipeline::{Outcome, Pipeline};
+//! use vonage::Client;
+//!
+//! # async fn run() -> vonage::Result<()> {
+//! let client = Client::new("<api_key>", "<api_secret>");
+//! let phone = phonenumber::parse(None, "+14155550100").unwrap();
+//! let pipeline = Pipeline::new().timeout(Duration::from_secs(10));
+//!
+//! let insight = pipeline.step("insight", async {
+//!     let path = format!("/ni/basic/json?number={}", phone.format().mode(phonenumber::Mode::E164));
+//!     client.request_raw(Method::GET, &path, "").await
+//! });
+//! insight.await?;
+//!
+//! let outcome = pipeline
+//!     .with_fallback(
+//!         "verify",
+//!         client.verify().request(phone.clone(), "ACME").send().map_ok(Some),
+//!         "sms",
+//!         |_| client.request_raw(Method::POST, "/sms/json", "...").map_ok(|_| None),
+//!     )
+//!     .await?;
+//!
+//! match outcome {
+//!     Outcome::Primary(pending) => println!("verification sent: {:?}", pending),
+//!     Outcome::Fallback { cause, .. } => println!("verification failed, sent SMS: {}", cause),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use futures::future::{self, Either};
+
+use crate::{rt, CancellationToken, Error, ErrorKind, Result};
+
+/// Runs the steps of a multi-product workflow with per-step timeouts and shared cancellation.
+///
+/// Pipelines are cheap to clone, and clones share the same cancellation token.
+#[derive(Clone, Debug, Default)]
+pub struct Pipeline {
+    timeout: Option<Duration>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl Pipeline {
+    /// Creates a new `Pipeline` without timeouts or cancellation.
+    pub fn new() -> Self {
+        Pipeline::default()
+    }
+
+    /// Sets the default timeout applied to every step.
+    ///
+    /// Steps exceeding it fail with an error of kind
+    /// [`ErrorKind::Timeout`](../enum.ErrorKind.html#variant.Timeout). By default, steps may run
+    /// indefinitely.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Registers a [`CancellationToken`](../struct.CancellationToken.html) which aborts the
+    /// running step and every subsequent step once cancelled.
+    ///
+    /// Aborted steps fail with an error of kind
+    /// [`ErrorKind::Cancelled`](../enum.ErrorKind.html#variant.Cancelled).
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Runs a single step named `name` with the default timeout.
+    ///
+    /// If the step fails, its name is available from
+    /// [`Error::step()`](../struct.Error.html#method.step).
+    pub async fn step<F, T>(&self, name: &str, step: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        self.run(name, self.timeout, step).await
+    }
+
+    /// Like [`step()`](#method.step), but overrides the default timeout for this step only.
+    pub async fn step_with_timeout<F, T>(&self, name: &str, timeout: Duration, step: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        self.run(name, Some(timeout), step).await
+    }
+
+    /// Runs the step `primary`, falling back to the step returned by `fallback` if it fails.
+    ///
+    /// `fallback` receives the error returned by `primary`, which is also reported in the
+    /// returned [`Outcome`](./enum.Outcome.html). There is no fallback if the pipeline was
+    /// cancelled, in which case the cancellation error is returned instead.
+    pub async fn with_fallback<F, G, U, T>(
+        &self,
+        primary_name: &str,
+        primary: F,
+        fallback_name: &str,
+        fallback: G,
+    ) -> Result<Outcome<T>>
+    where
+        F: Future<Output = Result<T>>,
+        G: FnOnce(&Error) -> U,
+        U: Future<Output = Result<T>>,
+    {
+        match self.step(primary_name, primary).await {
+            Ok(value) => Ok(Outcome::Primary(value)),
+            Err(e) if matches!(e.kind(), ErrorKind::Cancelled) => Err(e),
+            Err(cause) => {
+                let value = self.step(fallback_name, fallback(&cause)).await?;
+                Ok(Outcome::Fallback { value, cause })
+            }
+        }
+    }
+
+    async fn run<F, T>(&self, name: &str, timeout: Option<Duration>, step: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let timed = async {
+            let timeout = match timeout {
+                Some(timeout) => timeout,
+                None => return step.await,
+            };
+
+            futures::pin_mut!(step);
+            let sleep = rt::sleep(timeout);
+            futures::pin_mut!(sleep);
+            match future::select(step, sleep).await {
+                Either::Left((result, _)) => result,
+                Either::Right(_) => Err(Error::with_cause(
+                    ErrorKind::Timeout,
+                    anyhow!("step timed out after {:?}", timeout),
+                )),
+            }
+        };
+
+        let result = match self.cancellation.as_ref() {
+            Some(token) => token.run(timed).await,
+            None => timed.await,
+        };
+
+        result.map_err(|e| e.with_step(name))
+    }
+}
+
+/// The result of [`Pipeline::with_fallback()`](./struct.Pipeline.html#method.with_fallback).
+#[derive(Debug)]
+pub enum Outcome<T> {
+    /// The primary step succeeded.
+    Primary(T),
+    /// The primary step failed with `cause`, and the fallback step succeeded.
+    Fallback {
+        /// The value returned by the fallback step.
+        value: T,
+        /// The error returned by the primary step.
+        cause: Error,
+    },
+}
+
+impl<T> Outcome<T> {
+    /// Returns whether the fallback step was taken.
+    pub fn is_fallback(&self) -> bool {
+        matches!(self, Outcome::Fallback { .. })
+    }
+
+    /// Returns the value of whichever step succeeded.
+    pub fn into_inner(self) -> T {
+        match self {
+            Outcome::Primary(value) | Outcome::Fallback { value, .. } => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn times_out_slow_steps() {
+        let pipeline = Pipeline::new().timeout(Duration::from_secs(60));
+
+        let error = pipeline
+            .step_with_timeout("insight", Duration::from_millis(10), async {
+                rt::sleep(Duration::from_secs(60)).await;
+                Ok(())
+            })
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error.kind(), ErrorKind::Timeout));
+        assert_eq!(error.step(), Some("insight"));
+
+        let value = pipeline.step("verify", async { Ok(42) }).await.unwrap();
+        assert_eq!(value, 42);
+    }
+
+    #[tokio::test]
+    async fn falls_back_unless_cancelled() {
+        let token = CancellationToken::new();
+        let pipeline = Pipeline::new().cancellation_token(token.clone());
+        let failing = || async { Err::<u32, _>(Error::from(http::StatusCode::BAD_GATEWAY)) };
+
+        let outcome = pipeline
+            .with_fallback("verify", failing(), "sms", |cause| {
+                assert_eq!(cause.step(), Some("verify"));
+                async { Ok(7) }
+            })
+            .await
+            .unwrap();
+
+        assert!(outcome.is_fallback());
+        match outcome {
+            Outcome::Fallback { value, cause } => {
+                assert_eq!(value, 7);
+                assert!(matches!(
+                    cause.kind(),
+                    ErrorKind::Status(http::StatusCode::BAD_GATEWAY)
+                ));
+            }
+            Outcome::Primary(_) => unreachable!(),
+        }
+
+        token.cancel();
+        let error = pipeline
+            .with_fallback("verify", failing(), "sms", |_| async { Ok(7) })
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error.kind(), ErrorKind::Cancelled));
+        assert_eq!(error.step(), Some("verify"));
+    }
+}
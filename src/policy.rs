@@ -0,0 +1,135 @@
+//! Timeouts and retries of requests made through a product handle.
+
+use std::time::Duration;
+
+use http::{Method, StatusCode};
+
+/// The timeout and retry budget of requests made through a product handle.
+///
+/// Products have different needs: a verify check should fail fast so the user can try again,
+/// while a report download may take minutes. Each handle, such as
+/// [`VerifyApi`](./verify/struct.VerifyApi.html), therefore has its own policy, which is set with
+/// its `policy()` method and inherited by the requests and handles it returns. A default for all
+/// handles can be set with [`ClientBuilder::policy()`](./struct.ClientBuilder.html#method.policy).
+///
+/// By default, requests have no timeout and are not retried.
+///
+/// Requests are retried if they time out, fail to connect, or receive a `429 Too Many Requests`
+/// or `5xx` response, waiting for the delay given by `Retry-After` or an exponential backoff
+/// between attempts. A request which timed out may still have reached Vonage, so retrying
+/// requests which are not idempotent, such as sending a verify request, may perform them twice.
+/// This is avoided with [`idempotent_only()`](#method.idempotent_only), which retries `POST`
+/// requests only on `429 Too Many Requests`, since those were rejected without being processed.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use vonage::{Client, RequestPolicy};
+///
+/// # fn main() -> vonage::Result<()> {
+/// let client = Client::builder().api_key("<key>", "<secret>").build()?;
+///
+/// let verify = client.verify().policy(
+///     RequestPolicy::new()
+///         .timeout(Duration::from_secs(2))
+///         .max_retries(1),
+/// );
+/// let usage = client
+///     .usage()
+///     .policy(RequestPolicy::new().timeout(Duration::from_secs(120)).max_retries(5));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RequestPolicy {
+    timeout: Option<Duration>,
+    max_retries: u32,
+    backoff: Duration,
+    idempotent_only: bool,
+}
+
+/// An alias of [`RequestPolicy`], as used with
+/// [`ClientBuilder::retry()`](./struct.ClientBuilder.html#method.retry).
+pub type RetryPolicy = RequestPolicy;
+
+impl RequestPolicy {
+    /// Creates a new policy without a timeout or retries.
+    pub fn new() -> Self {
+        RequestPolicy {
+            timeout: None,
+            max_retries: 0,
+            backoff: Duration::from_millis(500),
+            idempotent_only: false,
+        }
+    }
+
+    /// Creates a new policy which retries failed requests up to `max_retries` times with an
+    /// exponential backoff, but only retries requests which are not idempotent if they were
+    /// rate limited.
+    ///
+    /// This is a safe default for all products, e.g. to set with
+    /// [`ClientBuilder::policy()`](./struct.ClientBuilder.html#method.policy).
+    pub fn exponential(max_retries: u32) -> Self {
+        RequestPolicy::new()
+            .max_retries(max_retries)
+            .idempotent_only(true)
+    }
+
+    /// Fails each attempt which receives no response within `timeout` with an error of kind
+    /// [`ErrorKind::Timeout`](./enum.ErrorKind.html#variant.Timeout).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retries a failed request up to `max_retries` times.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the delay before the first retry, which doubles with every further retry. Defaults to
+    /// 500 milliseconds.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Sets whether requests which are not idempotent, i.e. `POST` and `PATCH` requests, are only
+    /// retried if they receive a `429 Too Many Requests` response. Defaults to `false`.
+    pub fn idempotent_only(mut self, idempotent_only: bool) -> Self {
+        self.idempotent_only = idempotent_only;
+        self
+    }
+
+    pub(crate) fn timeout_duration(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub(crate) fn retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Returns whether a `method` request which failed with `status`, or without a response if
+    /// `None`, may be retried.
+    pub(crate) fn may_retry(&self, method: &Method, status: Option<StatusCode>) -> bool {
+        match status {
+            Some(StatusCode::TOO_MANY_REQUESTS) => true,
+            Some(status) if !status.is_server_error() => false,
+            _ => !self.idempotent_only || method.is_idempotent(),
+        }
+    }
+
+    /// Returns the delay before the retry following the given number of earlier retries.
+    pub(crate) fn delay(&self, retries: u32) -> Duration {
+        self.backoff.saturating_mul(1 << retries.min(16))
+    }
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        RequestPolicy::new()
+    }
+}
@@ -0,0 +1,150 @@
+//! Structured error details returned by newer Vonage APIs.
+
+use bytes::Bytes;
+use http::header::CONTENT_TYPE;
+use http::Response;
+use serde::{Deserialize, Serialize};
+
+const PROBLEM_JSON: &str = "application/problem+json";
+
+/// An [RFC 7807](https://tools.ietf.org/html/rfc7807) problem description returned by a Vonage
+/// API.
+///
+/// Newer APIs, such as Messages, Applications v2, and Verify v2, report failures as
+/// `application/problem+json` documents. Such details can be obtained from an [`Error`] with
+/// [`Error::as_problem()`].
+///
+/// [`Error`]: ./struct.Error.html
+/// [`Error::as_problem()`]: ./struct.Error.html#method.as_problem
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, thiserror::Error)]
+#[error("{title}")]
+pub struct Problem {
+    #[serde(rename = "type")]
+    kind: String,
+    title: String,
+    #[serde(default)]
+    detail: Option<String>,
+    #[serde(default)]
+    instance: Option<String>,
+    #[serde(default)]
+    invalid_parameters: Vec<InvalidParameter>,
+}
+
+impl Problem {
+    /// Returns a URI identifying the problem type, which links to its documentation.
+    #[inline]
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Returns a short, human-readable summary of the problem type.
+    #[inline]
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns a human-readable explanation specific to this occurrence of the problem, if any.
+    #[inline]
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    /// Returns an identifier for this occurrence of the problem, if any.
+    ///
+    /// Vonage support can use this to locate the failed request.
+    #[inline]
+    pub fn instance(&self) -> Option<&str> {
+        self.instance.as_deref()
+    }
+
+    /// Returns the request parameters which failed validation, if any.
+    #[inline]
+    pub fn invalid_parameters(&self) -> &[InvalidParameter] {
+        &self.invalid_parameters
+    }
+
+    /// Parses the body of `response` if it is an `application/problem+json` document.
+    pub(crate) fn from_response(response: &Response<Bytes>) -> Option<Self> {
+        let content_type = response.headers().get(CONTENT_TYPE)?.to_str().ok()?;
+        let media_type = content_type.split(';').next().unwrap_or_default().trim();
+        if !media_type.eq_ignore_ascii_case(PROBLEM_JSON) {
+            return None;
+        }
+
+        serde_json::from_slice(response.body()).ok()
+    }
+}
+
+/// A request parameter which failed validation, as reported in a [`Problem`] or an
+/// [`InvalidRequest`].
+///
+/// [`Problem`]: ./struct.Problem.html
+/// [`InvalidRequest`]: ./struct.InvalidRequest.html
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct InvalidParameter {
+    name: String,
+    reason: String,
+}
+
+impl InvalidParameter {
+    pub(crate) fn new(name: String, reason: String) -> Self {
+        InvalidParameter { name, reason }
+    }
+
+    /// Returns the name of the invalid parameter.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns why the parameter is invalid.
+    #[inline]
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http::StatusCode;
+
+    use crate::{Error, ErrorKind};
+
+    #[test]
+    fn parses_problem_details() {
+        let body = r#"{
+            "type": "https://developer.nexmo.com/api-errors#invalid-params",
+            "title": "Your request parameters did not validate.",
+            "detail": "The value of one or more parameters is invalid.",
+            "instance": "bf0ca0bf927b3b52e3cb03217e1a1ddf",
+            "invalid_parameters": [{"name": "to", "reason": "Invalid number"}]
+        }"#;
+
+        let response = Response::builder()
+            .status(StatusCode::UNPROCESSABLE_ENTITY)
+            .header(CONTENT_TYPE, "application/problem+json; charset=utf-8")
+            .body(Bytes::from(body))
+            .unwrap();
+
+        let error = Error::new_status(&response);
+        assert!(matches!(
+            error.kind(),
+            ErrorKind::Status(StatusCode::UNPROCESSABLE_ENTITY)
+        ));
+
+        let problem = error.as_problem().unwrap();
+        assert_eq!(problem.title(), "Your request parameters did not validate.");
+        assert_eq!(problem.instance(), Some("bf0ca0bf927b3b52e3cb03217e1a1ddf"));
+        assert_eq!(problem.invalid_parameters()[0].name(), "to");
+        assert_eq!(problem.invalid_parameters()[0].reason(), "Invalid number");
+
+        let response = Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Bytes::from(body))
+            .unwrap();
+        assert!(Error::new_status(&response).as_problem().is_none());
+    }
+}
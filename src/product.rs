@@ -0,0 +1,77 @@
+//! Identifiers for the individual Vonage products.
+
+use serde::{Deserialize, Serialize};
+
+/// A list of Vonage products supported by this library.
+///
+/// This is used to scope per-product client settings, such as
+/// [`ClientBuilder::rate_limit()`](./struct.ClientBuilder.html#method.rate_limit).
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum Product {
+    /// The [Verify (2FA)](https://developer.nexmo.com/api/verify) API.
+    Verify,
+    /// The [Numbers](https://developer.nexmo.com/api/numbers) API.
+    Numbers,
+    /// The [Number Insight](https://developer.nexmo.com/api/number-insight) API.
+    NumberInsight,
+    /// The [Voice](https://developer.nexmo.com/api/voice) API.
+    Voice,
+    /// The [Reports](https://developer.nexmo.com/api/reports) API.
+    Reports,
+    /// The [Applications](https://developer.nexmo.com/api/application.v2) API.
+    Applications,
+    /// The [SMS](https://developer.nexmo.com/api/sms) API.
+    Sms,
+    /// The [Messages](https://developer.nexmo.com/api/messages-olympus) API.
+    Messages,
+    /// Any endpoint which is not modeled by this library, called through
+    /// [`Client::request_raw()`](./struct.Client.html#method.request_raw).
+    Other,
+}
+
+impl Product {
+    /// Returns whether this product is served from multiple regional hosts.
+    pub(crate) fn supports_failover(self) -> bool {
+        match self {
+            Product::Voice | Product::Messages => true,
+            Product::Verify
+            | Product::Numbers
+            | Product::NumberInsight
+            | Product::Reports
+            | Product::Applications
+            | Product::Sms
+            | Product::Other => false,
+        }
+    }
+
+    /// Returns the product serving requests to `path` which are sent through
+    /// [`Client::request_raw()`](./struct.Client.html#method.request_raw).
+    pub(crate) fn for_raw_path(path: &str) -> Self {
+        if path.starts_with("/sms/") {
+            Product::Sms
+        } else if path == "/v1/messages" || path.starts_with("/v1/messages/") {
+            Product::Messages
+        } else {
+            Product::Other
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_raw_request_paths() {
+        let product = Product::for_raw_path;
+        assert_eq!(product("/sms/json"), Product::Sms);
+        assert_eq!(product("/v1/messages"), Product::Messages);
+        assert_eq!(product("/v1/messages/abc"), Product::Messages);
+        assert_eq!(product("/v1/messagesx"), Product::Other);
+        assert_eq!(product("/account/get-balance"), Product::Other);
+
+        assert!(Product::Messages.supports_failover());
+        assert!(!Product::Sms.supports_failover());
+    }
+}
@@ -0,0 +1,259 @@
+//! Client-side rate limiting of outgoing requests.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::rt::{self, Instant};
+use crate::Product;
+
+/// A token bucket rate limit applied to requests sent to a single Vonage product.
+///
+/// Requests exceeding the limit are delayed until a token becomes available, rather than being
+/// sent immediately and rejected by Vonage with a throttling error. For example, the
+/// [SMS API](https://developer.nexmo.com/messaging/sms/overview) accepts 30 requests per second by
+/// default, which can be expressed as `RateLimit::per_second(30)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    requests: u32,
+    period: Duration,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// Creates a new `RateLimit` allowing up to `requests` requests to be sent every `period`.
+    ///
+    /// By default, the burst size is equal to `requests`. Use [`RateLimit::burst()`] to change it.
+    ///
+    /// [`RateLimit::burst()`]: #method.burst
+    ///
+    /// # Panics
+    ///
+    /// Panics if either `requests` or `period` are zero.
+    pub fn new(requests: u32, period: Duration) -> Self {
+        assert!(requests > 0, "rate limit must allow at least one request");
+        assert!(
+            period > Duration::from_secs(0),
+            "rate limit period must be non-zero"
+        );
+        RateLimit {
+            requests,
+            period,
+            burst: requests,
+        }
+    }
+
+    /// Creates a new `RateLimit` allowing up to `requests` requests to be sent per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests` is zero.
+    #[inline]
+    pub fn per_second(requests: u32) -> Self {
+        RateLimit::new(requests, Duration::from_secs(1))
+    }
+
+    /// Sets the maximum number of requests which may be sent back-to-back without delay.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `burst` is zero.
+    pub fn burst(mut self, burst: u32) -> Self {
+        assert!(burst > 0, "rate limit burst size must be non-zero");
+        self.burst = burst;
+        self
+    }
+
+    fn tokens_per_sec(&self) -> f64 {
+        f64::from(self.requests) / self.period.as_secs_f64()
+    }
+}
+
+/// Rate limits which are enforced jointly by every client they are passed to.
+///
+/// The limits set with [`ClientBuilder::rate_limit()`] apply to a single `Client` and its clones.
+/// Separately built clients, e.g. with different credentials for the same account or different
+/// middleware, each get their own buckets. Vonage enforces its throughput limits per account
+/// though, so pass a `SharedRateLimiter` to
+/// [`ClientBuilder::shared_rate_limiter()`](./struct.ClientBuilder.html#method.shared_rate_limiter)
+/// of every such client to enforce a limit across all of them.
+///
+/// Clones of a `SharedRateLimiter` share the same buckets, so configure it before cloning it.
+///
+/// [`ClientBuilder::rate_limit()`]: ./struct.ClientBuilder.html#method.rate_limit
+///
+/// # Example
+///
+/// ```
+/// use vonage::{Client, Product, RateLimit, SharedRateLimiter};
+///
+/// let limiter = SharedRateLimiter::new()
+///     .account_limit(RateLimit::per_second(30))
+///     .rate_limit(Product::Verify, RateLimit::per_second(10));
+///
+/// let primary = Client::builder()
+///     .api_key("key", "secret")
+///     .shared_rate_limiter(limiter.clone())
+///     .build();
+/// let reporting = Client::builder()
+///     .api_key("key", "secret")
+///     .shared_rate_limiter(limiter)
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SharedRateLimiter {
+    buckets: HashMap<Product, Arc<TokenBucket>>,
+    account: Option<Arc<TokenBucket>>,
+}
+
+impl SharedRateLimiter {
+    /// Creates a new `SharedRateLimiter` without any limits.
+    pub fn new() -> Self {
+        SharedRateLimiter::default()
+    }
+
+    /// Limits the rate at which requests are sent to the given Vonage `product`.
+    ///
+    /// Calling this method again for the same product replaces the previous limit.
+    pub fn rate_limit(mut self, product: Product, limit: RateLimit) -> Self {
+        self.buckets
+            .insert(product, Arc::new(TokenBucket::new(limit)));
+        self
+    }
+
+    /// Limits the rate at which requests are sent to all Vonage products combined.
+    ///
+    /// This applies in addition to any per-product limit.
+    pub fn account_limit(mut self, limit: RateLimit) -> Self {
+        self.account = Some(Arc::new(TokenBucket::new(limit)));
+        self
+    }
+
+    fn reserve(&self, product: Product) -> Option<Duration> {
+        let product = self.buckets.get(&product).and_then(|b| b.reserve());
+        let account = self.account.as_ref().and_then(|b| b.reserve());
+        product.max(account)
+    }
+}
+
+/// A set of token buckets, one per rate-limited product, and optionally a set of buckets shared
+/// with other clients.
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+    buckets: HashMap<Product, TokenBucket>,
+    shared: Option<SharedRateLimiter>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: HashMap<Product, RateLimit>, shared: Option<SharedRateLimiter>) -> Self {
+        RateLimiter {
+            buckets: limits
+                .into_iter()
+                .map(|(product, limit)| (product, TokenBucket::new(limit)))
+                .collect(),
+            shared,
+        }
+    }
+
+    /// Waits until a request to the given `product` may be sent.
+    pub async fn acquire(&self, product: Product) {
+        let own = self.buckets.get(&product).and_then(TokenBucket::reserve);
+        let shared = self.shared.as_ref().and_then(|s| s.reserve(product));
+        if let Some(wait) = own.max(shared) {
+            rt::sleep(wait).await;
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    limit: RateLimit,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit) -> Self {
+        TokenBucket {
+            limit,
+            state: Mutex::new(BucketState {
+                tokens: f64::from(limit.burst),
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token from the bucket, returning how long the caller must wait before sending.
+    ///
+    /// The token count is allowed to go negative, which reserves a slot for the caller in the
+    /// order the reservations were made.
+    fn reserve(&self) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let rate = self.limit.tokens_per_sec();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+
+        state.tokens = (state.tokens + elapsed * rate).min(f64::from(self.limit.burst));
+        state.last_refill = now;
+        state.tokens -= 1.0;
+
+        if state.tokens >= 0.0 {
+            None
+        } else {
+            Some(Duration::from_secs_f64(-state.tokens / rate))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_burst_then_delays() {
+        let bucket = TokenBucket::new(RateLimit::per_second(10).burst(2));
+        assert_eq!(bucket.reserve(), None);
+        assert_eq!(bucket.reserve(), None);
+
+        let first = bucket.reserve().expect("bucket should be empty");
+        let second = bucket.reserve().expect("bucket should be empty");
+        assert!(first <= Duration::from_millis(100));
+        assert!(second > first);
+    }
+
+    #[tokio::test]
+    async fn ignores_unlimited_products() {
+        let limiter = RateLimiter::default();
+        for _ in 0..100 {
+            limiter.acquire(Product::Verify).await;
+        }
+    }
+
+    #[test]
+    fn shares_buckets_between_clones() {
+        let limiter = SharedRateLimiter::new()
+            .rate_limit(Product::Verify, RateLimit::per_second(10).burst(1))
+            .account_limit(RateLimit::per_second(10).burst(2));
+        let clone = limiter.clone();
+
+        assert_eq!(limiter.reserve(Product::Verify), None);
+        assert!(clone.reserve(Product::Verify).is_some());
+
+        let limiter = SharedRateLimiter::new().account_limit(RateLimit::per_second(10).burst(2));
+        let clone = limiter.clone();
+        assert_eq!(limiter.reserve(Product::Verify), None);
+        assert_eq!(clone.reserve(Product::Numbers), None);
+        assert!(limiter.reserve(Product::Voice).is_some());
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_zero_requests() {
+        let _ = RateLimit::per_second(0);
+    }
+}
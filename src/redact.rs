@@ -0,0 +1,106 @@
+//! Masking of secrets in encoded requests, for logging and recording.
+
+use http::header::{HeaderMap, CONTENT_TYPE};
+use http::Uri;
+use serde_json::Value;
+
+/// Parameters and JSON fields whose values are always masked.
+const SECRETS: &[&str] = &[
+    "api_key",
+    "api_secret",
+    "code",
+    "password",
+    "pin_code",
+    "private_key",
+    "sig",
+];
+const REDACTED: &str = "REDACTED";
+
+/// Returns the path and query of `uri` with all secret query parameters masked.
+pub(crate) fn redact_uri(uri: &Uri) -> String {
+    match uri.query() {
+        Some(query) => format!("{}?{}", uri.path(), redact_params(query)),
+        None => uri.path().to_owned(),
+    }
+}
+
+/// Returns `body` as a string with all secret form parameters or JSON fields masked.
+pub(crate) fn redact_body(headers: &HeaderMap, body: &[u8]) -> String {
+    let body = String::from_utf8_lossy(body);
+    match headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok()) {
+        Some("application/x-www-form-urlencoded") => redact_params(&body),
+        _ => redact_json(&body),
+    }
+}
+
+fn redact_params(encoded: &str) -> String {
+    match serde_urlencoded::from_str::<Vec<(String, String)>>(encoded) {
+        Ok(mut params) => {
+            for (key, value) in &mut params {
+                if SECRETS.contains(&key.as_str()) {
+                    *value = REDACTED.to_owned();
+                }
+            }
+            serde_urlencoded::to_string(params).unwrap_or_default()
+        }
+        Err(_) => encoded.to_owned(),
+    }
+}
+
+fn redact_json(body: &str) -> String {
+    fn redact(value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    if SECRETS.contains(&key.as_str()) {
+                        *value = Value::from(REDACTED);
+                    } else {
+                        redact(value);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(redact),
+            _ => {}
+        }
+    }
+
+    match serde_json::from_str::<Value>(body) {
+        Ok(mut value) => {
+            redact(&mut value);
+            value.to_string()
+        }
+        Err(_) => body.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_secrets() {
+        let uri: Uri = "/verify/search/json?api_key=key&api_secret=secret&request_id=abc"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            redact_uri(&uri),
+            "/verify/search/json?api_key=REDACTED&api_secret=REDACTED&request_id=abc"
+        );
+
+        let mut headers = HeaderMap::new();
+        let body = br#"{"private_key":"-----BEGIN","nested":[{"sig":"abc","to":"447700900000"}]}"#;
+        assert_eq!(
+            redact_body(&headers, body),
+            r#"{"nested":[{"sig":"REDACTED","to":"447700900000"}],"private_key":"REDACTED"}"#
+        );
+
+        headers.insert(
+            CONTENT_TYPE,
+            "application/x-www-form-urlencoded".parse().unwrap(),
+        );
+        assert_eq!(
+            redact_body(&headers, b"code=1234&request_id=abc"),
+            "code=REDACTED&request_id=abc"
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Metadata returned alongside decoded API responses.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use http::header::{HeaderMap, HeaderName, RETRY_AFTER};
+use http::{Response, StatusCode};
+
+pub(crate) const TRACE_ID: &str = "x-nexmo-trace-id";
+const RATE_LIMIT_LIMIT: &str = "x-ratelimit-limit";
+const RATE_LIMIT_REMAINING: &str = "x-ratelimit-remaining";
+
+/// The HTTP status and headers of a response received from the Vonage APIs.
+///
+/// This is useful for correlating requests with Vonage support via the trace ID, or for
+/// monitoring quota usage via the rate limit counters.
+#[derive(Clone, Debug, Default)]
+pub struct ResponseMeta {
+    status: StatusCode,
+    headers: HeaderMap,
+}
+
+impl ResponseMeta {
+    pub(crate) fn new<B>(response: &Response<B>) -> Self {
+        ResponseMeta {
+            status: response.status(),
+            headers: response.headers().clone(),
+        }
+    }
+
+    /// Returns the HTTP status code of the response.
+    #[inline]
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns all headers of the response.
+    #[inline]
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Returns the trace ID assigned to the request by Vonage, if any.
+    pub fn trace_id(&self) -> Option<&str> {
+        self.header(TRACE_ID)
+    }
+
+    /// Returns the maximum number of requests allowed in the current rate limit window, if known.
+    pub fn rate_limit(&self) -> Option<u64> {
+        self.header(RATE_LIMIT_LIMIT)?.parse().ok()
+    }
+
+    /// Returns the number of requests remaining in the current rate limit window, if known.
+    pub fn rate_limit_remaining(&self) -> Option<u64> {
+        self.header(RATE_LIMIT_REMAINING)?.parse().ok()
+    }
+
+    /// Returns how long Vonage asked the client to wait before sending another request, if
+    /// specified.
+    ///
+    /// This is parsed from the `Retry-After` header, which is usually present on
+    /// `429 Too Many Requests` and `503 Service Unavailable` responses.
+    pub fn retry_after(&self) -> Option<Duration> {
+        retry_after(&self.headers)
+    }
+
+    fn header(&self, name: &'static str) -> Option<&str> {
+        let value = self.headers.get(HeaderName::from_static(name))?;
+        value.to_str().ok()
+    }
+}
+
+/// Parses the `Retry-After` header, which is either a number of seconds or an HTTP date.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = date.with_timezone(&Utc) - Utc::now();
+    Some(delay.to_std().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_well_known_headers() {
+        let response = Response::builder()
+            .header("X-Nexmo-Trace-Id", "b3c0e1c8-0000-4000-8000-000000000000")
+            .header("X-RateLimit-Limit", "30")
+            .header("X-RateLimit-Remaining", "29")
+            .body(())
+            .unwrap();
+
+        let meta = ResponseMeta::new(&response);
+        assert_eq!(meta.status(), StatusCode::OK);
+        assert_eq!(
+            meta.trace_id(),
+            Some("b3c0e1c8-0000-4000-8000-000000000000")
+        );
+        assert_eq!(meta.rate_limit(), Some(30));
+        assert_eq!(meta.rate_limit_remaining(), Some(29));
+
+        let response = Response::builder()
+            .status(StatusCode::TOO_MANY_REQUESTS)
+            .header("Retry-After", "120")
+            .body(())
+            .unwrap();
+        let meta = ResponseMeta::new(&response);
+        assert_eq!(meta.retry_after(), Some(Duration::from_secs(120)));
+
+        let response = Response::builder()
+            .header("Retry-After", "Wed, 21 Oct 2015 07:28:00 GMT")
+            .body(())
+            .unwrap();
+        let meta = ResponseMeta::new(&response);
+        assert_eq!(meta.retry_after(), Some(Duration::default()));
+
+        let empty = ResponseMeta::default();
+        assert_eq!(empty.retry_after(), None);
+        assert_eq!(empty.trace_id(), None);
+        assert_eq!(empty.rate_limit_remaining(), None);
+    }
+
+    #[tokio::test]
+    async fn exposes_meta_on_pending_requests() {
+        use std::convert::Infallible;
+
+        use bytes::Bytes;
+        use http::Request;
+        use http_body_util::Full;
+        use tower::service_fn;
+
+        use crate::Client;
+
+        let service = service_fn(|_: Request<Full<Bytes>>| async {
+            let body = r#"{"request_id":"abcdef0123456789abcdef0123456789","status":"0"}"#;
+            let response = Response::builder()
+                .header("X-Nexmo-Trace-Id", "trace")
+                .body(Full::new(Bytes::from(body)))
+                .unwrap();
+            Ok::<_, Infallible>(response)
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let pending = client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(pending.response_meta().trace_id(), Some("trace"));
+    }
+}
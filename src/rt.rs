@@ -0,0 +1,47 @@
+//! Platform-specific timing primitives.
+//!
+//! `std::time::Instant` and Tokio's timer are unavailable on `wasm32-unknown-unknown`, so
+//! browser-backed equivalents are used there instead.
+
+use std::future::Future;
+use std::time::Duration;
+
+use futures::future::{self, Either};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::{Instant, SystemTime};
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::{Instant, SystemTime};
+
+/// A `Send` bound which is only enforced on platforms with multi-threaded runtimes.
+///
+/// Futures on `wasm32-unknown-unknown` run on a single thread and are generally not `Send`.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) trait MaybeSend: Send {}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send + ?Sized> MaybeSend for T {}
+
+/// A `Send` bound which is only enforced on platforms with multi-threaded runtimes.
+#[cfg(target_arch = "wasm32")]
+pub(crate) trait MaybeSend {}
+#[cfg(target_arch = "wasm32")]
+impl<T: ?Sized> MaybeSend for T {}
+
+/// Waits until `duration` has elapsed.
+pub(crate) async fn sleep(duration: Duration) {
+    #[cfg(not(target_arch = "wasm32"))]
+    tokio::time::sleep(duration).await;
+    #[cfg(target_arch = "wasm32")]
+    gloo_timers::future::sleep(duration).await;
+}
+
+/// Drives `future` to completion, unless `duration` elapses first.
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    futures::pin_mut!(future);
+    let sleep = sleep(duration);
+    futures::pin_mut!(sleep);
+    match future::select(future, sleep).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
@@ -0,0 +1,41 @@
+//! Routing of requests made in test mode.
+
+use bytes::Bytes;
+use http::uri::{Authority, Uri};
+use http::{Method, Request};
+
+use crate::{Error, ErrorKind, Result};
+
+/// The host of the [Messages API sandbox](https://developer.vonage.com/en/messages/concepts/messages-api-sandbox).
+const MESSAGES_SANDBOX_HOST: &str = "messages-sandbox.nexmo.com";
+
+/// The endpoints which have a sandbox equivalent, by method and path.
+const SANDBOXED: &[(Method, &str)] = &[(Method::POST, "/v1/messages")];
+
+/// Points `request` at the sandbox equivalent of its endpoint.
+///
+/// Returns an error of kind `ErrorKind::TestMode` if the endpoint has no sandbox equivalent, in
+/// which case the request must not be sent at all.
+pub(crate) fn route(request: &mut Request<Bytes>) -> Result<()> {
+    let path = request.uri().path();
+    let sandboxed = SANDBOXED
+        .iter()
+        .any(|(method, p)| request.method() == method && path == *p);
+
+    if !sandboxed {
+        let message = format!(
+            "`{} {}` has no sandbox equivalent and cannot be called in test mode",
+            request.method(),
+            path
+        );
+        return Err(Error::with_cause(
+            ErrorKind::TestMode,
+            anyhow::Error::msg(message),
+        ));
+    }
+
+    let mut parts = request.uri().clone().into_parts();
+    parts.authority = Some(Authority::from_static(MESSAGES_SANDBOX_HOST));
+    *request.uri_mut() = Uri::from_parts(parts).expect("replacing authority cannot fail");
+    Ok(())
+}
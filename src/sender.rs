@@ -0,0 +1,314 @@
+//! Selection of sender IDs for outgoing messages.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use phonenumber::country::Id;
+
+use crate::validation::Validator;
+use crate::Result;
+
+/// A pool of approved sender IDs, grouped by destination country.
+///
+/// Many countries only deliver messages from sender IDs which were registered in advance, and
+/// silently drop everything else. Once a pool is registered with
+/// [`ClientBuilder::sender_pool()`](./struct.ClientBuilder.html#method.sender_pool), requests
+/// which don't set a sender ID explicitly are assigned one from the pool, based on the country of
+/// the destination phone number. When a country has several sender IDs, they are used in turn.
+///
+/// Destinations whose country cannot be determined from the number alone use the fallback sender
+/// IDs, unless the country is set on the request, e.g. with
+/// [`Verify::country()`](./verify/struct.Verify.html#method.country).
+///
+/// # Example
+///
+/// ```
+/// use phonenumber::country::Id;
+/// use vonage::{Client, SenderPool};
+///
+/// let pool = SenderPool::new()
+///     .country(Id::GB, vec!["ACME", "ACMEUK"])
+///     .country(Id::US, vec!["14155550100"])
+///     .fallback(vec!["ACME"]);
+///
+/// let client = Client::builder()
+///     .api_key("<api_key>", "<api_secret>")
+///     .sender_pool(pool)
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SenderPool {
+    countries: HashMap<Id, Senders>,
+    fallback: Option<Senders>,
+}
+
+impl SenderPool {
+    /// Creates a new, empty `SenderPool`.
+    pub fn new() -> Self {
+        SenderPool::default()
+    }
+
+    /// Sets the sender IDs approved for destinations in `country`.
+    ///
+    /// Calling this method again for the same country replaces its sender IDs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `senders` is empty.
+    pub fn country<I, S>(mut self, country: Id, senders: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.countries.insert(country, Senders::new(senders));
+        self
+    }
+
+    /// Sets the sender IDs used for countries without sender IDs of their own.
+    ///
+    /// By default, no sender ID is assigned to such destinations, so the account default is used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `senders` is empty.
+    pub fn fallback<I, S>(mut self, senders: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.fallback = Some(Senders::new(senders));
+        self
+    }
+
+    /// Returns the next sender ID to use for a destination in `country`, if any.
+    pub fn select(&self, country: Option<Id>) -> Option<&str> {
+        country
+            .and_then(|id| self.countries.get(&id))
+            .or(self.fallback.as_ref())
+            .map(Senders::next)
+    }
+
+    /// Returns whether `sender_id` is listed for `country`, and is therefore assumed to be
+    /// registered there.
+    fn contains(&self, country: Id, sender_id: &str) -> bool {
+        let senders = self.countries.get(&country);
+        senders.is_some_and(|s| s.ids.iter().any(|id| id == sender_id))
+    }
+}
+
+/// How strictly sender IDs are checked against the rules of the destination country.
+///
+/// This is configured with
+/// [`ClientBuilder::sender_id_check()`](./struct.ClientBuilder.html#method.sender_id_check).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SenderIdCheck {
+    /// Sender IDs are sent as-is.
+    Off,
+    /// Problems are logged as warnings with the `log` feature enabled, but the request is still
+    /// sent.
+    ///
+    /// This is the default.
+    #[default]
+    Warn,
+    /// Requests with problematic sender IDs fail with an error of kind
+    /// [`ErrorKind::InvalidRequest`](./enum.ErrorKind.html#variant.InvalidRequest) without being
+    /// sent.
+    Deny,
+}
+
+/// The restrictions a country places on sender IDs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum SenderIdRule {
+    /// Any numeric or alphanumeric sender ID is accepted.
+    Any,
+    /// Only numeric sender IDs, i.e. phone numbers owned by the account, are accepted.
+    /// Alphanumeric sender IDs are replaced or dropped by carriers.
+    NumericOnly,
+    /// Alphanumeric sender IDs must be registered with local carriers in advance.
+    PreRegistered,
+}
+
+impl SenderIdRule {
+    /// Returns the sender ID rule of the given country.
+    ///
+    /// This covers the most common restrictions, but carrier rules change frequently; consult the
+    /// [country-specific features](https://developer.nexmo.com/messaging/sms/guides/country-specific-features)
+    /// guide for the authoritative list.
+    pub fn for_country(country: Id) -> Self {
+        use Id::*;
+
+        match country {
+            US | CA | PR => SenderIdRule::NumericOnly,
+            AE | BD | BH | CN | EG | ID | IN | JO | KW | KZ | LK | MA | OM | PH | QA | SA | TR
+            | VN => SenderIdRule::PreRegistered,
+            _ => SenderIdRule::Any,
+        }
+    }
+}
+
+/// A reason why a sender ID may not be delivered as intended.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum SenderIdProblem {
+    /// The sender ID is neither a phone number nor made up of ASCII letters, digits and spaces.
+    InvalidCharacters,
+    /// The destination country only accepts numeric sender IDs.
+    NumericOnly(Id),
+    /// The destination country requires alphanumeric sender IDs to be registered, and the sender
+    /// ID is not listed for it in the client's [`SenderPool`](./struct.SenderPool.html).
+    Unregistered(Id),
+}
+
+impl SenderIdProblem {
+    /// Checks `sender_id` against the rules of the destination `country`, if known.
+    ///
+    /// Alphanumeric sender IDs listed for `country` in `pool` are assumed to be registered.
+    pub fn find(sender_id: &str, country: Option<Id>, pool: Option<&SenderPool>) -> Option<Self> {
+        let numeric = sender_id.strip_prefix('+').unwrap_or(sender_id);
+        if !numeric.is_empty() && numeric.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        if !sender_id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b' ')
+        {
+            return Some(SenderIdProblem::InvalidCharacters);
+        }
+
+        let country = country?;
+        match SenderIdRule::for_country(country) {
+            SenderIdRule::Any => None,
+            SenderIdRule::NumericOnly => Some(SenderIdProblem::NumericOnly(country)),
+            SenderIdRule::PreRegistered => match pool {
+                Some(pool) if pool.contains(country, sender_id) => None,
+                _ => Some(SenderIdProblem::Unregistered(country)),
+            },
+        }
+    }
+}
+
+impl Display for SenderIdProblem {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SenderIdProblem::InvalidCharacters => f.write_str(
+                "must be a phone number or contain only ASCII letters, digits and spaces",
+            ),
+            SenderIdProblem::NumericOnly(id) => {
+                write!(f, "must be a phone number for destinations in {:?}", id)
+            }
+            SenderIdProblem::Unregistered(id) => {
+                write!(f, "must be registered for destinations in {:?}", id)
+            }
+        }
+    }
+}
+
+/// Applies the configured `check` to `sender_id`, returning `Err` if the request must not be sent.
+pub(crate) fn check_sender_id(
+    check: SenderIdCheck,
+    sender_id: &str,
+    country: Option<Id>,
+    pool: Option<&SenderPool>,
+) -> Result<()> {
+    if check == SenderIdCheck::Off {
+        return Ok(());
+    }
+
+    let problem = match SenderIdProblem::find(sender_id, country, pool) {
+        Some(problem) => problem,
+        None => return Ok(()),
+    };
+
+    if check == SenderIdCheck::Warn {
+        #[cfg(feature = "log")]
+        log::warn!(target: "vonage", "sender ID `{}` {}", sender_id, problem);
+        return Ok(());
+    }
+
+    let mut validator = Validator::new();
+    validator.check(false, "sender_id", &problem.to_string());
+    validator.finish()
+}
+
+#[derive(Clone, Debug)]
+struct Senders {
+    ids: Arc<[String]>,
+    next: Arc<AtomicUsize>,
+}
+
+impl Senders {
+    fn new<I, S>(senders: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let ids: Arc<[String]> = senders.into_iter().map(Into::into).collect();
+        assert!(!ids.is_empty(), "sender pool entries must not be empty");
+        Senders {
+            ids,
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn next(&self) -> &str {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.ids.len();
+        &self.ids[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_senders_per_country() {
+        let pool = SenderPool::new()
+            .country(Id::GB, vec!["ACME", "ACMEUK"])
+            .country(Id::DE, vec!["ACMEDE"]);
+
+        let picks: Vec<_> = (0..3).map(|_| pool.select(Some(Id::GB))).collect();
+        assert_eq!(picks, [Some("ACME"), Some("ACMEUK"), Some("ACME")]);
+        assert_eq!(pool.select(Some(Id::DE)), Some("ACMEDE"));
+        assert_eq!(pool.select(Some(Id::FR)), None);
+        assert_eq!(pool.select(None), None);
+
+        let pool = pool.fallback(vec!["GLOBAL"]);
+        assert_eq!(pool.select(Some(Id::FR)), Some("GLOBAL"));
+        assert_eq!(pool.select(None), Some("GLOBAL"));
+    }
+
+    #[test]
+    fn finds_country_specific_problems() {
+        let pool = SenderPool::new().country(Id::IN, vec!["ACMEIN"]);
+        let find = |sender_id, country| SenderIdProblem::find(sender_id, country, Some(&pool));
+
+        assert_eq!(find("ACME", Some(Id::DE)), None);
+        assert_eq!(find("ACME", None), None);
+        assert_eq!(find("+14155550100", Some(Id::US)), None);
+        assert_eq!(
+            find("AC-ME", Some(Id::DE)),
+            Some(SenderIdProblem::InvalidCharacters)
+        );
+        assert_eq!(
+            find("ACME", Some(Id::US)),
+            Some(SenderIdProblem::NumericOnly(Id::US))
+        );
+        assert_eq!(
+            find("ACME", Some(Id::IN)),
+            Some(SenderIdProblem::Unregistered(Id::IN))
+        );
+        assert_eq!(find("ACMEIN", Some(Id::IN)), None);
+
+        assert!(check_sender_id(SenderIdCheck::Off, "AC-ME", None, None).is_ok());
+        assert!(check_sender_id(SenderIdCheck::Warn, "AC-ME", None, None).is_ok());
+        let error = check_sender_id(SenderIdCheck::Deny, "AC-ME", None, None).unwrap_err();
+        assert_eq!(
+            error.as_invalid_request().unwrap().invalid_parameters()[0].name(),
+            "sender_id"
+        );
+    }
+}
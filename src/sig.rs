@@ -3,10 +3,13 @@
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::fmt::{self, Debug, Display, Formatter, Write};
+use std::sync::Arc;
 
 use hmac::{digest::Digest, Hmac, Mac, NewMac};
 use serde::{Deserialize, Serialize};
 
+use crate::rt::SystemTime;
+
 /// A cryptographic SMS signature.
 #[derive(Clone, Debug, Eq, Deserialize, Serialize)]
 pub struct Signature(String);
@@ -34,6 +37,7 @@ impl<T: AsRef<str>> PartialEq<T> for Signature {
 pub struct SignatureSecret {
     secret: Cow<'static, str>,
     method: SignatureMethod,
+    clock: Arc<dyn Fn() -> SystemTime + Send + Sync>,
 }
 
 impl SignatureSecret {
@@ -65,9 +69,58 @@ impl SignatureSecret {
         SignatureSecret {
             secret: secret.into(),
             method,
+            clock: Arc::new(SystemTime::now),
         }
     }
 
+    /// Reads the current time from `clock` instead of the system clock when adding the
+    /// `timestamp` parameter in [`sign_params()`](#method.sign_params).
+    ///
+    /// This is mainly useful for producing deterministic signatures in tests.
+    pub fn with_clock<F>(mut self, clock: F) -> Self
+    where
+        F: Fn() -> SystemTime + Send + Sync + 'static,
+    {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Signs `params`, appending the resulting `sig` parameter to them.
+    ///
+    /// Vonage rejects signed requests without a recent `timestamp` parameter, so one holding the
+    /// current Unix time is added first, unless `params` already contain one. Any existing `sig`
+    /// parameters are removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// use vonage::SignatureSecret;
+    ///
+    /// let secret = SignatureSecret::new("secret")
+    ///     .with_clock(|| UNIX_EPOCH + Duration::from_secs(1_577_880_000));
+    ///
+    /// let mut params = vec![("to".to_owned(), "14155550100".to_owned())];
+    /// let sig = secret.sign_params(&mut params);
+    ///
+    /// assert_eq!(params[1], ("timestamp".to_owned(), "1577880000".to_owned()));
+    /// assert_eq!(params[2], ("sig".to_owned(), sig.to_string()));
+    /// ```
+    pub fn sign_params(&self, params: &mut Vec<(String, String)>) -> Signature {
+        params.retain(|(name, _)| name != "sig");
+        if !params.iter().any(|(name, _)| name == "timestamp") {
+            let now = (self.clock)()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .expect("SystemTime is before the Unix epoch");
+            params.push(("timestamp".into(), now.as_secs().to_string()));
+        }
+
+        let sig = self.sign(&*params);
+        params.push(("sig".into(), sig.0.clone()));
+        sig
+    }
+
     pub(crate) fn sign<T: Serialize>(&self, query_params: T) -> Signature {
         let payload = to_payload_str(query_params);
         let hash = match &self.method {
@@ -104,7 +157,7 @@ impl SignatureSecret {
 impl Debug for SignatureSecret {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.debug_struct(stringify!(SignatureSecret))
-            .field("secret", &"secret")
+            .field("secret", &"<secret>")
             .field("method", &self.method)
             .finish()
     }
@@ -113,7 +166,7 @@ impl Debug for SignatureSecret {
 #[inline]
 fn to_payload_str<T: Serialize>(query_params: T) -> String {
     let encoded = serde_urlencoded::to_string(query_params).expect("query_params must be map-like");
-    let mut sorted: BTreeMap<&str, &str> = serde_urlencoded::from_str(&encoded).unwrap();
+    let mut sorted: BTreeMap<String, String> = serde_urlencoded::from_str(&encoded).unwrap();
     sorted.remove("sig");
     let buf = String::with_capacity(encoded.len()); // Reasonable heuristic, given likely similar lengths.
     sorted.into_iter().fold(buf, |mut acc, (k, v)| {
@@ -123,7 +176,8 @@ fn to_payload_str<T: Serialize>(query_params: T) -> String {
 }
 
 /// A list of supported SMS signature methods.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SignatureMethod {
     /// Concatenates the query string together with the signature secret and hashes the resulting
     /// string with MD5.
@@ -171,6 +225,25 @@ mod tests {
         assert_eq!(hash, "5ebe2294ecd0e0f08eab7690d2a6ee69");
     }
 
+    #[test]
+    fn signs_parameters_as_they_are_encoded() {
+        #[derive(Serialize)]
+        struct Params {
+            count: u32,
+            price: f64,
+            ratio: f32,
+        }
+
+        let params = Params {
+            count: 2,
+            price: 1.0,
+            ratio: 0.1,
+        };
+        let encoded = serde_urlencoded::to_string(&params).unwrap();
+        assert_eq!(to_payload_str(&params), format!("&{}", encoded));
+        assert_eq!(encoded, "count=2&price=1.0&ratio=0.1");
+    }
+
     #[test]
     fn generates_md5_signature() {
         #[derive(Serialize)]
@@ -185,6 +258,28 @@ mod tests {
         assert_eq!(hash, "129d3e7ca8b1acf36cb5ccb92dfec55c");
     }
 
+    #[test]
+    fn adds_timestamp_unless_present() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let secret = SignatureSecret::new("secret")
+            .with_clock(|| UNIX_EPOCH + Duration::from_secs(1_577_880_000));
+
+        let mut params = vec![("sig".to_owned(), "stale".to_owned())];
+        let sig = secret.sign_params(&mut params);
+        let expected = vec![
+            ("timestamp".to_owned(), "1577880000".to_owned()),
+            ("sig".to_owned(), sig.to_string()),
+        ];
+        assert_eq!(params, expected);
+        assert_eq!(sig, secret.sign([("timestamp", "1577880000")]));
+
+        let mut params = vec![("timestamp".to_owned(), "1".to_owned())];
+        secret.sign_params(&mut params);
+        assert_eq!(params[0], ("timestamp".to_owned(), "1".to_owned()));
+        assert_eq!(params.len(), 2);
+    }
+
     #[test]
     fn generates_sha1_signature() {
         let hash = SignatureSecret::with_method(SignatureMethod::Sha1Hmac, "secret").sign(());
@@ -0,0 +1,472 @@
+//! Sending text messages with the [SMS API](https://developer.nexmo.com/api/sms).
+//!
+//! Requests are authenticated with the API key and API secret of the client, or signed with its
+//! [SMS signature secret](../struct.ClientBuilder.html#method.sms_signature) if one was
+//! configured, in which case the API secret is never sent.
+//!
+//! Delivery receipts for sent messages are parsed and verified with [`verify_delivery_receipt()`].
+//! Messages received by your virtual numbers arrive as [`InboundSms`] webhooks, and long ones may
+//! be split into several parts, which a [`Reassembler`] combines.
+//!
+//! [`verify_delivery_receipt()`]: ./fn.verify_delivery_receipt.html
+//! [`InboundSms`]: ./struct.InboundSms.html
+//! [`Reassembler`]: ./struct.Reassembler.html
+//!
+//! # Example
+//!
+//! ```no_run
+//! use vonage::Client;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::new("<api_key>", "<api_secret>");
+//! let sent = client
+//!     .sms()
+//!     .send(&"+14155550100".parse()?, "ACME", "Your order has shipped")
+//!     .client_ref("order=42")
+//!     .send()
+//!     .await?;
+//! println!("sent in {} part(s)", sent.messages.len());
+//! # Ok(())
+//! # }
+//! ```
+
+pub use crate::webhooks::{ConcatPart, DeliveryReceipt, InboundSms, Reassembler};
+
+use std::fmt::{self, Debug, Formatter};
+
+use http::StatusCode;
+use phonenumber::PhoneNumber;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::{ApiKey, ApiSecret};
+use crate::http_client::HttpClient;
+use crate::transport::HttpTransport;
+use crate::validation::{is_http_url, Validator};
+use crate::{
+    DefaultTransport, Error, ErrorKind, Price, Product, RequestPolicy, Result, SignatureSecret,
+    MAX_CLIENT_REF_LEN,
+};
+
+/// A handle to the [SMS](https://developer.nexmo.com/api/sms) API.
+///
+/// This is returned from [`Client::sms()`](../struct.Client.html#method.sms). It holds the API
+/// key and API secret pair of the client, along with its SMS signature secret, if any, and is
+/// cheap to clone.
+pub struct SmsApi<C = DefaultTransport> {
+    http_client: HttpClient<C>,
+    key_pair: (ApiKey, ApiSecret),
+    signature: Option<SignatureSecret>,
+}
+
+impl<C> SmsApi<C> {
+    pub(crate) fn new(
+        http_client: HttpClient<C>,
+        key_pair: (ApiKey, ApiSecret),
+        signature: Option<SignatureSecret>,
+    ) -> Self {
+        SmsApi {
+            http_client,
+            key_pair,
+            signature,
+        }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`RequestPolicy`](../struct.RequestPolicy.html) for details.
+    pub fn policy(mut self, policy: RequestPolicy) -> Self {
+        self.http_client = self.http_client.with_policy(policy);
+        self
+    }
+
+    /// Sends `text` to `to` from `from`, which is either a virtual number in E.164 format without
+    /// a leading `+`, or an alphanumeric sender ID.
+    pub fn send(
+        &self,
+        to: &PhoneNumber,
+        from: impl Into<String>,
+        text: impl Into<String>,
+    ) -> SendSms<C> {
+        // Signed requests must not contain the API secret.
+        let api_secret = match self.signature {
+            Some(_) => None,
+            None => Some(self.key_pair.1.clone()),
+        };
+
+        SendSms {
+            http_client: self.http_client.clone(),
+            signature: self.signature.clone(),
+            form: SmsForm {
+                api_key: self.key_pair.0.clone(),
+                api_secret,
+                from: from.into(),
+                to: to.to_string().trim_start_matches('+').to_owned(),
+                text: text.into(),
+                message_type: None,
+                callback: None,
+                client_ref: None,
+            },
+        }
+    }
+}
+
+impl<C> Clone for SmsApi<C> {
+    fn clone(&self) -> Self {
+        SmsApi {
+            http_client: self.http_client.clone(),
+            key_pair: self.key_pair.clone(),
+            signature: self.signature.clone(),
+        }
+    }
+}
+
+impl<C> Debug for SmsApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(SmsApi))
+            .field("http_client", &self.http_client)
+            .field("key_pair", &self.key_pair)
+            .field("signature", &self.signature)
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SmsForm {
+    api_key: ApiKey,
+    api_secret: Option<ApiSecret>,
+    from: String,
+    to: String,
+    text: String,
+    #[serde(rename = "type")]
+    message_type: Option<&'static str>,
+    callback: Option<String>,
+    #[serde(rename = "client-ref")]
+    client_ref: Option<String>,
+}
+
+/// A builder for an outgoing SMS.
+///
+/// This is returned from [`SmsApi::send()`](./struct.SmsApi.html#method.send).
+pub struct SendSms<C> {
+    http_client: HttpClient<C>,
+    signature: Option<SignatureSecret>,
+    form: SmsForm,
+}
+
+impl<C> SendSms<C> {
+    /// Sends the text as Unicode, which is required for characters outside of the GSM-7
+    /// alphabet, such as emoji.
+    ///
+    /// Unicode messages fit fewer characters per part, so long messages are split into more
+    /// parts.
+    pub fn unicode(mut self) -> Self {
+        self.form.message_type = Some("unicode");
+        self
+    }
+
+    /// Overrides the URL which Vonage sends delivery receipts for this message to.
+    ///
+    /// By default, receipts are sent to the delivery receipt URL configured for the whole
+    /// account. The URL must be absolute and use either `http` or `https`.
+    pub fn callback_url(mut self, url: impl Into<String>) -> Self {
+        self.form.callback = Some(url.into());
+        self
+    }
+
+    /// Attaches a reference of at most [`MAX_CLIENT_REF_LEN`] characters, which is echoed back in
+    /// the delivery receipts of the message.
+    ///
+    /// Use [`Metadata::to_client_ref()`] to attach structured metadata.
+    ///
+    /// [`MAX_CLIENT_REF_LEN`]: ../constant.MAX_CLIENT_REF_LEN.html
+    /// [`Metadata::to_client_ref()`]: ../struct.Metadata.html#method.to_client_ref
+    pub fn client_ref(mut self, reference: impl Into<String>) -> Self {
+        self.form.client_ref = Some(reference.into());
+        self
+    }
+
+    /// Encodes the form, appending a timestamp and its signature if the client has a signature
+    /// secret.
+    fn encode_form(&self) -> Result<String> {
+        let encoded = serde_urlencoded::to_string(&self.form)?;
+        let signature = match self.signature.as_ref() {
+            Some(signature) => signature,
+            None => return Ok(encoded),
+        };
+
+        let mut params: Vec<(String, String)> = serde_urlencoded::from_str(&encoded)
+            .map_err(|e| Error::with_cause(ErrorKind::UrlEncode, e))?;
+        signature.sign_params(&mut params);
+        Ok(serde_urlencoded::to_string(params)?)
+    }
+}
+
+impl<C: HttpTransport> SendSms<C> {
+    /// Sends the message.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequest`] without sending anything if the
+    /// callback URL is not an absolute HTTP(S) URL, or the client reference is too long. If Vonage
+    /// rejects any part of the message, an error of kind [`ErrorKind::RateLimited`],
+    /// [`ErrorKind::Auth`], [`ErrorKind::InvalidRequest`] or [`ErrorKind::Status`] is returned.
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../enum.ErrorKind.html#variant.InvalidRequest
+    /// [`ErrorKind::RateLimited`]: ../enum.ErrorKind.html#variant.RateLimited
+    /// [`ErrorKind::Auth`]: ../enum.ErrorKind.html#variant.Auth
+    /// [`ErrorKind::Status`]: ../enum.ErrorKind.html#variant.Status
+    pub async fn send(self) -> Result<SentSms> {
+        use http::header::{ACCEPT, CONTENT_TYPE};
+
+        #[derive(Deserialize)]
+        struct Status {
+            messages: Vec<PartStatus>,
+        }
+
+        #[derive(Deserialize)]
+        struct PartStatus {
+            status: String,
+            #[serde(rename = "error-text", default)]
+            error_text: String,
+        }
+
+        let mut validator = Validator::new();
+        validator
+            .check(
+                self.form.callback.as_deref().is_none_or(is_http_url),
+                "callback",
+                "must be an absolute HTTP(S) URL",
+            )
+            .max_len(
+                self.form.client_ref.as_deref(),
+                "client_ref",
+                MAX_CLIENT_REF_LEN,
+            );
+        validator.finish()?;
+
+        let request = http::Request::builder()
+            .method(http::Method::POST)
+            .uri(crate::encode_rest_uri(&["sms", "json"]))
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(ACCEPT, "application/json")
+            .body(self.encode_form()?.into())
+            .expect("http::RequestBuilder cannot fail");
+
+        self.http_client
+            .send(Product::Sms, request, |response| {
+                match response.status() {
+                    StatusCode::OK => {}
+                    _ => return Err(Error::new_status(&response)),
+                }
+
+                // Failures are reported per part in the body of a `200 OK` response.
+                let status: Status = crate::http_client::decode_json(response.body())?;
+                match status.messages.iter().find(|part| part.status != "0") {
+                    Some(part) => Err(status_error(&part.status, &part.error_text)),
+                    None => crate::http_client::decode_json(response.body()),
+                }
+            })
+            .await
+    }
+}
+
+impl<C> Debug for SendSms<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(SendSms))
+            .field("form", &self.form)
+            .field("signed", &self.signature.is_some())
+            .finish()
+    }
+}
+
+/// Converts a non-zero SMS API `status` into an error.
+fn status_error(status: &str, message: &str) -> Error {
+    let kind = match status {
+        "1" => ErrorKind::RateLimited,
+        "2" | "3" | "6" | "29" => ErrorKind::InvalidRequest,
+        "4" | "14" => ErrorKind::Auth,
+        "9" => ErrorKind::Status(StatusCode::PAYMENT_REQUIRED),
+        "7" | "8" => ErrorKind::Status(StatusCode::FORBIDDEN),
+        _ => ErrorKind::Status(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let message = format!("SMS was rejected with status {}: {}", status, message);
+    Error::with_cause(kind, anyhow::Error::msg(message))
+}
+
+/// Parses a [`DeliveryReceipt`] sent with the given `content_type`, after verifying that its `sig`
+/// parameter was signed with `secret`, using the signature method of `secret`.
+///
+/// Both `application/json` and `application/x-www-form-urlencoded` receipts are supported. Receipts
+/// delivered with `GET` carry their parameters in the query string instead, which can be verified
+/// by passing it as the `body` with a form content type.
+///
+/// Returns an error of kind [`ErrorKind::Auth`] if the signature is missing or does not match, or
+/// its `timestamp` is more than 5 minutes away from the current time, or of kind
+/// [`ErrorKind::InvalidWebhook`] if the payload is not a delivery receipt.
+///
+/// [`DeliveryReceipt`]: ./struct.DeliveryReceipt.html
+/// [`ErrorKind::Auth`]: ../enum.ErrorKind.html#variant.Auth
+/// [`ErrorKind::InvalidWebhook`]: ../enum.ErrorKind.html#variant.InvalidWebhook
+///
+/// # Example
+///
+/// ```
+/// use vonage::{sms, SignatureSecret};
+///
+/// # fn run(query: &str) {
+/// let secret = SignatureSecret::new("<signature_secret>");
+/// let form = "application/x-www-form-urlencoded";
+/// match sms::verify_delivery_receipt(form, query.as_bytes(), &secret) {
+///     Ok(receipt) => println!("{} is {}", receipt.message_id, receipt.status),
+///     Err(e) => eprintln!("rejected delivery receipt: {}", e),
+/// }
+/// # }
+/// ```
+pub fn verify_delivery_receipt(
+    content_type: &str,
+    body: &[u8],
+    secret: &SignatureSecret,
+) -> Result<DeliveryReceipt> {
+    crate::webhooks::parse_signed(content_type, body, secret)
+}
+
+/// The result of [sending](./struct.SendSms.html#method.send) an SMS.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SentSms {
+    /// The parts the message was split into, each of which is billed separately.
+    pub messages: Vec<SentPart>,
+}
+
+/// A single part of a [sent SMS](./struct.SentSms.html).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SentPart {
+    /// The number the part was sent to, e.g. `447700900000`.
+    pub to: String,
+    /// The ID of the part, which delivery receipts refer to.
+    #[serde(rename = "message-id")]
+    pub message_id: String,
+    /// The reference attached with [`SendSms::client_ref()`], if any.
+    ///
+    /// [`SendSms::client_ref()`]: ./struct.SendSms.html#method.client_ref
+    #[serde(rename = "client-ref", default)]
+    pub client_ref: Option<String>,
+    /// The cost of the part, in EUR.
+    #[serde(rename = "message-price", default)]
+    pub message_price: Option<Price>,
+    /// The balance of the account after sending the part, in EUR.
+    #[serde(rename = "remaining-balance", default)]
+    pub remaining_balance: Option<Price>,
+    /// The code of the network the part was sent to, e.g. `23410`.
+    #[serde(default)]
+    pub network: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use bytes::Bytes;
+    use http::Request;
+    use http_body_util::{BodyExt, Full};
+    use tower::service_fn;
+
+    use crate::Client;
+
+    const FORM: &str = "application/x-www-form-urlencoded";
+    const SENT: &str = r#"{"message-count":"1","messages":[{"to":"14155550100","message-id":"0A0000000123ABCD1","status":"0","remaining-balance":"3.14159265","message-price":"0.03330000","network":"310004","client-ref":"order=42"}]}"#;
+
+    #[tokio::test]
+    async fn sends_with_api_secret_or_signature() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.uri(), "https://rest.nexmo.com/sms/json");
+            let body = request.into_body().collect().await.unwrap().to_bytes();
+            let params: Vec<(String, String)> = serde_urlencoded::from_bytes(&body).unwrap();
+            let names: Vec<_> = params.iter().map(|(name, _)| name.as_str()).collect();
+
+            let body = if params.iter().any(|(name, _)| name == "sig") {
+                let expected = ["api_key", "from", "to", "text", "timestamp", "sig"];
+                assert_eq!(names, expected);
+                r#"{"message-count":"1","messages":[{"status":"4","error-text":"Bad Credentials"}]}"#
+            } else {
+                let expected = ["api_key", "api_secret", "from", "to", "text", "client-ref"];
+                assert_eq!(names, expected);
+                SENT
+            };
+            Ok::<_, Infallible>(http::Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let to: PhoneNumber = "+14155550100".parse().unwrap();
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+        let sent = client
+            .sms()
+            .send(&to, "ACME", "Hello")
+            .client_ref("order=42")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(sent.messages[0].message_id, "0A0000000123ABCD1");
+        assert_eq!(sent.messages[0].client_ref.as_deref(), Some("order=42"));
+
+        let error = client
+            .sms()
+            .send(&to, "ACME", "Hello")
+            .callback_url("not a url")
+            .send()
+            .await
+            .unwrap_err();
+        let invalid = error.as_invalid_request().unwrap().invalid_parameters();
+        assert_eq!(invalid[0].name(), "callback");
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .sms_signature(SignatureSecret::new("signature-secret"))
+            .build()
+            .unwrap();
+        let error = client.sms().send(&to, "ACME", "Hello").send().await;
+        assert!(matches!(error.unwrap_err().kind(), ErrorKind::Auth));
+    }
+
+    #[test]
+    fn verifies_delivery_receipts() {
+        let secret = SignatureSecret::new("signature-secret");
+        let mut params: Vec<(String, String)> = [
+            ("msisdn", "14155550100"),
+            ("to", "ACME"),
+            ("network-code", "310004"),
+            ("messageId", "0A0000000123ABCD1"),
+            ("status", "delivered"),
+            ("err-code", "0"),
+            ("message-timestamp", "2020-01-01 12:00:00"),
+        ]
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+        secret.sign_params(&mut params);
+
+        let form = serde_urlencoded::to_string(&params).unwrap();
+        let receipt = verify_delivery_receipt(FORM, form.as_bytes(), &secret).unwrap();
+        assert_eq!(receipt.status, "delivered");
+        assert_eq!(receipt.err_code.as_deref(), Some("0"));
+        assert_eq!(receipt.network().unwrap().to_string(), "310004");
+
+        let fields: serde_json::Map<_, _> = params
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone().into()))
+            .collect();
+        let json = serde_json::to_vec(&fields).unwrap();
+        assert!(verify_delivery_receipt("application/json", &json, &secret).is_ok());
+
+        let tampered = form.replace("delivered", "failed");
+        let error = verify_delivery_receipt(FORM, tampered.as_bytes(), &secret).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Auth));
+
+        let other = SignatureSecret::new("other-secret");
+        let error = verify_delivery_receipt(FORM, form.as_bytes(), &other).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Auth));
+    }
+}
@@ -0,0 +1,294 @@
+//! Utilities for testing code which uses this library.
+//!
+//! The [`MockTransport`] can be passed to
+//! [`Client::from_transport()`](../struct.Client.html#method.from_transport) in place of a real
+//! HTTP client. It matches each outgoing request against a list of [`Mock`]s and returns the
+//! canned response of the first one which matches, so flows like verify requests can be unit
+//! tested without any network access.
+//!
+//! For hermetic integration tests against real API responses, a [`RecordingTransport`] can capture
+//! live traffic to a file (with secrets redacted) which a [`ReplayTransport`] later plays back.
+//!
+//! Webhook handlers can be exercised with signed requests fabricated by a [`Webhook`] builder.
+//!
+//! [`RecordingTransport`]: ./struct.RecordingTransport.html
+//! [`ReplayTransport`]: ./struct.ReplayTransport.html
+//! [`Webhook`]: ./struct.Webhook.html
+//!
+//! [`MockTransport`]: ./struct.MockTransport.html
+//! [`Mock`]: ./struct.Mock.html
+//!
+//! # Example
+//!
+//! ```
+//! use vonage::testing::{Mock, MockTransport};
+//! use vonage::verify::Code;
+//! use vonage::Client;
+//!
+//! # async fn run() -> vonage::Result<()> {
+//! let transport = MockTransport::new()
+//!     .mock(Mock::verify("abcdef0123456789abcdef0123456789"))
+//!     .mock(Mock::verify_check("abcdef0123456789abcdef0123456789").param("code", "1234"));
+//!
+//! let client = Client::from_transport(transport.clone())
+//!     .api_key("<api_key>", "<api_secret>")
+//!     .build()?;
+//!
+//! let pending = client
+//!     .verify()
+//!     .request("+14155550100".parse().unwrap(), "vonage-rs")
+//!     .send()
+//!     .await?;
+//! assert!(matches!(pending.check("1234").await?, Code::Match(_)));
+//! assert_eq!(transport.requests().len(), 2);
+//! # Ok(())
+//! # }
+//! ```
+
+pub use self::replay::{RecordingTransport, ReplayTransport};
+pub use self::webhooks::Webhook;
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
+use http::{Method, StatusCode};
+
+use crate::transport::{BoxError, Bytes, HttpTransport, Request, Response, TransportFuture};
+
+mod replay;
+mod webhooks;
+
+/// A single canned response, returned when a request matches its path and parameters.
+#[derive(Clone, Debug)]
+pub struct Mock {
+    method: Option<Method>,
+    path: String,
+    params: Vec<(String, String)>,
+    status: StatusCode,
+    body: Bytes,
+}
+
+impl Mock {
+    /// Creates a new `Mock` which matches requests to the given `path`, e.g. `/verify/json`.
+    ///
+    /// By default, it responds with `200 OK` and an empty JSON object.
+    pub fn new(path: impl Into<String>) -> Self {
+        Mock {
+            method: None,
+            path: path.into(),
+            params: Vec::new(),
+            status: StatusCode::OK,
+            body: Bytes::from_static(b"{}"),
+        }
+    }
+
+    /// Mocks a successful `/verify` request which returns the given `request_id`.
+    pub fn verify(request_id: &str) -> Self {
+        Mock::new("/verify/json").json(format!(r#"{{"request_id":"{}","status":"0"}}"#, request_id))
+    }
+
+    /// Mocks a successful `/verify/check` request for the given `request_id`.
+    pub fn verify_check(request_id: &str) -> Self {
+        Mock::new("/verify/check/json")
+            .param("request_id", request_id)
+            .json(format!(
+                r#"{{"request_id":"{}","event_id":"0A00000012345678","status":"0","price":"0.10000000","currency":"EUR"}}"#,
+                request_id
+            ))
+    }
+
+    /// Mocks a Vonage API error with the given `status` code (e.g. `"16"`) and `error_text`.
+    ///
+    /// Note that, like the real Vonage APIs, the HTTP status of the response is still `200 OK`.
+    pub fn error(path: impl Into<String>, status: &str, error_text: &str) -> Self {
+        let body = serde_json::json!({ "status": status, "error_text": error_text });
+        Mock::new(path).json(body.to_string())
+    }
+
+    /// Only matches requests with the given HTTP `method`.
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = Some(method);
+        self
+    }
+
+    /// Only matches requests which include the parameter `key` set to `value`.
+    ///
+    /// Parameters are read from the query string as well as from URL-encoded form bodies.
+    pub fn param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.push((key.into(), value.into()));
+        self
+    }
+
+    /// Sets the HTTP status code of the response.
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Sets the JSON body of the response.
+    pub fn json(mut self, body: impl Into<String>) -> Self {
+        self.body = Bytes::from(body.into());
+        self
+    }
+
+    fn matches(&self, request: &Request<Bytes>, params: &[(String, String)]) -> bool {
+        self.method.as_ref().is_none_or(|m| m == request.method())
+            && self.path == request.uri().path()
+            && self.params.iter().all(|p| params.contains(p))
+    }
+
+    fn response(&self) -> Response<Bytes> {
+        let mut response = Response::new(self.body.clone());
+        *response.status_mut() = self.status;
+        response
+    }
+}
+
+/// An [`HttpTransport`] which returns canned responses instead of sending requests.
+///
+/// Cloning a `MockTransport` yields a handle to the same list of received requests, so one clone
+/// can be passed to the [`Client`](../struct.Client.html) while the other is used to make
+/// assertions afterwards.
+///
+/// Requests which match none of the configured mocks fail with an
+/// [`ErrorKind::Http`](../enum.ErrorKind.html#variant.Http) error.
+///
+/// [`HttpTransport`]: ../transport/trait.HttpTransport.html
+#[derive(Clone, Default)]
+pub struct MockTransport {
+    mocks: Arc<Vec<Mock>>,
+    received: Arc<Mutex<Vec<Request<Bytes>>>>,
+}
+
+impl MockTransport {
+    /// Creates a new `MockTransport` without any mocks.
+    pub fn new() -> Self {
+        MockTransport::default()
+    }
+
+    /// Adds a mock to this transport.
+    ///
+    /// Mocks are tried in the order they were added, and the first match wins.
+    pub fn mock(mut self, mock: Mock) -> Self {
+        Arc::make_mut(&mut self.mocks).push(mock);
+        self
+    }
+
+    /// Returns copies of all requests received so far, in the order they were sent.
+    pub fn requests(&self) -> Vec<Request<Bytes>> {
+        let received = self.received.lock().unwrap();
+        received
+            .iter()
+            .map(|request| {
+                let mut copy = Request::new(request.body().clone());
+                *copy.method_mut() = request.method().clone();
+                *copy.uri_mut() = request.uri().clone();
+                *copy.headers_mut() = request.headers().clone();
+                copy
+            })
+            .collect()
+    }
+}
+
+impl HttpTransport for MockTransport {
+    fn send(&self, request: Request<Bytes>) -> TransportFuture {
+        let mut params: Vec<(String, String)> = request
+            .uri()
+            .query()
+            .and_then(|q| serde_urlencoded::from_str(q).ok())
+            .unwrap_or_default();
+        params.extend(serde_urlencoded::from_bytes::<Vec<_>>(request.body()).unwrap_or_default());
+
+        let result = match self.mocks.iter().find(|m| m.matches(&request, &params)) {
+            Some(mock) => Ok(mock.response()),
+            None => Err(BoxError::from(format!(
+                "no mock matched {} {}",
+                request.method(),
+                request.uri()
+            ))),
+        };
+
+        self.received.lock().unwrap().push(request);
+        Box::pin(futures::future::ready(result))
+    }
+}
+
+impl Debug for MockTransport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(MockTransport))
+            .field("mocks", &self.mocks)
+            .field("received", &self.received.lock().unwrap().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::verify::Code;
+    use crate::{Client, ErrorKind};
+
+    const REQUEST_ID: &str = "abcdef0123456789abcdef0123456789";
+
+    #[tokio::test]
+    async fn matches_requests_by_path_and_params() {
+        let transport = MockTransport::new()
+            .mock(Mock::verify(REQUEST_ID).param("number", "+14155550100"))
+            .mock(Mock::verify_check(REQUEST_ID).param("code", "1234"))
+            .mock(Mock::error(
+                "/verify/check/json",
+                "16",
+                "The code provided does not match the expected value",
+            ));
+
+        let client = Client::from_transport(transport.clone())
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let pending = client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .send()
+            .await
+            .unwrap();
+
+        let pending = match pending.check("0000").await.unwrap() {
+            Code::Mismatch(pending) => pending,
+            Code::Match(_) => panic!("code should not have matched"),
+        };
+
+        match pending.check("1234").await.unwrap() {
+            Code::Match(verified) => assert_eq!(verified.request_id.to_string(), REQUEST_ID),
+            Code::Mismatch(_) => panic!("code should have matched"),
+        }
+
+        let paths: Vec<_> = transport
+            .requests()
+            .iter()
+            .map(|r| r.uri().path().to_owned())
+            .collect();
+        assert_eq!(
+            paths,
+            ["/verify/json", "/verify/check/json", "/verify/check/json"]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_unmatched_requests() {
+        let client = Client::from_transport(MockTransport::new())
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let error = client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .send()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error.kind(), ErrorKind::Http));
+    }
+}
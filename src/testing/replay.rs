@@ -0,0 +1,262 @@
+//! Transports which record real API traffic to disk and replay it later.
+
+use std::fmt::{self, Debug, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use http::header::ACCEPT_ENCODING;
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::redact::{redact_body, redact_uri};
+use crate::transport::{BoxError, Bytes, HttpTransport, Request, Response, TransportFuture};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct RecordedRequest {
+    method: String,
+    uri: String,
+    body: String,
+}
+
+impl RecordedRequest {
+    fn new(request: &Request<Bytes>) -> Self {
+        RecordedRequest {
+            method: request.method().to_string(),
+            uri: redact_uri(request.uri()),
+            body: redact_body(request.headers(), request.body()),
+        }
+    }
+
+    fn path(&self) -> &str {
+        self.uri.split('?').next().unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct RecordedResponse {
+    status: u16,
+    body: String,
+}
+
+/// An [`HttpTransport`] which forwards requests to another transport and records every
+/// request/response pair to a file on disk.
+///
+/// API keys, secrets, signatures, and PIN codes are redacted before anything is written, and request headers
+/// (including `Authorization`) are not recorded at all. The resulting file can be checked into
+/// version control and played back with a [`ReplayTransport`].
+///
+/// The file is rewritten after every completed request, so it is always up to date even if the
+/// test panics partway through.
+///
+/// [`HttpTransport`]: ../transport/trait.HttpTransport.html
+/// [`ReplayTransport`]: ./struct.ReplayTransport.html
+pub struct RecordingTransport<T> {
+    inner: Arc<T>,
+    path: Arc<PathBuf>,
+    interactions: Arc<Mutex<Vec<Interaction>>>,
+}
+
+impl<T: HttpTransport> RecordingTransport<T> {
+    /// Creates a new `RecordingTransport` which sends requests through `inner` and records them to
+    /// the file at `path`, replacing any existing recording.
+    pub fn new(inner: T, path: impl Into<PathBuf>) -> Self {
+        RecordingTransport {
+            inner: Arc::new(inner),
+            path: Arc::new(path.into()),
+            interactions: Arc::default(),
+        }
+    }
+}
+
+impl<T> HttpTransport for RecordingTransport<T>
+where
+    T: HttpTransport + Send + Sync + 'static,
+{
+    fn send(&self, mut request: Request<Bytes>) -> TransportFuture {
+        // Ask for uncompressed responses so that the recording stays human-readable.
+        request.headers_mut().remove(ACCEPT_ENCODING);
+        let recorded = RecordedRequest::new(&request);
+        let inner = self.inner.clone();
+        let path = self.path.clone();
+        let interactions = self.interactions.clone();
+
+        Box::pin(async move {
+            let response = inner.send(request).await?;
+            let interaction = Interaction {
+                request: recorded,
+                response: RecordedResponse {
+                    status: response.status().as_u16(),
+                    body: String::from_utf8_lossy(response.body()).into_owned(),
+                },
+            };
+
+            let contents = {
+                let mut interactions = interactions.lock().unwrap();
+                interactions.push(interaction);
+                serde_json::to_string_pretty(&*interactions)?
+            };
+            fs::write(&*path, contents)?;
+
+            Ok(response)
+        })
+    }
+}
+
+impl<T> Clone for RecordingTransport<T> {
+    fn clone(&self) -> Self {
+        RecordingTransport {
+            inner: self.inner.clone(),
+            path: self.path.clone(),
+            interactions: self.interactions.clone(),
+        }
+    }
+}
+
+impl<T> Debug for RecordingTransport<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(RecordingTransport))
+            .field("path", &self.path)
+            .field("interactions", &self.interactions.lock().unwrap().len())
+            .finish()
+    }
+}
+
+/// An [`HttpTransport`] which plays back a recording made by a [`RecordingTransport`] without
+/// touching the network.
+///
+/// Responses are returned strictly in the order they were recorded. Each request must have the
+/// same method and path as the recorded one, otherwise it fails with an
+/// [`ErrorKind::Http`](../enum.ErrorKind.html#variant.Http) error. Query parameters and bodies are
+/// not compared, since they contain redacted secrets and may legitimately differ between runs.
+///
+/// [`HttpTransport`]: ../transport/trait.HttpTransport.html
+/// [`RecordingTransport`]: ./struct.RecordingTransport.html
+#[derive(Clone)]
+pub struct ReplayTransport {
+    interactions: Arc<Vec<Interaction>>,
+    position: Arc<Mutex<usize>>,
+}
+
+impl ReplayTransport {
+    /// Loads a recording from the file at `path`.
+    ///
+    /// Returns `Err` if the file cannot be read or is not a valid recording.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = fs::read(path)?;
+        let interactions = serde_json::from_slice(&contents)?;
+        Ok(ReplayTransport {
+            interactions: Arc::new(interactions),
+            position: Arc::default(),
+        })
+    }
+
+    /// Returns the number of recorded interactions which have not been played back yet.
+    pub fn remaining(&self) -> usize {
+        self.interactions.len() - *self.position.lock().unwrap()
+    }
+}
+
+impl HttpTransport for ReplayTransport {
+    fn send(&self, request: Request<Bytes>) -> TransportFuture {
+        let result = {
+            let mut position = self.position.lock().unwrap();
+            match self.interactions.get(*position) {
+                Some(i)
+                    if i.request.method == request.method().as_str()
+                        && i.request.path() == request.uri().path() =>
+                {
+                    *position += 1;
+                    StatusCode::from_u16(i.response.status)
+                        .map(|status| {
+                            let mut response = Response::new(Bytes::from(i.response.body.clone()));
+                            *response.status_mut() = status;
+                            response
+                        })
+                        .map_err(BoxError::from)
+                }
+                Some(i) => Err(BoxError::from(format!(
+                    "expected {} {}, got {} {}",
+                    i.request.method,
+                    i.request.path(),
+                    request.method(),
+                    request.uri().path()
+                ))),
+                None => Err(BoxError::from(format!(
+                    "no recorded interactions left for {} {}",
+                    request.method(),
+                    request.uri().path()
+                ))),
+            }
+        };
+
+        Box::pin(futures::future::ready(result))
+    }
+}
+
+impl Debug for ReplayTransport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(ReplayTransport))
+            .field("interactions", &self.interactions.len())
+            .field("position", &self.position.lock().unwrap())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::testing::{Mock, MockTransport};
+    use crate::verify::Code;
+    use crate::Client;
+
+    const REQUEST_ID: &str = "abcdef0123456789abcdef0123456789";
+
+    async fn verify_flow<T: HttpTransport>(transport: T) {
+        let client = Client::from_transport(transport)
+            .api_key("my-key", "my-secret")
+            .build()
+            .unwrap();
+
+        let pending = client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .send()
+            .await
+            .unwrap();
+
+        match pending.check("1234").await.unwrap() {
+            Code::Match(verified) => assert_eq!(verified.request_id.to_string(), REQUEST_ID),
+            Code::Mismatch(_) => panic!("code should have matched"),
+        }
+    }
+
+    #[tokio::test]
+    async fn records_and_replays_redacted_interactions() {
+        let path = std::env::temp_dir().join(format!("vonage-replay-{}.json", std::process::id()));
+
+        let live = MockTransport::new()
+            .mock(Mock::verify(REQUEST_ID))
+            .mock(Mock::verify_check(REQUEST_ID));
+        verify_flow(RecordingTransport::new(live, &path)).await;
+
+        let recording = fs::read_to_string(&path).unwrap();
+        assert!(!recording.contains("my-key"));
+        assert!(!recording.contains("my-secret"));
+        assert!(recording.contains("api_key=REDACTED"));
+
+        let replay = ReplayTransport::open(&path).unwrap();
+        verify_flow(replay.clone()).await;
+        assert_eq!(replay.remaining(), 0);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
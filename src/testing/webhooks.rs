@@ -0,0 +1,258 @@
+//! Fabricated webhook requests for exercising webhook handlers.
+
+use chrono::{SecondsFormat, Utc};
+use hmac::digest::Digest;
+use http::header::{AUTHORIZATION, CONTENT_TYPE};
+use rand::Rng;
+use serde_json::{Map, Value};
+
+use crate::transport::{Bytes, Request};
+use crate::webhooks::Auth;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Encoding {
+    Form,
+    Json,
+}
+
+/// A builder for realistic webhook requests, as if they were sent by Vonage.
+///
+/// This makes it possible to test webhook routing and handlers locally, without live traffic.
+/// The resulting requests can be fed to [`webhooks::from_http_parts()`] or directly to the HTTP
+/// handler under test. Identifiers and timestamps are generated randomly, but can be overridden
+/// with [`field()`](#method.field).
+///
+/// [`webhooks::from_http_parts()`]: ../webhooks/fn.from_http_parts.html
+///
+/// # Example
+///
+/// ```
+/// use vonage::testing::Webhook;
+/// use vonage::webhooks::{self, Auth, Event};
+///
+/// let auth = Auth::Jwt("signature secret".into());
+/// let request = Webhook::call_answered("14155550100", "12025550123")
+///     .sign(&auth)
+///     .into_request();
+///
+/// let (parts, body) = request.into_parts();
+/// let event = webhooks::from_http_parts(&parts, &body, &auth).unwrap();
+/// assert!(matches!(event, Event::Voice(call) if call.status == "answered"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Webhook {
+    path: &'static str,
+    encoding: Encoding,
+    fields: Map<String, Value>,
+    auth: Auth,
+}
+
+impl Webhook {
+    /// Creates an inbound SMS with the given `text`, sent from `msisdn` to your virtual number
+    /// `to`.
+    pub fn inbound_sms(msisdn: &str, to: &str, text: &str) -> Self {
+        Webhook::new("/webhooks/inbound-sms", Encoding::Form)
+            .field("msisdn", msisdn)
+            .field("to", to)
+            .field("messageId", message_id())
+            .field("text", text)
+            .field("type", "text")
+            .field(
+                "keyword",
+                text.split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .to_uppercase(),
+            )
+            .field("message-timestamp", sms_timestamp())
+    }
+
+    /// Creates a delivery receipt with the given `status`, e.g. `delivered`, for the SMS with the
+    /// given `message_id` sent to `msisdn`.
+    pub fn delivery_receipt(message_id: &str, msisdn: &str, status: &str) -> Self {
+        let err_code = if status == "delivered" { "0" } else { "1" };
+        Webhook::new("/webhooks/delivery-receipt", Encoding::Form)
+            .field("msisdn", msisdn)
+            .field("to", "Vonage")
+            .field("network-code", "310260")
+            .field("messageId", message_id)
+            .field("price", "0.00750000")
+            .field("status", status)
+            .field("scts", Utc::now().format("%y%m%d%H%M").to_string())
+            .field("err-code", err_code)
+            .field("message-timestamp", sms_timestamp())
+    }
+
+    /// Creates an `answered` event for a call from `from` to `to`.
+    pub fn call_answered(from: &str, to: &str) -> Self {
+        Webhook::new("/webhooks/event", Encoding::Json)
+            .field("uuid", uuid())
+            .field("conversation_uuid", format!("CON-{}", uuid()))
+            .field("status", "answered")
+            .field("direction", "outbound")
+            .field("from", from)
+            .field("to", to)
+            .field(
+                "timestamp",
+                Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            )
+    }
+
+    fn new(path: &'static str, encoding: Encoding) -> Self {
+        Webhook {
+            path,
+            encoding,
+            fields: Map::new(),
+            auth: Auth::None,
+        }
+    }
+
+    /// Sets the payload field `key` to `value`, replacing any generated value.
+    pub fn field(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.fields
+            .insert(key.to_owned(), Value::String(value.into()));
+        self
+    }
+
+    /// Signs the request like Vonage would for the given authentication method.
+    ///
+    /// By default, requests are not signed.
+    pub fn sign(mut self, auth: &Auth) -> Self {
+        self.auth = auth.clone();
+        self
+    }
+
+    /// Builds the HTTP request.
+    pub fn into_request(mut self) -> Request<Bytes> {
+        let builder = Request::post(self.path);
+
+        if let Auth::Signature(ref secret) = self.auth {
+            let mut params: Vec<(String, String)> = self
+                .fields
+                .iter()
+                .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_owned())))
+                .collect();
+            secret.sign_params(&mut params);
+            for (key, value) in params {
+                self.fields.insert(key, Value::String(value));
+            }
+        }
+
+        let (content_type, body) = match self.encoding {
+            Encoding::Form => {
+                let body = serde_urlencoded::to_string(&self.fields).expect("fields are strings");
+                ("application/x-www-form-urlencoded", body)
+            }
+            Encoding::Json => ("application/json", Value::Object(self.fields).to_string()),
+        };
+
+        let builder = match self.auth {
+            Auth::Jwt(ref secret) => {
+                builder.header(AUTHORIZATION, format!("Bearer {}", jwt(secret, &body)))
+            }
+            _ => builder,
+        };
+
+        builder
+            .header(CONTENT_TYPE, content_type)
+            .body(Bytes::from(body))
+            .expect("http::RequestBuilder cannot fail")
+    }
+}
+
+/// Signs a JWT covering the hash of `body`, like Vonage does for application webhooks.
+fn jwt(secret: &str, body: &str) -> String {
+    use jsonwebtoken::{EncodingKey, Header};
+
+    let claims = serde_json::json!({
+        "iat": Utc::now().timestamp(),
+        "jti": uuid(),
+        "iss": "Vonage",
+        "payload_hash": format!("{:x}", sha2::Sha256::digest(body.as_bytes())),
+    });
+
+    let key = EncodingKey::from_secret(secret.as_bytes());
+    jsonwebtoken::encode(&Header::default(), &claims, &key).expect("HS256 signing cannot fail")
+}
+
+fn message_id() -> String {
+    format!("{:016X}", rand::thread_rng().gen::<u64>())
+}
+
+fn sms_timestamp() -> String {
+    Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn uuid() -> String {
+    uuid::Builder::from_bytes(rand::random())
+        .set_variant(uuid::Variant::RFC4122)
+        .set_version(uuid::Version::Random)
+        .build()
+        .to_hyphenated()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::webhooks::{Event, Parser, Strictness};
+    use crate::{ErrorKind, SignatureMethod, SignatureSecret};
+
+    fn parse(request: Request<Bytes>, auth: &Auth) -> crate::Result<Event> {
+        let (parts, body) = request.into_parts();
+        Parser::new()
+            .strictness(Strictness::Strict)
+            .from_http_parts(&parts, &body, auth)
+            .map(|parsed| parsed.into_event())
+    }
+
+    #[test]
+    fn builds_signed_sms_webhooks() {
+        let auth = Auth::Signature(SignatureSecret::with_method(
+            SignatureMethod::Md5Hmac,
+            "secret",
+        ));
+
+        let request = Webhook::inbound_sms("14155550100", "12025550123", "STOP & unsubscribe")
+            .sign(&auth)
+            .into_request();
+        match parse(request, &auth).unwrap() {
+            Event::InboundSms(sms) => {
+                assert_eq!(sms.text.as_deref(), Some("STOP & unsubscribe"));
+                assert_eq!(sms.keyword.as_deref(), Some("STOP"));
+                assert_eq!(sms.message_id.len(), 16);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let request = Webhook::delivery_receipt("0A0000000123ABCD1", "14155550100", "failed")
+            .field("err-code", "6")
+            .sign(&auth)
+            .into_request();
+        match parse(request, &auth).unwrap() {
+            Event::DeliveryReceipt(dlr) => assert_eq!(dlr.err_code.as_deref(), Some("6")),
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let unsigned = Webhook::inbound_sms("14155550100", "12025550123", "hi").into_request();
+        let error = parse(unsigned, &auth).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Auth));
+    }
+
+    #[test]
+    fn builds_signed_call_events() {
+        let auth = Auth::Jwt("secret".into());
+        let request = Webhook::call_answered("14155550100", "12025550123")
+            .sign(&auth)
+            .into_request();
+
+        match parse(request, &auth).unwrap() {
+            Event::Voice(call) => {
+                assert_eq!(call.status, "answered");
+                assert!(call.conversation_uuid.starts_with("CON-"));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Pluggable HTTP transports used to send requests to the Vonage APIs.
+//!
+//! By default, [`Client`](../struct.Client.html) sends requests using a [`hyper_util`] client with
+//! TLS support. Any other HTTP stack (e.g. one built on `async-std` or `smol`, a corporate proxy,
+//! or a test double) can be plugged in by implementing the [`HttpTransport`] trait and passing it
+//! to [`Client::from_transport()`](../struct.Client.html#method.from_transport).
+//!
+//! [`hyper_util`]: https://docs.rs/hyper-util/0.1/hyper_util/client/legacy/struct.Client.html
+//! [`HttpTransport`]: ./trait.HttpTransport.html
+
+pub use bytes::Bytes;
+pub use http::{Request, Response};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use self::hyper_transport::{build_default_client, default_client_builder};
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::hyper_transport::{DefaultClient, HyperTransport};
+#[cfg(feature = "reqwest")]
+pub use self::reqwest_transport::ReqwestTransport;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use futures::{future, stream, Stream};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod hyper_transport;
+#[cfg(feature = "reqwest")]
+mod reqwest_transport;
+
+/// A type-erased error returned by an [`HttpTransport`](./trait.HttpTransport.html).
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The future returned by [`HttpTransport::send()`](./trait.HttpTransport.html#tymethod.send).
+///
+/// This future is `Send` on all targets except `wasm32`.
+#[cfg(not(target_arch = "wasm32"))]
+pub type TransportFuture = Pin<Box<dyn Future<Output = Result<Response<Bytes>, BoxError>> + Send>>;
+/// The future returned by [`HttpTransport::send()`](./trait.HttpTransport.html#tymethod.send).
+///
+/// This future is `Send` on all targets except `wasm32`.
+#[cfg(target_arch = "wasm32")]
+pub type TransportFuture = Pin<Box<dyn Future<Output = Result<Response<Bytes>, BoxError>>>>;
+
+/// A response body streamed in chunks, as returned by
+/// [`HttpTransport::send_streaming()`](./trait.HttpTransport.html#method.send_streaming).
+///
+/// This stream is `Send` on all targets except `wasm32`.
+#[cfg(not(target_arch = "wasm32"))]
+pub type BodyStream = Pin<Box<dyn Stream<Item = Result<Bytes, BoxError>> + Send>>;
+/// A response body streamed in chunks, as returned by
+/// [`HttpTransport::send_streaming()`](./trait.HttpTransport.html#method.send_streaming).
+///
+/// This stream is `Send` on all targets except `wasm32`.
+#[cfg(target_arch = "wasm32")]
+pub type BodyStream = Pin<Box<dyn Stream<Item = Result<Bytes, BoxError>>>>;
+
+/// The future returned by
+/// [`HttpTransport::send_streaming()`](./trait.HttpTransport.html#method.send_streaming).
+///
+/// This future is `Send` on all targets except `wasm32`.
+#[cfg(not(target_arch = "wasm32"))]
+pub type StreamingFuture =
+    Pin<Box<dyn Future<Output = Result<Response<BodyStream>, BoxError>> + Send>>;
+/// The future returned by
+/// [`HttpTransport::send_streaming()`](./trait.HttpTransport.html#method.send_streaming).
+///
+/// This future is `Send` on all targets except `wasm32`.
+#[cfg(target_arch = "wasm32")]
+pub type StreamingFuture = Pin<Box<dyn Future<Output = Result<Response<BodyStream>, BoxError>>>>;
+
+/// A low-level HTTP transport which sends fully-buffered requests and returns fully-buffered
+/// responses.
+///
+/// Implementations are only responsible for moving bytes over the wire. Everything else, including
+/// authentication, encoding, rate limiting, and error handling, is taken care of by the
+/// [`Client`](../struct.Client.html).
+///
+/// # Example
+///
+/// ```
+/// use vonage::transport::{Bytes, HttpTransport, Request, Response, TransportFuture};
+///
+/// struct AlwaysOk;
+///
+/// impl HttpTransport for AlwaysOk {
+///     fn send(&self, _request: Request<Bytes>) -> TransportFuture {
+///         Box::pin(async { Ok(Response::new(Bytes::from_static(b"{}"))) })
+///     }
+/// }
+///
+/// let client = vonage::Client::from_transport(AlwaysOk)
+///     .api_key("<api_key>", "<api_secret>")
+///     .build();
+/// ```
+pub trait HttpTransport {
+    /// Sends the given HTTP request and returns the response.
+    ///
+    /// Responses with non-successful status codes should be returned as `Ok`. The `Err` variant is
+    /// reserved for failures to complete the request at all, e.g. connection errors.
+    fn send(&self, request: Request<Bytes>) -> TransportFuture;
+
+    /// Sends the given HTTP request and returns the response with a body streamed in chunks.
+    ///
+    /// This is used for large downloads, such as call recordings, which should not be buffered in
+    /// memory. By default, the response is buffered by [`send()`](#tymethod.send) and its body is
+    /// yielded as a single chunk, so only transports which support streaming need to override
+    /// this.
+    fn send_streaming(&self, request: Request<Bytes>) -> StreamingFuture {
+        let response = self.send(request);
+        Box::pin(async move {
+            let response = response.await?;
+            Ok(response.map(|body| -> BodyStream { Box::pin(stream::once(future::ok(body))) }))
+        })
+    }
+}
+
+impl<T: HttpTransport + ?Sized> HttpTransport for Arc<T> {
+    #[inline]
+    fn send(&self, request: Request<Bytes>) -> TransportFuture {
+        (**self).send(request)
+    }
+
+    #[inline]
+    fn send_streaming(&self, request: Request<Bytes>) -> StreamingFuture {
+        (**self).send_streaming(request)
+    }
+}
+
+impl<T: HttpTransport + ?Sized> HttpTransport for Box<T> {
+    #[inline]
+    fn send(&self, request: Request<Bytes>) -> TransportFuture {
+        (**self).send(request)
+    }
+
+    #[inline]
+    fn send_streaming(&self, request: Request<Bytes>) -> StreamingFuture {
+        (**self).send_streaming(request)
+    }
+}
@@ -0,0 +1,117 @@
+//! A transport backed by any `tower` service, such as a `hyper` client.
+
+use bytes::Buf;
+use futures::future::poll_fn;
+use futures::TryStreamExt;
+use http_body::Body;
+use http_body_util::{BodyExt, Full};
+#[cfg(all(feature = "rustls", not(feature = "native-tls")))]
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+#[cfg(feature = "native-tls")]
+use hyper_tls::HttpsConnector;
+use hyper_util::client::legacy::{connect::HttpConnector, Builder, Client};
+use hyper_util::rt::TokioExecutor;
+use tower_service::Service;
+
+use super::{
+    BodyStream, BoxError, Bytes, HttpTransport, Request, Response, StreamingFuture, TransportFuture,
+};
+
+/// An [`HttpTransport`] which sends requests through a [`tower`] HTTP [`Service`].
+///
+/// This is the transport used by [`Client::builder()`] (wrapping a [`hyper_util`] client) and by
+/// [`Client::from_service()`], which accepts any compatible service. As such, the default client
+/// can be composed with any `tower` middleware, e.g. for tracing or timeouts.
+///
+/// [`HttpTransport`]: ./trait.HttpTransport.html
+/// [`tower`]: https://docs.rs/tower/0.5/tower/
+/// [`Service`]: https://docs.rs/tower-service/0.3/tower_service/trait.Service.html
+/// [`Client::builder()`]: ../struct.Client.html#method.builder
+/// [`hyper_util`]: https://docs.rs/hyper-util/0.1/hyper_util/client/legacy/struct.Client.html
+/// [`Client::from_service()`]: ../struct.Client.html#method.from_service
+#[derive(Clone, Debug)]
+pub struct HyperTransport<S>(S);
+
+/// The HTTPS client used by [`Client::builder()`](../struct.Client.html#method.builder).
+///
+/// This is the service which the middleware passed to
+/// [`Client::layered()`](../struct.Client.html#method.layered) wraps.
+pub type DefaultClient = Client<HttpsConnector<HttpConnector>, Full<Bytes>>;
+
+/// Returns a builder for the default client, configured to run on Tokio.
+pub(crate) fn default_client_builder() -> Builder {
+    Client::builder(TokioExecutor::new())
+}
+
+/// Builds the default client with the TLS backend selected by the crate features.
+pub(crate) fn build_default_client(builder: &Builder) -> DefaultClient {
+    #[cfg(feature = "native-tls")]
+    let connector = HttpsConnector::new();
+    #[cfg(all(feature = "rustls", not(feature = "native-tls")))]
+    let connector = HttpsConnectorBuilder::new()
+        .with_webpki_roots()
+        .https_or_http()
+        .enable_http1()
+        .enable_http2()
+        .build();
+
+    builder.build(connector)
+}
+
+impl<S> HyperTransport<S> {
+    /// Creates a new `HyperTransport` which sends requests through the given `service`.
+    #[inline]
+    pub fn new(service: S) -> Self {
+        HyperTransport(service)
+    }
+}
+
+impl<S, B> HttpTransport for HyperTransport<S>
+where
+    S: Service<Request<Full<Bytes>>, Response = Response<B>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+    B: Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    fn send(&self, request: Request<Bytes>) -> TransportFuture {
+        let mut service = self.0.clone();
+        Box::pin(async move {
+            poll_fn(|cx| service.poll_ready(cx))
+                .await
+                .map_err(Into::into)?;
+
+            let response = service
+                .call(request.map(Full::new))
+                .await
+                .map_err(Into::into)?;
+
+            let (parts, body) = response.into_parts();
+            let body = body.collect().await.map_err(Into::into)?.to_bytes();
+            Ok(Response::from_parts(parts, body))
+        })
+    }
+
+    fn send_streaming(&self, request: Request<Bytes>) -> StreamingFuture {
+        let mut service = self.0.clone();
+        Box::pin(async move {
+            poll_fn(|cx| service.poll_ready(cx))
+                .await
+                .map_err(Into::into)?;
+
+            let response = service
+                .call(request.map(Full::new))
+                .await
+                .map_err(Into::into)?;
+
+            Ok(response.map(|body| -> BodyStream {
+                let chunks = body
+                    .into_data_stream()
+                    .map_ok(|mut data| data.copy_to_bytes(data.remaining()))
+                    .map_err(Into::into);
+                Box::pin(chunks)
+            }))
+        })
+    }
+}
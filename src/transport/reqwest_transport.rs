@@ -0,0 +1,148 @@
+//! A transport backed by `reqwest`.
+
+#[cfg(not(target_arch = "wasm32"))]
+use futures::stream;
+
+#[cfg(not(target_arch = "wasm32"))]
+use super::{BodyStream, StreamingFuture};
+use super::{Bytes, HttpTransport, Request, Response, TransportFuture};
+
+/// An [`HttpTransport`] which sends requests using a [`reqwest::Client`].
+///
+/// This allows codebases which have standardized on `reqwest` to reuse their existing HTTP client
+/// and TLS configuration instead of pulling in a second HTTP stack. Note that `vonage-rs` does not
+/// enable any TLS features of `reqwest` on its own, so at least one (e.g. `default-tls`) must be
+/// enabled by the calling application.
+///
+/// On `wasm32` targets, `reqwest` sends requests using the `fetch()` API.
+///
+/// [`HttpTransport`]: ./trait.HttpTransport.html
+/// [`reqwest::Client`]: https://docs.rs/reqwest/0.12/reqwest/struct.Client.html
+///
+/// # Example
+///
+/// ```no_run
+/// use vonage::transport::ReqwestTransport;
+/// use vonage::Client;
+///
+/// let http_client = reqwest::Client::new();
+/// let client = Client::from_transport(ReqwestTransport::new(http_client))
+///     .api_key("<api_key>", "<api_secret>")
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestTransport(reqwest::Client);
+
+impl ReqwestTransport {
+    /// Creates a new `ReqwestTransport` which sends requests with the given `reqwest::Client`.
+    #[inline]
+    pub fn new(client: reqwest::Client) -> Self {
+        ReqwestTransport(client)
+    }
+}
+
+impl From<reqwest::Client> for ReqwestTransport {
+    #[inline]
+    fn from(client: reqwest::Client) -> Self {
+        ReqwestTransport::new(client)
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn send(&self, request: Request<Bytes>) -> TransportFuture {
+        let client = self.0.clone();
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let response = client
+                .request(parts.method, parts.uri.to_string())
+                .headers(parts.headers)
+                .body(body)
+                .send()
+                .await?;
+
+            let mut builder = Response::builder().status(response.status());
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                builder = builder.version(response.version());
+            }
+
+            if let Some(headers) = builder.headers_mut() {
+                *headers = response.headers().clone();
+            }
+
+            let body = response.bytes().await?;
+            Ok(builder.body(body)?)
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn send_streaming(&self, request: Request<Bytes>) -> StreamingFuture {
+        let client = self.0.clone();
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let response = client
+                .request(parts.method, parts.uri.to_string())
+                .headers(parts.headers)
+                .body(body)
+                .send()
+                .await?;
+
+            let mut builder = Response::builder()
+                .status(response.status())
+                .version(response.version());
+            if let Some(headers) = builder.headers_mut() {
+                *headers = response.headers().clone();
+            }
+
+            let chunks = stream::try_unfold(response, |mut response| async move {
+                let chunk = response.chunk().await?;
+                Ok(chunk.map(|chunk| (chunk, response)))
+            });
+            Ok(builder.body(Box::pin(chunks) as BodyStream)?)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http_body_util::{BodyExt, Full};
+    use hyper::body::Incoming;
+    use hyper::server::conn::http1;
+    use hyper::service::service_fn;
+    use hyper_util::rt::TokioIo;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn forwards_requests_to_reqwest() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let service = service_fn(|request: Request<Incoming>| async move {
+                let content_type = request.headers()["content-type"].clone();
+                let body = request.into_body().collect().await?.to_bytes();
+                let mut response = Response::new(Full::new(body));
+                response.headers_mut().insert("content-type", content_type);
+                Ok::<_, hyper::Error>(response)
+            });
+            http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+                .unwrap();
+        });
+
+        let request = Request::post(format!("http://{}/verify/json", addr))
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(Bytes::from_static(b"number=14155550100"))
+            .unwrap();
+
+        let response = ReqwestTransport::default().send(request).await.unwrap();
+        assert_eq!(
+            response.headers()["content-type"],
+            "application/x-www-form-urlencoded"
+        );
+        assert_eq!(&response.body()[..], b"number=14155550100");
+    }
+}
@@ -0,0 +1,436 @@
+//! Summaries of account usage and spend from the
+//! [Reports API](https://developer.nexmo.com/api/reports).
+//!
+//! The Reports API returns one record per message, call or request. [`UsageApi::summary()`]
+//! loads every record of the requested products over a date range and aggregates them into a
+//! count and a total spend per product, which is what billing dashboards usually need.
+//!
+//! [`UsageApi::summary()`]: ./struct.UsageApi.html#method.summary
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Formatter};
+
+use bytes::Bytes;
+use http::header::ACCEPT;
+use http::{Method, Request, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::auth::{ApiKey, Auth};
+use crate::datetime::{self, Timestamp};
+use crate::http_client::HttpClient;
+use crate::transport::HttpTransport;
+use crate::validation::Validator;
+use crate::{
+    Amount, CurrencyConverter, DefaultTransport, Error, ErrorKind, Product, RequestPolicy, Result,
+};
+
+/// A handle to the [Reports](https://developer.nexmo.com/api/reports) API.
+///
+/// This is returned from [`Client::usage()`](../struct.Client.html#method.usage). It holds the
+/// API key and API secret pair of the client, and is cheap to clone.
+pub struct UsageApi<C = DefaultTransport> {
+    http_client: HttpClient<C>,
+    auth: Auth,
+}
+
+impl<C> UsageApi<C> {
+    pub(crate) fn new(http_client: HttpClient<C>, auth: Auth) -> Self {
+        UsageApi { http_client, auth }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`RequestPolicy`](../struct.RequestPolicy.html) for details.
+    pub fn policy(mut self, policy: RequestPolicy) -> Self {
+        self.http_client = self.http_client.with_policy(policy);
+        self
+    }
+
+    /// Summarizes the usage of the account between `start` and `end`.
+    ///
+    /// By default, every product in [`ReportProduct::ALL`] is included.
+    ///
+    /// [`ReportProduct::ALL`]: ./enum.ReportProduct.html#associatedconstant.ALL
+    pub fn summary(&self, start: Timestamp, end: Timestamp) -> SummarizeUsage<C> {
+        SummarizeUsage {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+            products: Vec::new(),
+            start,
+            end,
+            include_subaccounts: false,
+        }
+    }
+}
+
+impl<C> Clone for UsageApi<C> {
+    fn clone(&self) -> Self {
+        UsageApi {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+        }
+    }
+}
+
+impl<C> Debug for UsageApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(UsageApi))
+            .field("http_client", &self.http_client)
+            .field("auth", &self.auth)
+            .finish()
+    }
+}
+
+/// A product whose usage is recorded by the Reports API.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+#[non_exhaustive]
+pub enum ReportProduct {
+    /// Outbound messages sent with the SMS API.
+    Sms,
+    /// Outbound messages sent with the Messages API, on any channel.
+    Messages,
+    /// Outbound calls made with the Voice API.
+    VoiceCall,
+    /// Verify (2FA) requests.
+    VerifyApi,
+    /// Number Insight lookups.
+    NumberInsight,
+}
+
+impl ReportProduct {
+    /// Every product which is summarized by default.
+    pub const ALL: &'static [ReportProduct] = &[
+        ReportProduct::Sms,
+        ReportProduct::Messages,
+        ReportProduct::VoiceCall,
+        ReportProduct::VerifyApi,
+        ReportProduct::NumberInsight,
+    ];
+
+    /// Returns the direction of the traffic which is billed for this product, if the Reports API
+    /// distinguishes directions for it.
+    fn direction(self) -> Option<&'static str> {
+        match self {
+            ReportProduct::Sms | ReportProduct::Messages | ReportProduct::VoiceCall => {
+                Some("outbound")
+            }
+            ReportProduct::VerifyApi | ReportProduct::NumberInsight => None,
+        }
+    }
+}
+
+/// A builder for a summary of account usage.
+///
+/// This is returned from [`UsageApi::summary()`](./struct.UsageApi.html#method.summary).
+pub struct SummarizeUsage<C> {
+    http_client: HttpClient<C>,
+    auth: Auth,
+    products: Vec<ReportProduct>,
+    start: Timestamp,
+    end: Timestamp,
+    include_subaccounts: bool,
+}
+
+impl<C> SummarizeUsage<C> {
+    /// Includes `product` in the summary.
+    ///
+    /// Once called, only the products passed to this method are included.
+    pub fn product(mut self, product: ReportProduct) -> Self {
+        if !self.products.contains(&product) {
+            self.products.push(product);
+        }
+        self
+    }
+
+    /// Includes the usage of all subaccounts of the account. Defaults to `false`.
+    pub fn include_subaccounts(mut self, include: bool) -> Self {
+        self.include_subaccounts = include;
+        self
+    }
+}
+
+impl<C: HttpTransport> SummarizeUsage<C> {
+    /// Loads the records of each product and aggregates them.
+    ///
+    /// Every page of records is loaded, so summaries over long date ranges of busy accounts may
+    /// take a while. Returns an error of kind [`ErrorKind::InvalidRequest`] without sending
+    /// anything if `end` is not after `start`.
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../enum.ErrorKind.html#variant.InvalidRequest
+    pub async fn send(self) -> Result<UsageSummary> {
+        let mut validator = Validator::new();
+        validator.check(
+            self.end > self.start,
+            "date_end",
+            "must be after `date_start`",
+        );
+        validator.finish()?;
+
+        let (account_id, _) = self.auth.api_key_pair()?;
+        let products = match self.products.as_slice() {
+            [] => ReportProduct::ALL,
+            products => products,
+        };
+
+        let mut summary = UsageSummary {
+            start: self.start,
+            end: self.end,
+            products: Vec::with_capacity(products.len()),
+        };
+
+        for &product in products {
+            let query = RecordsQuery {
+                account_id,
+                product,
+                direction: product.direction(),
+                date_start: &self.start,
+                date_end: &self.end,
+                include_subaccounts: self.include_subaccounts,
+            };
+
+            let query = serde_urlencoded::to_string(&query)
+                .map_err(|e| Error::with_cause(ErrorKind::UrlEncode, e))?;
+            let mut uri = format!("{}?{}", crate::resolve_url("v2/reports/records"), query);
+
+            let mut usage = ProductUsage::new(product);
+            loop {
+                let request = encode_request(&uri, &self.auth)?;
+                let page: RecordsPage = self
+                    .http_client
+                    .send(Product::Reports, request, |response| {
+                        match response.status() {
+                            StatusCode::OK => crate::http_client::decode_json(response.body()),
+                            _ => Err(Error::new_status(&response)),
+                        }
+                    })
+                    .await?;
+
+                for record in page.records {
+                    usage.add(record)?;
+                }
+
+                match page.links.next {
+                    Some(Link { href }) => uri = crate::resolve_url(&href),
+                    None => break,
+                }
+            }
+
+            summary.products.push(usage);
+        }
+
+        Ok(summary)
+    }
+}
+
+impl<C> Debug for SummarizeUsage<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(SummarizeUsage))
+            .field("products", &self.products)
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .field("include_subaccounts", &self.include_subaccounts)
+            .finish()
+    }
+}
+
+fn encode_request(uri: &str, auth: &Auth) -> Result<Request<Bytes>> {
+    let (name, value) = auth.to_auth_header()?;
+    Request::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .header(ACCEPT, "application/json")
+        .header(name, value)
+        .body(Bytes::new())
+        .map_err(|e| Error::with_cause(ErrorKind::Http, e))
+}
+
+/// The usage of an account over a date range.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UsageSummary {
+    /// The start of the date range.
+    pub start: Timestamp,
+    /// The end of the date range.
+    pub end: Timestamp,
+    /// The usage of each product which was requested, in the order requested.
+    pub products: Vec<ProductUsage>,
+}
+
+impl UsageSummary {
+    /// Returns the usage of `product`, if it was included in the summary.
+    pub fn get(&self, product: ReportProduct) -> Option<&ProductUsage> {
+        self.products.iter().find(|usage| usage.product == product)
+    }
+
+    /// Returns the number of records of all products.
+    pub fn total_count(&self) -> u64 {
+        self.products.iter().map(|usage| usage.count).sum()
+    }
+
+    /// Returns the spend of all products, per currency.
+    pub fn total_spend(&self) -> BTreeMap<String, Amount> {
+        let mut total = BTreeMap::new();
+        for (currency, amount) in self.products.iter().flat_map(|usage| &usage.spend) {
+            *total.entry(currency.clone()).or_default() += *amount;
+        }
+        total
+    }
+
+    /// Returns the spend of all products in the currency `to`.
+    ///
+    /// Returns `None` if `converter` does not support one of the currencies spent in.
+    pub fn total_spend_in(&self, to: &str, converter: &dyn CurrencyConverter) -> Option<Amount> {
+        let mut total = Amount::default();
+        for (currency, amount) in self.total_spend() {
+            total += converter.convert(amount, &currency, to)?;
+        }
+        Some(total)
+    }
+}
+
+/// The usage of a single product over a date range.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProductUsage {
+    /// The product.
+    pub product: ReportProduct,
+    /// The number of messages, calls or requests.
+    pub count: u64,
+    /// The total price of all records, keyed by ISO 4217 currency code.
+    ///
+    /// Accounts are billed in a single currency, so this usually has one entry at most.
+    pub spend: BTreeMap<String, Amount>,
+}
+
+impl ProductUsage {
+    fn new(product: ReportProduct) -> Self {
+        ProductUsage {
+            product,
+            count: 0,
+            spend: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, record: Record) -> Result<()> {
+        self.count += 1;
+
+        let price = match record.total_price.or(record.price) {
+            Some(price) if !price.is_empty() => price,
+            _ => return Ok(()),
+        };
+        let price: Amount = price.parse().map_err(|_| {
+            let message = format!("invalid price in usage record: {:?}", price);
+            Error::with_cause(ErrorKind::Decode, anyhow::Error::msg(message))
+        })?;
+
+        let currency = record.currency.unwrap_or_default();
+        *self.spend.entry(currency).or_default() += price;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct RecordsQuery<'a> {
+    account_id: &'a ApiKey,
+    product: ReportProduct,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    direction: Option<&'static str>,
+    #[serde(serialize_with = "datetime::iso8601::serialize")]
+    date_start: &'a Timestamp,
+    #[serde(serialize_with = "datetime::iso8601::serialize")]
+    date_end: &'a Timestamp,
+    include_subaccounts: bool,
+}
+
+#[derive(Deserialize)]
+struct RecordsPage {
+    #[serde(default)]
+    records: Vec<Record>,
+    #[serde(rename = "_links", default)]
+    links: Links,
+}
+
+#[derive(Default, Deserialize)]
+struct Links {
+    next: Option<Link>,
+}
+
+#[derive(Deserialize)]
+struct Link {
+    href: String,
+}
+
+#[derive(Deserialize)]
+struct Record {
+    total_price: Option<String>,
+    price: Option<String>,
+    currency: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{Request, Response};
+    use http_body_util::Full;
+    use tower::service_fn;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn aggregates_records_across_pages() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert!(request.headers()["authorization"]
+                .to_str()
+                .unwrap()
+                .starts_with("Basic "));
+
+            let query = request.uri().query().unwrap_or_default();
+            let body = if query.contains("product=SMS") {
+                assert!(query.contains("account_id=key"));
+                assert!(query.contains("direction=outbound"));
+                assert!(query.contains("date_start=2020-01-01T00%3A00%3A00Z"));
+                r#"{"records":[{"total_price":"0.0333","currency":"EUR"},{"total_price":"0.0333","currency":"EUR"}],"_links":{"next":{"href":"/v2/reports/records?cursor=abc"}}}"#
+            } else if query.contains("cursor=abc") {
+                r#"{"records":[{"total_price":"0.1","currency":"EUR"}],"_links":{}}"#
+            } else {
+                assert!(query.contains("product=VERIFY-API"));
+                assert!(!query.contains("direction"));
+                r#"{"records":[{"price":"0.05","currency":"EUR"},{"currency":"EUR"}]}"#
+            };
+            Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let client = crate::Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let start = datetime::parse("2020-01-01 00:00:00").unwrap();
+        let end = datetime::parse("2020-02-01 00:00:00").unwrap();
+        let summary = client
+            .usage()
+            .summary(start, end)
+            .product(ReportProduct::Sms)
+            .product(ReportProduct::VerifyApi)
+            .send()
+            .await
+            .unwrap();
+
+        let sms = summary.get(ReportProduct::Sms).unwrap();
+        assert_eq!(sms.count, 3);
+        let verify = summary.get(ReportProduct::VerifyApi).unwrap();
+        assert_eq!(verify.count, 2);
+        assert!(summary.get(ReportProduct::VoiceCall).is_none());
+        assert_eq!(summary.total_count(), 5);
+
+        let spend = summary.total_spend()["EUR"];
+        #[cfg(not(feature = "decimal"))]
+        assert!((spend - 0.2166).abs() < 1e-9);
+        #[cfg(feature = "decimal")]
+        assert_eq!(spend.to_string(), "0.2166");
+
+        let error = client.usage().summary(end, start).send().await.unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::InvalidRequest));
+    }
+}
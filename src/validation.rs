@@ -0,0 +1,134 @@
+//! Client-side validation of request parameters.
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::{Error, ErrorKind, InvalidParameter, Result};
+
+/// A request which was rejected before being sent, because some of its parameters are invalid.
+///
+/// This can be obtained from an [`Error`] with [`Error::as_invalid_request()`]. Catching such
+/// mistakes on the client side saves a round trip and, for some products, a charge.
+///
+/// [`Error`]: ./struct.Error.html
+/// [`Error::as_invalid_request()`]: ./struct.Error.html#method.as_invalid_request
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvalidRequest {
+    invalid_parameters: Vec<InvalidParameter>,
+}
+
+impl InvalidRequest {
+    /// Returns the request parameters which failed validation.
+    #[inline]
+    pub fn invalid_parameters(&self) -> &[InvalidParameter] {
+        &self.invalid_parameters
+    }
+}
+
+impl Display for InvalidRequest {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("invalid request parameters")?;
+        for (i, param) in self.invalid_parameters.iter().enumerate() {
+            let sep = if i == 0 { ": " } else { "; " };
+            write!(f, "{}`{}` {}", sep, param.name(), param.reason())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for InvalidRequest {}
+
+/// Collects invalid request parameters.
+#[derive(Debug, Default)]
+pub(crate) struct Validator {
+    invalid_parameters: Vec<InvalidParameter>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Validator::default()
+    }
+
+    /// Records the parameter `name` as invalid for the given `reason` unless `valid` holds.
+    pub fn check(&mut self, valid: bool, name: &str, reason: &str) -> &mut Self {
+        if !valid {
+            let param = InvalidParameter::new(name.to_owned(), reason.to_owned());
+            self.invalid_parameters.push(param);
+        }
+        self
+    }
+
+    /// Checks that the optional parameter `name` is at most `max` characters long.
+    pub fn max_len(&mut self, value: Option<&str>, name: &str, max: usize) -> &mut Self {
+        let valid = value.is_none_or(|v| v.chars().count() <= max);
+        self.check(valid, name, &format!("must be at most {} characters", max))
+    }
+
+    /// Checks that the optional parameter `name` lies within `min..=max`.
+    pub fn range(&mut self, value: Option<u64>, name: &str, min: u64, max: u64) -> &mut Self {
+        let valid = value.is_none_or(|v| (min..=max).contains(&v));
+        self.check(valid, name, &format!("must be between {} and {}", min, max))
+    }
+
+    /// Records parameters which were already found to be invalid elsewhere.
+    pub fn extend(&mut self, params: Vec<InvalidParameter>) -> &mut Self {
+        self.invalid_parameters.extend(params);
+        self
+    }
+
+    /// Returns the invalid parameters collected so far.
+    pub fn into_invalid_parameters(self) -> Vec<InvalidParameter> {
+        self.invalid_parameters
+    }
+
+    /// Returns an error of kind `ErrorKind::InvalidRequest` if any parameter was invalid.
+    pub fn finish(self) -> Result<()> {
+        if self.invalid_parameters.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::with_cause(
+                ErrorKind::InvalidRequest,
+                InvalidRequest {
+                    invalid_parameters: self.invalid_parameters,
+                },
+            ))
+        }
+    }
+}
+
+/// Returns whether `url` is an absolute HTTP(S) URL, as required for webhooks.
+pub(crate) fn is_http_url(url: &str) -> bool {
+    match url.parse::<http::Uri>() {
+        Ok(uri) => matches!(uri.scheme_str(), Some("http" | "https")) && uri.host().is_some(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_invalid_parameters() {
+        let mut validator = Validator::new();
+        validator.max_len(None, "brand", 1).range(None, "ttl", 1, 2);
+        assert!(validator.finish().is_ok());
+
+        let mut validator = Validator::new();
+        validator
+            .max_len(Some("too long"), "sender_id", 3)
+            .range(Some(10), "pin_expiry", 60, 3600)
+            .range(Some(60), "next_event_wait", 60, 900);
+
+        let error = validator.finish().unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::InvalidRequest));
+
+        let invalid = error.as_invalid_request().unwrap();
+        assert_eq!(invalid.invalid_parameters().len(), 2);
+        assert_eq!(invalid.invalid_parameters()[0].name(), "sender_id");
+        assert_eq!(
+            invalid.to_string(),
+            "invalid request parameters: `sender_id` must be at most 3 characters; \
+             `pin_expiry` must be between 60 and 3600"
+        );
+    }
+}
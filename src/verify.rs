@@ -1,73 +1,106 @@
 //! Interface to the Verify (2FA) API.
 
+pub use self::api::*;
+pub use self::lookup::*;
 pub use self::pending::*;
 pub use self::request::*;
 pub use self::search::*;
+pub use self::state::*;
 
 use std::fmt::{self, Debug, Display, Formatter};
+use std::str::FromStr;
 
-use hyper::{Body, Response, StatusCode};
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde::{Deserialize, Serialize};
 
-use super::{Error, Result};
+use self::wire::decode_response;
+use super::{money, Amount, CurrencyConverter, Error, ErrorKind, Price, Result};
 
+pub mod v2;
+pub mod wire;
+
+mod api;
+mod lookup;
 mod pending;
 mod request;
 mod search;
+mod state;
+
+/// The maximum length of a request ID assigned by Vonage.
+const MAX_REQUEST_ID_LEN: usize = 32;
 
 /// The unique identifier of a particular verify request.
+///
+/// Request IDs can be stored as strings and restored later with `str::parse()`, e.g. to check a
+/// code which was requested by an earlier process.
+///
+/// # Example
+///
+/// ```
+/// use vonage::verify::RequestId;
+///
+/// let id: RequestId = "abcdef0123456789abcdef0123456789".parse().unwrap();
+/// assert_eq!(id.as_ref(), "abcdef0123456789abcdef0123456789");
+///
+/// assert!("not a request ID".parse::<RequestId>().is_err());
+/// ```
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
 pub struct RequestId(String);
 
+impl AsRef<str> for RequestId {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
 impl Display for RequestId {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         Display::fmt(&self.0, f)
     }
 }
 
-async fn decode_response<T>(response: Response<Body>) -> Result<T>
-where
-    T: DeserializeOwned,
-{
-    #[derive(Deserialize)]
-    enum SuccessCode {
-        #[serde(rename = "0")]
-        Success,
-    }
-
-    #[derive(Deserialize)]
-    #[serde(deny_unknown_fields)]
-    #[serde(untagged)]
-    enum ResponseBody<T> {
-        Success {
-            #[allow(dead_code)]
-            status: SuccessCode,
-            #[serde(flatten)]
-            inner: T,
-        },
-        Error {
-            #[allow(dead_code)]
-            request_id: Option<RequestId>,
-            status: ErrorCode,
-            error_text: String,
-        },
-    }
-
-    match response.status() {
-        StatusCode::OK => {}
-        other => return Err(other.into()),
-    }
-
-    let bytes = hyper::body::to_bytes(response.into_body()).await?;
-    match serde_json::from_slice(&bytes).map_err(Error::new_verify)? {
-        ResponseBody::Success { inner, .. } => Ok(inner),
-        ResponseBody::Error {
-            status, error_text, ..
-        } => Err(VerifyError::new(status, error_text).into()),
+/// Converts a string into a `RequestId` without validating it.
+///
+/// This is intended for IDs which are already known to be valid, such as ones read back from a
+/// database. Use `str::parse()` to validate untrusted input instead.
+impl From<String> for RequestId {
+    #[inline]
+    fn from(id: String) -> Self {
+        RequestId(id)
+    }
+}
+
+impl FromStr for RequestId {
+    type Err = Error;
+
+    /// Parses a request ID, which must consist of 1 to 32 ASCII alphanumeric characters.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequestId`] if `s` is not a valid request ID.
+    ///
+    /// [`ErrorKind::InvalidRequestId`]: ../enum.ErrorKind.html#variant.InvalidRequestId
+    fn from_str(s: &str) -> Result<Self> {
+        let is_valid = !s.is_empty()
+            && s.len() <= MAX_REQUEST_ID_LEN
+            && s.bytes().all(|b| b.is_ascii_alphanumeric());
+
+        if is_valid {
+            Ok(RequestId(s.to_owned()))
+        } else {
+            let message = format!("`{}` is not a valid request ID", s);
+            Err(Error::with_cause(
+                ErrorKind::InvalidRequestId,
+                anyhow::Error::msg(message),
+            ))
+        }
     }
 }
 
 /// Status code and error text returned by the Verify API.
+///
+/// This can be obtained from an [`Error`] with [`Error::as_verify()`].
+///
+/// [`Error`]: ../struct.Error.html
+/// [`Error::as_verify()`]: ../struct.Error.html#method.as_verify
 #[derive(Debug, thiserror::Error)]
 #[error("{error_text} ({status})")]
 pub struct VerifyError {
@@ -79,6 +112,23 @@ impl VerifyError {
     fn new(status: ErrorCode, error_text: String) -> Self {
         VerifyError { status, error_text }
     }
+
+    /// Returns the status code reported by the Verify API.
+    #[inline]
+    pub fn status(&self) -> ErrorCode {
+        self.status
+    }
+
+    /// Returns the human-readable description of the error reported by the Verify API.
+    #[inline]
+    pub fn error_text(&self) -> &str {
+        &self.error_text
+    }
+
+    /// Returns whether the request was only rejected temporarily, e.g. due to throttling.
+    pub(crate) fn is_retryable(&self) -> bool {
+        matches!(self.status, ErrorCode::Throttled | ErrorCode::InternalError)
+    }
 }
 
 impl From<VerifyError> for Error {
@@ -90,38 +140,61 @@ impl From<VerifyError> for Error {
     }
 }
 
-#[derive(Debug, Deserialize)]
-enum ErrorCode {
+/// A list of status codes returned by the Verify API on failure.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// More than the maximum number of requests per second were sent.
     #[serde(rename = "1")]
     Throttled,
+    /// The request is missing a mandatory parameter.
     #[serde(rename = "2")]
     MissingParam,
+    /// The value of one or more parameters is invalid.
     #[serde(rename = "3")]
     InvalidParam,
+    /// The supplied API key or secret is invalid.
     #[serde(rename = "4")]
     InvalidCredentials,
+    /// An internal error occurred on Vonage's side.
     #[serde(rename = "5")]
     InternalError,
+    /// The request could not be routed to the destination number.
     #[serde(rename = "6")]
     RouteError,
+    /// The destination number is blacklisted for verification.
     #[serde(rename = "7")]
     BlacklistedPhone,
+    /// The account of the supplied API key has been barred.
     #[serde(rename = "8")]
     BarredApiKey,
+    /// The account does not have sufficient credit.
     #[serde(rename = "9")]
     ExceededPartnerQuota,
+    /// A verification to the same number is already in progress.
     #[serde(rename = "10")]
     Concurrent,
+    /// The destination number is on an unsupported network.
     #[serde(rename = "15")]
     UnsupportedNetwork,
+    /// The supplied PIN code does not match the expected value.
     #[serde(rename = "16")]
     CodeMismatch,
+    /// The wrong PIN code was supplied too many times.
     #[serde(rename = "17")]
     TooManyAttempts,
+    /// The verify request cannot be cancelled or advanced at this time.
     #[serde(rename = "19")]
     CancelOrTriggerNextFailed,
+    /// The account does not support custom PIN codes.
     #[serde(rename = "20")]
     PinCodeNotSupported,
+    /// A status code which is not known to this version of the library.
+    ///
+    /// The accompanying [`VerifyError::error_text()`](./struct.VerifyError.html#method.error_text)
+    /// describes the error.
+    #[serde(other)]
+    Unknown,
 }
 
 impl Display for ErrorCode {
@@ -142,6 +215,365 @@ impl Display for ErrorCode {
             ErrorCode::TooManyAttempts => f.write_str("error 17"),
             ErrorCode::CancelOrTriggerNextFailed => f.write_str("error 19"),
             ErrorCode::PinCodeNotSupported => f.write_str("error 20"),
+            ErrorCode::Unknown => f.write_str("unknown error"),
+        }
+    }
+}
+
+/// Returns the total cost of a verify request in the currency `to`.
+///
+/// The price of a request is reported in the currency of the account, while the estimated price of
+/// the messages sent is always reported in EUR.
+fn total_cost(
+    (price, currency): (&Price, &str),
+    estimated_price_messages_sent: Option<&Price>,
+    to: &str,
+    converter: &dyn CurrencyConverter,
+) -> Option<Amount> {
+    let mut cost = converter.convert(money::to_amount(price)?, currency, to)?;
+    if let Some(estimated) = estimated_price_messages_sent {
+        cost += converter.convert(money::to_amount(estimated)?, "EUR", to)?;
+    }
+    Some(cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use http::Response;
+    use serde::de::DeserializeOwned;
+
+    use super::*;
+
+    fn error_response(status: &str) -> Error {
+        let body = format!(r#"{{"status":"{}","error_text":"error"}}"#, status);
+        decode_response::<()>(Response::new(Bytes::from(body))).unwrap_err()
+    }
+
+    #[test]
+    fn exposes_error_details() {
+        let error = error_response("16");
+        let details = error.as_verify().unwrap();
+        assert_eq!(details.status(), ErrorCode::CodeMismatch);
+        assert_eq!(details.error_text(), "error");
+
+        let error = decode_response::<()>(Response::new(Bytes::from("{}"))).unwrap_err();
+        assert!(error.as_verify().is_none());
+    }
+
+    #[test]
+    fn classifies_throttling_as_retryable() {
+        assert!(error_response("1").is_retryable());
+        assert!(error_response("5").is_retryable());
+        assert!(!error_response("3").is_retryable());
+        assert!(!error_response("16").is_retryable());
+    }
+
+    #[test]
+    fn round_trips_public_types() {
+        fn round_trip<T>(value: T, json: &str)
+        where
+            T: Debug + DeserializeOwned + PartialEq + Serialize,
+        {
+            assert_eq!(serde_json::to_string(&value).unwrap(), json);
+            assert_eq!(serde_json::from_str::<T>(json).unwrap(), value);
+        }
+
+        round_trip(Workflow::SmsTts, "5");
+        round_trip(CodeLength::Six, "6");
+        round_trip(Language::EnglishUs, r#""en-us""#);
+        round_trip(VerifyStatus::InProgress, r#""IN PROGRESS""#);
+        round_trip(CheckStatus::Valid, r#""VALID""#);
+        round_trip(EventType::Tts, r#""tts""#);
+        round_trip(ErrorCode::Throttled, r#""1""#);
+
+        assert!(serde_json::from_str::<Workflow>("8").is_err());
+        assert!(serde_json::from_str::<CodeLength>("5").is_err());
+    }
+
+    #[test]
+    fn converts_costs() {
+        let verified: Verified = serde_json::from_str(
+            r#"{"request_id":"abcdef0123456789abcdef0123456789","event_id":"0A00000012345678","price":"0.10000000","currency":"GBP","estimated_price_messages_sent":"0.05000000"}"#,
+        )
+        .unwrap();
+
+        let rates = crate::ExchangeRates::new("EUR")
+            .rate("GBP", "0.5".parse().unwrap())
+            .rate("USD", "2".parse().unwrap());
+        assert_eq!(verified.cost_in("USD", &rates), "0.5".parse().ok());
+        assert_eq!(verified.cost_in("JPY", &rates), None);
+    }
+
+    #[test]
+    fn parses_request_ids() {
+        let id: RequestId = "abcdef0123456789abcdef0123456789".parse().unwrap();
+        assert_eq!(id, RequestId::from(id.to_string()));
+
+        for invalid in &["", "abc-def", "abcdef0123456789abcdef0123456789a"] {
+            let error = invalid.parse::<RequestId>().unwrap_err();
+            assert!(matches!(error.kind(), ErrorKind::InvalidRequestId));
         }
     }
+
+    #[test]
+    fn masks_codes_in_debug_output() {
+        let check: Check = serde_json::from_str(
+            r#"{"date_received":"2020-01-01 12:00:00","code":"123456","status":"INVALID","ip_address":null}"#,
+        )
+        .unwrap();
+        assert_eq!(check.code, "123456");
+        assert!(!format!("{:?}", check).contains("123456"));
+    }
+
+    #[test]
+    fn maps_countries_to_languages() {
+        use phonenumber::country::Id;
+        use phonenumber::PhoneNumber;
+
+        assert_eq!(Language::for_country(Id::AR), Some(Language::SpanishMexico));
+        assert_eq!(Language::for_country(Id::GB), Some(Language::EnglishUk));
+        assert_eq!(Language::for_country(Id::KE), None);
+        assert_eq!(
+            Psd2Language::for_country(Id::IE),
+            Some(Psd2Language::EnglishUk)
+        );
+        assert_eq!(Psd2Language::for_country(Id::US), None);
+
+        let phone: PhoneNumber = "+14155550100".parse().unwrap();
+        assert_eq!(
+            Language::for_phone_number(&phone),
+            Some(Language::EnglishUs)
+        );
+        assert_eq!(Psd2Language::for_phone_number(&phone), None);
+    }
+
+    #[test]
+    fn tolerates_unknown_variants() {
+        assert_eq!(
+            error_response("99").as_verify().unwrap().status(),
+            ErrorCode::Unknown
+        );
+
+        let status: VerifyStatus = serde_json::from_str(r#""PAUSED""#).unwrap();
+        assert_eq!(status, VerifyStatus::Unknown);
+        let status: CheckStatus = serde_json::from_str(r#""EXPIRED""#).unwrap();
+        assert_eq!(status, CheckStatus::Unknown);
+        let event: EventType = serde_json::from_str(r#""whatsapp""#).unwrap();
+        assert_eq!(event, EventType::Unknown);
+    }
+
+    #[tokio::test]
+    async fn honors_configured_check_attempts() {
+        use std::convert::Infallible;
+
+        use http::Request;
+        use http_body_util::Full;
+        use tower::service_fn;
+
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            let body = match request.uri().path() {
+                "/verify/json" => {
+                    r#"{"request_id":"abcdef0123456789abcdef0123456789","status":"0"}"#
+                }
+                _ => r#"{"status":"16","error_text":"The code provided does not match"}"#,
+            };
+            Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let client = crate::Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let pending = client
+            .verify()
+            .request("+14155550100".parse().unwrap(), "vonage-rs")
+            .check_attempts(1)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(pending.attempts_remaining(), 1);
+
+        let pending = match pending.check("0000").await.unwrap() {
+            Code::Mismatch(pending) => pending,
+            Code::Match(_) => panic!("code should not have matched"),
+        };
+        assert_eq!(pending.attempts_remaining(), 0);
+
+        let error = pending.check("0000").await.unwrap_err();
+        assert!(error.kind().is_code_mismatch());
+    }
+
+    #[tokio::test]
+    async fn sends_callback_url() {
+        use std::convert::Infallible;
+
+        use http::Request;
+        use http_body_util::{BodyExt, Full};
+        use tower::service_fn;
+
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            let body = request.into_body().collect().await.unwrap().to_bytes();
+            let params: Vec<(String, String)> = serde_urlencoded::from_bytes(&body).unwrap();
+            let callback = (
+                "callback".to_owned(),
+                "https://example.com/events".to_owned(),
+            );
+            assert!(params.contains(&callback));
+
+            let body = r#"{"request_id":"abcdef0123456789abcdef0123456789","status":"0"}"#;
+            Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let client = crate::Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+        let phone: phonenumber::PhoneNumber = "+14155550100".parse().unwrap();
+
+        client
+            .verify()
+            .request(phone.clone(), "vonage-rs")
+            .callback_url("https://example.com/events")
+            .send()
+            .await
+            .unwrap();
+
+        let error = client
+            .verify()
+            .request(phone, "vonage-rs")
+            .callback_url("/events")
+            .send()
+            .await
+            .unwrap_err();
+        let invalid = error.as_invalid_request().unwrap();
+        assert_eq!(invalid.invalid_parameters()[0].name(), "callback");
+    }
+
+    #[tokio::test]
+    async fn rejects_landlines_after_lookup() {
+        use std::convert::Infallible;
+
+        use http::Request;
+        use http_body_util::Full;
+        use tower::service_fn;
+
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            let body = match request.uri().path() {
+                "/ni/standard/json" => {
+                    assert!(request
+                        .uri()
+                        .query()
+                        .unwrap()
+                        .contains("number=14155550100"));
+                    r#"{"status":0,"current_carrier":{"network_type":"landline"}}"#
+                }
+                _ => r#"{"request_id":"abcdef0123456789abcdef0123456789","status":"0"}"#,
+            };
+            Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let client = crate::Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+        let phone: phonenumber::PhoneNumber = "+14155550100".parse().unwrap();
+
+        let error = client
+            .verify()
+            .request(phone.clone(), "vonage-rs")
+            .lookup(Lookup::Standard)
+            .send()
+            .await
+            .unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Undeliverable));
+        assert!(matches!(
+            error.as_undeliverable(),
+            Some(Undeliverable::Landline { .. })
+        ));
+
+        client
+            .verify()
+            .request(phone, "vonage-rs")
+            .workflow(Workflow::TtsTts)
+            .lookup(Lookup::Standard)
+            .send()
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn assigns_senders_from_pool() {
+        use std::convert::Infallible;
+        use std::sync::{Arc, Mutex};
+
+        use http::Request;
+        use http_body_util::{BodyExt, Full};
+        use phonenumber::country::Id;
+        use tower::service_fn;
+
+        let senders = Arc::new(Mutex::new(Vec::new()));
+        let seen = senders.clone();
+        let service = service_fn(move |request: Request<Full<Bytes>>| {
+            let seen = seen.clone();
+            async move {
+                let body = request.into_body().collect().await.unwrap().to_bytes();
+                let params: Vec<(String, String)> = serde_urlencoded::from_bytes(&body).unwrap();
+                let sender = params.into_iter().find(|(k, _)| k == "sender_id");
+                seen.lock().unwrap().push(sender.map(|(_, v)| v));
+
+                let body = r#"{"request_id":"abcdef0123456789abcdef0123456789","status":"0"}"#;
+                Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(body))))
+            }
+        });
+
+        let pool = crate::SenderPool::new().country(Id::DE, vec!["ACME", "ACMEDE"]);
+        let client = crate::Client::from_service(service)
+            .api_key("key", "secret")
+            .sender_pool(pool)
+            .build()
+            .unwrap();
+
+        let de: phonenumber::PhoneNumber = "+4915123456789".parse().unwrap();
+        let us: phonenumber::PhoneNumber = "+14155550100".parse().unwrap();
+        let verify = client.verify();
+        verify
+            .request(de.clone(), "vonage-rs")
+            .send()
+            .await
+            .unwrap();
+        verify
+            .request(de.clone(), "vonage-rs")
+            .send()
+            .await
+            .unwrap();
+        verify
+            .request(us.clone(), "vonage-rs")
+            .send()
+            .await
+            .unwrap();
+        verify
+            .request(us, "vonage-rs")
+            .country(Id::DE)
+            .send()
+            .await
+            .unwrap();
+        verify
+            .request(de, "vonage-rs")
+            .sender_id("OVERRIDE")
+            .send()
+            .await
+            .unwrap();
+
+        let expected = [
+            Some("ACME"),
+            Some("ACMEDE"),
+            None,
+            Some("ACME"),
+            Some("OVERRIDE"),
+        ];
+        let expected: Vec<_> = expected.iter().map(|s| s.map(str::to_owned)).collect();
+        assert_eq!(*senders.lock().unwrap(), expected);
+    }
 }
@@ -0,0 +1,86 @@
+//! Contains the entry point to the Verify API.
+
+use std::fmt::{self, Debug, Formatter};
+
+use phonenumber::PhoneNumber;
+
+use super::{RequestId, Search, Verify};
+use crate::auth::{ApiKey, ApiSecret};
+use crate::http_client::HttpClient;
+use crate::{DefaultTransport, RequestPolicy};
+
+/// A handle to the Verify (2FA) API.
+///
+/// This is returned from [`Client::verify()`](../struct.Client.html#method.verify). It holds the
+/// API key and API secret pair of the client, and is cheap to clone.
+pub struct VerifyApi<C = DefaultTransport> {
+    http_client: HttpClient<C>,
+    key_pair: (ApiKey, ApiSecret),
+}
+
+impl<C> VerifyApi<C> {
+    pub(crate) fn new(http_client: HttpClient<C>, key_pair: (ApiKey, ApiSecret)) -> Self {
+        VerifyApi {
+            http_client,
+            key_pair,
+        }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`RequestPolicy`](../struct.RequestPolicy.html) for details.
+    pub fn policy(mut self, policy: RequestPolicy) -> Self {
+        self.http_client = self.http_client.with_policy(policy);
+        self
+    }
+
+    /// Initiates a new [verify (2FA) request][verify] for the given phone number.
+    ///
+    /// [verify]: https://developer.nexmo.com/api/verify
+    pub fn request(&self, phone: PhoneNumber, brand: impl Into<String>) -> Verify<C> {
+        // FIXME: While "brand" is a required field in regular verify requests, it is not present
+        // at all in PSD2 verify requests. Currently, we discard the `brand` parameter if `.psd2()`
+        // is called anywhere in the method chain. There might be a better way to do this.
+        Verify::new(
+            self.http_client.clone(),
+            &self.key_pair,
+            phone,
+            brand.into(),
+        )
+    }
+
+    /// Retrieves details of past or current verify requests by ID.
+    ///
+    /// Large searches are split into chunks which are sent concurrently, and the results are
+    /// returned in the same order as `request_ids`. See [`Search`](./struct.Search.html) for
+    /// details.
+    pub fn search<I>(&self, request_ids: I) -> Search<C>
+    where
+        I: IntoIterator<Item = RequestId>,
+    {
+        Search::new(
+            self.http_client.clone(),
+            self.key_pair.clone(),
+            request_ids.into_iter().collect(),
+        )
+    }
+}
+
+impl<C> Clone for VerifyApi<C> {
+    fn clone(&self) -> Self {
+        VerifyApi {
+            http_client: self.http_client.clone(),
+            key_pair: self.key_pair.clone(),
+        }
+    }
+}
+
+impl<C> Debug for VerifyApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(VerifyApi))
+            .field("http_client", &self.http_client)
+            .field("key_pair", &self.key_pair)
+            .finish()
+    }
+}
@@ -0,0 +1,110 @@
+//! Screening of destination numbers with Number Insight before sending a verify request.
+
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use super::Result;
+use crate::auth::{ApiKey, ApiSecret};
+use crate::http_client::HttpClient;
+use crate::transport::HttpTransport;
+use crate::{Error, Product};
+
+/// A [Number Insight](https://developer.nexmo.com/api/number-insight) lookup which screens the
+/// destination number before a verify request is sent.
+///
+/// Verify requests to numbers which cannot receive the code are still charged. A lookup costs a
+/// fraction of that, and rejects such numbers with an error of kind
+/// [`ErrorKind::Undeliverable`](../enum.ErrorKind.html#variant.Undeliverable) instead.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Lookup {
+    /// A free lookup, which only rejects numbers which are not valid.
+    Basic,
+    /// A paid lookup, which additionally rejects landlines unless the workflow starts with a
+    /// voice call.
+    Standard,
+}
+
+/// The reason why a verify request was rejected by a [`Lookup`](./enum.Lookup.html).
+///
+/// This can be obtained from an [`Error`](../struct.Error.html) with
+/// [`Error::as_undeliverable()`](../struct.Error.html#method.as_undeliverable).
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum Undeliverable {
+    /// The number is not valid, or not in service.
+    #[error("phone number is not valid")]
+    InvalidNumber,
+    /// The number is a landline, which cannot receive SMS.
+    #[error("phone number is a landline (`{network_type}`)")]
+    Landline {
+        /// The network type reported by Number Insight, e.g. `landline_tollfree`.
+        network_type: String,
+    },
+}
+
+#[derive(Serialize)]
+struct Query<'a> {
+    api_key: &'a ApiKey,
+    api_secret: &'a ApiSecret,
+    number: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ResponseBody {
+    status: u8,
+    #[serde(default)]
+    status_message: String,
+    #[serde(default)]
+    current_carrier: Option<Carrier>,
+}
+
+#[derive(Deserialize)]
+struct Carrier {
+    #[serde(default)]
+    network_type: Option<String>,
+}
+
+/// Runs `lookup` on `number`, returning why it cannot be reached, if it cannot.
+///
+/// Landlines are only reported if `landline_ok` is false.
+pub(crate) async fn run<C: HttpTransport>(
+    http_client: &HttpClient<C>,
+    (api_key, api_secret): (&ApiKey, &ApiSecret),
+    number: &str,
+    lookup: Lookup,
+    landline_ok: bool,
+) -> Result<Option<Undeliverable>> {
+    let level = match lookup {
+        Lookup::Basic => "basic",
+        Lookup::Standard => "standard",
+    };
+
+    let query = Query {
+        api_key,
+        api_secret,
+        number: number.trim_start_matches('+'),
+    };
+    let request = crate::encode_request_get(&["ni", level], &query)?;
+    let body: ResponseBody = http_client
+        .send(Product::NumberInsight, request, |response| {
+            match response.status() {
+                StatusCode::OK => crate::http_client::decode_json(response.body()),
+                _ => Err(Error::new_status(&response)),
+            }
+        })
+        .await?;
+
+    match body.status {
+        0 => {
+            let network_type = body.current_carrier.and_then(|c| c.network_type);
+            Ok(network_type
+                .filter(|t| t.starts_with("landline") && !landline_ok)
+                .map(|network_type| Undeliverable::Landline { network_type }))
+        }
+        3 => Ok(Some(Undeliverable::InvalidNumber)),
+        status => Err(crate::number_insight::status_error(
+            status,
+            &body.status_message,
+        )),
+    }
+}
@@ -2,29 +2,33 @@
 
 use std::hash::{Hash, Hasher};
 
-use hyper::service::Service;
-use hyper::{Body, Request, Response};
 use serde::{Deserialize, Serialize};
 
 use super::{RequestId, Result};
 use std::fmt::{self, Debug, Formatter};
 
 use crate::auth::{ApiKey, ApiSecret};
-use crate::HyperClient;
+use crate::http_client::HttpClient;
+use crate::response::ResponseMeta;
+use crate::transport::HttpTransport;
+use crate::{Amount, CurrencyConverter, DefaultTransport, Price, Product};
 
 /// A handle to a pending verify request.
-pub struct PendingVerify<C = HyperClient> {
-    pub(super) http_client: C,
+///
+/// `PendingVerify` is cheap to clone, and is `Send` and `Sync` whenever its transport is, so it
+/// can be shared with other tasks. Clones share the connection pool and middleware of the
+/// originating [`Client`](../struct.Client.html), but track their remaining check attempts and
+/// response metadata independently.
+pub struct PendingVerify<C = DefaultTransport> {
+    pub(super) http_client: HttpClient<C>,
     pub(super) api_key: ApiKey,
     pub(super) api_secret: ApiSecret,
     pub(super) request_id: RequestId,
     pub(super) attempts_remaining: usize,
+    pub(super) meta: ResponseMeta,
 }
 
-impl<C> PendingVerify<C>
-where
-    C: Service<Request<Body>, Response = Response<Body>, Error = hyper::Error>,
-{
+impl<C: HttpTransport> PendingVerify<C> {
     /// Attempts to cancel this pending verify request.
     #[inline]
     pub async fn cancel(&mut self) -> Result<()> {
@@ -47,7 +51,7 @@ where
         }
 
         let request = crate::encode_request_post(
-            "/verify/control",
+            &["verify", "control"],
             RequestBody {
                 api_key: &self.api_key,
                 api_secret: &self.api_secret,
@@ -56,14 +60,21 @@ where
             },
         )?;
 
-        let response = self.http_client.call(request).await?;
-        super::decode_response(response).await
+        let (result, meta) = self
+            .http_client
+            .send_with_meta(Product::Verify, request, super::decode_response)
+            .await;
+        if let Some(meta) = meta {
+            self.meta = meta;
+        }
+        result
     }
 
     /// Checks whether the user-provided PIN code matches the expected value.
     ///
     /// Returns `Ok(Code::Match(_))` if the given PIN code is correct. Returns
-    /// `Ok(Code::Mismatch(_))` if the given PIN code is incorrect, allowing up to 3 attempts.
+    /// `Ok(Code::Mismatch(_))` if the given PIN code is incorrect, as long as
+    /// [`attempts_remaining()`](#method.attempts_remaining) is non-zero.
     /// Returns `Err` if the code expired, the request was canceled, or some other error occurred.
     pub async fn check(mut self, code: &str) -> Result<Code<C>> {
         #[derive(Serialize)]
@@ -75,7 +86,7 @@ where
         }
 
         let request = crate::encode_request_post(
-            "/verify/check",
+            &["verify", "check"],
             RequestBody {
                 api_key: &self.api_key,
                 api_secret: &self.api_secret,
@@ -84,9 +95,19 @@ where
             },
         )?;
 
-        let response = self.http_client.call(request).await?;
-        match super::decode_response(response).await {
-            Ok(verified) => Ok(Code::Match(verified)),
+        let (response, meta) = self
+            .http_client
+            .send_with_meta(Product::Verify, request, super::decode_response)
+            .await;
+        if let Some(meta) = meta {
+            self.meta = meta;
+        }
+
+        match response {
+            Ok(verified) => Ok(Code::Match(Verified {
+                meta: self.meta,
+                ..verified
+            })),
             Err(e) if e.kind().is_code_mismatch() && self.attempts_remaining > 0 => {
                 self.attempts_remaining -= 1;
                 Ok(Code::Mismatch(self))
@@ -95,7 +116,10 @@ where
         }
     }
 
-    /// Returns the number of check attempts remaining (maximum 3).
+    /// Returns the number of check attempts remaining.
+    ///
+    /// This starts at [`MAX_CHECK_ATTEMPTS`](./constant.MAX_CHECK_ATTEMPTS.html), unless changed
+    /// with [`Verify::check_attempts()`](./struct.Verify.html#method.check_attempts).
     #[inline]
     pub fn attempts_remaining(&self) -> usize {
         self.attempts_remaining
@@ -106,6 +130,25 @@ where
     pub fn request_id(&self) -> &RequestId {
         &self.request_id
     }
+
+    /// Returns the metadata of the most recent response received for this request.
+    #[inline]
+    pub fn response_meta(&self) -> &ResponseMeta {
+        &self.meta
+    }
+}
+
+impl<C> Clone for PendingVerify<C> {
+    fn clone(&self) -> Self {
+        PendingVerify {
+            http_client: self.http_client.clone(),
+            api_key: self.api_key.clone(),
+            api_secret: self.api_secret.clone(),
+            request_id: self.request_id.clone(),
+            attempts_remaining: self.attempts_remaining,
+            meta: self.meta.clone(),
+        }
+    }
 }
 
 impl<C> Debug for PendingVerify<C> {
@@ -143,7 +186,7 @@ pub enum Code<C> {
 }
 
 /// Details returned when a verify request succeeded.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Verified {
     /// The originating verify request ID.
@@ -151,7 +194,7 @@ pub struct Verified {
     /// The ID of the verification event, such as an SMS or TTS call.
     pub event_id: String,
     /// The cost incurred for this request.
-    pub price: String,
+    pub price: Price,
     /// The currency code.
     pub currency: String,
     /// The value indicates the cost (in EUR) of the calls made and messages sent for the
@@ -160,7 +203,28 @@ pub struct Verified {
     /// This value may be updated during and shortly after the request completes because user input
     /// events can overlap with message/call events. When this field is present, the total cost of
     /// the verification is the sum of this field and the price field.
-    pub estimated_price_messages_sent: Option<String>,
+    pub estimated_price_messages_sent: Option<Price>,
+    #[serde(skip)]
+    meta: ResponseMeta,
+}
+
+impl Verified {
+    /// Returns the metadata of the response which confirmed the code.
+    #[inline]
+    pub fn response_meta(&self) -> &ResponseMeta {
+        &self.meta
+    }
+
+    /// Returns the total cost of the verification in the currency `to`, including the estimated
+    /// price of the messages sent.
+    ///
+    /// Returns `None` if `converter` does not support the currencies involved, or if a price could
+    /// not be parsed.
+    pub fn cost_in(&self, to: &str, converter: &dyn CurrencyConverter) -> Option<Amount> {
+        let price = (&self.price, self.currency.as_str());
+        let estimated = self.estimated_price_messages_sent.as_ref();
+        super::total_cost(price, estimated, to, converter)
+    }
 }
 
 #[derive(Deserialize, Serialize)]
@@ -3,26 +3,50 @@
 pub use self::normal::Language;
 pub use self::psd2::Language as Psd2Language;
 
+pub(crate) use self::normal::Normal;
+pub(crate) use self::psd2::Psd2;
+
 use std::fmt::{self, Debug, Formatter};
 use std::time::Duration;
 
-use hyper::body::Body;
-use hyper::service::Service;
-use hyper::{Request, Response};
+use bytes::Bytes;
+use http::Request;
 use phonenumber::{country::Id, PhoneNumber};
+use serde::de::{self, Deserializer, Unexpected};
 use serde::{Deserialize, Serialize};
 
-use super::{PendingVerify, RequestId, Result};
-use crate::auth::{ApiKey, ApiSecret, Auth};
+use super::wire::Started;
+use super::{Lookup, PendingVerify, Result};
+use crate::auth::{ApiKey, ApiSecret};
+use crate::http_client::HttpClient;
+use crate::transport::HttpTransport;
+use crate::validation::{is_http_url, Validator};
+use crate::{Amount, Error, ErrorKind, InvalidParameter, Product};
 
 mod normal;
 mod psd2;
 
-const MAX_CHECK_ATTEMPTS: usize = 3;
+/// The default number of incorrect PIN codes which may be submitted to a verify request before
+/// [`PendingVerify::check()`](./struct.PendingVerify.html#method.check) returns an error.
+///
+/// This matches the default configuration of Vonage accounts. Use
+/// [`Verify::check_attempts()`](./struct.Verify.html#method.check_attempts) if your account is
+/// configured differently.
+pub const MAX_CHECK_ATTEMPTS: usize = 3;
 
 #[doc(hidden)]
 pub trait Verification: Default + Serialize {
-    const PATH: &'static str;
+    const PATH: &'static [&'static str];
+
+    /// Returns the request-specific parameters which are invalid, if any.
+    fn invalid_parameters(&self) -> Vec<InvalidParameter> {
+        Vec::new()
+    }
+
+    /// Returns the sender ID field, if this kind of request has one.
+    fn sender_id_mut(&mut self) -> Option<&mut Option<String>> {
+        None
+    }
 }
 
 /// A builder to configure a new verify request.
@@ -32,32 +56,38 @@ pub trait Verification: Default + Serialize {
 ///
 /// [`/verify`]: https://developer.nexmo.com/api/verify#verifyRequest
 /// [`/verify/psd2`]: https://developer.nexmo.com/api/verify#verifyRequestWithPSD2
-pub struct Verify<C, V: Verification = normal::Normal> {
-    http_client: C,
+pub struct Verify<C, V: Verification = Normal> {
+    http_client: HttpClient<C>,
     request_body: RequestBody<V>,
+    check_attempts: usize,
+    destination: Option<Id>,
+    lookup: Option<Lookup>,
 }
 
 impl<C> Verify<C> {
     pub(crate) fn new(
-        http_client: C,
-        auth: &Auth,
+        http_client: HttpClient<C>,
+        (api_key, api_secret): &(ApiKey, ApiSecret),
         phone: PhoneNumber,
         brand: String,
-    ) -> Result<Self> {
-        let (api_key, api_secret) = auth.api_key_pair()?;
-        Ok(Verify {
+    ) -> Self {
+        let destination = phone.country().id();
+        Verify {
             http_client,
             request_body: RequestBody {
                 api_key: api_key.clone(),
                 api_secret: api_secret.clone(),
                 number: phone.to_string(),
-                req_specific: normal::Normal {
+                req_specific: Normal {
                     brand,
                     ..Default::default()
                 },
                 ..Default::default()
             },
-        })
+            check_attempts: MAX_CHECK_ATTEMPTS,
+            destination,
+            lookup: None,
+        }
     }
 
     /// Sets the 11-character alphanumeric string that represents the identity of the sender of the
@@ -85,7 +115,7 @@ impl<C> Verify<C> {
     /// Changes this builder to construct a [Payment Services Directive 2 (PSD2)] request.
     ///
     /// [Payment Services Directive 2 (PSD2)]: https://developer.nexmo.com/api/verify#verifyRequestWithPSD2
-    pub fn psd2(self, payee: impl Into<String>, amount_eur: f64) -> Verify<C, psd2::Psd2> {
+    pub fn psd2(self, payee: impl Into<String>, amount_eur: Amount) -> Verify<C, Psd2> {
         Verify {
             http_client: self.http_client,
             request_body: RequestBody {
@@ -97,17 +127,21 @@ impl<C> Verify<C> {
                 pin_expiry: self.request_body.pin_expiry,
                 next_event_wait: self.request_body.next_event_wait,
                 workflow_id: self.request_body.workflow_id,
-                req_specific: psd2::Psd2 {
+                callback: self.request_body.callback,
+                req_specific: Psd2 {
                     payee: payee.into(),
                     amount: amount_eur,
                     language: None,
                 },
             },
+            check_attempts: self.check_attempts,
+            destination: self.destination,
+            lookup: self.lookup,
         }
     }
 }
 
-impl<C> Verify<C, psd2::Psd2> {
+impl<C> Verify<C, Psd2> {
     /// Sets the language used for the SMS or TTS message to be sent.
     ///
     /// By default, the SMS or text-to-speech (TTS) message is generated in the locale that matches
@@ -123,7 +157,7 @@ impl<C> Verify<C, psd2::Psd2> {
 
 impl<C, V> Verify<C, V>
 where
-    C: Service<Request<Body>, Response = Response<Body>, Error = hyper::Error>,
+    C: HttpTransport,
     V: Verification,
 {
     /// Overrides the country code of the phone number.
@@ -170,31 +204,120 @@ where
         self
     }
 
+    /// Overrides the URL which Vonage sends events about this verify request to.
+    ///
+    /// By default, events are sent to the callback URL configured for the whole account. Setting
+    /// this lets several services sharing one account each receive their own verification events.
+    /// The URL must be absolute and use either `http` or `https`.
+    pub fn callback_url(mut self, url: impl Into<String>) -> Self {
+        self.request_body.callback = Some(url.into());
+        self
+    }
+
+    /// Sets how many incorrect PIN codes may be submitted before
+    /// [`PendingVerify::check()`](./struct.PendingVerify.html#method.check) gives up.
+    ///
+    /// This is a client-side budget which should match the configuration of your Vonage account.
+    /// Defaults to [`MAX_CHECK_ATTEMPTS`](./constant.MAX_CHECK_ATTEMPTS.html).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `attempts` is zero.
+    pub fn check_attempts(mut self, attempts: usize) -> Self {
+        assert!(attempts > 0, "at least one check attempt must be allowed");
+        self.check_attempts = attempts;
+        self
+    }
+
+    /// Screens the phone number with a Number Insight lookup before sending the verify request.
+    ///
+    /// Numbers which are not valid are rejected by either lookup. A [`Lookup::Standard`] also
+    /// rejects landlines, unless the [`workflow`](#method.workflow) starts with a voice call.
+    /// Rejected requests fail with an error of kind [`ErrorKind::Undeliverable`] and are not
+    /// charged, apart from the lookup itself.
+    ///
+    /// [`Lookup::Standard`]: ./enum.Lookup.html#variant.Standard
+    /// [`ErrorKind::Undeliverable`]: ../enum.ErrorKind.html#variant.Undeliverable
+    pub fn lookup(mut self, lookup: Lookup) -> Self {
+        self.lookup = Some(lookup);
+        self
+    }
+
     /// Submits the verify request and returns a `PendingVerify` to control its state.
+    ///
+    /// If no [`sender_id`](#method.sender_id) was set and the client has a
+    /// [`SenderPool`](../struct.SenderPool.html), one is selected from the pool based on the
+    /// destination country.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequest`] without sending anything if any of
+    /// the parameters is out of range, e.g. a sender ID longer than 11 characters.
+    ///
+    /// If a [`lookup`](#method.lookup) was requested, it runs first, and may fail with an error of
+    /// kind [`ErrorKind::Undeliverable`].
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../enum.ErrorKind.html#variant.InvalidRequest
+    /// [`ErrorKind::Undeliverable`]: ../enum.ErrorKind.html#variant.Undeliverable
     pub async fn send(mut self) -> Result<PendingVerify<C>> {
-        #[derive(Deserialize)]
-        struct ResponseBody {
-            request_id: RequestId,
+        let country = self.request_body.country.or(self.destination);
+        let pool = self.http_client.sender_pool();
+        if let Some(sender_id) = self.request_body.req_specific.sender_id_mut() {
+            if sender_id.is_none() {
+                *sender_id = pool.and_then(|p| p.select(country)).map(str::to_owned);
+            }
         }
 
+        self.request_body.validate()?;
+        if let Some(Some(sender_id)) = self.request_body.req_specific.sender_id_mut() {
+            let check = self.http_client.sender_id_check();
+            crate::sender::check_sender_id(check, sender_id, country, pool)?;
+        }
+        if let Some(lookup) = self.lookup {
+            let body = &self.request_body;
+            let credentials = (&body.api_key, &body.api_secret);
+            let landline_ok = body.workflow_id.is_some_and(Workflow::starts_with_tts);
+            let result = super::lookup::run(
+                &self.http_client,
+                credentials,
+                &body.number,
+                lookup,
+                landline_ok,
+            );
+            if let Some(reason) = result.await? {
+                return Err(Error::with_cause(ErrorKind::Undeliverable, reason));
+            }
+        }
         let request = crate::encode_request_post(V::PATH, &self.request_body)?;
-        let response = self.http_client.call(request).await?;
-        let ResponseBody { request_id } = super::decode_response(response).await?;
+        let (result, meta) = self
+            .http_client
+            .send_with_meta(Product::Verify, request, super::decode_response)
+            .await;
+        let Started { request_id } = result?;
 
         Ok(PendingVerify {
             http_client: self.http_client,
             api_key: self.request_body.api_key,
             api_secret: self.request_body.api_secret,
             request_id,
-            attempts_remaining: MAX_CHECK_ATTEMPTS,
+            attempts_remaining: self.check_attempts,
+            meta: meta.unwrap_or_default(),
         })
     }
 }
 
+impl<C, V: Verification> Verify<C, V> {
+    /// Validates and encodes the request, as sent by [`send()`](#method.send).
+    pub(super) fn encode(&self) -> Result<Request<Bytes>> {
+        self.request_body.validate()?;
+        crate::encode_request_post(V::PATH, &self.request_body)
+    }
+}
+
 impl<C, V: Debug + Verification> Debug for Verify<C, V> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.debug_struct(stringify!(Verify))
             .field("request_body", &self.request_body)
+            .field("check_attempts", &self.check_attempts)
+            .field("lookup", &self.lookup)
             .finish()
     }
 }
@@ -210,10 +333,27 @@ struct RequestBody<V: Verification> {
     pin_expiry: Option<u64>,
     next_event_wait: Option<u64>,
     workflow_id: Option<Workflow>,
+    callback: Option<String>,
     #[serde(flatten)]
     req_specific: V,
 }
 
+impl<V: Verification> RequestBody<V> {
+    fn validate(&self) -> Result<()> {
+        let mut validator = Validator::new();
+        validator
+            .range(self.pin_expiry, "pin_expiry", 60, 3600)
+            .range(self.next_event_wait, "next_event_wait", 60, 900)
+            .check(
+                self.callback.as_deref().is_none_or(is_http_url),
+                "callback",
+                "must be an absolute HTTP(S) URL",
+            )
+            .extend(self.req_specific.invalid_parameters());
+        validator.finish()
+    }
+}
+
 /// The number of digits in a verification code.
 #[derive(Clone, Copy, Debug, PartialEq, Serialize)]
 #[serde(into = "u8")]
@@ -222,15 +362,31 @@ pub enum CodeLength {
     Six,
 }
 
-impl Into<u8> for CodeLength {
-    fn into(self) -> u8 {
-        match self {
+impl From<CodeLength> for u8 {
+    fn from(len: CodeLength) -> u8 {
+        match len {
             CodeLength::Four => 4,
             CodeLength::Six => 6,
         }
     }
 }
 
+impl<'de> Deserialize<'de> for CodeLength {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            4 => Ok(CodeLength::Four),
+            6 => Ok(CodeLength::Six),
+            n => Err(de::Error::invalid_value(
+                Unexpected::Unsigned(n.into()),
+                &"4 or 6",
+            )),
+        }
+    }
+}
+
 /// A list of possible verify workflows supported by the Verify API ([source]).
 ///
 /// [source]: https://developer.nexmo.com/verify/guides/workflows-and-events
@@ -242,6 +398,17 @@ impl Into<u8> for CodeLength {
 ///
 /// When the user sends you the code, you send the code along with the `request_id` through to
 /// Vonage to check the code is correct.
+///
+/// The Verify API has no parameters specific to TTS calls. Their behavior is tuned with the
+/// settings of the request as a whole instead:
+///
+/// * The workflow decides how many calls are placed, and whether an SMS is sent first.
+/// * [`Verify::language()`] selects the language and voice the code is spoken in.
+/// * [`Verify::next_event_wait()`] sets how long each call waits before the next one is placed,
+///   which is how a spoken code is repeated.
+///
+/// [`Verify::language()`]: ./struct.Verify.html#method.language
+/// [`Verify::next_event_wait()`]: ./struct.Verify.html#method.next_event_wait
 #[derive(Clone, Copy, Debug, PartialEq, Serialize)]
 #[serde(into = "u8")]
 pub enum Workflow {
@@ -268,9 +435,15 @@ pub enum Workflow {
     Tts,
 }
 
-impl Into<u8> for Workflow {
-    fn into(self) -> u8 {
-        match self {
+impl Workflow {
+    fn starts_with_tts(self) -> bool {
+        matches!(self, Workflow::TtsTts | Workflow::Tts)
+    }
+}
+
+impl From<Workflow> for u8 {
+    fn from(w: Workflow) -> u8 {
+        match w {
             Workflow::SmsTtsTts => 1,
             Workflow::SmsSmsTts => 2,
             Workflow::TtsTts => 3,
@@ -281,3 +454,24 @@ impl Into<u8> for Workflow {
         }
     }
 }
+
+impl<'de> Deserialize<'de> for Workflow {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            1 => Ok(Workflow::SmsTtsTts),
+            2 => Ok(Workflow::SmsSmsTts),
+            3 => Ok(Workflow::TtsTts),
+            4 => Ok(Workflow::SmsSms),
+            5 => Ok(Workflow::SmsTts),
+            6 => Ok(Workflow::Sms),
+            7 => Ok(Workflow::Tts),
+            n => Err(de::Error::invalid_value(
+                Unexpected::Unsigned(n.into()),
+                &"a workflow ID between 1 and 7",
+            )),
+        }
+    }
+}
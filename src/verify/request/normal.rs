@@ -1,8 +1,12 @@
 //! Types specific to the `/verify` endpoint.
 
-use serde::Serialize;
+use phonenumber::country::Id;
+use phonenumber::PhoneNumber;
+use serde::{Deserialize, Serialize};
 
 use super::Verification;
+use crate::validation::Validator;
+use crate::InvalidParameter;
 
 /// Request fields specific to the `/verify` endpoint.
 #[derive(Debug, Default, Serialize)]
@@ -14,11 +18,25 @@ pub struct Normal {
 }
 
 impl Verification for Normal {
-    const PATH: &'static str = "/verify";
+    const PATH: &'static [&'static str] = &["verify"];
+
+    fn invalid_parameters(&self) -> Vec<InvalidParameter> {
+        let mut validator = Validator::new();
+        validator.max_len(Some(&self.brand), "brand", 18).max_len(
+            self.sender_id.as_deref(),
+            "sender_id",
+            11,
+        );
+        validator.into_invalid_parameters()
+    }
+
+    fn sender_id_mut(&mut self) -> Option<&mut Option<String>> {
+        Some(&mut self.sender_id)
+    }
 }
 
 /// A list of supported languages for verify SMS or TTS messages.
-#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub enum Language {
     #[serde(rename = "ar-xa")]
     Arabic,
@@ -95,3 +113,64 @@ pub enum Language {
     #[serde(rename = "zh-tw")]
     ChineseTaiwan,
 }
+
+impl Language {
+    /// Returns the supported language which is most widely spoken in the given country, if any.
+    ///
+    /// Countries without a dedicated dialect map to the closest one, e.g. Argentina maps to
+    /// [`SpanishMexico`](#variant.SpanishMexico) and New Zealand to
+    /// [`EnglishAu`](#variant.EnglishAu). Returns `None` if no supported language is a sensible
+    /// fit, in which case the API default of [`EnglishUs`](#variant.EnglishUs) is used.
+    pub fn for_country(country: Id) -> Option<Self> {
+        use Id::*;
+
+        let language = match country {
+            AE | BH | DZ | EG | IQ | JO | KW | LB | LY | MA | OM | PS | QA | SA | SY | TN | YE => {
+                Language::Arabic
+            }
+            CZ => Language::Czech,
+            DK | FO | GL => Language::Danish,
+            AT | CH | DE | LI => Language::German,
+            CY | GR => Language::Greek,
+            AU | NZ => Language::EnglishAu,
+            GB | GG | GI | IE | IM | JE | ZA => Language::EnglishUk,
+            IN => Language::EnglishIndia,
+            CA | US => Language::EnglishUs,
+            ES => Language::Spanish,
+            AR | BO | CL | CO | CR | CU | DO | EC | GT | HN | MX | NI | PA | PE | PY | SV | UY
+            | VE => Language::SpanishMexico,
+            FI => Language::Finnish,
+            PH => Language::Filipino,
+            FR | LU | MC => Language::French,
+            HU => Language::Hungarian,
+            ID => Language::Indonesian,
+            IS => Language::Icelandic,
+            IT | SM | VA => Language::Italian,
+            JP => Language::Japanese,
+            KR => Language::Korean,
+            NO => Language::Norwegian,
+            BE | NL | SR => Language::Dutch,
+            PL => Language::Polish,
+            BR => Language::PortugueseBrazil,
+            AO | MZ | PT => Language::Portuguese,
+            MD | RO => Language::Romanian,
+            SE => Language::Swedish,
+            TH => Language::Thai,
+            VN => Language::Vietnamese,
+            HK | MO => Language::Cantonese,
+            CN => Language::ChineseMainland,
+            TW => Language::ChineseTaiwan,
+            _ => return None,
+        };
+
+        Some(language)
+    }
+
+    /// Returns the supported language which is most widely spoken in the country of `phone`, if
+    /// any.
+    ///
+    /// See [`Language::for_country()`](#method.for_country) for details.
+    pub fn for_phone_number(phone: &PhoneNumber) -> Option<Self> {
+        phone.country().id().and_then(Language::for_country)
+    }
+}
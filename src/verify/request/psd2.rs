@@ -1,24 +1,34 @@
 //! Types specific to the `/verify/psd2` endpoint.
 
-use serde::Serialize;
+use phonenumber::country::Id;
+use phonenumber::PhoneNumber;
+use serde::{Deserialize, Serialize};
 
 use super::Verification;
+use crate::validation::Validator;
+use crate::{Amount, InvalidParameter};
 
 /// Request fields specific to the `/verify/psd2` endpoint.
 #[derive(Debug, Default, Serialize)]
 pub struct Psd2 {
     pub payee: String,
-    pub amount: f64,
+    pub amount: Amount,
     #[serde(rename = "lg")]
     pub language: Option<Language>,
 }
 
 impl Verification for Psd2 {
-    const PATH: &'static str = "/verify/psd2";
+    const PATH: &'static [&'static str] = &["verify", "psd2"];
+
+    fn invalid_parameters(&self) -> Vec<InvalidParameter> {
+        let mut validator = Validator::new();
+        validator.max_len(Some(&self.payee), "payee", 18);
+        validator.into_invalid_parameters()
+    }
 }
 
 /// A list of supported languages for PSD2 SMS or TTS messages.
-#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub enum Language {
     #[serde(rename = "bg-bg")]
     Bulgarian,
@@ -63,3 +73,47 @@ pub enum Language {
     #[serde(rename = "sv-se")]
     Swedish,
 }
+
+impl Language {
+    /// Returns the supported language which is most widely spoken in the given country, if any.
+    ///
+    /// Returns `None` if no supported language is a sensible fit, which is the case for most
+    /// countries outside of the European Economic Area.
+    pub fn for_country(country: Id) -> Option<Self> {
+        use Id::*;
+
+        let language = match country {
+            BG => Language::Bulgarian,
+            CZ => Language::Czech,
+            DK | FO | GL => Language::Danish,
+            AT | CH | DE | LI => Language::German,
+            GB | GG | GI | IE | IM | JE => Language::EnglishUk,
+            EE => Language::Estonian,
+            CY | GR => Language::Greek,
+            ES => Language::Spanish,
+            FI => Language::Finnish,
+            FR | LU | MC => Language::French,
+            HU => Language::Hungarian,
+            IT | SM | VA => Language::Italian,
+            LV => Language::Latvian,
+            LT => Language::Lithuanian,
+            MT => Language::Maltese,
+            BE | NL => Language::Dutch,
+            PL => Language::Polish,
+            SK => Language::Slovak,
+            SI => Language::Slovenian,
+            SE => Language::Swedish,
+            _ => return None,
+        };
+
+        Some(language)
+    }
+
+    /// Returns the supported language which is most widely spoken in the country of `phone`, if
+    /// any.
+    ///
+    /// See [`Language::for_country()`](#method.for_country) for details.
+    pub fn for_phone_number(phone: &PhoneNumber) -> Option<Self> {
+        phone.country().id().and_then(Language::for_country)
+    }
+}
@@ -1,109 +1,263 @@
 //! Contains types for the `/verify/search` request.
 
-use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
 
-use chrono::NaiveDateTime;
-use hyper::service::Service;
-use hyper::{Body, Request, Response, StatusCode};
+use futures::{StreamExt, TryStreamExt};
+use http::StatusCode;
 use phonenumber::PhoneNumber;
-use serde::de::{self, Deserializer};
 use serde::{Deserialize, Serialize};
 
-use super::{Error, PendingVerify, RequestId, Result};
+use super::{Error, ErrorCode, PendingVerify, RequestId, Result, VerifyError};
 use crate::auth::{ApiKey, ApiSecret};
+use crate::datetime::{self, Timestamp};
+use crate::http_client::HttpClient;
+use crate::transport::HttpTransport;
+use crate::{Amount, CurrencyConverter, DefaultTransport, Price, Product};
+
+/// The maximum number of request IDs the Verify API accepts in a single search request.
+///
+/// [`Search`](./struct.Search.html) automatically splits larger searches into chunks of this
+/// size.
+pub const MAX_SEARCH_IDS: usize = 10;
+
+const DEFAULT_CONCURRENCY: usize = 4;
 
 /// Retrieves details of past or current verify requests.
+///
+/// The results are returned in the same order as `iter`. Entries which the Verify API has no
+/// record of are returned as `None`. To search for bare request IDs, or to tune concurrency and
+/// observe progress, use [`VerifyApi::search()`](./struct.VerifyApi.html#method.search) instead.
 pub async fn search<'a, I, C>(iter: I) -> Result<Vec<Option<VerifyInfo>>>
 where
     I: IntoIterator<Item = &'a PendingVerify<C>>,
-    C: Service<Request<Body>, Response = Response<Body>, Error = hyper::Error> + Clone + 'static,
+    C: HttpTransport + 'static,
 {
+    let mut pending = iter.into_iter().peekable();
+    let (http_client, key_pair) = match pending.peek() {
+        Some(first) => (
+            first.http_client.clone(),
+            (first.api_key.clone(), first.api_secret.clone()),
+        ),
+        None => return Ok(Vec::new()),
+    };
+
+    let request_ids = pending.map(|v| v.request_id.clone()).collect();
+    Search::new(http_client, key_pair, request_ids).send().await
+}
+
+/// A search for many verify requests at once.
+///
+/// This is returned from [`VerifyApi::search()`](./struct.VerifyApi.html#method.search). The
+/// request IDs are split into chunks of [`MAX_SEARCH_IDS`](./constant.MAX_SEARCH_IDS.html), which
+/// are sent concurrently. Nothing is sent until [`send()`](#method.send) is called.
+pub struct Search<C = DefaultTransport, P = fn(usize, usize)> {
+    http_client: HttpClient<C>,
+    key_pair: (ApiKey, ApiSecret),
+    request_ids: Vec<RequestId>,
+    concurrency: usize,
+    on_progress: P,
+}
+
+impl<C> Search<C> {
+    pub(crate) fn new(
+        http_client: HttpClient<C>,
+        key_pair: (ApiKey, ApiSecret),
+        request_ids: Vec<RequestId>,
+    ) -> Self {
+        Search {
+            http_client,
+            key_pair,
+            request_ids,
+            concurrency: DEFAULT_CONCURRENCY,
+            on_progress: |_, _| {},
+        }
+    }
+}
+
+impl<C, P: FnMut(usize, usize)> Search<C, P> {
+    /// Sets the maximum number of chunks which may be in flight at once.
+    ///
+    /// Defaults to 4.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `concurrency` is zero.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        assert!(concurrency > 0, "search concurrency must be non-zero");
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Calls `f` with the number of request IDs searched so far and the total number of request
+    /// IDs after every chunk completes, e.g. to report the progress of an audit backfill.
+    pub fn on_progress<F: FnMut(usize, usize)>(self, f: F) -> Search<C, F> {
+        Search {
+            http_client: self.http_client,
+            key_pair: self.key_pair,
+            request_ids: self.request_ids,
+            concurrency: self.concurrency,
+            on_progress: f,
+        }
+    }
+}
+
+impl<C: HttpTransport + 'static, P: FnMut(usize, usize)> Search<C, P> {
+    /// Sends the search, returning one entry per request ID in the order they were given.
+    ///
+    /// Entries which the Verify API has no record of are returned as `None`. If any chunk fails,
+    /// the remaining chunks are abandoned and the error is returned.
+    pub async fn send(self) -> Result<Vec<Option<VerifyInfo>>> {
+        let Search {
+            http_client,
+            key_pair,
+            request_ids,
+            concurrency,
+            mut on_progress,
+        } = self;
+
+        let total = request_ids.len();
+        let mut results = Vec::with_capacity(total);
+        let mut chunks = futures::stream::iter(request_ids.chunks(MAX_SEARCH_IDS))
+            .map(|chunk| search_chunk(&http_client, &key_pair, chunk))
+            .buffered(concurrency);
+
+        while let Some(chunk) = chunks.try_next().await? {
+            results.extend(chunk);
+            on_progress(results.len(), total);
+        }
+
+        Ok(results)
+    }
+}
+
+impl<C, P> Debug for Search<C, P> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Search))
+            .field("http_client", &self.http_client)
+            .field("key_pair", &self.key_pair)
+            .field("request_ids", &self.request_ids)
+            .field("concurrency", &self.concurrency)
+            .finish()
+    }
+}
+
+async fn search_chunk<C: HttpTransport + 'static>(
+    http_client: &HttpClient<C>,
+    (api_key, api_secret): &(ApiKey, ApiSecret),
+    request_ids: &[RequestId],
+) -> Result<Vec<Option<VerifyInfo>>> {
     #[derive(Serialize)]
-    struct RequestBody<'a> {
-        api_key: &'a ApiKey,
-        api_secret: &'a ApiSecret,
-        request_ids: Vec<&'a RequestId>,
+    #[serde(untagged)]
+    enum Param<'a> {
+        Key(&'a ApiKey),
+        Secret(&'a ApiSecret),
+        Id(&'a RequestId),
     }
 
     #[derive(Deserialize)]
-    enum ErrorCode {
+    enum NotFoundCode {
         #[serde(rename = "101")]
-        DoesNotExist,
+        NotFound,
     }
 
     #[allow(dead_code)]
     #[derive(Deserialize)]
     #[serde(untagged)]
     enum Response {
-        Success(VerifyInfo),
-        Error { status: ErrorCode },
+        Many {
+            verification_requests: Vec<VerifyInfo>,
+        },
+        One(Box<VerifyInfo>),
+        NotFound {
+            status: NotFoundCode,
+        },
+        Error {
+            status: ErrorCode,
+            error_text: String,
+        },
     }
 
-    let queries: Vec<_> = iter
-        .into_iter()
-        .map(|v| (&v.http_client, &v.api_key, &v.api_secret, &v.request_id))
-        .collect();
+    let mut params = Vec::with_capacity(request_ids.len() + 2);
+    params.push(("api_key", Param::Key(api_key)));
+    params.push(("api_secret", Param::Secret(api_secret)));
+    params.extend(request_ids.iter().map(|id| ("request_ids", Param::Id(id))));
 
-    if queries.is_empty() {
-        Ok(Vec::new())
-    } else {
-        let (client, api_key, api_secret, _) = queries[0];
-        let mut http_client = client.clone();
-        let request = crate::encode_request_get(
-            "/verify/search",
-            RequestBody {
-                api_key,
-                api_secret,
-                request_ids: queries.into_iter().map(|(_, _, _, id)| id).collect(),
-            },
-        )?;
-
-        let response = http_client.call(request).await?;
-        match response.status() {
-            StatusCode::OK => {}
-            other => return Err(other.into()),
-        }
+    let request = crate::encode_request_get(&["verify", "search"], params)?;
+    let response: Response = http_client
+        .send(Product::Verify, request, |response| {
+            match response.status() {
+                StatusCode::OK => {}
+                _ => return Err(Error::new_status(&response)),
+            }
 
-        let bytes = hyper::body::to_bytes(response.into_body()).await?;
-        let list: Vec<Response> = serde_json::from_slice(&bytes).map_err(Error::new_verify)?;
-        let results = list
+            crate::http_client::decode_json(response.body())
+        })
+        .await?;
+
+    let found: HashMap<_, _> = match response {
+        Response::Many {
+            verification_requests,
+        } => verification_requests
             .into_iter()
-            .map(|res| match res {
-                Response::Success(info) => Some(info),
-                Response::Error { .. } => None,
-            })
-            .collect();
+            .map(|info| (info.request_id.clone(), info))
+            .collect(),
+        Response::One(info) => Some((info.request_id.clone(), *info)).into_iter().collect(),
+        Response::NotFound { .. } => HashMap::new(),
+        Response::Error { status, error_text } => {
+            return Err(VerifyError::new(status, error_text).into())
+        }
+    };
 
-        Ok(results)
-    }
+    let results = request_ids
+        .iter()
+        .map(|id| found.get(id).cloned())
+        .collect();
+
+    Ok(results)
 }
 
 /// A search result from a call to [`verify::search()`](./fn.search.html).
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct VerifyInfo {
     pub request_id: RequestId,
     pub account_id: String,
     pub status: VerifyStatus,
+    #[serde(with = "crate::phone::e164")]
     pub number: PhoneNumber,
-    pub price: String,
+    pub price: Price,
     pub currency: String,
     pub sender_id: String,
-    #[serde(deserialize_with = "deserialize_date")]
-    pub date_submitted: NaiveDateTime,
-    #[serde(deserialize_with = "deserialize_date")]
-    pub date_finalized: NaiveDateTime,
-    #[serde(deserialize_with = "deserialize_date")]
-    pub first_event_date: NaiveDateTime,
-    #[serde(deserialize_with = "deserialize_date")]
-    pub last_event_date: NaiveDateTime,
+    #[serde(with = "datetime")]
+    pub date_submitted: Timestamp,
+    #[serde(with = "datetime")]
+    pub date_finalized: Timestamp,
+    #[serde(with = "datetime")]
+    pub first_event_date: Timestamp,
+    #[serde(with = "datetime")]
+    pub last_event_date: Timestamp,
     pub checks: Vec<Check>,
     pub events: Vec<(EventType, String)>,
-    pub estimated_price_messages_sent: Option<String>,
+    pub estimated_price_messages_sent: Option<Price>,
+}
+
+impl VerifyInfo {
+    /// Returns the total cost of the verify request in the currency `to`, including the estimated
+    /// price of the messages sent.
+    ///
+    /// Returns `None` if `converter` does not support the currencies involved, or if a price could
+    /// not be parsed.
+    pub fn cost_in(&self, to: &str, converter: &dyn CurrencyConverter) -> Option<Amount> {
+        let price = (&self.price, self.currency.as_str());
+        let estimated = self.estimated_price_messages_sent.as_ref();
+        super::total_cost(price, estimated, to, converter)
+    }
 }
 
 /// The current status of a particular verify request.
-#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
 pub enum VerifyStatus {
     #[serde(rename = "IN PROGRESS")]
     InProgress,
@@ -115,43 +269,154 @@ pub enum VerifyStatus {
     Expired,
     #[serde(rename = "CANCELLED")]
     Cancelled,
+    /// A status which is not known to this version of the library.
+    #[serde(other)]
+    Unknown,
 }
 
 /// Details of an attempted PIN code check.
-#[derive(Clone, Debug, Deserialize)]
+///
+/// The submitted `code` is masked in the `Debug` output, so that it does not leak into logs.
+#[derive(Clone, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Check {
-    #[serde(deserialize_with = "deserialize_date")]
-    pub date_received: NaiveDateTime,
+    #[serde(with = "datetime")]
+    pub date_received: Timestamp,
     pub code: String,
     pub status: CheckStatus,
     pub ip_address: Option<std::net::IpAddr>,
 }
 
+impl Debug for Check {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Check))
+            .field("date_received", &self.date_received)
+            .field("code", &"<code>")
+            .field("status", &self.status)
+            .field("ip_address", &self.ip_address)
+            .finish()
+    }
+}
+
 /// A list of possible PIN code check outcomes.
-#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[non_exhaustive]
 pub enum CheckStatus {
     /// The user-provided code matched the expected value.
     Valid,
     /// The user-provided code did not match the expected value.
     Invalid,
+    /// An outcome which is not known to this version of the library.
+    #[serde(other)]
+    Unknown,
 }
 
 /// A list of possible events that can occur in a verify request.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
+#[non_exhaustive]
 pub enum EventType {
     /// The PIN was sent by SMS.
     Sms,
     /// The PIN was spoken by voice call using TTS (text-to-speech).
     Tts,
+    /// An event which is not known to this version of the library.
+    #[serde(other)]
+    Unknown,
 }
 
-fn deserialize_date<'de, D>(deserializer: D) -> std::result::Result<NaiveDateTime, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s = Cow::<'de, str>::deserialize(deserializer)?;
-    NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S").map_err(|e| de::Error::custom(e))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+    use std::sync::{Arc, Mutex};
+
+    use bytes::Bytes;
+    use http::{Request, Response};
+    use http_body_util::Full;
+    use tower::service_fn;
+
+    use crate::Client;
+
+    fn verify_info(request_id: &str) -> serde_json::Value {
+        serde_json::json!({
+            "request_id": request_id,
+            "account_id": "abcdef01",
+            "status": "SUCCESS",
+            "number": "14155550100",
+            "price": "0.10000000",
+            "currency": "EUR",
+            "sender_id": "verify",
+            "date_submitted": "2020-01-01 12:00:00",
+            "date_finalized": "2020-01-01 12:00:30",
+            "first_event_date": "2020-01-01 12:00:00",
+            "last_event_date": "2020-01-01 12:00:20",
+            "checks": [],
+            "events": [],
+            "estimated_price_messages_sent": "0.03330000"
+        })
+    }
+
+    #[tokio::test]
+    async fn searches_in_ordered_chunks() {
+        let chunk_sizes = Arc::new(Mutex::new(Vec::new()));
+        let sizes = chunk_sizes.clone();
+        let service = service_fn(move |request: Request<Full<Bytes>>| {
+            let sizes = sizes.clone();
+            async move {
+                assert_eq!(request.uri().path(), "/verify/search/json");
+                let query = request.uri().query().unwrap();
+                let ids: Vec<_> = form_urlencoded::parse(query.as_bytes())
+                    .filter(|(key, _)| key == "request_ids")
+                    .map(|(_, id)| id.into_owned())
+                    .collect();
+                sizes.lock().unwrap().push(ids.len());
+
+                // Reply out of order and omit every third ID to exercise the reordering.
+                let found: Vec<_> = ids
+                    .iter()
+                    .rev()
+                    .filter(|id| u32::from_str_radix(id, 16).unwrap() % 3 != 0)
+                    .map(|id| verify_info(id))
+                    .collect();
+                let body = serde_json::json!({ "verification_requests": found });
+                Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(body.to_string()))))
+            }
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let ids: Vec<RequestId> = (0..23)
+            .map(|i| format!("{:032x}", i).parse().unwrap())
+            .collect();
+
+        let mut progress = Vec::new();
+        let results = client
+            .verify()
+            .search(ids.clone())
+            .concurrency(2)
+            .on_progress(|done, total| progress.push((done, total)))
+            .send()
+            .await
+            .unwrap();
+
+        let mut sizes = chunk_sizes.lock().unwrap().clone();
+        sizes.sort_unstable();
+        assert_eq!(sizes, [3, 10, 10]);
+        assert_eq!(progress, [(10, 23), (20, 23), (23, 23)]);
+
+        assert_eq!(results.len(), ids.len());
+        for (i, (id, result)) in ids.iter().zip(&results).enumerate() {
+            match result {
+                Some(info) => assert_eq!(&info.request_id, id),
+                None => assert_eq!(i % 3, 0),
+            }
+        }
+        assert_eq!(results.iter().filter(|r| r.is_none()).count(), 8);
+    }
 }
@@ -0,0 +1,320 @@
+//! Contains a state machine which tracks a verify request through webhooks and check results.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::Stream;
+
+use super::{Code, ErrorCode, RequestId, Result};
+use crate::rt::SystemTime;
+use crate::webhooks::VerifyEvent;
+
+/// The state of a verify request, as tracked by a [`StateMachine`](./struct.StateMachine.html).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum VerifyState {
+    /// The request was accepted, and the code is being sent.
+    Sent,
+    /// The code was delivered to the user.
+    Delivered,
+    /// The user entered a code which did not match, `attempts` times in total.
+    Checked {
+        /// The number of mismatched codes entered so far.
+        attempts: u32,
+    },
+    /// The user entered the correct code.
+    Completed,
+    /// The code expired before the user entered the correct one.
+    Expired,
+    /// The request was cancelled.
+    Cancelled,
+    /// The request failed, e.g. because the wrong code was entered too many times.
+    Failed,
+}
+
+impl VerifyState {
+    /// Returns whether no further transitions are possible from this state.
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            VerifyState::Completed
+                | VerifyState::Expired
+                | VerifyState::Cancelled
+                | VerifyState::Failed
+        )
+    }
+
+    /// Returns the position of this state in the lifecycle, which only ever moves forward.
+    fn rank(self) -> (u8, u32) {
+        match self {
+            VerifyState::Sent => (0, 0),
+            VerifyState::Delivered => (1, 0),
+            VerifyState::Checked { attempts } => (2, attempts),
+            _ => (3, 0),
+        }
+    }
+}
+
+/// What caused a [`Transition`](./struct.Transition.html).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Trigger {
+    /// A verify webhook was received with the given `status`.
+    Webhook {
+        /// The status reported by the webhook, e.g. `delivered`.
+        status: String,
+    },
+    /// A checked code matched.
+    CodeMatched,
+    /// A checked code did not match.
+    CodeMismatched,
+    /// Checking a code failed with the given status code.
+    CheckFailed(ErrorCode),
+    /// The request was cancelled successfully.
+    Cancelled,
+}
+
+/// A change in the state of a verify request.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Transition {
+    /// The state before the transition.
+    pub from: VerifyState,
+    /// The state after the transition.
+    pub to: VerifyState,
+    /// What caused the transition.
+    pub trigger: Trigger,
+    /// When the transition was applied.
+    pub at: SystemTime,
+}
+
+/// Tracks the state of a verify request from the webhooks and check results it produces.
+///
+/// Each input is applied with [`apply_event()`](#method.apply_event),
+/// [`apply_check()`](#method.apply_check) or [`apply_cancel()`](#method.apply_cancel). States
+/// only ever move forward through `Sent`, `Delivered` and `Checked`, until they reach a terminal
+/// state, so webhooks which arrive late, twice or out of order are ignored. Every transition is
+/// recorded in the [`history()`](#method.history) and sent to all streams returned by
+/// [`transitions()`](#method.transitions), e.g. to be written to an audit log.
+///
+/// # Example
+///
+/// ```
+/// use vonage::verify::{StateMachine, VerifyState};
+/// use vonage::webhooks::{self, Event};
+///
+/// let mut machine = StateMachine::new("abcdef0123456789abcdef0123456789".parse().unwrap());
+///
+/// let body = br#"{"request_id":"abcdef0123456789abcdef0123456789","status":"delivered"}"#;
+/// if let Event::Verify(event) = webhooks::parse("application/json", body).unwrap() {
+///     machine.apply_event(&event);
+/// }
+///
+/// assert_eq!(machine.state(), VerifyState::Delivered);
+/// ```
+#[derive(Debug)]
+pub struct StateMachine {
+    request_id: RequestId,
+    state: VerifyState,
+    history: Vec<Transition>,
+    subscribers: Vec<UnboundedSender<Transition>>,
+}
+
+impl StateMachine {
+    /// Creates a state machine for the request `request_id`, starting in the `Sent` state.
+    pub fn new(request_id: RequestId) -> Self {
+        StateMachine {
+            request_id,
+            state: VerifyState::Sent,
+            history: Vec::new(),
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Returns the ID of the tracked request.
+    #[inline]
+    pub fn request_id(&self) -> &RequestId {
+        &self.request_id
+    }
+
+    /// Returns the current state.
+    #[inline]
+    pub fn state(&self) -> VerifyState {
+        self.state
+    }
+
+    /// Returns every transition applied so far, oldest first.
+    #[inline]
+    pub fn history(&self) -> &[Transition] {
+        &self.history
+    }
+
+    /// Returns a stream of the transitions applied from now on.
+    ///
+    /// The stream ends once a terminal state is reached, or the state machine is dropped.
+    pub fn transitions(&mut self) -> Transitions {
+        let (sender, receiver) = mpsc::unbounded();
+        if !self.state.is_terminal() {
+            self.subscribers.push(sender);
+        }
+        Transitions { receiver }
+    }
+
+    /// Applies a verify webhook, returning the resulting transition, if any.
+    ///
+    /// Webhooks for other requests, and with statuses which are not recognized, are ignored.
+    pub fn apply_event(&mut self, event: &VerifyEvent) -> Option<&Transition> {
+        if event.request_id != self.request_id.as_ref() {
+            return None;
+        }
+
+        let to = match event.status.to_ascii_lowercase().as_str() {
+            "delivered" => VerifyState::Delivered,
+            "completed" | "success" => VerifyState::Completed,
+            "expired" => VerifyState::Expired,
+            "cancelled" | "canceled" => VerifyState::Cancelled,
+            "failed" | "rejected" | "user_rejected" => VerifyState::Failed,
+            _ => return None,
+        };
+
+        let status = event.status.clone();
+        self.transition(to, Trigger::Webhook { status })
+    }
+
+    /// Applies the result of [`PendingVerify::check()`], returning the resulting transition, if
+    /// any.
+    ///
+    /// Errors which do not reflect on the state of the request, such as network failures, are
+    /// ignored.
+    ///
+    /// [`PendingVerify::check()`]: ./struct.PendingVerify.html#method.check
+    pub fn apply_check<C>(&mut self, result: &Result<Code<C>>) -> Option<&Transition> {
+        match result {
+            Ok(Code::Match(verified)) if verified.request_id != self.request_id => None,
+            Ok(Code::Match(_)) => self.transition(VerifyState::Completed, Trigger::CodeMatched),
+            Ok(Code::Mismatch(pending)) if pending.request_id != self.request_id => None,
+            Ok(Code::Mismatch(_)) => {
+                let attempts = match self.state {
+                    VerifyState::Checked { attempts } => attempts + 1,
+                    _ => 1,
+                };
+                let to = VerifyState::Checked { attempts };
+                self.transition(to, Trigger::CodeMismatched)
+            }
+            Err(e) => {
+                let status = e.as_verify()?.status();
+                let to = match status {
+                    ErrorCode::CodeMismatch | ErrorCode::TooManyAttempts => VerifyState::Failed,
+                    ErrorCode::RouteError => VerifyState::Expired,
+                    _ => return None,
+                };
+                self.transition(to, Trigger::CheckFailed(status))
+            }
+        }
+    }
+
+    /// Applies the result of [`PendingVerify::cancel()`], returning the resulting transition, if
+    /// any.
+    ///
+    /// [`PendingVerify::cancel()`]: ./struct.PendingVerify.html#method.cancel
+    pub fn apply_cancel(&mut self, result: &Result<()>) -> Option<&Transition> {
+        match result {
+            Ok(()) => self.transition(VerifyState::Cancelled, Trigger::Cancelled),
+            Err(_) => None,
+        }
+    }
+
+    fn transition(&mut self, to: VerifyState, trigger: Trigger) -> Option<&Transition> {
+        if self.state.is_terminal() || to.rank() <= self.state.rank() {
+            return None;
+        }
+
+        let transition = Transition {
+            from: self.state,
+            to,
+            trigger,
+            at: SystemTime::now(),
+        };
+
+        self.state = to;
+        self.subscribers
+            .retain(|sender| sender.unbounded_send(transition.clone()).is_ok());
+        if to.is_terminal() {
+            self.subscribers.clear();
+        }
+
+        self.history.push(transition);
+        self.history.last()
+    }
+}
+
+/// A stream of the transitions of a [`StateMachine`](./struct.StateMachine.html).
+///
+/// This is returned from
+/// [`StateMachine::transitions()`](./struct.StateMachine.html#method.transitions).
+#[derive(Debug)]
+pub struct Transitions {
+    receiver: UnboundedReceiver<Transition>,
+}
+
+impl Stream for Transitions {
+    type Item = Transition;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures::StreamExt;
+    use http::Response;
+
+    use super::*;
+    use crate::DefaultTransport;
+
+    const REQUEST_ID: &str = "abcdef0123456789abcdef0123456789";
+
+    fn event(request_id: &str, status: &str) -> VerifyEvent {
+        let body = format!(r#"{{"request_id":"{}","status":"{}"}}"#, request_id, status);
+        serde_json::from_str(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn tracks_transitions_in_order() {
+        let mut machine = StateMachine::new(REQUEST_ID.parse().unwrap());
+        let transitions = machine.transitions();
+
+        assert!(machine.apply_event(&event("other", "delivered")).is_none());
+        assert!(machine
+            .apply_event(&event(REQUEST_ID, "delivered"))
+            .is_some());
+        assert!(machine
+            .apply_event(&event(REQUEST_ID, "delivered"))
+            .is_none());
+
+        let body = r#"{"status":"16","error_text":"The code provided does not match"}"#;
+        let error = super::super::decode_response::<()>(Response::new(Bytes::from(body)));
+        let result: Result<Code<DefaultTransport>> = Err(error.unwrap_err());
+        let transition = machine.apply_check(&result).unwrap();
+        assert_eq!(
+            transition.trigger,
+            Trigger::CheckFailed(ErrorCode::CodeMismatch)
+        );
+        assert_eq!(machine.state(), VerifyState::Failed);
+
+        assert!(machine
+            .apply_event(&event(REQUEST_ID, "completed"))
+            .is_none());
+        assert!(machine.apply_cancel(&Ok(())).is_none());
+
+        let states: Vec<_> = transitions.map(|t| (t.from, t.to)).collect().await;
+        let expected = [
+            (VerifyState::Sent, VerifyState::Delivered),
+            (VerifyState::Delivered, VerifyState::Failed),
+        ];
+        assert_eq!(states, expected);
+        assert_eq!(machine.history().len(), 2);
+    }
+}
@@ -0,0 +1,700 @@
+//! Interface to the [Verify v2](https://developer.vonage.com/en/api/verify.v2) API.
+//!
+//! Unlike the original Verify API, Verify v2 sends codes over a workflow of up to three
+//! [`Channel`]s, including WhatsApp, email and silent authentication, and accepts either a JWT
+//! or the API key and API secret pair. Requests are started with [`Client::verify_v2()`].
+//!
+//! [`Channel`]: ./enum.Channel.html
+//! [`Client::verify_v2()`]: ../../struct.Client.html#method.verify_v2
+//!
+//! # Example
+//!
+//! ```no_run
+//! use vonage::verify::v2::Channel;
+//! use vonage::Client;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::builder().jwt("<app_id>", "<private_key>").build()?;
+//! let phone = "+14155550100".parse()?;
+//! let pending = client
+//!     .verify_v2()
+//!     .request("vonage-rs")
+//!     .workflow(Channel::whatsapp(&phone, &"+12025550123".parse()?))
+//!     .workflow(Channel::sms(&phone))
+//!     .send()
+//!     .await?;
+//!
+//! if pending.check("123456").await? {
+//!     println!("verified {}", pending.request_id());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::header::{ACCEPT, CONTENT_TYPE};
+use http::{Method, Request, StatusCode};
+use phonenumber::PhoneNumber;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::auth::Auth;
+use crate::http_client::HttpClient;
+use crate::transport::HttpTransport;
+use crate::validation::Validator;
+use crate::{DefaultTransport, Error, ErrorKind, Product, RequestPolicy, Result};
+
+/// The maximum number of channels in a workflow.
+pub const MAX_WORKFLOW_LEN: usize = 3;
+
+/// The unique identifier of a Verify v2 request, which is a UUID.
+///
+/// Request IDs can be stored as strings and restored later with `str::parse()`, e.g. to check a
+/// code which was requested by an earlier process. Parsing and deserializing are the only ways to
+/// construct one, so that every request ID is safe to place in a request path.
+///
+/// # Example
+///
+/// ```
+/// use vonage::verify::v2::RequestId;
+///
+/// let id: RequestId = "c11236f4-00bf-4b89-84ba-88b25df97315".parse().unwrap();
+/// assert_eq!(id.as_ref(), "c11236f4-00bf-4b89-84ba-88b25df97315");
+///
+/// assert!("abcdef0123456789abcdef0123456789".parse::<RequestId>().is_err());
+/// assert!(serde_json::from_str::<RequestId>(r#""../next_workflow""#).is_err());
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize)]
+pub struct RequestId(String);
+
+impl AsRef<str> for RequestId {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for RequestId {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl<'de> Deserialize<'de> for RequestId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        id.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for RequestId {
+    type Err = Error;
+
+    /// Parses a request ID, which must be a UUID in its hyphenated form, e.g.
+    /// `c11236f4-00bf-4b89-84ba-88b25df97315`.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequestId`] if `s` is not a valid request ID.
+    ///
+    /// [`ErrorKind::InvalidRequestId`]: ../../enum.ErrorKind.html#variant.InvalidRequestId
+    fn from_str(s: &str) -> Result<Self> {
+        let is_valid = s.len() == 36
+            && s.char_indices().all(|(i, c)| match i {
+                8 | 13 | 18 | 23 => c == '-',
+                _ => c.is_ascii_hexdigit(),
+            });
+
+        if is_valid {
+            Ok(RequestId(s.to_owned()))
+        } else {
+            let message = format!("`{}` is not a valid request ID", s);
+            Err(Error::with_cause(
+                ErrorKind::InvalidRequestId,
+                anyhow::Error::msg(message),
+            ))
+        }
+    }
+}
+
+/// A channel over which a code or silent authentication check is sent.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "channel", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Channel {
+    /// A text message with the code.
+    Sms {
+        /// The phone number, in E.164 format without the leading `+`.
+        to: String,
+        /// The hash of an Android app, which lets it read the code automatically.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        app_hash: Option<String>,
+    },
+    /// A WhatsApp message with the code.
+    #[serde(rename = "whatsapp")]
+    WhatsApp {
+        /// The phone number, in E.164 format without the leading `+`.
+        to: String,
+        /// The WhatsApp Business number sending the message, in E.164 format without the leading
+        /// `+`.
+        from: String,
+    },
+    /// A WhatsApp message with a button, which the user taps instead of entering a code.
+    #[serde(rename = "whatsapp_interactive")]
+    WhatsAppInteractive {
+        /// The phone number, in E.164 format without the leading `+`.
+        to: String,
+    },
+    /// A text-to-speech call reading out the code.
+    Voice {
+        /// The phone number, in E.164 format without the leading `+`.
+        to: String,
+    },
+    /// An email with the code.
+    Email {
+        /// The email address.
+        to: String,
+        /// The address sending the email, if not the default of Vonage.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        from: Option<String>,
+    },
+    /// A check with the mobile network that the user's device holds the SIM card of the number,
+    /// completed by the device opening the [`check_url()`].
+    ///
+    /// [`check_url()`]: ./struct.PendingVerify.html#method.check_url
+    SilentAuth {
+        /// The phone number, in E.164 format without the leading `+`.
+        to: String,
+    },
+}
+
+impl Channel {
+    /// Returns a channel which sends the code in a text message to `to`.
+    pub fn sms(to: &PhoneNumber) -> Self {
+        Channel::Sms {
+            to: e164_digits(to),
+            app_hash: None,
+        }
+    }
+
+    /// Returns a channel which sends the code in a WhatsApp message from the WhatsApp Business
+    /// number `from` to `to`.
+    pub fn whatsapp(to: &PhoneNumber, from: &PhoneNumber) -> Self {
+        Channel::WhatsApp {
+            to: e164_digits(to),
+            from: e164_digits(from),
+        }
+    }
+
+    /// Returns a channel which sends an interactive WhatsApp message to `to`.
+    pub fn whatsapp_interactive(to: &PhoneNumber) -> Self {
+        Channel::WhatsAppInteractive {
+            to: e164_digits(to),
+        }
+    }
+
+    /// Returns a channel which reads out the code in a call to `to`.
+    pub fn voice(to: &PhoneNumber) -> Self {
+        Channel::Voice {
+            to: e164_digits(to),
+        }
+    }
+
+    /// Returns a channel which sends the code in an email to `to`.
+    pub fn email(to: impl Into<String>) -> Self {
+        Channel::Email {
+            to: to.into(),
+            from: None,
+        }
+    }
+
+    /// Returns a channel which authenticates the device holding the SIM card of `to` through the
+    /// mobile network.
+    ///
+    /// This must be the first channel of a workflow.
+    pub fn silent_auth(to: &PhoneNumber) -> Self {
+        Channel::SilentAuth {
+            to: e164_digits(to),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Channel::Sms { .. } => "sms",
+            Channel::WhatsApp { .. } => "whatsapp",
+            Channel::WhatsAppInteractive { .. } => "whatsapp_interactive",
+            Channel::Voice { .. } => "voice",
+            Channel::Email { .. } => "email",
+            Channel::SilentAuth { .. } => "silent_auth",
+        }
+    }
+}
+
+fn e164_digits(number: &PhoneNumber) -> String {
+    number.to_string().trim_start_matches('+').to_owned()
+}
+
+/// A handle to the Verify v2 API.
+///
+/// This is returned from [`Client::verify_v2()`](../../struct.Client.html#method.verify_v2). It
+/// holds the credentials of the client, and is cheap to clone.
+pub struct VerifyApi<C = DefaultTransport> {
+    http_client: HttpClient<C>,
+    auth: Auth,
+}
+
+impl<C> VerifyApi<C> {
+    pub(crate) fn new(http_client: HttpClient<C>, auth: Auth) -> Self {
+        VerifyApi { http_client, auth }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`RequestPolicy`](../../struct.RequestPolicy.html) for details.
+    pub fn policy(mut self, policy: RequestPolicy) -> Self {
+        self.http_client = self.http_client.with_policy(policy);
+        self
+    }
+
+    /// Starts building a verify request on behalf of `brand`, which is included in the messages
+    /// sent to the user.
+    ///
+    /// At least one channel must be added with [`Verify::workflow()`] before it is sent.
+    ///
+    /// [`Verify::workflow()`]: ./struct.Verify.html#method.workflow
+    pub fn request(&self, brand: impl Into<String>) -> Verify<C> {
+        Verify {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+            body: RequestBody {
+                brand: brand.into(),
+                workflow: Vec::new(),
+                locale: None,
+                channel_timeout: None,
+                client_ref: None,
+                code_length: None,
+            },
+        }
+    }
+
+    /// Returns a handle to the existing request `request_id`, e.g. to check a code submitted in a
+    /// later HTTP request than the one which started it.
+    pub fn pending(&self, request_id: RequestId) -> PendingVerify<C> {
+        PendingVerify {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+            request_id,
+            check_url: None,
+        }
+    }
+}
+
+impl<C> Clone for VerifyApi<C> {
+    fn clone(&self) -> Self {
+        VerifyApi {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+        }
+    }
+}
+
+impl<C> Debug for VerifyApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(VerifyApi))
+            .field("http_client", &self.http_client)
+            .field("auth", &self.auth)
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct RequestBody {
+    brand: String,
+    workflow: Vec<Channel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locale: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channel_timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code_length: Option<u64>,
+}
+
+/// A builder to configure a new Verify v2 request.
+///
+/// This is returned from [`VerifyApi::request()`](./struct.VerifyApi.html#method.request).
+pub struct Verify<C> {
+    http_client: HttpClient<C>,
+    auth: Auth,
+    body: RequestBody,
+}
+
+impl<C> Verify<C> {
+    /// Appends `channel` to the workflow.
+    ///
+    /// Channels are tried in order, moving to the next one once the
+    /// [timeout](#method.channel_timeout) of the current one elapses without a correct code.
+    pub fn workflow(mut self, channel: Channel) -> Self {
+        self.body.workflow.push(channel);
+        self
+    }
+
+    /// Sets the locale of the messages sent to the user, e.g. `en-us`.
+    ///
+    /// By default, the locale is derived from the phone number.
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.body.locale = Some(locale.into());
+        self
+    }
+
+    /// Sets how long to wait for a correct code before moving to the next channel, from 60 to
+    /// 900 seconds. Defaults to 300 seconds.
+    pub fn channel_timeout(mut self, timeout: Duration) -> Self {
+        self.body.channel_timeout = Some(timeout.as_secs());
+        self
+    }
+
+    /// Sets a reference of up to 16 characters, which is included in webhooks about the request.
+    pub fn client_ref(mut self, client_ref: impl Into<String>) -> Self {
+        self.body.client_ref = Some(client_ref.into());
+        self
+    }
+
+    /// Sets the number of digits of the generated code, from 4 to 10. Defaults to 4.
+    pub fn code_length(mut self, len: u8) -> Self {
+        self.body.code_length = Some(len.into());
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        let workflow = &self.body.workflow;
+        let silent_auth_index = workflow
+            .iter()
+            .position(|channel| matches!(channel, Channel::SilentAuth { .. }));
+
+        let mut validator = Validator::new();
+        validator
+            .check(!self.body.brand.is_empty(), "brand", "must not be empty")
+            .max_len(Some(&self.body.brand), "brand", 16)
+            .check(
+                (1..=MAX_WORKFLOW_LEN).contains(&workflow.len()),
+                "workflow",
+                "must contain from 1 to 3 channels",
+            )
+            .check(
+                silent_auth_index.is_none_or(|index| index == 0),
+                "workflow",
+                "silent_auth must be the first channel",
+            )
+            .check(
+                workflow
+                    .iter()
+                    .enumerate()
+                    .all(|(i, channel)| workflow[..i].iter().all(|c| c.name() != channel.name())),
+                "workflow",
+                "must not contain a channel more than once",
+            )
+            .range(self.body.channel_timeout, "channel_timeout", 60, 900)
+            .max_len(self.body.client_ref.as_deref(), "client_ref", 16)
+            .range(self.body.code_length, "code_length", 4, 10);
+        validator.finish()
+    }
+}
+
+impl<C: HttpTransport> Verify<C> {
+    /// Sends the request, returning a handle to check the code the user enters.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequest`] without sending anything if the
+    /// brand is empty or too long, the workflow is empty or has more than three channels, or any
+    /// other parameter is out of range.
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../../enum.ErrorKind.html#variant.InvalidRequest
+    pub async fn send(self) -> Result<PendingVerify<C>> {
+        #[derive(Deserialize)]
+        struct ResponseBody {
+            request_id: RequestId,
+            check_url: Option<String>,
+        }
+
+        self.validate()?;
+
+        let body = serde_json::to_vec(&self.body).expect("request must be serializable to JSON");
+        let request = json_request(&self.auth, Method::POST, "v2/verify", body.into())?;
+        let started: ResponseBody = self
+            .http_client
+            .send(Product::Verify, request, |response| {
+                match response.status() {
+                    StatusCode::OK | StatusCode::ACCEPTED => {}
+                    _ => return Err(Error::new_status(&response)),
+                }
+
+                crate::http_client::decode_json(response.body())
+            })
+            .await?;
+
+        Ok(PendingVerify {
+            http_client: self.http_client,
+            auth: self.auth,
+            request_id: started.request_id,
+            check_url: started.check_url,
+        })
+    }
+}
+
+impl<C> Debug for Verify<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Verify))
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+/// A handle to a pending Verify v2 request.
+///
+/// This is returned from [`Verify::send()`](./struct.Verify.html#method.send) and
+/// [`VerifyApi::pending()`](./struct.VerifyApi.html#method.pending), and is cheap to clone.
+pub struct PendingVerify<C = DefaultTransport> {
+    http_client: HttpClient<C>,
+    auth: Auth,
+    request_id: RequestId,
+    check_url: Option<String>,
+}
+
+impl<C> PendingVerify<C> {
+    /// Returns the unique request ID.
+    #[inline]
+    pub fn request_id(&self) -> &RequestId {
+        &self.request_id
+    }
+
+    /// Returns the URL which the user's device must open over its mobile data connection to
+    /// complete a [`Channel::SilentAuth`](./enum.Channel.html#variant.SilentAuth) check.
+    ///
+    /// This is only present if the workflow starts with silent authentication.
+    #[inline]
+    pub fn check_url(&self) -> Option<&str> {
+        self.check_url.as_deref()
+    }
+}
+
+impl<C: HttpTransport> PendingVerify<C> {
+    /// Checks whether the code entered by the user is correct.
+    ///
+    /// Returns `Ok(false)` if the code is incorrect, in which case the user may try again.
+    /// Returns an error of kind [`ErrorKind::Status`] with `410 Gone` once the request has
+    /// expired, or too many incorrect codes have been entered.
+    ///
+    /// [`ErrorKind::Status`]: ../../enum.ErrorKind.html#variant.Status
+    pub async fn check(&self, code: &str) -> Result<bool> {
+        #[derive(Serialize)]
+        struct RequestBody<'a> {
+            code: &'a str,
+        }
+
+        let body = serde_json::to_vec(&RequestBody { code })
+            .expect("request must be serializable to JSON");
+        let path = format!("v2/verify/{}", self.request_id);
+        let request = json_request(&self.auth, Method::POST, &path, body.into())?;
+        self.http_client
+            .send(Product::Verify, request, |response| {
+                match response.status() {
+                    StatusCode::OK => Ok(true),
+                    StatusCode::BAD_REQUEST => Ok(false),
+                    _ => Err(Error::new_status(&response)),
+                }
+            })
+            .await
+    }
+
+    /// Cancels the request, unless it has already completed.
+    pub async fn cancel(&self) -> Result<()> {
+        let path = format!("v2/verify/{}", self.request_id);
+        let request = json_request(&self.auth, Method::DELETE, &path, Bytes::new())?;
+        self.http_client
+            .send(Product::Verify, request, |response| {
+                match response.status() {
+                    StatusCode::OK | StatusCode::NO_CONTENT => Ok(()),
+                    _ => Err(Error::new_status(&response)),
+                }
+            })
+            .await
+    }
+
+    /// Moves to the next channel of the workflow without waiting for the current one to time
+    /// out.
+    pub async fn next_workflow(&self) -> Result<()> {
+        let path = format!("v2/verify/{}/next_workflow", self.request_id);
+        let request = json_request(&self.auth, Method::POST, &path, Bytes::new())?;
+        self.http_client
+            .send(Product::Verify, request, |response| {
+                match response.status() {
+                    StatusCode::OK | StatusCode::NO_CONTENT => Ok(()),
+                    _ => Err(Error::new_status(&response)),
+                }
+            })
+            .await
+    }
+}
+
+impl<C> Clone for PendingVerify<C> {
+    fn clone(&self) -> Self {
+        PendingVerify {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+            request_id: self.request_id.clone(),
+            check_url: self.check_url.clone(),
+        }
+    }
+}
+
+impl<C> Debug for PendingVerify<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(PendingVerify))
+            .field("request_id", &self.request_id)
+            .field("check_url", &self.check_url)
+            .finish()
+    }
+}
+
+fn json_request(auth: &Auth, method: Method, path: &str, body: Bytes) -> Result<Request<Bytes>> {
+    let (name, value) = auth.to_preferred_auth_header()?;
+    Request::builder()
+        .method(method)
+        .uri(crate::resolve_url(path))
+        .header(CONTENT_TYPE, "application/json")
+        .header(ACCEPT, "application/json")
+        .header(name, value)
+        .body(body)
+        .map_err(|e| Error::with_cause(ErrorKind::Http, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use http_body_util::{BodyExt, Full};
+    use serde_json::{json, Value};
+    use tower::service_fn;
+
+    use crate::Client;
+
+    #[tokio::test]
+    async fn sends_workflows_and_checks_codes() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert!(request.headers()["authorization"]
+                .to_str()
+                .unwrap()
+                .starts_with("Bearer "));
+
+            let (status, body) = match (request.method(), request.uri().path()) {
+                (&Method::POST, "/v2/verify") => {
+                    let body = request.into_body().collect().await.unwrap().to_bytes();
+                    let body: Value = serde_json::from_slice(&body).unwrap();
+                    assert_eq!(
+                        body,
+                        json!({
+                            "brand": "vonage-rs",
+                            "workflow": [
+                                {"channel": "silent_auth", "to": "14155550100"},
+                                {"channel": "email", "to": "user@example.com"}
+                            ],
+                            "code_length": 6
+                        })
+                    );
+                    let body = r#"{"request_id":"c11236f4-00bf-4b89-84ba-88b25df97315","check_url":"https://api.nexmo.com/v2/verify/c11236f4-00bf-4b89-84ba-88b25df97315/silent-auth/redirect"}"#;
+                    (StatusCode::ACCEPTED, body)
+                }
+                (&Method::POST, "/v2/verify/c11236f4-00bf-4b89-84ba-88b25df97315") => {
+                    let body = request.into_body().collect().await.unwrap().to_bytes();
+                    match &body[..] {
+                        br#"{"code":"123456"}"# => (StatusCode::OK, r#"{"status":"completed"}"#),
+                        _ => (StatusCode::BAD_REQUEST, r#"{"title":"Invalid Code"}"#),
+                    }
+                }
+                (&Method::DELETE, "/v2/verify/c11236f4-00bf-4b89-84ba-88b25df97315") => {
+                    (StatusCode::NO_CONTENT, "")
+                }
+                (method, path) => panic!("unexpected request: {} {}", method, path),
+            };
+
+            let mut response = http::Response::new(Full::new(Bytes::from(body)));
+            *response.status_mut() = status;
+            Ok::<_, Infallible>(response)
+        });
+
+        let client = Client::from_service(service)
+            .jwt("app_id", crate::auth::TEST_PRIVATE_KEY)
+            .build()
+            .unwrap();
+
+        let phone = "+14155550100".parse().unwrap();
+        let pending = client
+            .verify_v2()
+            .request("vonage-rs")
+            .workflow(Channel::silent_auth(&phone))
+            .workflow(Channel::email("user@example.com"))
+            .code_length(6)
+            .send()
+            .await
+            .unwrap();
+        assert!(pending
+            .check_url()
+            .unwrap()
+            .ends_with("/silent-auth/redirect"));
+
+        assert!(!pending.check("000000").await.unwrap());
+
+        let request_id: RequestId = "c11236f4-00bf-4b89-84ba-88b25df97315".parse().unwrap();
+        assert_eq!(*pending.request_id(), request_id);
+        let pending = client.verify_v2().pending(request_id);
+        assert!(pending.check("123456").await.unwrap());
+        pending.cancel().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_workflows() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let sent = Arc::new(AtomicUsize::new(0));
+        let service = service_fn({
+            let sent = sent.clone();
+            move |_: Request<Full<Bytes>>| {
+                sent.fetch_add(1, Ordering::SeqCst);
+                async move { Ok::<_, Infallible>(http::Response::new(Full::new(Bytes::new()))) }
+            }
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let phone = "+14155550100".parse().unwrap();
+        let error = client
+            .verify_v2()
+            .request("vonage-rs")
+            .workflow(Channel::sms(&phone))
+            .workflow(Channel::silent_auth(&phone))
+            .workflow(Channel::sms(&phone))
+            .code_length(12)
+            .send()
+            .await
+            .unwrap_err();
+
+        let names: Vec<_> = error
+            .as_invalid_request()
+            .unwrap()
+            .invalid_parameters()
+            .iter()
+            .map(|param| param.name())
+            .collect();
+        assert_eq!(names, ["workflow", "workflow", "code_length"]);
+        assert_eq!(sent.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,222 @@
+//! Encoding and decoding of Verify API payloads, without sending them.
+//!
+//! These produce and consume the exact requests and responses exchanged with Vonage, which lets
+//! proxies forward verify requests on behalf of other services, and test doubles answer them
+//! without hard-coding JSON. The Verify API wraps every response in an envelope with a `status`
+//! code, which [`encode_response()`] and [`encode_error()`] add and [`decode_response()`] removes.
+//!
+//! [`encode_response()`]: ./fn.encode_response.html
+//! [`encode_error()`]: ./fn.encode_error.html
+//! [`decode_response()`]: ./fn.decode_response.html
+//!
+//! # Example
+//!
+//! ```
+//! use vonage::verify::wire::{self, Started};
+//! use vonage::verify::{ErrorCode, RequestId};
+//!
+//! let request_id: RequestId = "abcdef0123456789abcdef0123456789".parse().unwrap();
+//! let response = wire::encode_response(&Started { request_id });
+//! assert_eq!(
+//!     response.body().as_ref(),
+//!     br#"{"request_id":"abcdef0123456789abcdef0123456789","status":"0"}"#
+//! );
+//!
+//! let response = wire::encode_error(ErrorCode::Concurrent, "Concurrent verifications", None);
+//! let error = wire::decode_response::<Started>(response).unwrap_err();
+//! assert_eq!(error.as_verify().unwrap().status(), ErrorCode::Concurrent);
+//! ```
+
+use bytes::Bytes;
+use http::header::CONTENT_TYPE;
+use http::{Request, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::{ErrorCode, RequestId, Verification, Verify, VerifyError};
+use crate::{Error, Result};
+
+/// The body of a successful response to a verify request.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Started {
+    /// The ID assigned to the new verify request.
+    pub request_id: RequestId,
+}
+
+/// Encodes `verify` into the HTTP request which [`Verify::send()`] would send.
+///
+/// The request is validated first, as by `send()`. Sender IDs are not assigned from the
+/// [`SenderPool`](../../struct.SenderPool.html) of the client, and no
+/// [`Lookup`](../enum.Lookup.html) is run.
+///
+/// [`Verify::send()`]: ../struct.Verify.html#method.send
+pub fn encode_request<C, V: Verification>(verify: &Verify<C, V>) -> Result<Request<Bytes>> {
+    verify.encode()
+}
+
+/// Encodes a successful response of the Verify API with the given `body`, e.g.
+/// [`Started`](./struct.Started.html) or [`Verified`](../struct.Verified.html).
+///
+/// # Panics
+///
+/// Panics if `body` does not serialize to a JSON object.
+pub fn encode_response<T: Serialize>(body: &T) -> Response<Bytes> {
+    #[derive(Serialize)]
+    struct Envelope<'a, T> {
+        #[serde(flatten)]
+        inner: &'a T,
+        status: &'static str,
+    }
+
+    encode_json(&Envelope {
+        inner: body,
+        status: "0",
+    })
+}
+
+/// Encodes an error response of the Verify API.
+///
+/// Note that, like the real Verify API, the HTTP status of the response is still `200 OK`.
+/// [`ErrorCode::Unknown`](../enum.ErrorCode.html#variant.Unknown) has no status code of its own,
+/// and cannot be encoded faithfully.
+pub fn encode_error(
+    status: ErrorCode,
+    error_text: &str,
+    request_id: Option<&RequestId>,
+) -> Response<Bytes> {
+    #[derive(Serialize)]
+    struct Envelope<'a> {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<&'a RequestId>,
+        status: ErrorCode,
+        error_text: &'a str,
+    }
+
+    encode_json(&Envelope {
+        request_id,
+        status,
+        error_text,
+    })
+}
+
+fn encode_json<T: Serialize>(body: &T) -> Response<Bytes> {
+    let body = serde_json::to_vec(body).expect("Verify API responses must serialize to JSON");
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Bytes::from(body))
+        .expect("http::ResponseBuilder cannot fail")
+}
+
+/// Decodes a response of the Verify API, unwrapping its success or error envelope.
+///
+/// Every Verify endpoint responds with `200 OK`, and reports failures with a non-zero `status`
+/// and an `error_text` instead. These are returned as errors which can be inspected with
+/// [`Error::as_verify()`](../../struct.Error.html#method.as_verify). On success, the `status` is
+/// stripped and the remaining fields are decoded as `T`, e.g. [`Started`](./struct.Started.html)
+/// or [`Verified`](../struct.Verified.html).
+pub fn decode_response<T>(response: Response<Bytes>) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    #[derive(Deserialize)]
+    enum SuccessCode {
+        #[serde(rename = "0")]
+        Success,
+    }
+
+    // Unknown fields are tolerated here, e.g. the `command` echoed back by `/verify/control`, and
+    // left to `T` to reject if it wants to.
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ResponseBody<T> {
+        Success {
+            #[allow(dead_code)]
+            status: SuccessCode,
+            #[serde(flatten)]
+            inner: T,
+        },
+        Error {
+            request_id: Option<RequestId>,
+            status: ErrorCode,
+            error_text: String,
+        },
+    }
+
+    match response.status() {
+        StatusCode::OK => {}
+        _ => return Err(Error::new_status(&response)),
+    }
+
+    match crate::http_client::decode_json(response.body())? {
+        ResponseBody::Success { inner, .. } => Ok(inner),
+        ResponseBody::Error {
+            request_id,
+            status,
+            error_text,
+        } => {
+            let error = Error::from(VerifyError::new(status, error_text));
+            match request_id {
+                Some(RequestId(id)) => Err(error.with_request_id(id)),
+                None => Err(error),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::verify::Workflow;
+
+    #[test]
+    fn encodes_requests_without_sending() {
+        let client = crate::Client::builder()
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+        let phone: phonenumber::PhoneNumber = "+14155550100".parse().unwrap();
+        let verify = client
+            .verify()
+            .request(phone, "vonage-rs")
+            .workflow(Workflow::Sms);
+
+        let request = encode_request(&verify).unwrap();
+        assert_eq!(request.uri(), "https://api.nexmo.com/verify/json");
+        assert_eq!(
+            request.body().as_ref(),
+            &b"api_key=key&api_secret=secret&number=%2B14155550100&workflow_id=6&brand=vonage-rs"[..]
+        );
+
+        let verify = verify.pin_expiry(std::time::Duration::from_secs(30));
+        let error = encode_request(&verify).unwrap_err();
+        assert_eq!(
+            error.as_invalid_request().unwrap().invalid_parameters()[0].name(),
+            "pin_expiry"
+        );
+    }
+
+    #[test]
+    fn round_trips_envelopes() {
+        let request_id: RequestId = "abcdef0123456789".parse().unwrap();
+
+        let response = encode_response(&Started {
+            request_id: request_id.clone(),
+        });
+        let started: Started = decode_response(response).unwrap();
+        assert_eq!(started.request_id, request_id);
+
+        let response = encode_error(ErrorCode::CodeMismatch, "wrong code", Some(&request_id));
+        assert_eq!(
+            response.body().as_ref(),
+            &br#"{"request_id":"abcdef0123456789","status":"16","error_text":"wrong code"}"#[..]
+        );
+        let error = decode_response::<Started>(response).unwrap_err();
+        assert!(error.kind().is_code_mismatch());
+        assert_eq!(error.request_id(), Some("abcdef0123456789"));
+
+        let control = Bytes::from_static(br#"{"status":"0","command":"cancel"}"#);
+        decode_response::<()>(Response::new(control)).unwrap();
+    }
+}
@@ -0,0 +1,23 @@
+//! Interface to the [Voice](https://developer.nexmo.com/api/voice) API.
+//!
+//! The Voice API authenticates with a JWT signed by the private key of a Vonage application, so
+//! the client must be configured with [`ClientBuilder::jwt()`](../struct.ClientBuilder.html#method.jwt).
+
+pub use self::api::*;
+pub use self::call::*;
+pub use self::conference::*;
+pub use self::transcript::*;
+pub use self::transfer::*;
+pub use self::voicemail::*;
+
+pub mod ncco;
+pub mod screening;
+pub mod ssml;
+pub mod tts;
+
+mod api;
+mod call;
+mod conference;
+mod transcript;
+mod transfer;
+mod voicemail;
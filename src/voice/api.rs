@@ -0,0 +1,125 @@
+//! Contains the entry point to the Voice API.
+
+use std::fmt::{self, Debug, Formatter};
+
+use phonenumber::PhoneNumber;
+
+use super::{AttendedTransfer, Conference, CreateCall, Endpoint, FetchTranscript, VoicemailDrop};
+use crate::auth::Auth;
+use crate::http_client::HttpClient;
+use crate::{DefaultTransport, RequestPolicy};
+
+/// A handle to the Voice API.
+///
+/// This is returned from [`Client::voice()`](../struct.Client.html#method.voice). It holds the
+/// application credentials of the client, and is cheap to clone.
+pub struct VoiceApi<C = DefaultTransport> {
+    http_client: HttpClient<C>,
+    auth: Auth,
+}
+
+impl<C> VoiceApi<C> {
+    pub(crate) fn new(http_client: HttpClient<C>, auth: Auth) -> Self {
+        VoiceApi { http_client, auth }
+    }
+
+    /// Sends all requests made through this handle, and the handles it returns, with the given
+    /// timeout and retry policy.
+    ///
+    /// See [`RequestPolicy`](../struct.RequestPolicy.html) for details.
+    pub fn policy(mut self, policy: RequestPolicy) -> Self {
+        self.http_client = self.http_client.with_policy(policy);
+        self
+    }
+
+    /// Places an outbound call from the virtual number `from` to `to`.
+    ///
+    /// The call is controlled by the NCCO returned from an answer URL, or by an NCCO given
+    /// inline, one of which must be set before the request is sent.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vonage::phone::{self, Country};
+    /// use vonage::voice::Endpoint;
+    /// use vonage::Client;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::builder().jwt("<app_id>", "<private_key>").build()?;
+    /// let to = phone::parse("(415) 555-0100", Country::US)?;
+    /// let from = phone::parse("(202) 555-0123", Country::US)?;
+    /// let call = client
+    ///     .voice()
+    ///     .create_call(Endpoint::phone(&to), &from)
+    ///     .answer_url("https://example.com/webhooks/answer")
+    ///     .send()
+    ///     .await?;
+    ///
+    /// println!("started call {}", call.uuid());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_call(&self, to: Endpoint, from: &PhoneNumber) -> CreateCall<C> {
+        CreateCall::new(self.http_client.clone(), self.auth.clone(), to, from)
+    }
+
+    /// Returns a handle to the conference called `name`, which is created once the first call
+    /// joins it.
+    ///
+    /// See [`Conference`](./struct.Conference.html) for details.
+    pub fn conference(&self, name: impl Into<String>) -> Conference<C> {
+        Conference::new(self.http_client.clone(), self.auth.clone(), name.into())
+    }
+
+    /// Starts an attended transfer of the caller on the leg `caller_uuid` from the agent on the
+    /// leg `agent_uuid` to a third party.
+    ///
+    /// See [`AttendedTransfer`](./struct.AttendedTransfer.html) for details.
+    pub fn attended_transfer(&self, caller_uuid: &str, agent_uuid: &str) -> AttendedTransfer<C> {
+        AttendedTransfer::new(
+            self.http_client.clone(),
+            self.auth.clone(),
+            caller_uuid.to_owned(),
+            agent_uuid.to_owned(),
+        )
+    }
+
+    /// Retrieves the transcript of a recording.
+    ///
+    /// `url` is the `transcription_url` reported by a
+    /// [`TranscriptionEvent`](../webhooks/struct.TranscriptionEvent.html) once a transcription
+    /// requested with [`Record::transcription()`](./ncco/struct.Record.html#method.transcription)
+    /// has completed. Relative paths are resolved against `https://api.nexmo.com`.
+    pub fn transcript(&self, url: &str) -> FetchTranscript<C> {
+        FetchTranscript::new(self.http_client.clone(), &self.auth, url)
+    }
+
+    /// Leaves the MP3 or WAV file at `audio_url` as a message on answering machines.
+    ///
+    /// See [`VoicemailDrop`](./struct.VoicemailDrop.html) for details.
+    pub fn voicemail_drop(&self, audio_url: impl Into<String>) -> VoicemailDrop<C> {
+        VoicemailDrop::new(
+            self.http_client.clone(),
+            self.auth.clone(),
+            audio_url.into(),
+        )
+    }
+}
+
+impl<C> Clone for VoiceApi<C> {
+    fn clone(&self) -> Self {
+        VoiceApi {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+        }
+    }
+}
+
+impl<C> Debug for VoiceApi<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(VoiceApi))
+            .field("http_client", &self.http_client)
+            .field("auth", &self.auth)
+            .finish()
+    }
+}
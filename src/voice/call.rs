@@ -0,0 +1,620 @@
+//! Creation and control of outbound calls.
+
+use std::fmt::{self, Debug, Formatter};
+
+use bytes::Bytes;
+use http::header::{ACCEPT, CONTENT_TYPE};
+use http::{Method, Request, StatusCode};
+use phonenumber::PhoneNumber;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::auth::Auth;
+use crate::http_client::HttpClient;
+use crate::transport::HttpTransport;
+use crate::validation::{is_http_url, Validator};
+use crate::{DefaultTransport, Error, ErrorKind, Product, Result};
+
+/// One end of a call.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum Endpoint {
+    /// A phone number, in E.164 format without the leading `+`.
+    Phone {
+        /// The phone number.
+        number: String,
+        /// Digits to send once the call is answered, e.g. to dial an extension.
+        #[serde(rename = "dtmfAnswer", skip_serializing_if = "Option::is_none")]
+        dtmf_answer: Option<String>,
+    },
+    /// A SIP URI, e.g. `sip:rebekka@sip.example.com`.
+    Sip {
+        /// The SIP URI.
+        uri: String,
+    },
+    /// A WebSocket, which receives the audio of the call.
+    Websocket {
+        /// The `ws://` or `wss://` URI of the WebSocket.
+        uri: String,
+        /// The audio format, e.g. `audio/l16;rate=16000`.
+        #[serde(rename = "content-type")]
+        content_type: String,
+    },
+    /// A user of a Client SDK application.
+    App {
+        /// The name of the user.
+        user: String,
+    },
+}
+
+impl Endpoint {
+    /// Returns an endpoint which calls the given phone number.
+    pub fn phone(number: &PhoneNumber) -> Self {
+        Endpoint::Phone {
+            number: e164_digits(number),
+            dtmf_answer: None,
+        }
+    }
+
+    /// Returns an endpoint which calls the given SIP URI.
+    pub fn sip(uri: impl Into<String>) -> Self {
+        Endpoint::Sip { uri: uri.into() }
+    }
+
+    /// Returns an endpoint which streams the call to the given WebSocket.
+    pub fn websocket(uri: impl Into<String>, content_type: impl Into<String>) -> Self {
+        Endpoint::Websocket {
+            uri: uri.into(),
+            content_type: content_type.into(),
+        }
+    }
+
+    /// Returns an endpoint which calls the given Client SDK user.
+    pub fn app(user: impl Into<String>) -> Self {
+        Endpoint::App { user: user.into() }
+    }
+}
+
+pub(super) fn e164_digits(number: &PhoneNumber) -> String {
+    number.to_string().trim_start_matches('+').to_owned()
+}
+
+/// How the call is controlled once it is answered.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+enum Answer {
+    #[serde(rename = "answer_url")]
+    Url([String; 1]),
+    #[serde(rename = "ncco")]
+    Ncco(Value),
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct CallBody {
+    to: [Endpoint; 1],
+    from: Endpoint,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    answer: Option<Answer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event_url: Option<[String; 1]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ringing_timer: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    length_timer: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    machine_detection: Option<MachineDetection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    advanced_machine_detection: Option<AdvancedMachineDetection>,
+}
+
+/// What happens to a call once an answering machine is detected.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MachineDetection {
+    /// The call continues, and a `machine` event is sent to the event URL.
+    Continue,
+    /// The call is hung up.
+    Hangup,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+struct AdvancedMachineDetection {
+    behavior: MachineDetection,
+    mode: &'static str,
+    beep_timeout: u64,
+}
+
+/// A request to place an outbound call.
+///
+/// This is returned from [`VoiceApi::create_call()`](./struct.VoiceApi.html#method.create_call).
+/// Either an [answer URL](#method.answer_url) or an [NCCO](#method.ncco) must be set before the
+/// request is sent.
+pub struct CreateCall<C> {
+    http_client: HttpClient<C>,
+    auth: Auth,
+    body: CallBody,
+}
+
+impl<C> CreateCall<C> {
+    pub(crate) fn new(
+        http_client: HttpClient<C>,
+        auth: Auth,
+        to: Endpoint,
+        from: &PhoneNumber,
+    ) -> Self {
+        CreateCall {
+            http_client,
+            auth,
+            body: CallBody {
+                to: [to],
+                from: Endpoint::phone(from),
+                answer: None,
+                event_url: None,
+                ringing_timer: None,
+                length_timer: None,
+                machine_detection: None,
+                advanced_machine_detection: None,
+            },
+        }
+    }
+
+    /// Requests the NCCO which controls the call from the given URL once it is answered.
+    ///
+    /// This replaces any NCCO set with [`ncco()`](#method.ncco).
+    pub fn answer_url(mut self, url: impl Into<String>) -> Self {
+        self.body.answer = Some(Answer::Url([url.into()]));
+        self
+    }
+
+    /// Controls the call with the given NCCO once it is answered, which must serialize to a JSON
+    /// array of actions, e.g. a slice of [`ncco`](./ncco/index.html) actions.
+    ///
+    /// This replaces any URL set with [`answer_url()`](#method.answer_url).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ncco` cannot be serialized to JSON, e.g. because it contains a map with
+    /// non-string keys.
+    pub fn ncco<T: Serialize + ?Sized>(mut self, ncco: &T) -> Self {
+        let ncco = serde_json::to_value(ncco).expect("NCCO must be serializable to JSON");
+        self.body.answer = Some(Answer::Ncco(ncco));
+        self
+    }
+
+    /// Sends status updates of the call to the given URL, overriding the event URL of the
+    /// application.
+    pub fn event_url(mut self, url: impl Into<String>) -> Self {
+        self.body.event_url = Some([url.into()]);
+        self
+    }
+
+    /// Sets how long the call may ring before it is abandoned, from 1 to 120 seconds. Defaults to
+    /// 60 seconds.
+    pub fn ringing_timer(mut self, seconds: u64) -> Self {
+        self.body.ringing_timer = Some(seconds);
+        self
+    }
+
+    /// Sets the maximum length of the call, from 1 to 86400 seconds. Defaults to 7200 seconds.
+    pub fn length_timer(mut self, seconds: u64) -> Self {
+        self.body.length_timer = Some(seconds);
+        self
+    }
+
+    /// Detects whether the call was answered by an answering machine, and applies `behavior` if
+    /// so.
+    ///
+    /// This replaces any detection set with
+    /// [`advanced_machine_detection()`](#method.advanced_machine_detection).
+    pub fn machine_detection(mut self, behavior: MachineDetection) -> Self {
+        self.body.machine_detection = Some(behavior);
+        self.body.advanced_machine_detection = None;
+        self
+    }
+
+    /// Detects answering machines like [`machine_detection()`](#method.machine_detection), and
+    /// additionally waits up to `beep_timeout` seconds, from 45 to 120, for the beep which starts
+    /// the recording.
+    ///
+    /// With [`MachineDetection::Continue`], the event URL then receives a `machine` event whose
+    /// `sub_state` is either `beep_start` or `beep_timeout`.
+    ///
+    /// [`MachineDetection::Continue`]: ./enum.MachineDetection.html#variant.Continue
+    pub fn advanced_machine_detection(
+        mut self,
+        behavior: MachineDetection,
+        beep_timeout: u64,
+    ) -> Self {
+        self.body.machine_detection = None;
+        self.body.advanced_machine_detection = Some(AdvancedMachineDetection {
+            behavior,
+            mode: "detect_beep",
+            beep_timeout,
+        });
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        let answer_url = match &self.body.answer {
+            Some(Answer::Url([url])) => Some(url.as_str()),
+            _ => None,
+        };
+        let event_url = self.body.event_url.as_ref().map(|[url]| url.as_str());
+
+        let mut validator = Validator::new();
+        validator
+            .check(
+                self.body.answer.is_some(),
+                "answer_url",
+                "either an answer URL or an NCCO is required",
+            )
+            .check(
+                answer_url.is_none_or(is_http_url),
+                "answer_url",
+                "must be an absolute HTTP(S) URL",
+            )
+            .check(
+                event_url.is_none_or(is_http_url),
+                "event_url",
+                "must be an absolute HTTP(S) URL",
+            )
+            .range(self.body.ringing_timer, "ringing_timer", 1, 120)
+            .range(self.body.length_timer, "length_timer", 1, 86400)
+            .range(
+                self.body
+                    .advanced_machine_detection
+                    .as_ref()
+                    .map(|amd| amd.beep_timeout),
+                "beep_timeout",
+                45,
+                120,
+            );
+        validator.finish()
+    }
+}
+
+impl<C: HttpTransport> CreateCall<C> {
+    /// Sends the request, returning a handle to the call once it has been started.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequest`] without sending the request if
+    /// neither an answer URL nor an NCCO was set, or if any parameter is out of range.
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../enum.ErrorKind.html#variant.InvalidRequest
+    pub async fn send(self) -> Result<Call<C>> {
+        self.validate()?;
+
+        let body = serde_json::to_vec(&self.body).expect("call must be serializable to JSON");
+        let request = json_request(&self.auth, Method::POST, "v1/calls", body)?;
+        let created: CreatedCall = self
+            .http_client
+            .send(Product::Voice, request, |response| {
+                match response.status() {
+                    StatusCode::OK | StatusCode::CREATED => {}
+                    _ => return Err(Error::new_status(&response)),
+                }
+
+                crate::http_client::decode_json(response.body())
+            })
+            .await?;
+
+        Ok(Call {
+            http_client: self.http_client,
+            auth: self.auth,
+            uuid: created.uuid,
+            conversation_uuid: created.conversation_uuid,
+            status: created.status,
+            direction: created.direction,
+        })
+    }
+}
+
+impl<C> Debug for CreateCall<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(CreateCall))
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+pub(super) fn json_request(
+    auth: &Auth,
+    method: Method,
+    path: &str,
+    body: Vec<u8>,
+) -> Result<Request<Bytes>> {
+    let (name, value) = auth.to_preferred_auth_header()?;
+    Request::builder()
+        .method(method)
+        .uri(crate::resolve_url(path))
+        .header(CONTENT_TYPE, "application/json")
+        .header(ACCEPT, "application/json")
+        .header(name, value)
+        .body(body.into())
+        .map_err(|e| Error::with_cause(ErrorKind::Http, e))
+}
+
+#[derive(Deserialize)]
+struct CreatedCall {
+    uuid: String,
+    conversation_uuid: String,
+    status: CallStatus,
+    direction: Direction,
+}
+
+/// A handle to a call.
+///
+/// This is returned from [`CreateCall::send()`](./struct.CreateCall.html#method.send). It holds
+/// the state of the call as reported when it was created, which is not updated afterwards; status
+/// changes are sent to the event URL as [`VoiceEvent`](../webhooks/struct.VoiceEvent.html)s.
+pub struct Call<C = DefaultTransport> {
+    http_client: HttpClient<C>,
+    auth: Auth,
+    uuid: String,
+    conversation_uuid: String,
+    status: CallStatus,
+    direction: Direction,
+}
+
+impl<C> Call<C> {
+    /// Returns the UUID of the call.
+    #[inline]
+    pub fn uuid(&self) -> &str {
+        &self.uuid
+    }
+
+    /// Returns the UUID of the conversation the call belongs to.
+    #[inline]
+    pub fn conversation_uuid(&self) -> &str {
+        &self.conversation_uuid
+    }
+
+    /// Returns the status of the call when it was created.
+    #[inline]
+    pub fn status(&self) -> CallStatus {
+        self.status
+    }
+
+    /// Returns the direction of the call.
+    #[inline]
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+}
+
+impl<C: HttpTransport> Call<C> {
+    /// Hangs up the call.
+    pub async fn hangup(&self) -> Result<()> {
+        let body = br#"{"action":"hangup"}"#.to_vec();
+        update_call(&self.http_client, &self.auth, &self.uuid, body).await
+    }
+
+    /// Mutes the call, so that the other parties can no longer hear it.
+    pub async fn mute(&self) -> Result<()> {
+        let body = br#"{"action":"mute"}"#.to_vec();
+        update_call(&self.http_client, &self.auth, &self.uuid, body).await
+    }
+
+    /// Unmutes a call muted with [`mute()`](#method.mute).
+    pub async fn unmute(&self) -> Result<()> {
+        let body = br#"{"action":"unmute"}"#.to_vec();
+        update_call(&self.http_client, &self.auth, &self.uuid, body).await
+    }
+
+    /// Replaces the NCCO controlling the call with `ncco`, which must serialize to a JSON array
+    /// of actions.
+    ///
+    /// The call is hung up once the new NCCO completes, unless it connects the call elsewhere.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ncco` cannot be serialized to JSON, e.g. because it contains a map with
+    /// non-string keys.
+    pub async fn transfer_ncco<T: Serialize + ?Sized>(&self, ncco: &T) -> Result<()> {
+        transfer_ncco(&self.http_client, &self.auth, &self.uuid, ncco).await
+    }
+}
+
+/// Replaces the NCCO controlling the call `uuid`.
+pub(super) async fn transfer_ncco<C, T>(
+    http_client: &HttpClient<C>,
+    auth: &Auth,
+    uuid: &str,
+    ncco: &T,
+) -> Result<()>
+where
+    C: HttpTransport,
+    T: Serialize + ?Sized,
+{
+    let body = serde_json::json!({
+        "action": "transfer",
+        "destination": {
+            "type": "ncco",
+            "ncco": serde_json::to_value(ncco).expect("NCCO must be serializable to JSON"),
+        },
+    });
+    let body = serde_json::to_vec(&body).expect("body must be serializable to JSON");
+    update_call(http_client, auth, uuid, body).await
+}
+
+pub(super) async fn update_call<C: HttpTransport>(
+    http_client: &HttpClient<C>,
+    auth: &Auth,
+    uuid: &str,
+    body: Vec<u8>,
+) -> Result<()> {
+    let path = format!("v1/calls/{}", crate::encode_path_segment("uuid", uuid)?);
+    let request = json_request(auth, Method::PUT, &path, body)?;
+    http_client
+        .send(Product::Voice, request, |response| {
+            match response.status() {
+                StatusCode::OK | StatusCode::NO_CONTENT => Ok(()),
+                _ => Err(Error::new_status(&response)),
+            }
+        })
+        .await
+}
+
+impl<C> Clone for Call<C> {
+    fn clone(&self) -> Self {
+        Call {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+            uuid: self.uuid.clone(),
+            conversation_uuid: self.conversation_uuid.clone(),
+            status: self.status,
+            direction: self.direction,
+        }
+    }
+}
+
+impl<C> Debug for Call<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Call))
+            .field("uuid", &self.uuid)
+            .field("conversation_uuid", &self.conversation_uuid)
+            .field("status", &self.status)
+            .field("direction", &self.direction)
+            .finish()
+    }
+}
+
+/// The status of a call.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum CallStatus {
+    /// The call was created.
+    Started,
+    /// The destination is ringing.
+    Ringing,
+    /// The call was answered.
+    Answered,
+    /// The call was answered by an answering machine.
+    Machine,
+    /// The call ended after being answered.
+    Completed,
+    /// The destination was busy.
+    Busy,
+    /// The call was cancelled before it was answered.
+    Cancelled,
+    /// The call could not be connected.
+    Failed,
+    /// The call was rejected by the destination.
+    Rejected,
+    /// The destination did not answer within the ringing timer.
+    Timeout,
+    /// The destination did not answer.
+    Unanswered,
+    /// A status which is not known to this version of the library.
+    #[serde(other)]
+    Unknown,
+}
+
+/// The direction of a call.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// A call placed to one of your virtual numbers.
+    Inbound,
+    /// A call placed by your application.
+    Outbound,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use http_body_util::{BodyExt, Full};
+    use serde_json::json;
+    use tower::service_fn;
+
+    use crate::Client;
+
+    #[tokio::test]
+    async fn creates_calls_with_inline_ncco() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.method(), Method::POST);
+            assert_eq!(request.uri().path(), "/v1/calls");
+            assert!(request.headers()["authorization"]
+                .to_str()
+                .unwrap()
+                .starts_with("Bearer "));
+
+            let body = request.into_body().collect().await.unwrap().to_bytes();
+            let body: Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(
+                body,
+                json!({
+                    "to": [{"type": "phone", "number": "14155550100"}],
+                    "from": {"type": "phone", "number": "12025550123"},
+                    "ncco": [{"action": "talk", "text": "Hello"}],
+                    "ringing_timer": 30
+                })
+            );
+
+            let body = r#"{"uuid":"63f61863-4a51-4f6b-86e1-46edebcf9356","status":"started","direction":"outbound","conversation_uuid":"CON-f972836a-550f-45fa-956c-12a2ab5b7d22"}"#;
+            let mut response = http::Response::new(Full::new(Bytes::from(body)));
+            *response.status_mut() = StatusCode::CREATED;
+            Ok::<_, Infallible>(response)
+        });
+
+        let client = Client::from_service(service)
+            .jwt("app_id", crate::auth::TEST_PRIVATE_KEY)
+            .build()
+            .unwrap();
+
+        let to: PhoneNumber = "+14155550100".parse().unwrap();
+        let from: PhoneNumber = "+12025550123".parse().unwrap();
+        let call = client
+            .voice()
+            .create_call(Endpoint::phone(&to), &from)
+            .ncco(&json!([{"action": "talk", "text": "Hello"}]))
+            .ringing_timer(30)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(call.uuid(), "63f61863-4a51-4f6b-86e1-46edebcf9356");
+        assert_eq!(call.status(), CallStatus::Started);
+        assert_eq!(call.direction(), Direction::Outbound);
+
+        let error = client
+            .voice()
+            .create_call(Endpoint::sip("sip:rebekka@sip.example.com"), &from)
+            .event_url("/events")
+            .send()
+            .await
+            .unwrap_err();
+        let invalid = error.as_invalid_request().unwrap().invalid_parameters();
+        assert_eq!(invalid.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn escapes_call_uuids() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.method(), Method::PUT);
+            assert_eq!(request.uri().path(), "/v1/calls/a%2F..%3Fb");
+            let mut response = http::Response::new(Full::new(Bytes::new()));
+            *response.status_mut() = StatusCode::NO_CONTENT;
+            Ok::<_, Infallible>(response)
+        });
+
+        let client = Client::from_service(service)
+            .jwt("app_id", crate::auth::TEST_PRIVATE_KEY)
+            .build()
+            .unwrap();
+
+        let to: PhoneNumber = "+14155550100".parse().unwrap();
+        let from: PhoneNumber = "+12025550123".parse().unwrap();
+        let error = client
+            .voice()
+            .attended_transfer("a/..?b", "..")
+            .consult(Endpoint::phone(&to), &from)
+            .await
+            .unwrap_err();
+        let invalid = error.as_invalid_request().unwrap().invalid_parameters();
+        assert_eq!(invalid[0].name(), "uuid");
+    }
+}
@@ -0,0 +1,355 @@
+//! Contains a facade which manages a conference and its participants.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
+use http::{Method, StatusCode};
+use phonenumber::PhoneNumber;
+
+use super::call::{json_request, transfer_ncco, update_call};
+use super::ncco::Conversation;
+use super::{Call, CreateCall, Endpoint};
+use crate::auth::Auth;
+use crate::http_client::HttpClient;
+use crate::transport::HttpTransport;
+use crate::validation::{is_http_url, Validator};
+use crate::{DefaultTransport, Error, Product, Result};
+
+/// A conference, and the participants who were dialed into it.
+///
+/// This is returned from [`VoiceApi::conference()`](./struct.VoiceApi.html#method.conference).
+/// The conference is created once the first call joins it, either by being dialed out with
+/// [`add_participant()`](#method.add_participant), or by being answered with the NCCO returned
+/// from [`ncco()`](#method.ncco). Clones share the same list of participants.
+///
+/// Supervisors can [`monitor()`](#method.monitor) the conference, [`whisper()`](#method.whisper)
+/// to an agent, or [`barge()`](#method.barge) in. Each moves the supervisor's leg into the
+/// conference with the given audio controls, so calling another switches between them.
+///
+/// # Example
+///
+/// ```no_run
+/// use vonage::phone::{self, Country};
+/// use vonage::voice::Endpoint;
+/// use vonage::Client;
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = Client::builder().jwt("<app_id>", "<private_key>").build()?;
+/// let from = phone::parse("(202) 555-0123", Country::US)?;
+/// let alice = phone::parse("(415) 555-0100", Country::US)?;
+/// let bob = phone::parse("(415) 555-0101", Country::US)?;
+///
+/// let conference = client.voice().conference("standup");
+/// conference.add_participant(Endpoint::phone(&alice), &from).await?;
+/// let bob = conference.add_participant(Endpoint::phone(&bob), &from).await?;
+///
+/// conference.start_recording("https://example.com/webhooks/recording").await?;
+/// conference.mute(bob.uuid()).await?;
+/// conference.stop_recording().await?;
+/// conference.remove_participant(bob.uuid()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Conference<C = DefaultTransport> {
+    http_client: HttpClient<C>,
+    auth: Auth,
+    name: String,
+    participants: Arc<Mutex<Vec<Call<C>>>>,
+}
+
+impl<C> Conference<C> {
+    pub(crate) fn new(http_client: HttpClient<C>, auth: Auth, name: String) -> Self {
+        Conference {
+            http_client,
+            auth,
+            name,
+            participants: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Returns the name of the conference.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the action which joins a call to the conference, e.g. to answer inbound calls
+    /// with.
+    pub fn ncco(&self) -> Conversation {
+        Conversation::new(self.name.clone())
+    }
+
+    /// Returns the participants which were dialed out and not removed yet, oldest first.
+    pub fn participants(&self) -> Vec<Call<C>> {
+        self.participants.lock().unwrap().clone()
+    }
+
+    fn conversation_uuid(&self) -> Result<String> {
+        let participants = self.participants.lock().unwrap();
+        let first = participants.first();
+
+        let mut validator = Validator::new();
+        validator.check(
+            first.is_some(),
+            "participants",
+            "at least one participant must have been added",
+        );
+        validator.finish()?;
+
+        Ok(first.unwrap().conversation_uuid().to_owned())
+    }
+}
+
+impl<C: HttpTransport> Conference<C> {
+    /// Dials out from the virtual number `from` to `to`, and joins the call to the conference
+    /// once it is answered.
+    pub async fn add_participant(&self, to: Endpoint, from: &PhoneNumber) -> Result<Call<C>> {
+        let call = CreateCall::new(self.http_client.clone(), self.auth.clone(), to, from)
+            .ncco(&[self.ncco()])
+            .send()
+            .await?;
+
+        self.participants.lock().unwrap().push(call.clone());
+        Ok(call)
+    }
+
+    /// Hangs up the call `uuid`, removing it from the conference.
+    ///
+    /// `uuid` need not have been added with [`add_participant()`](#method.add_participant).
+    pub async fn remove_participant(&self, uuid: &str) -> Result<()> {
+        let body = br#"{"action":"hangup"}"#.to_vec();
+        update_call(&self.http_client, &self.auth, uuid, body).await?;
+        self.participants
+            .lock()
+            .unwrap()
+            .retain(|call| call.uuid() != uuid);
+        Ok(())
+    }
+
+    /// Mutes the call `uuid`, so that the other participants can no longer hear it.
+    pub async fn mute(&self, uuid: &str) -> Result<()> {
+        let body = br#"{"action":"mute"}"#.to_vec();
+        update_call(&self.http_client, &self.auth, uuid, body).await
+    }
+
+    /// Unmutes a call muted with [`mute()`](#method.mute).
+    pub async fn unmute(&self, uuid: &str) -> Result<()> {
+        let body = br#"{"action":"unmute"}"#.to_vec();
+        update_call(&self.http_client, &self.auth, uuid, body).await
+    }
+
+    /// Joins the supervisor on the leg `supervisor_leg` to the conference without letting anyone
+    /// hear them, so that they can listen in.
+    pub async fn monitor(&self, supervisor_leg: &str) -> Result<()> {
+        let room = self.ncco().can_speak(Vec::<String>::new());
+        self.supervise(supervisor_leg, room).await
+    }
+
+    /// Joins the supervisor on the leg `supervisor_leg` to the conference so that only the agent
+    /// on the leg `agent_leg` hears them, e.g. to coach the agent without the caller noticing.
+    pub async fn whisper(&self, supervisor_leg: &str, agent_leg: &str) -> Result<()> {
+        let room = self.ncco().can_speak(vec![agent_leg]);
+        self.supervise(supervisor_leg, room).await
+    }
+
+    /// Joins the supervisor on the leg `supervisor_leg` to the conference so that everyone hears
+    /// them, e.g. to take over from a monitored or whispered-to agent.
+    pub async fn barge(&self, supervisor_leg: &str) -> Result<()> {
+        self.supervise(supervisor_leg, self.ncco()).await
+    }
+
+    async fn supervise(&self, supervisor_leg: &str, room: Conversation) -> Result<()> {
+        transfer_ncco(&self.http_client, &self.auth, supervisor_leg, &[room]).await
+    }
+
+    /// Starts recording the conference, sending the recording to `event_url` once it stops.
+    ///
+    /// The conference is identified by the conversation of the first participant, so returns an
+    /// error of kind [`ErrorKind::InvalidRequest`] without sending anything if no participant was
+    /// added yet, or if `event_url` is not an absolute HTTP(S) URL.
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../enum.ErrorKind.html#variant.InvalidRequest
+    pub async fn start_recording(&self, event_url: &str) -> Result<()> {
+        let mut validator = Validator::new();
+        validator.check(
+            is_http_url(event_url),
+            "event_url",
+            "must be an absolute HTTP(S) URL",
+        );
+        validator.finish()?;
+
+        let body = serde_json::json!({ "action": "start", "event_url": [event_url] });
+        self.record(body).await
+    }
+
+    /// Stops a recording started with [`start_recording()`](#method.start_recording).
+    pub async fn stop_recording(&self) -> Result<()> {
+        self.record(serde_json::json!({ "action": "stop" })).await
+    }
+
+    async fn record(&self, body: serde_json::Value) -> Result<()> {
+        let conversation_uuid = self.conversation_uuid()?;
+        let conversation_uuid =
+            crate::encode_path_segment("conversation_uuid", &conversation_uuid)?;
+        let path = format!("v1/conversations/{}/record", conversation_uuid);
+        let body = serde_json::to_vec(&body).expect("body must be serializable to JSON");
+        let request = json_request(&self.auth, Method::PUT, &path, body)?;
+        self.http_client
+            .send(Product::Voice, request, |response| {
+                match response.status() {
+                    StatusCode::OK | StatusCode::NO_CONTENT => Ok(()),
+                    _ => Err(Error::new_status(&response)),
+                }
+            })
+            .await
+    }
+}
+
+impl<C> Clone for Conference<C> {
+    fn clone(&self) -> Self {
+        Conference {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+            name: self.name.clone(),
+            participants: self.participants.clone(),
+        }
+    }
+}
+
+impl<C> Debug for Conference<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Conference))
+            .field("name", &self.name)
+            .field("participants", &self.participants.lock().unwrap())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use bytes::Bytes;
+    use http::Request;
+    use http_body_util::{BodyExt, Full};
+    use serde_json::{json, Value};
+    use tower::service_fn;
+
+    use crate::Client;
+
+    #[tokio::test]
+    async fn moves_supervisors_between_modes() {
+        let rooms = Arc::new(Mutex::new(Vec::new()));
+        let service = service_fn({
+            let rooms = rooms.clone();
+            move |request: Request<Full<Bytes>>| {
+                let rooms = rooms.clone();
+                async move {
+                    let (parts, body) = request.into_parts();
+                    assert_eq!(parts.uri.path(), "/v1/calls/supervisor");
+                    let body: Value =
+                        serde_json::from_slice(&body.collect().await.unwrap().to_bytes()).unwrap();
+                    rooms
+                        .lock()
+                        .unwrap()
+                        .push(body["destination"]["ncco"][0].clone());
+                    Ok::<_, Infallible>(http::Response::new(Full::new(Bytes::new())))
+                }
+            }
+        });
+
+        let client = Client::from_service(service)
+            .jwt("app_id", crate::auth::TEST_PRIVATE_KEY)
+            .build()
+            .unwrap();
+        let conference = client.voice().conference("standup");
+
+        conference.monitor("supervisor").await.unwrap();
+        conference.whisper("supervisor", "agent").await.unwrap();
+        conference.barge("supervisor").await.unwrap();
+
+        let expected = [
+            json!({"action": "conversation", "name": "standup", "canSpeak": []}),
+            json!({"action": "conversation", "name": "standup", "canSpeak": ["agent"]}),
+            json!({"action": "conversation", "name": "standup"}),
+        ];
+        assert_eq!(*rooms.lock().unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn manages_participants_and_recording() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            let (parts, body) = request.into_parts();
+            let body: Value =
+                serde_json::from_slice(&body.collect().await.unwrap().to_bytes()).unwrap();
+            let response = match (parts.method, parts.uri.path()) {
+                (Method::POST, "/v1/calls") => {
+                    assert_eq!(
+                        body["ncco"],
+                        json!([{"action": "conversation", "name": "standup"}])
+                    );
+                    let to = body["to"][0]["number"].as_str().unwrap();
+                    json!({
+                        "uuid": format!("call-{}", to),
+                        "conversation_uuid": "CON-1",
+                        "status": "started",
+                        "direction": "outbound"
+                    })
+                }
+                (Method::PUT, "/v1/conversations/CON-1/record") => {
+                    let action = body["action"].as_str().unwrap();
+                    assert!(action == "stop" || body["event_url"] == json!(["https://x.io/rec"]));
+                    json!({})
+                }
+                (Method::PUT, "/v1/calls/call-14155550101") => {
+                    let action = body["action"].as_str().unwrap();
+                    assert!(action == "mute" || action == "hangup");
+                    json!({})
+                }
+                (method, path) => panic!("unexpected request {} {}", method, path),
+            };
+            let body = Bytes::from(response.to_string());
+            Ok::<_, Infallible>(http::Response::new(Full::new(body)))
+        });
+
+        let client = Client::from_service(service)
+            .jwt("app_id", crate::auth::TEST_PRIVATE_KEY)
+            .build()
+            .unwrap();
+        let conference = client.voice().conference("standup");
+
+        let error = conference.stop_recording().await.unwrap_err();
+        let invalid = error.as_invalid_request().unwrap().invalid_parameters();
+        assert_eq!(invalid[0].name(), "participants");
+
+        let from = crate::phone::parse("+12025550123", None).unwrap();
+        for number in &["+14155550100", "+14155550101"] {
+            let to = crate::phone::parse(number, None).unwrap();
+            conference
+                .add_participant(Endpoint::phone(&to), &from)
+                .await
+                .unwrap();
+        }
+        assert_eq!(conference.participants().len(), 2);
+
+        conference
+            .start_recording("https://x.io/rec")
+            .await
+            .unwrap();
+        conference.mute("call-14155550101").await.unwrap();
+        conference.stop_recording().await.unwrap();
+        conference
+            .remove_participant("call-14155550101")
+            .await
+            .unwrap();
+
+        let remaining: Vec<_> = conference
+            .participants()
+            .iter()
+            .map(|call| call.uuid().to_owned())
+            .collect();
+        assert_eq!(remaining, ["call-14155550100"]);
+    }
+}
@@ -0,0 +1,788 @@
+//! Typed actions of an NCCO (Nexmo Call Control Object).
+//!
+//! Each action serializes to the JSON object Vonage expects. Actions of different types can be
+//! combined into a single NCCO through [`Action`], and the list can be returned from an answer
+//! webhook with [`NccoResponse`](../../webhooks/struct.NccoResponse.html), or passed to
+//! [`CreateCall::ncco()`](../struct.CreateCall.html#method.ncco).
+//!
+//! [`Action`]: ./enum.Action.html
+//!
+//! # Example
+//!
+//! ```
+//! use vonage::voice::ncco::{Action, Dtmf, Input, Record, Talk, Transcription};
+//! use vonage::webhooks::NccoResponse;
+//!
+//! let ncco: Vec<Action> = vec![
+//!     Talk::new("Please leave a message after the tone.").into(),
+//!     Record::new()
+//!         .beep_start(true)
+//!         .event_url("https://example.com/webhooks/recording")
+//!         .transcription(Transcription::new().language("en-US"))
+//!         .into(),
+//!     Talk::new("Press 1 to hear your message again.").into(),
+//!     Input::new()
+//!         .dtmf(Dtmf::new().max_digits(1))
+//!         .event_url("https://example.com/webhooks/input")
+//!         .into(),
+//! ];
+//!
+//! let response = NccoResponse::new(&ncco);
+//! ```
+
+use phonenumber::PhoneNumber;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::call::e164_digits;
+use super::tts::Voice;
+use super::Endpoint;
+
+/// Any NCCO action, so that actions of different types can be combined into a single NCCO.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+#[non_exhaustive]
+pub enum Action {
+    /// A `talk` action.
+    Talk(Talk),
+    /// A `stream` action.
+    Stream(Stream),
+    /// An `input` action.
+    Input(Input),
+    /// A `record` action.
+    Record(Record),
+    /// A `connect` action.
+    Connect(Connect),
+    /// A `conversation` action.
+    Conversation(Conversation),
+    /// A `notify` action.
+    Notify(Notify),
+}
+
+macro_rules! impl_from_action {
+    ($($action:ident),*) => {
+        $(
+            impl From<$action> for Action {
+                fn from(action: $action) -> Self {
+                    Action::$action(action)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_action!(Talk, Stream, Input, Record, Connect, Conversation, Notify);
+
+/// A `talk` action, which reads out text to the call with text-to-speech.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "action", rename = "talk", rename_all = "camelCase")]
+pub struct Talk {
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    barge_in: Option<bool>,
+    #[serde(rename = "loop", skip_serializing_if = "Option::is_none")]
+    loop_count: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    level: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    style: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    premium: Option<bool>,
+}
+
+impl Talk {
+    /// Creates a new `talk` action which reads out `text`.
+    ///
+    /// The text may be plain text, or an [`Ssml`](../ssml/struct.Ssml.html) document.
+    pub fn new(text: impl ToString) -> Self {
+        Talk {
+            text: text.to_string(),
+            barge_in: None,
+            loop_count: None,
+            level: None,
+            language: None,
+            style: None,
+            premium: None,
+        }
+    }
+
+    /// Lets the user interrupt the text by pressing a key or speaking, in which case the next
+    /// action must be an [`Input`](./struct.Input.html).
+    pub fn barge_in(mut self, barge_in: bool) -> Self {
+        self.barge_in = Some(barge_in);
+        self
+    }
+
+    /// Repeats the text the given number of times, or indefinitely if `0`. Defaults to 1.
+    pub fn loop_count(mut self, count: u8) -> Self {
+        self.loop_count = Some(count);
+        self
+    }
+
+    /// Sets the volume, from -1 to 1. Defaults to 0.
+    pub fn level(mut self, level: f32) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Reads out the text with the given voice from the [`tts`](../tts/index.html) catalog.
+    pub fn voice(mut self, voice: &Voice) -> Self {
+        self.language = Some(voice.language());
+        self.style = Some(voice.style());
+        self.premium = Some(voice.is_premium());
+        self
+    }
+}
+
+/// A `stream` action, which plays an audio file to the call.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "action", rename = "stream", rename_all = "camelCase")]
+pub struct Stream {
+    stream_url: [String; 1],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    level: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    barge_in: Option<bool>,
+    #[serde(rename = "loop", skip_serializing_if = "Option::is_none")]
+    loop_count: Option<u8>,
+}
+
+impl Stream {
+    /// Creates a new `stream` action which plays the MP3 or WAV file at `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Stream {
+            stream_url: [url.into()],
+            level: None,
+            barge_in: None,
+            loop_count: None,
+        }
+    }
+
+    /// Sets the volume, from -1 to 1. Defaults to 0.
+    pub fn level(mut self, level: f32) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Lets the user interrupt the audio by pressing a key or speaking, in which case the next
+    /// action must be an [`Input`](./struct.Input.html).
+    pub fn barge_in(mut self, barge_in: bool) -> Self {
+        self.barge_in = Some(barge_in);
+        self
+    }
+
+    /// Repeats the audio the given number of times, or indefinitely if `0`. Defaults to 1.
+    pub fn loop_count(mut self, count: u8) -> Self {
+        self.loop_count = Some(count);
+        self
+    }
+}
+
+/// An `input` action, which collects digits or speech from the user.
+///
+/// The input is sent to the [event URL](#method.event_url), which responds with the NCCO to
+/// continue the call with. At least one of [`dtmf()`](#method.dtmf) and
+/// [`speech()`](#method.speech) should be set.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(tag = "action", rename = "input", rename_all = "camelCase")]
+pub struct Input {
+    #[serde(rename = "type")]
+    kinds: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dtmf: Option<Dtmf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    speech: Option<Speech>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event_url: Option<[String; 1]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event_method: Option<String>,
+}
+
+impl Input {
+    /// Creates a new `input` action which collects nothing yet.
+    pub fn new() -> Self {
+        Input::default()
+    }
+
+    /// Collects digits pressed on the keypad.
+    pub fn dtmf(mut self, dtmf: Dtmf) -> Self {
+        if self.dtmf.is_none() {
+            self.kinds.push("dtmf");
+        }
+        self.dtmf = Some(dtmf);
+        self
+    }
+
+    /// Collects speech, which is recognized and sent to the event URL as text.
+    pub fn speech(mut self, speech: Speech) -> Self {
+        if self.speech.is_none() {
+            self.kinds.push("speech");
+        }
+        self.speech = Some(speech);
+        self
+    }
+
+    /// Sets the webhook which receives the collected input.
+    pub fn event_url(mut self, url: impl Into<String>) -> Self {
+        self.event_url = Some([url.into()]);
+        self
+    }
+
+    /// Sets the HTTP method used to call the [event URL](#method.event_url). Defaults to `POST`.
+    pub fn event_method(mut self, method: http::Method) -> Self {
+        self.event_method = Some(method.to_string());
+        self
+    }
+}
+
+/// Settings for collecting digits with an [`Input`](./struct.Input.html) action.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dtmf {
+    #[serde(rename = "timeOut", skip_serializing_if = "Option::is_none")]
+    time_out: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_digits: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    submit_on_hash: Option<bool>,
+}
+
+impl Dtmf {
+    /// Creates new digit settings with the defaults.
+    pub fn new() -> Self {
+        Dtmf::default()
+    }
+
+    /// Submits the digits after the given number of seconds without a key press, from 0 to 10.
+    /// Defaults to 3.
+    pub fn timeout(mut self, seconds: u8) -> Self {
+        self.time_out = Some(seconds);
+        self
+    }
+
+    /// Submits the digits once the given number have been pressed, from 1 to 20. Defaults to 4.
+    pub fn max_digits(mut self, max: u8) -> Self {
+        self.max_digits = Some(max);
+        self
+    }
+
+    /// Submits the digits as soon as `#` is pressed.
+    pub fn submit_on_hash(mut self, submit: bool) -> Self {
+        self.submit_on_hash = Some(submit);
+        self
+    }
+}
+
+/// Settings for collecting speech with an [`Input`](./struct.Input.html) action.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Speech {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    context: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_on_silence: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_timeout: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_duration: Option<u8>,
+}
+
+impl Speech {
+    /// Creates new speech settings with the defaults.
+    pub fn new() -> Self {
+        Speech::default()
+    }
+
+    /// Sets the BCP-47 code of the language spoken by the user. Defaults to `en-US`.
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Adds a word or phrase the user is likely to say, which improves recognition.
+    pub fn context(mut self, hint: impl Into<String>) -> Self {
+        self.context.push(hint.into());
+        self
+    }
+
+    /// Ends recognition after the given number of seconds of silence, from 0.4 to 10. Defaults to
+    /// 2.
+    pub fn end_on_silence(mut self, seconds: f32) -> Self {
+        self.end_on_silence = Some(seconds);
+        self
+    }
+
+    /// Gives up if the user does not start speaking within the given number of seconds, from 1 to
+    /// 60. Defaults to 10.
+    pub fn start_timeout(mut self, seconds: u8) -> Self {
+        self.start_timeout = Some(seconds);
+        self
+    }
+
+    /// Ends recognition after the given number of seconds, from 1 to 60. Defaults to 60.
+    pub fn max_duration(mut self, seconds: u8) -> Self {
+        self.max_duration = Some(seconds);
+        self
+    }
+}
+
+/// A `record` action, which records all or part of a call.
+///
+/// Once the recording is available, its URL is sent to the [event URL](#method.event_url).
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(tag = "action", rename = "record", rename_all = "camelCase")]
+pub struct Record {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<RecordingFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    split: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channels: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_on_silence: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_on_key: Option<char>,
+    #[serde(rename = "timeOut", skip_serializing_if = "Option::is_none")]
+    time_out: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    beep_start: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event_url: Option<[String; 1]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event_method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transcription: Option<Transcription>,
+}
+
+impl Record {
+    /// Creates a new `record` action with the default settings.
+    pub fn new() -> Self {
+        Record::default()
+    }
+
+    /// Sets the file format of the recording. Defaults to MP3.
+    pub fn format(mut self, format: RecordingFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Records each of the given number of call legs on its own audio channel, from 1 to 32.
+    ///
+    /// Channels are required to tell the speakers apart in a transcript.
+    pub fn split_channels(mut self, channels: u8) -> Self {
+        self.split = Some("conversation");
+        self.channels = Some(channels);
+        self
+    }
+
+    /// Stops recording after the given number of seconds of silence, from 3 to 10.
+    pub fn end_on_silence(mut self, seconds: u8) -> Self {
+        self.end_on_silence = Some(seconds);
+        self
+    }
+
+    /// Stops recording when the given key is pressed, i.e. a digit, `*` or `#`.
+    pub fn end_on_key(mut self, key: char) -> Self {
+        self.end_on_key = Some(key);
+        self
+    }
+
+    /// Sets the maximum length of the recording in seconds, from 3 to 7200. Defaults to 7200.
+    pub fn timeout(mut self, seconds: u16) -> Self {
+        self.time_out = Some(seconds);
+        self
+    }
+
+    /// Plays a beep when the recording starts.
+    pub fn beep_start(mut self, beep: bool) -> Self {
+        self.beep_start = Some(beep);
+        self
+    }
+
+    /// Sets the webhook which is notified once the recording is available.
+    pub fn event_url(mut self, url: impl Into<String>) -> Self {
+        self.event_url = Some([url.into()]);
+        self
+    }
+
+    /// Sets the HTTP method used to call the [event URL](#method.event_url). Defaults to `POST`.
+    pub fn event_method(mut self, method: http::Method) -> Self {
+        self.event_method = Some(method.to_string());
+        self
+    }
+
+    /// Transcribes the recording once the call has ended.
+    ///
+    /// When the transcription completes, a
+    /// [`TranscriptionEvent`](../../webhooks/struct.TranscriptionEvent.html) is sent to its event
+    /// URL, and the transcript can be retrieved with
+    /// [`VoiceApi::transcript()`](../struct.VoiceApi.html#method.transcript).
+    pub fn transcription(mut self, transcription: Transcription) -> Self {
+        self.transcription = Some(transcription);
+        self
+    }
+}
+
+/// The file format of a recording.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingFormat {
+    /// MPEG-1 Audio Layer III.
+    Mp3,
+    /// Waveform audio. Required for recordings with more than two channels.
+    Wav,
+    /// Ogg Vorbis.
+    Ogg,
+}
+
+/// Settings for transcribing a recording.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transcription {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event_url: Option<[String; 1]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event_method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sentiment_analysis: Option<bool>,
+}
+
+impl Transcription {
+    /// Creates new transcription settings with the defaults.
+    pub fn new() -> Self {
+        Transcription::default()
+    }
+
+    /// Sets the BCP-47 code of the language spoken in the recording. Defaults to `en-US`.
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Sets the webhook which is notified once the transcription is complete.
+    ///
+    /// Defaults to the event URL of the `record` action.
+    pub fn event_url(mut self, url: impl Into<String>) -> Self {
+        self.event_url = Some([url.into()]);
+        self
+    }
+
+    /// Sets the HTTP method used to call the [event URL](#method.event_url). Defaults to `POST`.
+    pub fn event_method(mut self, method: http::Method) -> Self {
+        self.event_method = Some(method.to_string());
+        self
+    }
+
+    /// Includes a sentiment score for each sentence in the transcript.
+    pub fn sentiment_analysis(mut self, enabled: bool) -> Self {
+        self.sentiment_analysis = Some(enabled);
+        self
+    }
+}
+
+/// A `connect` action, which connects the call to another endpoint.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "action", rename = "connect", rename_all = "camelCase")]
+pub struct Connect {
+    endpoint: [Endpoint; 1],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timeout: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ringback_tone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event_url: Option<[String; 1]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event_method: Option<String>,
+}
+
+impl Connect {
+    /// Creates a new `connect` action which calls `endpoint`.
+    pub fn new(endpoint: Endpoint) -> Self {
+        Connect {
+            endpoint: [endpoint],
+            from: None,
+            timeout: None,
+            limit: None,
+            ringback_tone: None,
+            event_url: None,
+            event_method: None,
+        }
+    }
+
+    /// Sets the virtual number shown as the caller when calling a phone number.
+    pub fn from(mut self, number: &PhoneNumber) -> Self {
+        self.from = Some(e164_digits(number));
+        self
+    }
+
+    /// Gives up if the endpoint does not answer within the given number of seconds, from 1 to
+    /// 120. Defaults to 60.
+    pub fn timeout(mut self, seconds: u8) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// Ends the connected call after the given number of seconds, up to 7200. Defaults to 7200.
+    pub fn limit(mut self, seconds: u32) -> Self {
+        self.limit = Some(seconds);
+        self
+    }
+
+    /// Plays the audio file at `url` to the caller while the endpoint is ringing.
+    pub fn ringback_tone(mut self, url: impl Into<String>) -> Self {
+        self.ringback_tone = Some(url.into());
+        self
+    }
+
+    /// Sets the webhook which is notified of status changes of the connected call.
+    pub fn event_url(mut self, url: impl Into<String>) -> Self {
+        self.event_url = Some([url.into()]);
+        self
+    }
+
+    /// Sets the HTTP method used to call the [event URL](#method.event_url). Defaults to `POST`.
+    pub fn event_method(mut self, method: http::Method) -> Self {
+        self.event_method = Some(method.to_string());
+        self
+    }
+}
+
+/// A `conversation` action, which adds the call to a named conference.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "action", rename = "conversation", rename_all = "camelCase")]
+pub struct Conversation {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    music_on_hold_url: Option<[String; 1]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_on_enter: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_on_exit: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    record: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    can_speak: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    can_hear: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mute: Option<bool>,
+}
+
+impl Conversation {
+    /// Creates a new `conversation` action which joins the conference called `name`, creating it
+    /// if needed.
+    pub fn new(name: impl Into<String>) -> Self {
+        Conversation {
+            name: name.into(),
+            music_on_hold_url: None,
+            start_on_enter: None,
+            end_on_exit: None,
+            record: None,
+            can_speak: None,
+            can_hear: None,
+            mute: None,
+        }
+    }
+
+    /// Plays the audio file at `url` to the call until the conference starts.
+    pub fn music_on_hold_url(mut self, url: impl Into<String>) -> Self {
+        self.music_on_hold_url = Some([url.into()]);
+        self
+    }
+
+    /// Sets whether the conference starts when this call joins. Defaults to `true`.
+    ///
+    /// Set this to `false` for attendees who wait for a moderator.
+    pub fn start_on_enter(mut self, start: bool) -> Self {
+        self.start_on_enter = Some(start);
+        self
+    }
+
+    /// Ends the conference when this call leaves, e.g. for the moderator.
+    pub fn end_on_exit(mut self, end: bool) -> Self {
+        self.end_on_exit = Some(end);
+        self
+    }
+
+    /// Records the conference.
+    pub fn record(mut self, record: bool) -> Self {
+        self.record = Some(record);
+        self
+    }
+
+    /// Only lets the call legs with the given UUIDs hear this call.
+    pub fn can_speak<I>(mut self, leg_uuids: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.can_speak = Some(leg_uuids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Only lets this call hear the call legs with the given UUIDs.
+    pub fn can_hear<I>(mut self, leg_uuids: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.can_hear = Some(leg_uuids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Mutes the call in the conference.
+    pub fn mute(mut self, mute: bool) -> Self {
+        self.mute = Some(mute);
+        self
+    }
+}
+
+/// A `notify` action, which sends a custom payload to a webhook and continues the call.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "action", rename = "notify", rename_all = "camelCase")]
+pub struct Notify {
+    payload: Value,
+    event_url: [String; 1],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event_method: Option<String>,
+}
+
+impl Notify {
+    /// Creates a new `notify` action which sends `payload` to `event_url`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `payload` cannot be serialized to JSON, e.g. because it contains a map with
+    /// non-string keys.
+    pub fn new<T: Serialize + ?Sized>(payload: &T, event_url: impl Into<String>) -> Self {
+        Notify {
+            payload: serde_json::to_value(payload).expect("payload must be serializable to JSON"),
+            event_url: [event_url.into()],
+            event_method: None,
+        }
+    }
+
+    /// Sets the HTTP method used to call the event URL. Defaults to `POST`.
+    pub fn event_method(mut self, method: http::Method) -> Self {
+        self.event_method = Some(method.to_string());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_record_with_transcription() {
+        let record = Record::new()
+            .format(RecordingFormat::Wav)
+            .split_channels(2)
+            .end_on_key('#')
+            .timeout(600)
+            .event_url("https://example.com/recording")
+            .transcription(
+                Transcription::new()
+                    .language("de-DE")
+                    .event_url("https://example.com/transcription")
+                    .sentiment_analysis(true),
+            );
+
+        assert_eq!(
+            serde_json::to_value(&record).unwrap(),
+            serde_json::json!({
+                "action": "record",
+                "format": "wav",
+                "split": "conversation",
+                "channels": 2,
+                "endOnKey": "#",
+                "timeOut": 600,
+                "eventUrl": ["https://example.com/recording"],
+                "transcription": {
+                    "language": "de-DE",
+                    "eventUrl": ["https://example.com/transcription"],
+                    "sentimentAnalysis": true,
+                },
+            })
+        );
+
+        let record = serde_json::to_string(&Record::new()).unwrap();
+        assert_eq!(record, r#"{"action":"record"}"#);
+    }
+
+    #[test]
+    fn serializes_mixed_actions() {
+        let agent: PhoneNumber = "+14155550100".parse().unwrap();
+        let from: PhoneNumber = "+12025550123".parse().unwrap();
+        let ncco: Vec<Action> = vec![
+            Talk::new("Hello").barge_in(true).loop_count(2).into(),
+            Stream::new("https://example.com/hold.mp3")
+                .level(0.5)
+                .into(),
+            Input::new()
+                .dtmf(Dtmf::new().max_digits(1).submit_on_hash(true))
+                .speech(Speech::new().context("sales").end_on_silence(1.5))
+                .event_url("https://example.com/input")
+                .into(),
+            Connect::new(Endpoint::phone(&agent))
+                .from(&from)
+                .timeout(30)
+                .into(),
+            Conversation::new("support")
+                .start_on_enter(false)
+                .can_hear(vec!["aaaaaaaa-bbbb-cccc-dddd-0123456789ab"])
+                .into(),
+            Notify::new(
+                &serde_json::json!({"step": 1}),
+                "https://example.com/notify",
+            )
+            .into(),
+        ];
+
+        assert_eq!(
+            serde_json::to_value(&ncco).unwrap(),
+            serde_json::json!([
+                {"action": "talk", "text": "Hello", "bargeIn": true, "loop": 2},
+                {"action": "stream", "streamUrl": ["https://example.com/hold.mp3"], "level": 0.5},
+                {
+                    "action": "input",
+                    "type": ["dtmf", "speech"],
+                    "dtmf": {"maxDigits": 1, "submitOnHash": true},
+                    "speech": {"context": ["sales"], "endOnSilence": 1.5},
+                    "eventUrl": ["https://example.com/input"],
+                },
+                {
+                    "action": "connect",
+                    "endpoint": [{"type": "phone", "number": "14155550100"}],
+                    "from": "12025550123",
+                    "timeout": 30,
+                },
+                {
+                    "action": "conversation",
+                    "name": "support",
+                    "startOnEnter": false,
+                    "canHear": ["aaaaaaaa-bbbb-cccc-dddd-0123456789ab"],
+                },
+                {"action": "notify", "payload": {"step": 1}, "eventUrl": ["https://example.com/notify"]},
+            ])
+        );
+    }
+
+    #[test]
+    fn serializes_talk_with_voice() {
+        let voice = crate::voice::tts::find("en-GB", 0).unwrap();
+        let talk = serde_json::to_value(Talk::new("Hi").voice(voice)).unwrap();
+        assert_eq!(talk["language"], "en-GB");
+        assert_eq!(talk["style"], 0);
+    }
+}
@@ -0,0 +1,369 @@
+//! Screening of inbound calls with Number Insight.
+//!
+//! A [`CallScreener`] answers an [`AnswerRequest`] by looking up the caller with a
+//! [standard Number Insight lookup], asking a [`ScreeningPolicy`] whether to accept the call, and
+//! producing the NCCO which either connects the call or rejects it.
+//!
+//! [`CallScreener`]: ./struct.CallScreener.html
+//! [`AnswerRequest`]: ../../webhooks/struct.AnswerRequest.html
+//! [standard Number Insight lookup]: ../../number_insight/struct.NumberInsightApi.html#method.standard
+//! [`ScreeningPolicy`]: ./trait.ScreeningPolicy.html
+//!
+//! # Example
+//!
+//! ```no_run
+//! use vonage::number_insight::Ported;
+//! use vonage::voice::screening::{CallScreener, Caller, Decision};
+//! use vonage::voice::Endpoint;
+//! use vonage::webhooks::{self, Auth, NccoResponse};
+//! use vonage::Client;
+//!
+//! # async fn run(parts: http::request::Parts) -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::new("<api_key>", "<api_secret>");
+//! let agent = "+14155550100".parse()?;
+//!
+//! let policy = |caller: &Caller| match caller.insight() {
+//!     Some(insight) if insight.ported == Ported::Ported && !insight.is_landline() => Decision::Deny,
+//!     Some(_) => Decision::Allow,
+//!     // Fail open if the lookup failed, but reject anonymous callers.
+//!     None if caller.number().is_some() => Decision::Allow,
+//!     None => Decision::Deny,
+//! };
+//!
+//! let screener = CallScreener::new(client.number_insight(), policy, Endpoint::phone(&agent))
+//!     .reject_message("Sorry, we cannot take your call.");
+//!
+//! let answer = webhooks::answer_from_http_parts(&parts, &[], &Auth::None)?;
+//! let response: NccoResponse = screener.screen(&answer).await.into();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use phonenumber::PhoneNumber;
+
+use super::ncco::{Action, Connect, Talk};
+use super::Endpoint;
+use crate::number_insight::{NumberInsightApi, StandardInsight};
+use crate::transport::HttpTransport;
+use crate::webhooks::{AnswerRequest, NccoResponse};
+use crate::{DefaultTransport, Error};
+
+/// Whether an inbound call is accepted.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Decision {
+    /// The call is connected to the configured endpoint.
+    Allow,
+    /// The call is rejected.
+    Deny,
+}
+
+/// The caller of an inbound call, as passed to a [`ScreeningPolicy`](./trait.ScreeningPolicy.html).
+#[derive(Debug)]
+pub struct Caller<'a> {
+    from: &'a str,
+    number: Option<&'a PhoneNumber>,
+    insight: Option<&'a StandardInsight>,
+    lookup_error: Option<&'a Error>,
+}
+
+impl Caller<'_> {
+    /// Returns the caller as reported by the answer webhook, e.g. `14155550100`.
+    #[inline]
+    pub fn from(&self) -> &str {
+        self.from
+    }
+
+    /// Returns the phone number of the caller, unless it was withheld or is not a valid number.
+    #[inline]
+    pub fn number(&self) -> Option<&PhoneNumber> {
+        self.number
+    }
+
+    /// Returns the result of looking up the caller, if the lookup succeeded.
+    #[inline]
+    pub fn insight(&self) -> Option<&StandardInsight> {
+        self.insight
+    }
+
+    /// Returns why looking up the caller failed, if it did.
+    ///
+    /// This is `None` both if the lookup succeeded and if there was no number to look up.
+    #[inline]
+    pub fn lookup_error(&self) -> Option<&Error> {
+        self.lookup_error
+    }
+}
+
+/// Decides whether to accept inbound calls.
+///
+/// This is implemented for all functions and closures taking a [`Caller`](./struct.Caller.html).
+pub trait ScreeningPolicy {
+    /// Returns whether to accept a call from `caller`.
+    fn screen(&self, caller: &Caller<'_>) -> Decision;
+}
+
+impl<F> ScreeningPolicy for F
+where
+    F: Fn(&Caller<'_>) -> Decision,
+{
+    fn screen(&self, caller: &Caller<'_>) -> Decision {
+        self(caller)
+    }
+}
+
+/// Screens inbound calls, connecting those accepted by a [`ScreeningPolicy`] to an endpoint.
+///
+/// See the [module documentation](./index.html) for an example.
+///
+/// [`ScreeningPolicy`]: ./trait.ScreeningPolicy.html
+pub struct CallScreener<P, C = DefaultTransport> {
+    number_insight: NumberInsightApi<C>,
+    policy: Arc<P>,
+    connect_to: Endpoint,
+    caller_id: Option<PhoneNumber>,
+    reject_message: Option<String>,
+}
+
+impl<P, C> CallScreener<P, C> {
+    /// Creates a new `CallScreener` which looks up callers with `number_insight`, and connects
+    /// calls accepted by `policy` to `connect_to`.
+    pub fn new(number_insight: NumberInsightApi<C>, policy: P, connect_to: Endpoint) -> Self {
+        CallScreener {
+            number_insight,
+            policy: Arc::new(policy),
+            connect_to,
+            caller_id: None,
+            reject_message: None,
+        }
+    }
+
+    /// Sets the virtual number shown as the caller when connecting accepted calls to a phone
+    /// number.
+    ///
+    /// By default, the number which was called is shown.
+    pub fn caller_id(mut self, number: &PhoneNumber) -> Self {
+        self.caller_id = Some(number.clone());
+        self
+    }
+
+    /// Reads out `text` to rejected callers before hanging up.
+    ///
+    /// By default, rejected calls are hung up without a message.
+    pub fn reject_message(mut self, text: impl Into<String>) -> Self {
+        self.reject_message = Some(text.into());
+        self
+    }
+}
+
+impl<P: ScreeningPolicy, C: HttpTransport> CallScreener<P, C> {
+    /// Looks up the caller of `answer` and applies the policy, returning the NCCO to respond
+    /// with.
+    ///
+    /// Failed lookups are passed to the policy as a [`Caller`] without an insight, so that it can
+    /// decide whether to fail open or closed.
+    ///
+    /// [`Caller`]: ./struct.Caller.html
+    pub async fn screen(&self, answer: &AnswerRequest) -> Screening {
+        let number = parse_number(&answer.from);
+        let lookup = match number.as_ref() {
+            Some(number) => Some(self.number_insight.standard(number).send().await),
+            None => None,
+        };
+
+        let caller = Caller {
+            from: &answer.from,
+            number: number.as_ref(),
+            insight: lookup.as_ref().and_then(|lookup| lookup.as_ref().ok()),
+            lookup_error: lookup.as_ref().and_then(|lookup| lookup.as_ref().err()),
+        };
+        let decision = self.policy.screen(&caller);
+
+        let ncco = match decision {
+            Decision::Allow => {
+                let mut connect = Connect::new(self.connect_to.clone());
+                let caller_id = self.caller_id.clone().or_else(|| parse_number(&answer.to));
+                if let Some(caller_id) = caller_id.as_ref() {
+                    connect = connect.from(caller_id);
+                }
+                vec![connect.into()]
+            }
+            Decision::Deny => self
+                .reject_message
+                .iter()
+                .map(|text| Talk::new(text).into())
+                .collect(),
+        };
+
+        Screening {
+            decision,
+            insight: lookup.and_then(Result::ok),
+            ncco,
+        }
+    }
+}
+
+fn parse_number(number: &str) -> Option<PhoneNumber> {
+    let digits = number.trim_start_matches('+');
+    let is_number = !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit());
+    if is_number {
+        crate::phone::parse(&format!("+{}", digits), None).ok()
+    } else {
+        None
+    }
+}
+
+impl<P, C> Clone for CallScreener<P, C> {
+    fn clone(&self) -> Self {
+        CallScreener {
+            number_insight: self.number_insight.clone(),
+            policy: self.policy.clone(),
+            connect_to: self.connect_to.clone(),
+            caller_id: self.caller_id.clone(),
+            reject_message: self.reject_message.clone(),
+        }
+    }
+}
+
+impl<P, C> Debug for CallScreener<P, C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(CallScreener))
+            .field("number_insight", &self.number_insight)
+            .field("connect_to", &self.connect_to)
+            .field("caller_id", &self.caller_id)
+            .field("reject_message", &self.reject_message)
+            .finish()
+    }
+}
+
+/// The outcome of screening a call.
+///
+/// This is returned from [`CallScreener::screen()`](./struct.CallScreener.html#method.screen),
+/// and converts into an [`NccoResponse`](../../webhooks/struct.NccoResponse.html).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Screening {
+    decision: Decision,
+    insight: Option<StandardInsight>,
+    ncco: Vec<Action>,
+}
+
+impl Screening {
+    /// Returns whether the call was accepted.
+    #[inline]
+    pub fn decision(&self) -> Decision {
+        self.decision
+    }
+
+    /// Returns the result of looking up the caller, if the lookup succeeded.
+    #[inline]
+    pub fn insight(&self) -> Option<&StandardInsight> {
+        self.insight.as_ref()
+    }
+
+    /// Returns the NCCO which connects or rejects the call.
+    #[inline]
+    pub fn ncco(&self) -> &[Action] {
+        &self.ncco
+    }
+}
+
+impl From<Screening> for NccoResponse {
+    fn from(screening: Screening) -> Self {
+        NccoResponse::new(&screening.ncco)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use bytes::Bytes;
+    use http::Request;
+    use http_body_util::Full;
+    use serde_json::json;
+    use tower::service_fn;
+
+    use crate::Client;
+
+    fn answer(from: &str) -> AnswerRequest {
+        AnswerRequest {
+            uuid: "63f61863-4a51-4f6b-86e1-46edebcf9356".into(),
+            conversation_uuid: "CON-f972836a-550f-45fa-956c-12a2ab5b7d22".into(),
+            from: from.into(),
+            to: "12025550123".into(),
+            region_url: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn connects_or_rejects_callers() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(request.uri().path(), "/ni/standard/json");
+            let landline = request
+                .uri()
+                .query()
+                .unwrap()
+                .contains("number=14155550100");
+            let network_type = if landline { "landline" } else { "mobile" };
+            let body = json!({
+                "status": 0,
+                "request_id": "aaaa",
+                "international_format_number": "14155550100",
+                "national_format_number": "(415) 555-0100",
+                "country_code": "US",
+                "country_code_iso3": "USA",
+                "country_name": "United States of America",
+                "country_prefix": "1",
+                "current_carrier": {
+                    "network_code": "310410",
+                    "name": "AT&T Mobility",
+                    "country": "US",
+                    "network_type": network_type
+                }
+            });
+            let body = Bytes::from(body.to_string());
+            Ok::<_, Infallible>(http::Response::new(Full::new(body)))
+        });
+
+        let client = Client::from_service(service)
+            .api_key("key", "secret")
+            .build()
+            .unwrap();
+
+        let agent = crate::phone::parse("+14155550199", None).unwrap();
+        let policy = |caller: &Caller| match caller.insight() {
+            Some(insight) if insight.is_landline() => Decision::Deny,
+            Some(_) => Decision::Allow,
+            None => Decision::Deny,
+        };
+        let screener = CallScreener::new(client.number_insight(), policy, Endpoint::phone(&agent))
+            .reject_message("Goodbye");
+
+        let screening = screener.screen(&answer("14155550188")).await;
+        assert_eq!(screening.decision(), Decision::Allow);
+        assert!(screening.insight().is_some());
+        assert_eq!(
+            serde_json::to_value(screening.ncco()).unwrap(),
+            json!([{
+                "action": "connect",
+                "endpoint": [{"type": "phone", "number": "14155550199"}],
+                "from": "12025550123"
+            }])
+        );
+
+        let screening = screener.screen(&answer("14155550100")).await;
+        assert_eq!(screening.decision(), Decision::Deny);
+        assert_eq!(
+            serde_json::to_value(screening.ncco()).unwrap(),
+            json!([{"action": "talk", "text": "Goodbye"}])
+        );
+
+        let screening = screener.screen(&answer("Unknown")).await;
+        assert_eq!(screening.decision(), Decision::Deny);
+        assert!(screening.insight().is_none());
+    }
+}
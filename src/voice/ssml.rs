@@ -0,0 +1,438 @@
+//! A typed builder for [SSML] in text-to-speech talk actions.
+//!
+//! The `text` of a talk action may contain SSML markup to control pauses, pronunciation and
+//! intonation. Malformed markup is not rejected by Vonage, but read out as plain text instead,
+//! tags included. [`Ssml`] only produces well-formed documents, and escapes any text it is given.
+//!
+//! [SSML]: https://developer.nexmo.com/voice/voice-api/guides/customizing-tts
+//! [`Ssml`]: ./struct.Ssml.html
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use serde_json::json;
+//! use vonage::voice::ssml::{Prosody, Rate, SayAs, Ssml};
+//!
+//! let text = Ssml::new()
+//!     .text("Your code is ")
+//!     .say_as("1234", SayAs::Digits)
+//!     .pause(Duration::from_millis(500))
+//!     .prosody(Prosody::new().rate(Rate::Slow), Ssml::new().text("Goodbye & thanks"));
+//!
+//! assert_eq!(
+//!     text.to_string(),
+//!     "<speak>Your code is <say-as interpret-as=\"digits\">1234</say-as>\
+//!      <break time=\"500ms\"/><prosody rate=\"slow\">Goodbye &amp; thanks</prosody></speak>"
+//! );
+//!
+//! let ncco = json!([{ "action": "talk", "text": text }]);
+//! ```
+
+use std::fmt::{self, Display, Formatter, Write};
+use std::time::Duration;
+
+use serde::{Serialize, Serializer};
+
+/// The longest pause supported by a single `<break>` element.
+pub const MAX_PAUSE: Duration = Duration::from_secs(10);
+
+/// An SSML document for a text-to-speech talk action.
+///
+/// The document is rendered with its `<speak>` root element through `Display`, and serializes as
+/// a JSON string, so it can be used directly as the `text` of a talk action.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Ssml {
+    body: String,
+}
+
+impl Ssml {
+    /// Creates a new, empty `Ssml` document.
+    pub fn new() -> Self {
+        Ssml::default()
+    }
+
+    /// Appends plain text, which is escaped as needed.
+    pub fn text(mut self, text: &str) -> Self {
+        escape_into(&mut self.body, text);
+        self
+    }
+
+    /// Appends a pause of the given duration.
+    ///
+    /// Durations longer than [`MAX_PAUSE`](./constant.MAX_PAUSE.html) are shortened to it.
+    pub fn pause(mut self, duration: Duration) -> Self {
+        let millis = duration.min(MAX_PAUSE).as_millis();
+        let _ = write!(self.body, r#"<break time="{}ms"/>"#, millis);
+        self
+    }
+
+    /// Appends a pause of the given strength, whose duration is chosen by the voice.
+    pub fn pause_strength(mut self, strength: Strength) -> Self {
+        let _ = write!(self.body, r#"<break strength="{}"/>"#, strength.as_str());
+        self
+    }
+
+    /// Appends `text`, which is read out as the given kind of content.
+    pub fn say_as(mut self, text: &str, kind: SayAs) -> Self {
+        let _ = write!(
+            self.body,
+            r#"<say-as interpret-as="{}""#,
+            kind.interpret_as()
+        );
+        if let SayAs::Date(format) = kind {
+            let _ = write!(self.body, r#" format="{}""#, format.as_str());
+        }
+        self.body.push('>');
+        escape_into(&mut self.body, text);
+        self.body.push_str("</say-as>");
+        self
+    }
+
+    /// Appends `text`, which is pronounced according to the phonetic transcription `ph`.
+    pub fn phoneme(mut self, text: &str, ph: &str, alphabet: Alphabet) -> Self {
+        let _ = write!(
+            self.body,
+            r#"<phoneme alphabet="{}" ph=""#,
+            alphabet.as_str()
+        );
+        escape_into(&mut self.body, ph);
+        self.body.push_str(r#"">"#);
+        escape_into(&mut self.body, text);
+        self.body.push_str("</phoneme>");
+        self
+    }
+
+    /// Appends `content`, which is read out with the given rate, pitch and volume.
+    ///
+    /// If `prosody` leaves everything unchanged, `content` is appended as-is.
+    pub fn prosody(mut self, prosody: Prosody, content: Ssml) -> Self {
+        if prosody == Prosody::default() {
+            self.body.push_str(&content.body);
+            return self;
+        }
+
+        self.body.push_str("<prosody");
+        if let Some(rate) = prosody.rate {
+            let _ = write!(self.body, r#" rate="{}""#, rate);
+        }
+        if let Some(pitch) = prosody.pitch {
+            let _ = write!(self.body, r#" pitch="{}""#, pitch);
+        }
+        if let Some(volume) = prosody.volume {
+            let _ = write!(self.body, r#" volume="{}""#, volume);
+        }
+        self.body.push('>');
+        self.body.push_str(&content.body);
+        self.body.push_str("</prosody>");
+        self
+    }
+
+    /// Returns whether nothing was appended to this document yet.
+    pub fn is_empty(&self) -> bool {
+        self.body.is_empty()
+    }
+}
+
+impl Display for Ssml {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "<speak>{}</speak>", self.body)
+    }
+}
+
+impl Serialize for Ssml {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// The strength of a pause, from none to longest.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Strength {
+    /// No pause at all, e.g. to suppress a pause which would otherwise follow a comma.
+    None,
+    /// A very short pause.
+    XWeak,
+    /// A short pause.
+    Weak,
+    /// A pause of the same length as after a comma.
+    Medium,
+    /// A pause of the same length as after a sentence.
+    Strong,
+    /// A pause of the same length as after a paragraph.
+    XStrong,
+}
+
+impl Strength {
+    fn as_str(self) -> &'static str {
+        match self {
+            Strength::None => "none",
+            Strength::XWeak => "x-weak",
+            Strength::Weak => "weak",
+            Strength::Medium => "medium",
+            Strength::Strong => "strong",
+            Strength::XStrong => "x-strong",
+        }
+    }
+}
+
+/// How text inside a `<say-as>` element is read out.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum SayAs {
+    /// Spelled out letter by letter, e.g. for codes or acronyms.
+    Characters,
+    /// Read as individual digits, e.g. "1 2 3 4" for `1234`.
+    Digits,
+    /// Read as a number, e.g. "one thousand two hundred thirty four" for `1234`.
+    Cardinal,
+    /// Read as an ordinal number, e.g. "first" for `1`.
+    Ordinal,
+    /// Read as a fraction, e.g. "three halves" for `3/2`.
+    Fraction,
+    /// Read as a phone number.
+    Telephone,
+    /// Read as a date in the given format.
+    Date(DateFormat),
+    /// Read as a time or duration, e.g. `1'21"`.
+    Time,
+    /// Read as a street address.
+    Address,
+    /// Read as a measurement with a unit, e.g. `10kg`.
+    Unit,
+    /// Bleeped out.
+    Expletive,
+}
+
+impl SayAs {
+    fn interpret_as(self) -> &'static str {
+        match self {
+            SayAs::Characters => "characters",
+            SayAs::Digits => "digits",
+            SayAs::Cardinal => "cardinal",
+            SayAs::Ordinal => "ordinal",
+            SayAs::Fraction => "fraction",
+            SayAs::Telephone => "telephone",
+            SayAs::Date(_) => "date",
+            SayAs::Time => "time",
+            SayAs::Address => "address",
+            SayAs::Unit => "unit",
+            SayAs::Expletive => "expletive",
+        }
+    }
+}
+
+/// The order of the components of a date read out with
+/// [`SayAs::Date`](./enum.SayAs.html#variant.Date).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DateFormat {
+    Mdy,
+    Dmy,
+    Ymd,
+    Md,
+    Dm,
+    Ym,
+    My,
+    D,
+    M,
+    Y,
+}
+
+impl DateFormat {
+    fn as_str(self) -> &'static str {
+        match self {
+            DateFormat::Mdy => "mdy",
+            DateFormat::Dmy => "dmy",
+            DateFormat::Ymd => "ymd",
+            DateFormat::Md => "md",
+            DateFormat::Dm => "dm",
+            DateFormat::Ym => "ym",
+            DateFormat::My => "my",
+            DateFormat::D => "d",
+            DateFormat::M => "m",
+            DateFormat::Y => "y",
+        }
+    }
+}
+
+/// The phonetic alphabet of a transcription in a `<phoneme>` element.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Alphabet {
+    /// The International Phonetic Alphabet.
+    Ipa,
+    /// The Extended Speech Assessment Methods Phonetic Alphabet.
+    XSampa,
+}
+
+impl Alphabet {
+    fn as_str(self) -> &'static str {
+        match self {
+            Alphabet::Ipa => "ipa",
+            Alphabet::XSampa => "x-sampa",
+        }
+    }
+}
+
+/// The rate, pitch and volume of speech inside a `<prosody>` element.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Prosody {
+    rate: Option<Rate>,
+    pitch: Option<Pitch>,
+    volume: Option<Volume>,
+}
+
+impl Prosody {
+    /// Creates a new `Prosody` which leaves the speech unchanged.
+    pub fn new() -> Self {
+        Prosody::default()
+    }
+
+    /// Sets the speaking rate.
+    pub fn rate(mut self, rate: Rate) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+
+    /// Sets the pitch.
+    pub fn pitch(mut self, pitch: Pitch) -> Self {
+        self.pitch = Some(pitch);
+        self
+    }
+
+    /// Sets the volume.
+    pub fn volume(mut self, volume: Volume) -> Self {
+        self.volume = Some(volume);
+        self
+    }
+}
+
+/// A speaking rate.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Rate {
+    XSlow,
+    Slow,
+    Medium,
+    Fast,
+    XFast,
+    /// A percentage of the default rate, from 20% to 200%. Other values are clamped.
+    Percent(u16),
+}
+
+impl Display for Rate {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Rate::XSlow => f.write_str("x-slow"),
+            Rate::Slow => f.write_str("slow"),
+            Rate::Medium => f.write_str("medium"),
+            Rate::Fast => f.write_str("fast"),
+            Rate::XFast => f.write_str("x-fast"),
+            Rate::Percent(percent) => write!(f, "{}%", percent.clamp(20, 200)),
+        }
+    }
+}
+
+/// A pitch of speech.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Pitch {
+    XLow,
+    Low,
+    Medium,
+    High,
+    XHigh,
+    /// A change relative to the default pitch, from -33% to +50%. Other values are clamped.
+    Percent(i8),
+}
+
+impl Display for Pitch {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Pitch::XLow => f.write_str("x-low"),
+            Pitch::Low => f.write_str("low"),
+            Pitch::Medium => f.write_str("medium"),
+            Pitch::High => f.write_str("high"),
+            Pitch::XHigh => f.write_str("x-high"),
+            Pitch::Percent(percent) => write!(f, "{:+}%", percent.clamp(-33, 50)),
+        }
+    }
+}
+
+/// A volume of speech.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Volume {
+    Silent,
+    XSoft,
+    Soft,
+    Medium,
+    Loud,
+    XLoud,
+    /// A change relative to the default volume in decibels, from -6dB to +6dB. Other values are
+    /// clamped.
+    Decibels(i8),
+}
+
+impl Display for Volume {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Volume::Silent => f.write_str("silent"),
+            Volume::XSoft => f.write_str("x-soft"),
+            Volume::Soft => f.write_str("soft"),
+            Volume::Medium => f.write_str("medium"),
+            Volume::Loud => f.write_str("loud"),
+            Volume::XLoud => f.write_str("x-loud"),
+            Volume::Decibels(db) => write!(f, "{:+}dB", db.clamp(-6, 6)),
+        }
+    }
+}
+
+fn escape_into(buf: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            '"' => buf.push_str("&quot;"),
+            '\'' => buf.push_str("&apos;"),
+            c => buf.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_escaped_markup() {
+        let ssml = Ssml::new()
+            .text("<b>\"R&D\"</b> ")
+            .say_as("12/25", SayAs::Date(DateFormat::Md))
+            .pause(Duration::from_secs(30))
+            .pause_strength(Strength::XStrong)
+            .phoneme("pecan", "pɪˈkɑːn", Alphabet::Ipa)
+            .prosody(Prosody::new(), Ssml::new().text("plain"))
+            .prosody(
+                Prosody::new()
+                    .rate(Rate::Percent(500))
+                    .pitch(Pitch::Percent(10))
+                    .volume(Volume::Decibels(-3)),
+                Ssml::new().text("it's loud"),
+            );
+
+        assert_eq!(
+            ssml.to_string(),
+            concat!(
+                "<speak>&lt;b&gt;&quot;R&amp;D&quot;&lt;/b&gt; ",
+                r#"<say-as interpret-as="date" format="md">12/25</say-as>"#,
+                r#"<break time="10000ms"/><break strength="x-strong"/>"#,
+                r#"<phoneme alphabet="ipa" ph="pɪˈkɑːn">pecan</phoneme>plain"#,
+                r#"<prosody rate="200%" pitch="+10%" volume="-3dB">it&apos;s loud</prosody>"#,
+                "</speak>",
+            )
+        );
+
+        let json = serde_json::to_string(&Ssml::new().text("hi")).unwrap();
+        assert_eq!(json, r#""<speak>hi</speak>""#);
+        assert!(Ssml::new().is_empty());
+    }
+}
@@ -0,0 +1,186 @@
+//! Retrieval of transcripts of call recordings.
+
+use std::fmt::{self, Debug, Formatter};
+
+use bytes::Bytes;
+use http::header::ACCEPT;
+use http::{Method, Request, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::auth::Auth;
+use crate::http_client::HttpClient;
+use crate::transport::HttpTransport;
+use crate::{Error, ErrorKind, Product, Result};
+
+/// A request for the transcript of a recording.
+///
+/// This is returned from [`VoiceApi::transcript()`](./struct.VoiceApi.html#method.transcript).
+pub struct FetchTranscript<C> {
+    http_client: HttpClient<C>,
+    request: Result<Request<Bytes>>,
+}
+
+impl<C> FetchTranscript<C> {
+    pub(crate) fn new(http_client: HttpClient<C>, auth: &Auth, url: &str) -> Self {
+        let request = auth.to_preferred_auth_header().and_then(|(name, value)| {
+            Request::builder()
+                .method(Method::GET)
+                .uri(crate::resolve_url(url))
+                .header(ACCEPT, "application/json")
+                .header(name, value)
+                .body(Bytes::new())
+                .map_err(|e| Error::with_cause(ErrorKind::Http, e))
+        });
+
+        FetchTranscript {
+            http_client,
+            request,
+        }
+    }
+}
+
+impl<C: HttpTransport> FetchTranscript<C> {
+    /// Sends the request.
+    ///
+    /// Returns an error of kind [`ErrorKind::Status`] with `404 Not Found` if the transcription
+    /// has not completed yet, or was already deleted.
+    ///
+    /// [`ErrorKind::Status`]: ../enum.ErrorKind.html#variant.Status
+    pub async fn send(self) -> Result<Transcript> {
+        self.http_client
+            .send(Product::Voice, self.request?, |response| {
+                match response.status() {
+                    StatusCode::OK => {}
+                    _ => return Err(Error::new_status(&response)),
+                }
+
+                crate::http_client::decode_json(response.body())
+            })
+            .await
+    }
+}
+
+impl<C> Debug for FetchTranscript<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(FetchTranscript))
+            .field("request", &self.request.as_ref().map(Request::uri))
+            .finish()
+    }
+}
+
+/// The transcript of a call recording.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Transcript {
+    /// The transcribed audio channels, i.e. one per leg if the recording was split.
+    #[serde(default)]
+    pub channels: Vec<TranscriptChannel>,
+}
+
+impl Transcript {
+    /// Returns the text of all channels, one channel per line.
+    pub fn text(&self) -> String {
+        let channels: Vec<_> = self.channels.iter().map(TranscriptChannel::text).collect();
+        channels.join("\n")
+    }
+}
+
+/// The transcript of a single audio channel of a recording.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TranscriptChannel {
+    /// The sentences spoken on this channel, in chronological order.
+    #[serde(default)]
+    pub transcript: Vec<Sentence>,
+}
+
+impl TranscriptChannel {
+    /// Returns the text of all sentences, separated by spaces.
+    pub fn text(&self) -> String {
+        let sentences: Vec<_> = self
+            .transcript
+            .iter()
+            .map(|s| s.sentence.as_str())
+            .collect();
+        sentences.join(" ")
+    }
+}
+
+/// A transcribed sentence.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Sentence {
+    /// The sentence with punctuation and capitalization applied.
+    pub sentence: String,
+    /// The sentence as recognized, without punctuation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_sentence: Option<String>,
+    /// The offset of the sentence from the start of the recording, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<f64>,
+    /// The length of the sentence, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+    /// The individual words of the sentence.
+    #[serde(default)]
+    pub words: Vec<Word>,
+}
+
+/// A transcribed word.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Word {
+    /// The recognized word.
+    pub word: String,
+    /// The offset of the start of the word from the start of the recording, in seconds.
+    pub start_time: f64,
+    /// The offset of the end of the word from the start of the recording, in seconds.
+    pub end_time: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use http_body_util::Full;
+    use tower::service_fn;
+
+    use crate::Client;
+
+    #[tokio::test]
+    async fn fetches_transcripts_with_jwt() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            assert_eq!(
+                request.uri().path(),
+                "/v1/files/aaaaaaaa-bbbb-cccc-dddd-0123456789ab"
+            );
+            assert!(request.headers()["authorization"]
+                .to_str()
+                .unwrap()
+                .starts_with("Bearer "));
+
+            let body = r#"{"channels":[
+                {"transcript":[
+                    {"sentence":"Hello.","raw_sentence":"hello","timestamp":0.5,"duration":0.4,
+                     "words":[{"word":"Hello","start_time":0.5,"end_time":0.9}]},
+                    {"sentence":"How can I help?","words":[]}
+                ]},
+                {"transcript":[{"sentence":"Hi there."}]}
+            ]}"#;
+            Ok::<_, Infallible>(http::Response::new(Full::new(Bytes::from(body))))
+        });
+
+        let client = Client::from_service(service)
+            .jwt("app_id", crate::auth::TEST_PRIVATE_KEY)
+            .build()
+            .unwrap();
+
+        let transcript = client
+            .voice()
+            .transcript("/v1/files/aaaaaaaa-bbbb-cccc-dddd-0123456789ab")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(transcript.text(), "Hello. How can I help?\nHi there.");
+        assert_eq!(transcript.channels[0].transcript[0].words[0].end_time, 0.9);
+    }
+}
@@ -0,0 +1,290 @@
+//! Contains a helper which performs attended transfers between calls.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
+use phonenumber::PhoneNumber;
+
+use super::call::{transfer_ncco, update_call};
+use super::ncco::Conversation;
+use super::{Call, CreateCall, Endpoint};
+use crate::auth::Auth;
+use crate::http_client::HttpClient;
+use crate::transport::HttpTransport;
+use crate::validation::Validator;
+use crate::{DefaultTransport, Result};
+
+/// An attended transfer of a caller from an agent to a third party.
+///
+/// This is returned from
+/// [`VoiceApi::attended_transfer()`](./struct.VoiceApi.html#method.attended_transfer). A transfer
+/// moves the legs between two conferences named after the caller's leg:
+///
+/// 1. [`consult()`](#method.consult) puts the caller on hold, moves the agent to the consult
+///    conference, and dials the third party into it.
+/// 2. [`complete()`](#method.complete) joins the caller to the consult conference and hangs up
+///    the agent, while [`bridge()`](#method.bridge) keeps the agent on a three-way call.
+///    Alternatively, [`cancel()`](#method.cancel) hangs up the third party and reconnects the
+///    caller to the agent.
+///
+/// Clones share the same consult leg.
+///
+/// # Example
+///
+/// ```no_run
+/// use vonage::phone::{self, Country};
+/// use vonage::voice::Endpoint;
+/// use vonage::Client;
+///
+/// # async fn run(caller_uuid: &str, agent_uuid: &str) -> Result<(), Box<dyn std::error::Error>> {
+/// let client = Client::builder().jwt("<app_id>", "<private_key>").build()?;
+/// let supervisor = phone::parse("(415) 555-0199", Country::US)?;
+/// let from = phone::parse("(202) 555-0123", Country::US)?;
+///
+/// let transfer = client
+///     .voice()
+///     .attended_transfer(caller_uuid, agent_uuid)
+///     .hold_music("https://example.com/audio/hold.mp3");
+/// transfer.consult(Endpoint::phone(&supervisor), &from).await?;
+/// // Once the agent has briefed the supervisor:
+/// transfer.complete().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AttendedTransfer<C = DefaultTransport> {
+    http_client: HttpClient<C>,
+    auth: Auth,
+    caller_uuid: String,
+    agent_uuid: String,
+    hold_music: Option<String>,
+    consult_leg: Arc<Mutex<Option<String>>>,
+}
+
+impl<C> AttendedTransfer<C> {
+    pub(crate) fn new(
+        http_client: HttpClient<C>,
+        auth: Auth,
+        caller_uuid: String,
+        agent_uuid: String,
+    ) -> Self {
+        AttendedTransfer {
+            http_client,
+            auth,
+            caller_uuid,
+            agent_uuid,
+            hold_music: None,
+            consult_leg: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Plays the audio file at `url` to the caller while on hold.
+    ///
+    /// By default, the caller hears silence.
+    pub fn hold_music(mut self, url: impl Into<String>) -> Self {
+        self.hold_music = Some(url.into());
+        self
+    }
+
+    /// Returns the UUID of the leg of the third party, once [consulted](#method.consult).
+    pub fn consult_leg(&self) -> Option<String> {
+        self.consult_leg.lock().unwrap().clone()
+    }
+
+    fn hold_room(&self) -> Conversation {
+        let room = Conversation::new(format!("transfer-{}-hold", self.caller_uuid));
+        match self.hold_music.as_ref() {
+            Some(url) => room.music_on_hold_url(url.clone()).start_on_enter(false),
+            None => room,
+        }
+    }
+
+    fn consult_room(&self) -> Conversation {
+        Conversation::new(format!("transfer-{}-consult", self.caller_uuid))
+    }
+
+    fn take_consult_leg(&self) -> Result<String> {
+        let consult_leg = self.consult_leg.lock().unwrap().take();
+
+        let mut validator = Validator::new();
+        validator.check(
+            consult_leg.is_some(),
+            "consult_leg",
+            "a third party must have been consulted",
+        );
+        validator.finish()?;
+
+        Ok(consult_leg.unwrap())
+    }
+}
+
+impl<C: HttpTransport> AttendedTransfer<C> {
+    /// Puts the caller on hold, and connects the agent to a new call from the virtual number
+    /// `from` to `to`.
+    pub async fn consult(&self, to: Endpoint, from: &PhoneNumber) -> Result<Call<C>> {
+        let (http_client, auth) = (&self.http_client, &self.auth);
+        transfer_ncco(http_client, auth, &self.caller_uuid, &[self.hold_room()]).await?;
+        transfer_ncco(http_client, auth, &self.agent_uuid, &[self.consult_room()]).await?;
+
+        let call = CreateCall::new(http_client.clone(), auth.clone(), to, from)
+            .ncco(&[self.consult_room()])
+            .send()
+            .await?;
+
+        *self.consult_leg.lock().unwrap() = Some(call.uuid().to_owned());
+        Ok(call)
+    }
+
+    /// Connects the caller to the third party, and hangs up the agent.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequest`] without sending anything if no third
+    /// party was [consulted](#method.consult).
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../enum.ErrorKind.html#variant.InvalidRequest
+    pub async fn complete(&self) -> Result<()> {
+        self.bridge().await?;
+        let body = br#"{"action":"hangup"}"#.to_vec();
+        update_call(&self.http_client, &self.auth, &self.agent_uuid, body).await
+    }
+
+    /// Connects the caller to both the agent and the third party.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequest`] without sending anything if no third
+    /// party was [consulted](#method.consult).
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../enum.ErrorKind.html#variant.InvalidRequest
+    pub async fn bridge(&self) -> Result<()> {
+        let consult_leg = self.take_consult_leg()?;
+        let room = [self.consult_room()];
+        let result = transfer_ncco(&self.http_client, &self.auth, &self.caller_uuid, &room).await;
+        if result.is_err() {
+            *self.consult_leg.lock().unwrap() = Some(consult_leg);
+        }
+        result
+    }
+
+    /// Hangs up the third party, and reconnects the caller to the agent.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequest`] without sending anything if no third
+    /// party was [consulted](#method.consult).
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../enum.ErrorKind.html#variant.InvalidRequest
+    pub async fn cancel(&self) -> Result<()> {
+        let consult_leg = self.take_consult_leg()?;
+        let body = br#"{"action":"hangup"}"#.to_vec();
+        if let Err(e) = update_call(&self.http_client, &self.auth, &consult_leg, body).await {
+            *self.consult_leg.lock().unwrap() = Some(consult_leg);
+            return Err(e);
+        }
+
+        let room = [self.consult_room()];
+        transfer_ncco(&self.http_client, &self.auth, &self.caller_uuid, &room).await
+    }
+}
+
+impl<C> Clone for AttendedTransfer<C> {
+    fn clone(&self) -> Self {
+        AttendedTransfer {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+            caller_uuid: self.caller_uuid.clone(),
+            agent_uuid: self.agent_uuid.clone(),
+            hold_music: self.hold_music.clone(),
+            consult_leg: self.consult_leg.clone(),
+        }
+    }
+}
+
+impl<C> Debug for AttendedTransfer<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(AttendedTransfer))
+            .field("caller_uuid", &self.caller_uuid)
+            .field("agent_uuid", &self.agent_uuid)
+            .field("hold_music", &self.hold_music)
+            .field("consult_leg", &self.consult_leg.lock().unwrap())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use bytes::Bytes;
+    use http::{Method, Request};
+    use http_body_util::{BodyExt, Full};
+    use serde_json::{json, Value};
+    use tower::service_fn;
+
+    use crate::Client;
+
+    #[tokio::test]
+    async fn moves_legs_between_conferences() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let service = service_fn({
+            let log = log.clone();
+            move |request: Request<Full<Bytes>>| {
+                let log = log.clone();
+                async move {
+                    let (parts, body) = request.into_parts();
+                    let body: Value =
+                        serde_json::from_slice(&body.collect().await.unwrap().to_bytes()).unwrap();
+
+                    let leg = parts.uri.path().trim_start_matches("/v1/calls").to_owned();
+                    let entry = match body["action"].as_str() {
+                        Some("transfer") => {
+                            let room = &body["destination"]["ncco"][0]["name"];
+                            format!("{} -> {}", leg, room.as_str().unwrap())
+                        }
+                        Some(action) => format!("{} {}", leg, action),
+                        None => format!("dial -> {}", body["ncco"][0]["name"].as_str().unwrap()),
+                    };
+                    log.lock().unwrap().push(entry);
+
+                    let body = match parts.method {
+                        Method::POST => json!({
+                            "uuid": "third",
+                            "conversation_uuid": "CON-1",
+                            "status": "started",
+                            "direction": "outbound"
+                        }),
+                        _ => json!({}),
+                    };
+                    let body = Bytes::from(body.to_string());
+                    Ok::<_, Infallible>(http::Response::new(Full::new(body)))
+                }
+            }
+        });
+
+        let client = Client::from_service(service)
+            .jwt("app_id", crate::auth::TEST_PRIVATE_KEY)
+            .build()
+            .unwrap();
+        let transfer = client.voice().attended_transfer("caller", "agent");
+
+        let error = transfer.complete().await.unwrap_err();
+        assert!(error.as_invalid_request().is_some());
+
+        let to = crate::phone::parse("+14155550199", None).unwrap();
+        let from = crate::phone::parse("+12025550123", None).unwrap();
+        transfer.consult(Endpoint::phone(&to), &from).await.unwrap();
+        assert_eq!(transfer.consult_leg().as_deref(), Some("third"));
+        transfer.cancel().await.unwrap();
+
+        transfer.consult(Endpoint::phone(&to), &from).await.unwrap();
+        transfer.complete().await.unwrap();
+        assert_eq!(transfer.consult_leg(), None);
+
+        let consult = [
+            "/caller -> transfer-caller-hold",
+            "/agent -> transfer-caller-consult",
+            "dial -> transfer-caller-consult",
+        ];
+        let mut expected = consult.to_vec();
+        expected.extend(&["/third hangup", "/caller -> transfer-caller-consult"]);
+        expected.extend(&consult);
+        expected.extend(&["/caller -> transfer-caller-consult", "/agent hangup"]);
+        assert_eq!(*log.lock().unwrap(), expected);
+    }
+}
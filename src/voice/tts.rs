@@ -0,0 +1,267 @@
+//! The catalog of text-to-speech voices supported by the Voice API.
+//!
+//! Text-to-speech voices are selected by a BCP-47 language code and a numeric style, where each
+//! style of a language has its own gender and accent. Premium voices sound more natural, but are
+//! billed at a higher rate. This catalog reflects the voices documented on the
+//! [text-to-speech guide](https://developer.nexmo.com/voice/voice-api/guides/text-to-speech), and
+//! can be used to present valid options to users or to validate a selection before placing a call.
+//!
+//! # Example
+//!
+//! ```
+//! use vonage::voice::tts::{self, Gender};
+//!
+//! let british_male: Vec<_> = tts::voices()
+//!     .iter()
+//!     .filter(|voice| voice.language() == "en-GB" && voice.gender() == Gender::Male)
+//!     .map(|voice| voice.style())
+//!     .collect();
+//! assert!(!british_male.is_empty());
+//!
+//! assert!(tts::find("en-gb", 0).is_some());
+//! assert!(tts::find("en-GB", 99).is_none());
+//! ```
+
+use self::Gender::{Female as F, Male as M};
+
+/// The perceived gender of a text-to-speech voice.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Gender {
+    /// A female voice.
+    Female,
+    /// A male voice.
+    Male,
+}
+
+/// A text-to-speech voice, identified by its language and style.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Voice {
+    language: &'static str,
+    language_name: &'static str,
+    style: u8,
+    gender: Gender,
+    premium: bool,
+}
+
+impl Voice {
+    /// Returns the BCP-47 code of the language spoken by this voice, e.g. `en-GB`.
+    pub fn language(&self) -> &'static str {
+        self.language
+    }
+
+    /// Returns the English name of the language spoken by this voice, e.g. `English (UK)`.
+    pub fn language_name(&self) -> &'static str {
+        self.language_name
+    }
+
+    /// Returns the style of this voice, which is unique within its language.
+    pub fn style(&self) -> u8 {
+        self.style
+    }
+
+    /// Returns the perceived gender of this voice.
+    pub fn gender(&self) -> Gender {
+        self.gender
+    }
+
+    /// Returns whether this is a premium voice, which is billed at a higher rate.
+    pub fn is_premium(&self) -> bool {
+        self.premium
+    }
+}
+
+/// Returns all supported voices, ordered by language and style.
+pub fn voices() -> &'static [Voice] {
+    VOICES
+}
+
+/// Returns the BCP-47 codes of all supported languages, in alphabetical order.
+pub fn languages() -> impl Iterator<Item = &'static str> {
+    let mut previous = None;
+    VOICES.iter().filter_map(move |voice| {
+        if previous == Some(voice.language) {
+            return None;
+        }
+        previous = Some(voice.language);
+        Some(voice.language)
+    })
+}
+
+/// Returns the voice with the given language and style, if it is supported.
+///
+/// Language codes are compared case-insensitively.
+pub fn find(language: &str, style: u8) -> Option<&'static Voice> {
+    VOICES
+        .iter()
+        .find(|voice| voice.style == style && voice.language.eq_ignore_ascii_case(language))
+}
+
+const fn voice(
+    language: &'static str,
+    language_name: &'static str,
+    style: u8,
+    gender: Gender,
+    premium: bool,
+) -> Voice {
+    Voice {
+        language,
+        language_name,
+        style,
+        gender,
+        premium,
+    }
+}
+
+#[rustfmt::skip]
+static VOICES: &[Voice] = &[
+    voice("ar", "Arabic", 0, F, false),
+    voice("ar", "Arabic", 1, M, false),
+    voice("ar", "Arabic", 2, F, true),
+    voice("ar", "Arabic", 3, M, true),
+    voice("bg-BG", "Bulgarian", 0, F, false),
+    voice("ca-ES", "Catalan", 0, F, false),
+    voice("cmn-CN", "Chinese, Mandarin", 0, F, false),
+    voice("cmn-CN", "Chinese, Mandarin", 1, M, false),
+    voice("cmn-CN", "Chinese, Mandarin", 2, F, true),
+    voice("cmn-CN", "Chinese, Mandarin", 3, M, true),
+    voice("cs-CZ", "Czech", 0, F, false),
+    voice("cs-CZ", "Czech", 1, F, true),
+    voice("cy-GB", "Welsh", 0, F, false),
+    voice("da-DK", "Danish", 0, F, false),
+    voice("da-DK", "Danish", 1, M, false),
+    voice("da-DK", "Danish", 2, F, true),
+    voice("de-DE", "German", 0, F, false),
+    voice("de-DE", "German", 1, M, false),
+    voice("de-DE", "German", 2, F, false),
+    voice("de-DE", "German", 3, F, true),
+    voice("de-DE", "German", 4, M, true),
+    voice("el-GR", "Greek", 0, F, false),
+    voice("el-GR", "Greek", 1, F, true),
+    voice("en-AU", "English (Australia)", 0, F, false),
+    voice("en-AU", "English (Australia)", 1, M, false),
+    voice("en-AU", "English (Australia)", 2, F, false),
+    voice("en-AU", "English (Australia)", 3, F, true),
+    voice("en-AU", "English (Australia)", 4, M, true),
+    voice("en-GB", "English (UK)", 0, F, false),
+    voice("en-GB", "English (UK)", 1, F, false),
+    voice("en-GB", "English (UK)", 2, M, false),
+    voice("en-GB", "English (UK)", 3, F, true),
+    voice("en-GB", "English (UK)", 4, M, true),
+    voice("en-IN", "English (India)", 0, F, false),
+    voice("en-IN", "English (India)", 1, F, false),
+    voice("en-IN", "English (India)", 2, F, true),
+    voice("en-IN", "English (India)", 3, M, true),
+    voice("en-US", "English (US)", 0, F, false),
+    voice("en-US", "English (US)", 1, M, false),
+    voice("en-US", "English (US)", 2, F, false),
+    voice("en-US", "English (US)", 3, M, false),
+    voice("en-US", "English (US)", 4, F, false),
+    voice("en-US", "English (US)", 5, F, false),
+    voice("en-US", "English (US)", 6, F, true),
+    voice("en-US", "English (US)", 7, M, true),
+    voice("en-US", "English (US)", 8, F, true),
+    voice("en-US", "English (US)", 9, M, true),
+    voice("es-ES", "Spanish", 0, F, false),
+    voice("es-ES", "Spanish", 1, M, false),
+    voice("es-ES", "Spanish", 2, F, true),
+    voice("es-ES", "Spanish", 3, M, true),
+    voice("es-MX", "Spanish (Mexico)", 0, F, false),
+    voice("es-MX", "Spanish (Mexico)", 1, F, true),
+    voice("es-US", "Spanish (US)", 0, F, false),
+    voice("es-US", "Spanish (US)", 1, M, false),
+    voice("es-US", "Spanish (US)", 2, F, true),
+    voice("es-US", "Spanish (US)", 3, M, true),
+    voice("fi-FI", "Finnish", 0, F, false),
+    voice("fi-FI", "Finnish", 1, F, true),
+    voice("fr-CA", "French (Canada)", 0, F, false),
+    voice("fr-CA", "French (Canada)", 1, F, true),
+    voice("fr-CA", "French (Canada)", 2, M, true),
+    voice("fr-FR", "French", 0, F, false),
+    voice("fr-FR", "French", 1, M, false),
+    voice("fr-FR", "French", 2, F, true),
+    voice("fr-FR", "French", 3, M, true),
+    voice("hi-IN", "Hindi", 0, F, false),
+    voice("hi-IN", "Hindi", 1, F, true),
+    voice("hi-IN", "Hindi", 2, M, true),
+    voice("hu-HU", "Hungarian", 0, F, false),
+    voice("hu-HU", "Hungarian", 1, F, true),
+    voice("id-ID", "Indonesian", 0, F, false),
+    voice("id-ID", "Indonesian", 1, F, true),
+    voice("id-ID", "Indonesian", 2, M, true),
+    voice("is-IS", "Icelandic", 0, F, false),
+    voice("is-IS", "Icelandic", 1, M, false),
+    voice("it-IT", "Italian", 0, F, false),
+    voice("it-IT", "Italian", 1, M, false),
+    voice("it-IT", "Italian", 2, F, true),
+    voice("it-IT", "Italian", 3, M, true),
+    voice("ja-JP", "Japanese", 0, F, false),
+    voice("ja-JP", "Japanese", 1, M, false),
+    voice("ja-JP", "Japanese", 2, F, true),
+    voice("ja-JP", "Japanese", 3, M, true),
+    voice("ko-KR", "Korean", 0, F, false),
+    voice("ko-KR", "Korean", 1, F, true),
+    voice("ko-KR", "Korean", 2, M, true),
+    voice("nb-NO", "Norwegian", 0, F, false),
+    voice("nb-NO", "Norwegian", 1, F, true),
+    voice("nl-NL", "Dutch", 0, F, false),
+    voice("nl-NL", "Dutch", 1, M, false),
+    voice("nl-NL", "Dutch", 2, F, true),
+    voice("nl-NL", "Dutch", 3, M, true),
+    voice("pl-PL", "Polish", 0, F, false),
+    voice("pl-PL", "Polish", 1, M, false),
+    voice("pl-PL", "Polish", 2, F, true),
+    voice("pl-PL", "Polish", 3, M, true),
+    voice("pt-BR", "Portuguese (Brazil)", 0, F, false),
+    voice("pt-BR", "Portuguese (Brazil)", 1, M, false),
+    voice("pt-BR", "Portuguese (Brazil)", 2, F, true),
+    voice("pt-PT", "Portuguese", 0, F, false),
+    voice("pt-PT", "Portuguese", 1, M, false),
+    voice("pt-PT", "Portuguese", 2, F, true),
+    voice("ro-RO", "Romanian", 0, F, false),
+    voice("ro-RO", "Romanian", 1, F, true),
+    voice("ru-RU", "Russian", 0, F, false),
+    voice("ru-RU", "Russian", 1, M, false),
+    voice("ru-RU", "Russian", 2, F, true),
+    voice("sk-SK", "Slovak", 0, F, false),
+    voice("sv-SE", "Swedish", 0, F, false),
+    voice("sv-SE", "Swedish", 1, F, true),
+    voice("th-TH", "Thai", 0, F, false),
+    voice("th-TH", "Thai", 1, F, true),
+    voice("tr-TR", "Turkish", 0, F, false),
+    voice("tr-TR", "Turkish", 1, F, true),
+    voice("tr-TR", "Turkish", 2, M, true),
+    voice("uk-UA", "Ukrainian", 0, F, false),
+    voice("uk-UA", "Ukrainian", 1, F, true),
+    voice("vi-VN", "Vietnamese", 0, F, false),
+    voice("vi-VN", "Vietnamese", 1, F, true),
+    voice("vi-VN", "Vietnamese", 2, M, true),
+    voice("yue-CN", "Chinese, Cantonese", 0, F, false),
+    voice("yue-CN", "Chinese, Cantonese", 1, F, true),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_is_sorted_and_unique() {
+        for pair in VOICES.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            assert!(
+                (a.language, a.style) < (b.language, b.style),
+                "{}/{} is out of order",
+                b.language,
+                b.style
+            );
+        }
+
+        let languages: Vec<_> = languages().collect();
+        assert!(languages.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(languages.contains(&"cy-GB"));
+
+        let voice = find("EN-us", 6).unwrap();
+        assert_eq!(voice.language_name(), "English (US)");
+        assert!(voice.is_premium());
+        assert_eq!(find("xx-XX", 0), None);
+    }
+}
@@ -0,0 +1,284 @@
+//! Contains a utility which leaves prerecorded messages on answering machines.
+
+use std::fmt::{self, Debug, Formatter};
+
+use phonenumber::PhoneNumber;
+use serde::Serialize;
+
+use super::call::transfer_ncco;
+use super::ncco::Stream;
+use super::{Call, CreateCall, Endpoint, MachineDetection};
+use crate::auth::Auth;
+use crate::http_client::HttpClient;
+use crate::transport::HttpTransport;
+use crate::webhooks::VoiceEvent;
+use crate::{DefaultTransport, Result};
+
+/// Places calls which play a prerecorded message once an answering machine starts recording.
+///
+/// This is returned from [`VoiceApi::voicemail_drop()`](./struct.VoiceApi.html#method.voicemail_drop).
+/// Calls are placed with [advanced machine detection], and the [`VoiceEvent`]s sent to their event
+/// URL must be passed to [`handle_event()`](#method.handle_event). Once the beep of an answering
+/// machine is heard, or none was heard before the beep timeout, the call is transferred to an NCCO
+/// which streams the message, after which the call is hung up.
+///
+/// # Example
+///
+/// ```no_run
+/// use vonage::phone::{self, Country};
+/// use vonage::webhooks::Event;
+/// use vonage::Client;
+///
+/// # async fn run(event: Event) -> Result<(), Box<dyn std::error::Error>> {
+/// let client = Client::builder().jwt("<app_id>", "<private_key>").build()?;
+/// let to = phone::parse("(415) 555-0100", Country::US)?;
+/// let from = phone::parse("(202) 555-0123", Country::US)?;
+///
+/// let voicemail = client
+///     .voice()
+///     .voicemail_drop("https://example.com/audio/reminder.mp3")
+///     .human_ncco(&[vonage::voice::ncco::Talk::new("Please call us back.")]);
+/// voicemail
+///     .place(&to, &from, "https://example.com/webhooks/event")
+///     .await?;
+///
+/// // Later, in the handler of the event URL:
+/// if let Event::Voice(event) = event {
+///     voicemail.handle_event(&event).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [advanced machine detection]: ./struct.CreateCall.html#method.advanced_machine_detection
+/// [`VoiceEvent`]: ../webhooks/struct.VoiceEvent.html
+pub struct VoicemailDrop<C = DefaultTransport> {
+    http_client: HttpClient<C>,
+    auth: Auth,
+    audio_url: String,
+    human_ncco: Option<serde_json::Value>,
+    beep_timeout: u64,
+}
+
+impl<C> VoicemailDrop<C> {
+    pub(crate) fn new(http_client: HttpClient<C>, auth: Auth, audio_url: String) -> Self {
+        VoicemailDrop {
+            http_client,
+            auth,
+            audio_url,
+            human_ncco: None,
+            beep_timeout: 45,
+        }
+    }
+
+    /// Controls calls answered by a person with the given NCCO, which must serialize to a JSON
+    /// array of actions.
+    ///
+    /// The NCCO starts as soon as the call is answered, before an answering machine can be
+    /// detected, and is replaced by the message if one is. By default, it streams the same
+    /// message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ncco` cannot be serialized to JSON, e.g. because it contains a map with
+    /// non-string keys.
+    pub fn human_ncco<T: Serialize + ?Sized>(mut self, ncco: &T) -> Self {
+        let ncco = serde_json::to_value(ncco).expect("NCCO must be serializable to JSON");
+        self.human_ncco = Some(ncco);
+        self
+    }
+
+    /// Sets how long to wait for the beep of an answering machine before playing the message
+    /// anyway, from 45 to 120 seconds. Defaults to 45 seconds.
+    pub fn beep_timeout(mut self, seconds: u64) -> Self {
+        self.beep_timeout = seconds;
+        self
+    }
+
+    fn message(&self) -> [Stream; 1] {
+        [Stream::new(self.audio_url.clone())]
+    }
+}
+
+impl<C: HttpTransport> VoicemailDrop<C> {
+    /// Places a call from the virtual number `from` to `to`, which sends status updates to
+    /// `event_url`.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidRequest`] without placing the call if
+    /// `event_url` is not an absolute HTTP(S) URL, or the beep timeout is out of range.
+    ///
+    /// [`ErrorKind::InvalidRequest`]: ../enum.ErrorKind.html#variant.InvalidRequest
+    pub async fn place(
+        &self,
+        to: &PhoneNumber,
+        from: &PhoneNumber,
+        event_url: impl Into<String>,
+    ) -> Result<Call<C>> {
+        let call = CreateCall::new(
+            self.http_client.clone(),
+            self.auth.clone(),
+            Endpoint::phone(to),
+            from,
+        );
+        let call = match self.human_ncco.as_ref() {
+            Some(ncco) => call.ncco(ncco),
+            None => call.ncco(&self.message()),
+        };
+
+        call.event_url(event_url)
+            .advanced_machine_detection(MachineDetection::Continue, self.beep_timeout)
+            .send()
+            .await
+    }
+
+    /// Plays the message to the call which sent `event` if it reports that an answering machine
+    /// is ready to record, returning whether it did.
+    ///
+    /// All other events are ignored.
+    pub async fn handle_event(&self, event: &VoiceEvent) -> Result<bool> {
+        let is_recording = event.status == "machine"
+            && matches!(
+                event.sub_state.as_deref(),
+                Some("beep_start") | Some("beep_timeout")
+            );
+        if !is_recording {
+            return Ok(false);
+        }
+
+        transfer_ncco(&self.http_client, &self.auth, &event.uuid, &self.message()).await?;
+        Ok(true)
+    }
+}
+
+impl<C> Clone for VoicemailDrop<C> {
+    fn clone(&self) -> Self {
+        VoicemailDrop {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+            audio_url: self.audio_url.clone(),
+            human_ncco: self.human_ncco.clone(),
+            beep_timeout: self.beep_timeout,
+        }
+    }
+}
+
+impl<C> Debug for VoicemailDrop<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(VoicemailDrop))
+            .field("audio_url", &self.audio_url)
+            .field("human_ncco", &self.human_ncco)
+            .field("beep_timeout", &self.beep_timeout)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use bytes::Bytes;
+    use http::{Method, Request};
+    use http_body_util::{BodyExt, Full};
+    use serde_json::{json, Value};
+    use tower::service_fn;
+
+    use crate::Client;
+
+    const UUID: &str = "63f61863-4a51-4f6b-86e1-46edebcf9356";
+
+    fn event(status: &str, sub_state: Option<&str>) -> VoiceEvent {
+        let mut event = json!({
+            "uuid": UUID,
+            "conversation_uuid": "CON-f972836a-550f-45fa-956c-12a2ab5b7d22",
+            "status": status,
+        });
+        if let Some(sub_state) = sub_state {
+            event["sub_state"] = sub_state.into();
+        }
+        serde_json::from_value(event).unwrap()
+    }
+
+    #[tokio::test]
+    async fn streams_message_once_machine_beeps() {
+        let service = service_fn(|request: Request<Full<Bytes>>| async move {
+            let (parts, body) = request.into_parts();
+            let body: Value =
+                serde_json::from_slice(&body.collect().await.unwrap().to_bytes()).unwrap();
+            let response = match (parts.method, parts.uri.path()) {
+                (Method::POST, "/v1/calls") => {
+                    assert_eq!(
+                        body["advanced_machine_detection"],
+                        json!({"behavior": "continue", "mode": "detect_beep", "beep_timeout": 60})
+                    );
+                    assert_eq!(body["ncco"], json!([{"action": "talk", "text": "Hello"}]));
+                    json!({
+                        "uuid": UUID,
+                        "conversation_uuid": "CON-f972836a-550f-45fa-956c-12a2ab5b7d22",
+                        "status": "started",
+                        "direction": "outbound"
+                    })
+                }
+                (Method::PUT, path) => {
+                    assert_eq!(path, format!("/v1/calls/{}", UUID));
+                    assert_eq!(
+                        body,
+                        json!({
+                            "action": "transfer",
+                            "destination": {
+                                "type": "ncco",
+                                "ncco": [{
+                                    "action": "stream",
+                                    "streamUrl": ["https://example.com/message.mp3"]
+                                }]
+                            }
+                        })
+                    );
+                    return Ok::<_, Infallible>(http::Response::new(Full::new(Bytes::new())));
+                }
+                (method, path) => panic!("unexpected request {} {}", method, path),
+            };
+            let body = Bytes::from(response.to_string());
+            Ok(http::Response::new(Full::new(body)))
+        });
+
+        let client = Client::from_service(service)
+            .jwt("app_id", crate::auth::TEST_PRIVATE_KEY)
+            .build()
+            .unwrap();
+
+        let to = crate::phone::parse("+14155550100", None).unwrap();
+        let from = crate::phone::parse("+12025550123", None).unwrap();
+        let voicemail = client
+            .voice()
+            .voicemail_drop("https://example.com/message.mp3")
+            .human_ncco(&[super::super::ncco::Talk::new("Hello")])
+            .beep_timeout(60);
+
+        let call = voicemail
+            .place(&to, &from, "https://example.com/event")
+            .await
+            .unwrap();
+        assert_eq!(call.uuid(), UUID);
+
+        assert!(!voicemail
+            .handle_event(&event("answered", None))
+            .await
+            .unwrap());
+        assert!(!voicemail.handle_event(&event("human", None)).await.unwrap());
+        assert!(voicemail
+            .handle_event(&event("machine", Some("beep_start")))
+            .await
+            .unwrap());
+
+        let error = voicemail
+            .clone()
+            .beep_timeout(30)
+            .place(&to, &from, "https://example.com/event")
+            .await
+            .unwrap_err();
+        let invalid = error.as_invalid_request().unwrap().invalid_parameters();
+        assert_eq!(invalid[0].name(), "beep_timeout");
+    }
+}
@@ -0,0 +1,647 @@
+//! Parsing of webhook callbacks sent by Vonage.
+//!
+//! Vonage notifies applications of inbound messages, delivery receipts, call progress, and other
+//! events by sending HTTP requests to webhook URLs configured in the dashboard. The [`parse()`]
+//! function recognizes the payloads of all supported products, so a single endpoint can dispatch
+//! every callback by matching on the returned [`Event`].
+//!
+//! [`parse()`]: ./fn.parse.html
+//! [`Event`]: ./enum.Event.html
+//!
+//! # Example
+//!
+//! ```
+//! use vonage::webhooks::{self, Event};
+//!
+//! let body = b"msisdn=14155550100&to=12025550123&messageId=0A0000000123ABCD1&text=Hello&type=text";
+//! match webhooks::parse("application/x-www-form-urlencoded", body).unwrap() {
+//!     Event::InboundSms(sms) => assert_eq!(sms.text.as_deref(), Some("Hello")),
+//!     other => panic!("unexpected event: {:?}", other),
+//! }
+//! ```
+
+pub use self::auth::Auth;
+pub use self::concat::{ConcatPart, Reassembler};
+pub use self::dedup::{DedupStore, Deduplicator, Fingerprint, MemoryDedupStore};
+pub use self::ncco::NccoResponse;
+pub use self::payload::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::router::Router;
+#[cfg(feature = "server")]
+pub use self::server::serve;
+
+use std::time::Duration;
+
+use http::header::CONTENT_TYPE;
+use http::request::Parts;
+use http::Method;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{Error, ErrorKind, Metadata, Result, SignatureSecret};
+
+mod auth;
+mod concat;
+mod dedup;
+mod ncco;
+mod payload;
+#[cfg(not(target_arch = "wasm32"))]
+mod router;
+#[cfg(feature = "server")]
+mod server;
+
+const FORM: &str = "application/x-www-form-urlencoded";
+const JSON: &str = "application/json";
+
+/// Fields which Vonage adds to signed SMS webhooks, on top of the payload of the event.
+const SIGNATURE_FIELDS: &[&str] = &["sig", "timestamp", "nonce"];
+
+/// A list of webhook events which can be sent by Vonage.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum Event {
+    /// An SMS was received by one of your virtual numbers.
+    InboundSms(InboundSms),
+    /// The delivery status of an outbound SMS changed.
+    DeliveryReceipt(DeliveryReceipt),
+    /// The status of a voice call changed.
+    Voice(VoiceEvent),
+    /// The transcription of a call recording completed or failed.
+    Transcription(TranscriptionEvent),
+    /// The status of a message sent with the Messages API changed.
+    MessageStatus(MessageStatus),
+    /// The status of a Verify request changed.
+    Verify(VerifyEvent),
+    /// An event occurred in a Conversation, e.g. a member joined.
+    Rtc(RtcEvent),
+}
+
+/// Parses the body of a webhook request into a typed [`Event`].
+///
+/// Both `application/json` and `application/x-www-form-urlencoded` payloads are supported, as
+/// determined by `content_type`. Webhooks delivered with `GET` carry their parameters in the
+/// query string instead, which can be parsed by passing it as the `body` with a form content
+/// type.
+///
+/// Returns an error of kind [`ErrorKind::InvalidWebhook`] if the content type is not supported
+/// or the payload is not recognized.
+///
+/// [`Event`]: ./enum.Event.html
+/// [`ErrorKind::InvalidWebhook`]: ../enum.ErrorKind.html#variant.InvalidWebhook
+pub fn parse(content_type: &str, body: &[u8]) -> Result<Event> {
+    from_fields(parse_fields(content_type, body)?)
+}
+
+/// Verifies and parses a webhook request received by any HTTP server framework.
+///
+/// The request is authenticated with `auth` before its payload is parsed, and must have been signed
+/// within the last 5 minutes. Use [`Parser::max_age()`] to change this tolerance. Parameters of
+/// `GET` requests are read from the query string, while those of other requests are read from
+/// `body` according to their `Content-Type` header, like [`parse()`].
+///
+/// [`Parser::max_age()`]: ./struct.Parser.html#method.max_age
+/// Returns an error of kind [`ErrorKind::Auth`] if verification fails, or of kind
+/// [`ErrorKind::InvalidWebhook`] if the payload is not recognized.
+///
+/// [`parse()`]: ./fn.parse.html
+/// [`ErrorKind::Auth`]: ../enum.ErrorKind.html#variant.Auth
+/// [`ErrorKind::InvalidWebhook`]: ../enum.ErrorKind.html#variant.InvalidWebhook
+///
+/// # Example
+///
+/// ```
+/// use http::Request;
+/// use vonage::webhooks::{self, Auth, Event};
+///
+/// let request = Request::get("/webhooks/inbound-sms?msisdn=14155550100&to=12025550123&messageId=0A0000000123ABCD1&text=Hi&type=text")
+///     .body(Vec::new())
+///     .unwrap();
+///
+/// let (parts, body) = request.into_parts();
+/// let event = webhooks::from_http_parts(&parts, &body, &Auth::None).unwrap();
+/// assert!(matches!(event, Event::InboundSms(_)));
+/// ```
+pub fn from_http_parts(parts: &Parts, body: &[u8], auth: &Auth) -> Result<Event> {
+    from_fields(request_fields(parts, body, auth, auth::DEFAULT_MAX_AGE)?)
+}
+
+/// Verifies that a webhook request was sent by Vonage, without recognizing its payload.
+///
+/// This applies the same verification as [`from_http_parts()`](./fn.from_http_parts.html), e.g.
+/// for callbacks of products whose payloads are not parsed by this crate.
+///
+/// Returns an error of kind [`ErrorKind::Auth`] if verification fails, or of kind
+/// [`ErrorKind::InvalidWebhook`] if the body cannot be decoded.
+///
+/// [`ErrorKind::Auth`]: ../enum.ErrorKind.html#variant.Auth
+/// [`ErrorKind::InvalidWebhook`]: ../enum.ErrorKind.html#variant.InvalidWebhook
+pub fn verify_signature(parts: &Parts, body: &[u8], auth: &Auth) -> Result<()> {
+    request_fields(parts, body, auth, auth::DEFAULT_MAX_AGE).map(drop)
+}
+
+/// Verifies and parses a request to the answer URL of a Voice application.
+///
+/// Answer webhooks are sent with `GET` by default, in which case their parameters are read from
+/// the query string, like [`from_http_parts()`](./fn.from_http_parts.html).
+///
+/// Returns an error of kind [`ErrorKind::Auth`] if verification fails, or of kind
+/// [`ErrorKind::InvalidWebhook`] if the request is not an answer webhook.
+///
+/// [`ErrorKind::Auth`]: ../enum.ErrorKind.html#variant.Auth
+/// [`ErrorKind::InvalidWebhook`]: ../enum.ErrorKind.html#variant.InvalidWebhook
+pub fn answer_from_http_parts(parts: &Parts, body: &[u8], auth: &Auth) -> Result<AnswerRequest> {
+    decode(request_fields(parts, body, auth, auth::DEFAULT_MAX_AGE)?)
+}
+
+/// How a [`Parser`](./struct.Parser.html) treats fields which are not part of the payload of the
+/// recognized event.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Strictness {
+    /// Unknown fields are collected in [`Parsed::unknown_fields()`], and otherwise ignored.
+    ///
+    /// This suits production, where a field newly added by Vonage should be noticed without
+    /// rejecting the webhook.
+    ///
+    /// [`Parsed::unknown_fields()`]: ./struct.Parsed.html#method.unknown_fields
+    #[default]
+    Lenient,
+    /// Payloads with unknown fields are rejected with an error of kind
+    /// [`ErrorKind::InvalidWebhook`].
+    ///
+    /// This suits tests, which then fail as soon as a recorded or fabricated payload no longer
+    /// matches the schema modeled by this library.
+    ///
+    /// [`ErrorKind::InvalidWebhook`]: ../enum.ErrorKind.html#variant.InvalidWebhook
+    Strict,
+}
+
+/// A webhook parser which detects fields not modeled by this library.
+///
+/// [`parse()`] and [`from_http_parts()`] silently ignore unknown fields. A `Parser` instead
+/// reports them according to its [`Strictness`], so that integrations notice changes to the
+/// payloads sent by Vonage early. The signature parameters of signed SMS webhooks are never
+/// considered unknown.
+///
+/// [`parse()`]: ./fn.parse.html
+/// [`from_http_parts()`]: ./fn.from_http_parts.html
+/// [`Strictness`]: ./enum.Strictness.html
+///
+/// # Example
+///
+/// ```
+/// use vonage::webhooks::{Parser, Strictness};
+///
+/// let body = br#"{"request_id":"c11236f4-00bf-4b89-84ba-88b25df97315","status":"completed","new_field":1}"#;
+///
+/// let parsed = Parser::new().parse("application/json", body).unwrap();
+/// assert_eq!(parsed.unknown_fields(), ["new_field"]);
+///
+/// let strict = Parser::new().strictness(Strictness::Strict);
+/// assert!(strict.parse("application/json", body).is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Parser {
+    strictness: Strictness,
+    max_age: Duration,
+}
+
+impl Parser {
+    /// Creates a new lenient `Parser`.
+    #[inline]
+    pub fn new() -> Self {
+        Parser::default()
+    }
+
+    /// Sets how far the time at which a request was signed may differ from the current time.
+    /// Defaults to 5 minutes.
+    ///
+    /// Older requests fail verification in [`from_http_parts()`](#method.from_http_parts), so
+    /// that captured webhooks cannot be replayed.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Sets how unknown fields are treated. Defaults to [`Strictness::Lenient`].
+    ///
+    /// [`Strictness::Lenient`]: ./enum.Strictness.html#variant.Lenient
+    pub fn strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    /// Parses the body of a webhook request, like [`parse()`](./fn.parse.html).
+    pub fn parse(&self, content_type: &str, body: &[u8]) -> Result<Parsed> {
+        self.decode_fields(parse_fields(content_type, body)?)
+    }
+
+    /// Verifies and parses a webhook request, like
+    /// [`from_http_parts()`](./fn.from_http_parts.html).
+    pub fn from_http_parts(&self, parts: &Parts, body: &[u8], auth: &Auth) -> Result<Parsed> {
+        self.decode_fields(request_fields(parts, body, auth, self.max_age)?)
+    }
+
+    fn decode_fields(&self, fields: Map<String, Value>) -> Result<Parsed> {
+        let event = from_fields(fields.clone())?;
+        let known = match serde_json::to_value(&event) {
+            Ok(Value::Object(mut tagged)) => match tagged.values_mut().next().map(Value::take) {
+                Some(Value::Object(known)) => known,
+                _ => Map::new(),
+            },
+            _ => Map::new(),
+        };
+
+        let unknown_fields: Vec<String> = fields
+            .into_iter()
+            .filter(|(key, value)| {
+                !value.is_null()
+                    && !known.contains_key(key)
+                    && !SIGNATURE_FIELDS.contains(&key.as_str())
+            })
+            .map(|(key, _)| key)
+            .collect();
+
+        if self.strictness == Strictness::Strict && !unknown_fields.is_empty() {
+            let message = format!("unknown webhook fields: {}", unknown_fields.join(", "));
+            return Err(invalid(message));
+        }
+
+        Ok(Parsed {
+            event,
+            unknown_fields,
+        })
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Parser {
+            strictness: Strictness::default(),
+            max_age: auth::DEFAULT_MAX_AGE,
+        }
+    }
+}
+
+/// A webhook event parsed by a [`Parser`](./struct.Parser.html), together with the fields it did
+/// not recognize.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Parsed {
+    event: Event,
+    unknown_fields: Vec<String>,
+}
+
+impl Parsed {
+    /// Returns the parsed event.
+    #[inline]
+    pub fn event(&self) -> &Event {
+        &self.event
+    }
+
+    /// Returns the names of the fields which are not modeled by the payload of the event.
+    ///
+    /// This is always empty for [strict](./enum.Strictness.html#variant.Strict) parsers.
+    #[inline]
+    pub fn unknown_fields(&self) -> &[String] {
+        &self.unknown_fields
+    }
+
+    /// Consumes this `Parsed`, returning the event.
+    #[inline]
+    pub fn into_event(self) -> Event {
+        self.event
+    }
+}
+
+/// Reads and authenticates the fields of a webhook request.
+fn request_fields(
+    parts: &Parts,
+    body: &[u8],
+    auth: &Auth,
+    max_age: Duration,
+) -> Result<Map<String, Value>> {
+    let fields = if parts.method == Method::GET {
+        let query = parts.uri.query().unwrap_or_default();
+        parse_fields(FORM, query.as_bytes())?
+    } else {
+        let content_type = parts
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or(JSON);
+        parse_fields(content_type, body)?
+    };
+
+    auth.check(&parts.headers, &fields, body, max_age)?;
+    Ok(fields)
+}
+
+/// Parses a payload of type `T`, after verifying its `sig` parameter with `secret`.
+pub(crate) fn parse_signed<T: DeserializeOwned>(
+    content_type: &str,
+    body: &[u8],
+    secret: &SignatureSecret,
+) -> Result<T> {
+    let fields = parse_fields(content_type, body)?;
+    auth::check_signature(secret, &fields, auth::DEFAULT_MAX_AGE)?;
+    decode(fields)
+}
+
+/// Returns the [`Metadata`](../struct.Metadata.html) appended to the URL of a webhook request.
+///
+/// This recovers metadata which was attached to the event URL of a call with
+/// [`Metadata::append_to_url()`](../struct.Metadata.html#method.append_to_url). Any other query
+/// parameters are included as well.
+pub fn url_metadata(parts: &Parts) -> Metadata {
+    let query = parts.uri.query().unwrap_or_default();
+    query.parse().unwrap_or_default()
+}
+
+/// Decodes the fields of a JSON or form-encoded payload.
+fn parse_fields(content_type: &str, body: &[u8]) -> Result<Map<String, Value>> {
+    let media_type = content_type.split(';').next().unwrap_or_default().trim();
+    let fields = if media_type.eq_ignore_ascii_case(JSON) {
+        serde_json::from_slice(body).map_err(invalid)?
+    } else if media_type.eq_ignore_ascii_case(FORM) {
+        let pairs: Vec<(String, String)> = serde_urlencoded::from_bytes(body).map_err(invalid)?;
+        pairs
+            .into_iter()
+            .map(|(key, value)| (key, Value::String(value)))
+            .collect()
+    } else {
+        let message = format!("unsupported content type `{}`", content_type);
+        return Err(invalid(message));
+    };
+
+    Ok(fields)
+}
+
+/// Recognizes the kind of event from the fields present in the payload.
+fn from_fields(fields: Map<String, Value>) -> Result<Event> {
+    let has = |key: &str| fields.contains_key(key);
+    let kind = fields.get("type").and_then(Value::as_str);
+
+    if has("msisdn") && has("messageId") {
+        if has("status") {
+            decode(fields).map(Event::DeliveryReceipt)
+        } else {
+            decode(fields).map(Event::InboundSms)
+        }
+    } else if has("message_uuid") && has("status") {
+        decode(fields).map(Event::MessageStatus)
+    } else if has("uuid") && has("conversation_uuid") {
+        decode(fields).map(Event::Voice)
+    } else if has("request_id") && has("status") {
+        decode(fields).map(Event::Verify)
+    } else if has("recording_uuid") && kind == Some("transcription") {
+        decode(fields).map(Event::Transcription)
+    } else if kind.is_some_and(|kind| kind.contains(':')) {
+        decode(fields).map(Event::Rtc)
+    } else {
+        Err(invalid("unrecognized webhook payload"))
+    }
+}
+
+fn decode<T: DeserializeOwned>(fields: Map<String, Value>) -> Result<T> {
+    serde_json::from_value(Value::Object(fields)).map_err(invalid)
+}
+
+fn invalid<E>(error: E) -> Error
+where
+    E: std::fmt::Display + std::fmt::Debug + Send + Sync + 'static,
+{
+    Error::with_cause(ErrorKind::InvalidWebhook, anyhow::Error::msg(error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sms_payloads() {
+        let body = b"msisdn=14155550100&to=12025550123&messageId=0A0000000123ABCD1&text=Hello%20world&type=text&keyword=HELLO&message-timestamp=2020-01-01+12%3A00%3A00";
+        let event = parse("application/x-www-form-urlencoded; charset=utf-8", body).unwrap();
+        match event {
+            Event::InboundSms(sms) => {
+                assert_eq!(sms.msisdn, "14155550100");
+                assert_eq!(sms.text.as_deref(), Some("Hello world"));
+                assert_eq!(
+                    sms.message_timestamp.as_deref(),
+                    Some("2020-01-01 12:00:00")
+                );
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let body = br#"{"msisdn":"14155550100","to":"AcmeInc","network-code":"12345","messageId":"0A0000000123ABCD1","price":"0.03330000","status":"delivered","scts":"2001011400","err-code":"0","message-timestamp":"2020-01-01 12:00:00"}"#;
+        match parse("application/json", body).unwrap() {
+            Event::DeliveryReceipt(dlr) => {
+                assert_eq!(dlr.status, "delivered");
+                assert_eq!(dlr.err_code.as_deref(), Some("0"));
+                assert_eq!(dlr.price, Some("0.03330000".parse().unwrap()));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_json_payloads() {
+        let body = br#"{"uuid":"63f61863-4a51-4f6b-86e1-46edebcf9356","conversation_uuid":"CON-f972836a-550f-45fa-956c-12a2ab5b7d22","status":"answered","direction":"outbound","timestamp":"2020-01-01T12:00:00.000Z","from":"12025550123","to":"14155550100"}"#;
+        assert!(matches!(parse(JSON, body).unwrap(), Event::Voice(e) if e.status == "answered"));
+
+        let body = br#"{"message_uuid":"aaaaaaaa-bbbb-cccc-dddd-0123456789ab","to":"14155550100","from":"12025550123","timestamp":"2020-01-01T12:00:00.000Z","status":"delivered","channel":"whatsapp"}"#;
+        assert!(
+            matches!(parse(JSON, body).unwrap(), Event::MessageStatus(e) if e.status == "delivered")
+        );
+
+        let body = br#"{"message_uuid":"aaaaaaaa-bbbb-cccc-dddd-0123456789ab","to":"14155550100","from":"12025550123","timestamp":"2020-01-01T12:00:00.000Z","status":"read","client_ref":"order=42&tenant=acme"}"#;
+        match parse(JSON, body).unwrap() {
+            Event::MessageStatus(e) => {
+                let metadata = e.metadata().unwrap();
+                assert_eq!(metadata.get("order"), Some("42"));
+                assert_eq!(metadata.get("tenant"), Some("acme"));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let body = br#"{"request_id":"c11236f4-00bf-4b89-84ba-88b25df97315","triggered_at":"2020-01-01T12:00:00.000Z","type":"summary","channel":"sms","status":"completed"}"#;
+        assert!(matches!(parse(JSON, body).unwrap(), Event::Verify(e) if e.status == "completed"));
+
+        let body = br#"{"conversation_uuid":"CON-f972836a-550f-45fa-956c-12a2ab5b7d22","type":"transcription","recording_uuid":"d4b4e4b2-4c6e-4d8e-9b5c-0123456789ab","status":"completed","transcription_url":"https://api.nexmo.com/v1/files/aaaaaaaa-bbbb-cccc-dddd-0123456789ab"}"#;
+        match parse(JSON, body).unwrap() {
+            Event::Transcription(e) => {
+                assert_eq!(e.status, "completed");
+                assert!(e.transcription_url.unwrap().ends_with("0123456789ab"));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let body = br#"{"type":"member:joined","conversation_id":"CON-f972836a-550f-45fa-956c-12a2ab5b7d22","body":{"user":{"name":"alice"}}}"#;
+        match parse(JSON, body).unwrap() {
+            Event::Rtc(e) => {
+                assert_eq!(e.kind, "member:joined");
+                assert_eq!(e.body["user"]["name"], "alice");
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn detects_unknown_fields() {
+        let body = b"msisdn=14155550100&to=12025550123&messageId=0A0000000123ABCD1&text=Hi&sig=abc&timestamp=1577880000&carrier=acme";
+        let parsed = Parser::new().parse(FORM, body).unwrap();
+        assert!(matches!(parsed.event(), Event::InboundSms(_)));
+        assert_eq!(parsed.unknown_fields(), ["carrier"]);
+
+        let error = Parser::new()
+            .strictness(Strictness::Strict)
+            .parse(FORM, body)
+            .unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::InvalidWebhook));
+
+        let body = br#"{"type":"member:joined","conversation_id":"CON-f972836a-550f-45fa-956c-12a2ab5b7d22","timestamp":null,"body":{"user":{"name":"alice"}}}"#;
+        let parsed = Parser::new()
+            .strictness(Strictness::Strict)
+            .parse(JSON, body)
+            .unwrap();
+        assert!(parsed.unknown_fields().is_empty());
+    }
+
+    #[test]
+    fn verifies_sms_signatures() {
+        use std::collections::BTreeMap;
+
+        use http::Request;
+
+        use crate::{SignatureMethod, SignatureSecret};
+
+        let secret = SignatureSecret::with_method(SignatureMethod::Sha256Hmac, "secret");
+        let mut params = BTreeMap::new();
+        params.insert("msisdn", "14155550100".to_owned());
+        params.insert("to", "12025550123".to_owned());
+        params.insert("messageId", "0A0000000123ABCD1".to_owned());
+        params.insert("text", "Hello & welcome".to_owned());
+        params.insert("timestamp", chrono::Utc::now().timestamp().to_string());
+        let sign = |mut params: BTreeMap<&'static str, String>| {
+            let sig = secret.sign(&params).to_string();
+            params.insert("sig", sig);
+            serde_urlencoded::to_string(&params).unwrap()
+        };
+
+        let body = sign(params.clone());
+        let request = |body: String| {
+            let request = Request::post("/webhooks/inbound-sms")
+                .header(CONTENT_TYPE, FORM)
+                .body(body)
+                .unwrap();
+            request.into_parts()
+        };
+
+        let auth = Auth::Signature(secret.clone());
+        let (parts, body) = request(body);
+        let event = from_http_parts(&parts, body.as_bytes(), &auth).unwrap();
+        assert!(
+            matches!(event, Event::InboundSms(sms) if sms.text.as_deref() == Some("Hello & welcome"))
+        );
+
+        let (parts, tampered) = request(body.replace("welcome", "goodbye"));
+        let error = from_http_parts(&parts, tampered.as_bytes(), &auth).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Auth));
+
+        let sig =
+            serde_urlencoded::from_str::<BTreeMap<String, String>>(&body).unwrap()["sig"].clone();
+        let (parts, uppercase) = request(body.replace(&sig, &sig.to_uppercase()));
+        from_http_parts(&parts, uppercase.as_bytes(), &auth).unwrap();
+
+        let mut stale = params.clone();
+        stale.insert("timestamp", "1577880000".to_owned());
+        let (parts, stale) = request(sign(stale));
+        let error = from_http_parts(&parts, stale.as_bytes(), &auth).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Auth));
+        let tolerant = Parser::new().max_age(std::time::Duration::from_secs(u32::MAX.into()));
+        tolerant
+            .from_http_parts(&parts, stale.as_bytes(), &auth)
+            .unwrap();
+
+        let mut undated = params;
+        undated.remove("timestamp");
+        let (parts, undated) = request(sign(undated));
+        let error = from_http_parts(&parts, undated.as_bytes(), &auth).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Auth));
+    }
+
+    #[test]
+    fn verifies_signed_jwts() {
+        use hmac::digest::Digest;
+        use http::Request;
+        use jsonwebtoken::{EncodingKey, Header};
+
+        let body = br#"{"uuid":"63f61863-4a51-4f6b-86e1-46edebcf9356","conversation_uuid":"CON-f972836a-550f-45fa-956c-12a2ab5b7d22","status":"ringing"}"#;
+        let now = chrono::Utc::now().timestamp();
+        let request = |claims: serde_json::Value| {
+            let token = jsonwebtoken::encode(
+                &Header::default(),
+                &claims,
+                &EncodingKey::from_secret(b"secret"),
+            )
+            .unwrap();
+
+            let (parts, _) = Request::post("/webhooks/event")
+                .header(CONTENT_TYPE, JSON)
+                .header("Authorization", format!("Bearer {}", token))
+                .body(())
+                .unwrap()
+                .into_parts();
+            parts
+        };
+
+        let parts = request(serde_json::json!({
+            "iat": now,
+            "payload_hash": format!("{:x}", sha2::Sha256::digest(body)),
+        }));
+
+        let event = from_http_parts(&parts, body, &Auth::Jwt("secret".into())).unwrap();
+        assert!(matches!(event, Event::Voice(e) if e.status == "ringing"));
+        verify_signature(&parts, br#"{"custom":true}"#, &Auth::Jwt("secret".into())).unwrap_err();
+        verify_signature(&parts, body, &Auth::Jwt("secret".into())).unwrap();
+
+        let error = from_http_parts(&parts, body, &Auth::Jwt("other".into())).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Auth));
+
+        let tampered = String::from_utf8_lossy(body).replace("ringing", "answered");
+        let error = from_http_parts(&parts, tampered.as_bytes(), &Auth::Jwt("secret".into()));
+        assert!(matches!(error.unwrap_err().kind(), ErrorKind::Auth));
+
+        let auth = Auth::Jwt("hunter2".into());
+        assert!(!format!("{:?}", auth).contains("hunter2"));
+
+        let auth = Auth::Jwt("secret".into());
+        let stale = request(serde_json::json!({
+            "iat": now - 600,
+            "payload_hash": format!("{:x}", sha2::Sha256::digest(body)),
+        }));
+        let error = from_http_parts(&stale, body, &auth).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Auth));
+        let tolerant = Parser::new().max_age(std::time::Duration::from_secs(900));
+        tolerant.from_http_parts(&stale, body, &auth).unwrap();
+
+        let undated = request(serde_json::json!({ "iat": null }));
+        let error = verify_signature(&undated, body, &auth).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Auth));
+
+        let mut unhashed = request(serde_json::json!({ "iat": now }));
+        let error = verify_signature(&unhashed, body, &auth).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Auth));
+        unhashed.method = Method::GET;
+        verify_signature(&unhashed, b"", &auth).unwrap();
+    }
+
+    #[test]
+    fn rejects_unknown_payloads() {
+        let error = parse(JSON, br#"{"hello":"world"}"#).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::InvalidWebhook));
+
+        let error = parse("text/plain", b"hello").unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::InvalidWebhook));
+    }
+}
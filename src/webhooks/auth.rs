@@ -0,0 +1,171 @@
+//! Verification that webhook requests were sent by Vonage.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Formatter};
+use std::time::Duration;
+
+use hmac::digest::Digest;
+use http::header::{HeaderMap, AUTHORIZATION};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use subtle::ConstantTimeEq;
+
+use crate::rt::SystemTime;
+use crate::{Error, Result, SignatureSecret};
+
+/// How far the time at which a webhook was signed may differ from the current time by default.
+pub(crate) const DEFAULT_MAX_AGE: Duration = Duration::from_secs(5 * 60);
+
+/// A list of methods for verifying that webhook requests were sent by Vonage.
+///
+/// Which method applies depends on the product: SMS webhooks carry a `sig` parameter derived
+/// from your signature secret, while Voice, Messages, and other application webhooks carry a
+/// JWT in the `Authorization` header, signed with the same secret.
+///
+/// Secrets are masked in the `Debug` output, so that they do not leak into logs.
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum Auth {
+    /// Accepts all requests without verifying them.
+    ///
+    /// This should only be used during local development.
+    None,
+    /// Verifies the `sig` parameter of SMS webhooks with the given signature secret, including
+    /// the signed `timestamp` parameter.
+    Signature(SignatureSecret),
+    /// Verifies the `Authorization: Bearer` JWT of application webhooks with the given signature
+    /// secret, including its issue time and the hash of the payload.
+    ///
+    /// Tokens without a `payload_hash` claim are only accepted for requests with an empty body.
+    Jwt(String),
+}
+
+impl Auth {
+    /// Returns `Ok` if the webhook with the given `headers`, parsed `fields` and raw `body` passes
+    /// verification, and was signed no more than `max_age` away from the current time.
+    pub(crate) fn check(
+        &self,
+        headers: &HeaderMap,
+        fields: &Map<String, Value>,
+        body: &[u8],
+        max_age: Duration,
+    ) -> Result<()> {
+        match self {
+            Auth::None => Ok(()),
+            Auth::Signature(secret) => check_signature(secret, fields, max_age),
+            Auth::Jwt(secret) => check_jwt(secret, headers, body, max_age),
+        }
+    }
+}
+
+impl Debug for Auth {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Auth::None => f.write_str("None"),
+            Auth::Signature(secret) => f.debug_tuple("Signature").field(secret).finish(),
+            Auth::Jwt(_) => f.debug_tuple("Jwt").field(&"<secret>").finish(),
+        }
+    }
+}
+
+pub(super) fn check_signature(
+    secret: &SignatureSecret,
+    fields: &Map<String, Value>,
+    max_age: Duration,
+) -> Result<()> {
+    let params: BTreeMap<&str, String> = fields
+        .iter()
+        .map(|(key, value)| match value {
+            Value::String(s) => (key.as_str(), s.clone()),
+            other => (key.as_str(), other.to_string()),
+        })
+        .collect();
+
+    let sig = params
+        .get("sig")
+        .ok_or_else(|| Error::new_auth(anyhow::Error::msg("webhook is missing `sig` parameter")))?;
+
+    let expected = decode_hex(secret.sign(&params).as_ref());
+    let matches = match (expected, decode_hex(sig)) {
+        (Some(expected), Some(sig)) => bool::from(expected.ct_eq(&sig)),
+        _ => false,
+    };
+    if !matches {
+        return Err(Error::new_auth(anyhow::Error::msg(
+            "webhook signature does not match",
+        )));
+    }
+
+    let timestamp = params.get("timestamp").and_then(|t| t.parse().ok());
+    let timestamp = timestamp.ok_or_else(|| {
+        Error::new_auth(anyhow::Error::msg(
+            "webhook is missing `timestamp` parameter",
+        ))
+    })?;
+    check_age(timestamp, max_age)
+}
+
+/// Decodes a hexadecimal string in either case, returning `None` if it is malformed.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let digit = |c: u8| (c as char).to_digit(16);
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| match *pair {
+            [high, low] => Some((digit(high)? * 16 + digit(low)?) as u8),
+            _ => None,
+        })
+        .collect()
+}
+
+fn check_jwt(secret: &str, headers: &HeaderMap, body: &[u8], max_age: Duration) -> Result<()> {
+    use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+
+    #[derive(Deserialize)]
+    struct Claims {
+        iat: u64,
+        #[serde(default)]
+        payload_hash: Option<String>,
+    }
+
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| Error::new_auth(anyhow::Error::msg("webhook is missing bearer token")))?;
+
+    let validation = Validation {
+        validate_exp: false,
+        ..Validation::new(Algorithm::HS256)
+    };
+    let key = DecodingKey::from_secret(secret.as_bytes());
+    let claims = jsonwebtoken::decode::<Claims>(token.trim(), &key, &validation)?.claims;
+    check_age(claims.iat, max_age)?;
+
+    match claims.payload_hash {
+        Some(hash) if !hash.eq_ignore_ascii_case(&format!("{:x}", sha2::Sha256::digest(body))) => {
+            Err(Error::new_auth(anyhow::Error::msg(
+                "webhook payload does not match signed hash",
+            )))
+        }
+        None if !body.is_empty() => Err(Error::new_auth(anyhow::Error::msg(
+            "webhook token does not cover the payload",
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Returns `Ok` if the Unix time `signed_at` is no more than `max_age` away from the current time.
+fn check_age(signed_at: u64, max_age: Duration) -> Result<()> {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("SystemTime is before the Unix epoch")
+        .as_secs();
+
+    if now.abs_diff(signed_at) <= max_age.as_secs() {
+        Ok(())
+    } else {
+        Err(Error::new_auth(anyhow::Error::msg(
+            "webhook was not signed recently",
+        )))
+    }
+}
@@ -0,0 +1,271 @@
+//! Reassembly of inbound SMS which were delivered in several parts.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::InboundSms;
+use crate::rt::Instant;
+
+/// The position of a part within a concatenated inbound SMS.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ConcatPart {
+    /// The reference shared by all parts of the message.
+    pub reference: String,
+    /// The number of parts of the message.
+    pub total: u32,
+    /// The number of this part, starting at 1.
+    pub part: u32,
+}
+
+impl InboundSms {
+    /// Returns the position of this SMS within a concatenated message, or `None` if it is a
+    /// complete message or its `concat` fields are malformed.
+    ///
+    /// The `concat` flag is accepted as `true` in any case, or as `1`.
+    pub fn concat_part(&self) -> Option<ConcatPart> {
+        if !self.is_concat() {
+            return None;
+        }
+
+        let part = ConcatPart {
+            reference: self.concat_ref.clone()?,
+            total: self.concat_total.as_ref()?.parse().ok()?,
+            part: self.concat_part.as_ref()?.parse().ok()?,
+        };
+
+        let valid = part.total > 0 && (1..=part.total).contains(&part.part);
+        Some(part).filter(|_| valid)
+    }
+
+    fn is_concat(&self) -> bool {
+        let concat = self.concat.as_deref().map(str::trim);
+        concat.is_some_and(|flag| flag.eq_ignore_ascii_case("true") || flag == "1")
+    }
+}
+
+type Key = (String, String, String);
+
+struct Pending {
+    total: u32,
+    parts: BTreeMap<u32, InboundSms>,
+    expires_at: Instant,
+}
+
+/// Combines the parts of concatenated inbound SMS into complete messages.
+///
+/// Parts may arrive in any order, and are kept in process memory until the last one arrives.
+/// Parts of messages which are still incomplete after 1 hour are discarded by default.
+///
+/// # Example
+///
+/// ```
+/// use vonage::webhooks::{self, Event, Reassembler};
+///
+/// let reassembler = Reassembler::new();
+/// let parts: [&[u8]; 2] = [
+///     b"msisdn=14155550100&to=12025550123&messageId=2&text=world&concat=true&concat-ref=7&concat-total=2&concat-part=2",
+///     b"msisdn=14155550100&to=12025550123&messageId=1&text=Hello+&concat=true&concat-ref=7&concat-total=2&concat-part=1",
+/// ];
+///
+/// let mut complete = Vec::new();
+/// for body in parts.iter() {
+///     if let Event::InboundSms(sms) = webhooks::parse("application/x-www-form-urlencoded", body).unwrap() {
+///         complete.extend(reassembler.push(sms));
+///     }
+/// }
+///
+/// assert_eq!(complete.len(), 1);
+/// assert_eq!(complete[0].text.as_deref(), Some("Hello world"));
+/// ```
+#[derive(Clone)]
+pub struct Reassembler {
+    pending: Arc<Mutex<HashMap<Key, Pending>>>,
+    ttl: Duration,
+}
+
+impl Reassembler {
+    /// Creates a new, empty `Reassembler`.
+    pub fn new() -> Self {
+        Reassembler {
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            ttl: Duration::from_secs(60 * 60),
+        }
+    }
+
+    /// Sets how long the parts of an incomplete message are kept.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Adds `sms` to the message it belongs to, returning the complete message once all of its
+    /// parts have arrived.
+    ///
+    /// Messages which were not concatenated are returned as-is. A complete message has the text of
+    /// all parts in order, the remaining fields of its first part, and no `concat` fields.
+    /// Redeliveries of a part replace the earlier delivery.
+    ///
+    /// Parts with malformed `concat` fields, e.g. a part number of 0 or above the total, are
+    /// discarded, as are parts whose total differs from that of the first part received for the
+    /// same message.
+    pub fn push(&self, sms: InboundSms) -> Option<InboundSms> {
+        let concat = match sms.concat_part() {
+            Some(concat) => concat,
+            None if sms.is_concat() => return None,
+            None => return Some(sms),
+        };
+
+        let now = Instant::now();
+        let mut pending = self.pending.lock().unwrap();
+        pending.retain(|_, message| message.expires_at > now);
+
+        let key = (sms.msisdn.clone(), sms.to.clone(), concat.reference);
+        let total = concat.total;
+        let message = pending.entry(key.clone()).or_insert_with(|| Pending {
+            total,
+            parts: BTreeMap::new(),
+            expires_at: now + self.ttl,
+        });
+        if message.total != total {
+            return None;
+        }
+
+        message.parts.insert(concat.part, sms);
+        if message.parts.len() < total as usize {
+            return None;
+        }
+
+        let mut parts = pending.remove(&key)?.parts.into_values();
+        let mut complete = parts.next()?;
+        let mut text = complete.text.take().unwrap_or_default();
+        for part in parts {
+            text.push_str(part.text.as_deref().unwrap_or_default());
+        }
+
+        complete.text = Some(text);
+        complete.concat = None;
+        complete.concat_ref = None;
+        complete.concat_total = None;
+        complete.concat_part = None;
+        Some(complete)
+    }
+
+    /// Returns the number of messages of which some, but not all, parts have arrived.
+    pub fn pending(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+}
+
+impl Default for Reassembler {
+    fn default() -> Self {
+        Reassembler::new()
+    }
+}
+
+impl Debug for Reassembler {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Reassembler))
+            .field("pending", &self.pending())
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webhooks::{parse, Event};
+
+    fn sms(body: &str) -> InboundSms {
+        parse_as("application/json", body)
+    }
+
+    fn parse_as(content_type: &str, body: &str) -> InboundSms {
+        match parse(content_type, body.as_bytes()).unwrap() {
+            Event::InboundSms(sms) => sms,
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reassembles_parts_in_order() {
+        let reassembler = Reassembler::new();
+        let part = |id: u32, part: u32, text: &str| {
+            sms(&format!(
+                r#"{{"msisdn":"14155550100","to":"12025550123","messageId":"{}","text":"{}","concat":true,"concat-ref":42,"concat-total":3,"concat-part":{}}}"#,
+                id, text, part
+            ))
+        };
+
+        assert_eq!(
+            part(3, 3, "c").concat_part(),
+            Some(ConcatPart {
+                reference: "42".into(),
+                total: 3,
+                part: 3,
+            })
+        );
+
+        assert!(reassembler.push(part(3, 3, "c")).is_none());
+        assert!(reassembler.push(part(1, 1, "a")).is_none());
+        assert!(reassembler.push(part(1, 1, "a")).is_none());
+        assert_eq!(reassembler.pending(), 1);
+
+        let complete = reassembler.push(part(2, 2, "b")).unwrap();
+        assert_eq!(complete.text.as_deref(), Some("abc"));
+        assert_eq!(complete.message_id, "1");
+        assert_eq!(complete.concat_part(), None);
+        assert_eq!(reassembler.pending(), 0);
+
+        let single =
+            sms(r#"{"msisdn":"14155550100","to":"12025550123","messageId":"4","text":"hi"}"#);
+        assert_eq!(reassembler.push(single.clone()), Some(single));
+    }
+
+    #[test]
+    fn discards_inconsistent_parts() {
+        let reassembler = Reassembler::new();
+        let part = |total: u32, part: u32, text: &str| {
+            sms(&format!(
+                r#"{{"msisdn":"14155550100","to":"12025550123","messageId":"{}","text":"{}","concat":"true","concat-ref":"9","concat-total":"{}","concat-part":"{}"}}"#,
+                part, text, total, part
+            ))
+        };
+
+        assert!(reassembler.push(part(3, 1, "a")).is_none());
+        assert!(reassembler.push(part(2, 2, "x")).is_none());
+        assert!(reassembler.push(part(3, 0, "x")).is_none());
+        assert!(reassembler.push(part(3, 4, "x")).is_none());
+        assert!(reassembler.push(part(3, 2, "b")).is_none());
+        assert_eq!(reassembler.pending(), 1);
+
+        let complete = reassembler.push(part(3, 3, "c")).unwrap();
+        assert_eq!(complete.text.as_deref(), Some("abc"));
+        assert_eq!(reassembler.pending(), 0);
+    }
+
+    #[test]
+    fn accepts_form_encoded_concat_flags() {
+        let part = |concat: &str| {
+            let body = format!(
+                "msisdn=14155550100&to=12025550123&messageId=1&text=a&concat={}&concat-ref=7&concat-total=2&concat-part=1",
+                concat
+            );
+            parse_as("application/x-www-form-urlencoded", &body)
+        };
+
+        let expected = Some(ConcatPart {
+            reference: "7".into(),
+            total: 2,
+            part: 1,
+        });
+        for flag in ["true", "TRUE", "True", "1"].iter() {
+            assert_eq!(part(flag).concat_part(), expected, "concat={}", flag);
+        }
+        for flag in ["false", "0", ""].iter() {
+            assert_eq!(part(flag).concat_part(), None, "concat={}", flag);
+        }
+    }
+}
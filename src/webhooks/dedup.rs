@@ -0,0 +1,234 @@
+//! Detection of webhooks which were delivered more than once.
+
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+use super::Event;
+use crate::rt::Instant;
+
+/// A deterministic identifier of a webhook event.
+///
+/// Vonage redelivers a webhook whenever it does not receive a `200 OK` response in time, so the
+/// same event may arrive several times. Redeliveries of an event share the same fingerprint, which
+/// is derived from the ID of the message, call or request it belongs to, its status, and its
+/// timestamp. Distinct status updates of the same message have distinct fingerprints.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Fingerprint(String);
+
+impl AsRef<str> for Fingerprint {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Event {
+    /// Returns the fingerprint of this event, which is shared by all of its redeliveries.
+    pub fn fingerprint(&self) -> Fingerprint {
+        let none = String::new();
+        let rtc_id;
+        let fields: [&str; 4] = match self {
+            Event::InboundSms(e) => ["sms", &e.message_id, "", ""],
+            Event::DeliveryReceipt(e) => [
+                "dlr",
+                &e.message_id,
+                &e.status,
+                e.message_timestamp.as_ref().unwrap_or(&none),
+            ],
+            Event::Voice(e) => [
+                "voice",
+                &e.uuid,
+                &e.status,
+                e.timestamp.as_ref().unwrap_or(&none),
+            ],
+            Event::Transcription(e) => ["transcription", &e.recording_uuid, &e.status, ""],
+            Event::MessageStatus(e) => ["message", &e.message_uuid, &e.status, &e.timestamp],
+            Event::Verify(e) => [
+                "verify",
+                &e.request_id,
+                &e.status,
+                e.finalized_at
+                    .as_ref()
+                    .or(e.triggered_at.as_ref())
+                    .unwrap_or(&none),
+            ],
+            Event::Rtc(e) => {
+                rtc_id = e.id.as_ref().map(ToString::to_string).unwrap_or_default();
+                [
+                    "rtc",
+                    &rtc_id,
+                    &e.kind,
+                    e.timestamp.as_ref().unwrap_or(&none),
+                ]
+            }
+        };
+
+        let mut hasher = Sha256::new();
+        for field in &fields {
+            hasher.update(field.as_bytes());
+            hasher.update([0x1f]);
+        }
+        Fingerprint(format!("{:x}", hasher.finalize()))
+    }
+}
+
+/// A storage backend for the fingerprints of processed webhooks.
+///
+/// The default [`MemoryDedupStore`](./struct.MemoryDedupStore.html) keeps fingerprints in process
+/// memory. Implement this trait to share them between several instances of a webhook server,
+/// e.g. with Redis `SET NX EX`.
+pub trait DedupStore: Send + Sync {
+    /// Records `fingerprint` for the given `ttl`, returning `false` if it was already recorded and
+    /// has not expired yet.
+    ///
+    /// This must be atomic, so that concurrent redeliveries are not both reported as new.
+    fn insert(&self, fingerprint: &Fingerprint, ttl: Duration) -> bool;
+}
+
+impl<S: DedupStore + ?Sized> DedupStore for Arc<S> {
+    fn insert(&self, fingerprint: &Fingerprint, ttl: Duration) -> bool {
+        (**self).insert(fingerprint, ttl)
+    }
+}
+
+/// A [`DedupStore`](./trait.DedupStore.html) which keeps fingerprints in process memory.
+///
+/// Expired fingerprints are evicted whenever a new one is inserted.
+#[derive(Debug, Default)]
+pub struct MemoryDedupStore {
+    seen: Mutex<HashMap<Fingerprint, Instant>>,
+}
+
+impl MemoryDedupStore {
+    /// Creates a new, empty `MemoryDedupStore`.
+    pub fn new() -> Self {
+        MemoryDedupStore::default()
+    }
+}
+
+impl DedupStore for MemoryDedupStore {
+    fn insert(&self, fingerprint: &Fingerprint, ttl: Duration) -> bool {
+        let now = Instant::now();
+        let mut seen = self.seen.lock().unwrap();
+        seen.retain(|_, expires_at| *expires_at > now);
+        if seen.contains_key(fingerprint) {
+            return false;
+        }
+
+        seen.insert(fingerprint.clone(), now + ttl);
+        true
+    }
+}
+
+/// Filters out redeliveries of webhook events which were already processed.
+///
+/// Fingerprints are remembered for 24 hours by default, which covers the retry period of all
+/// Vonage products. Register a deduplicator with
+/// [`Router::deduplicate()`](./struct.Router.html#method.deduplicate), or call
+/// [`is_duplicate()`](#method.is_duplicate) from your own webhook handler.
+///
+/// # Example
+///
+/// ```
+/// use vonage::webhooks::{self, Deduplicator};
+///
+/// let dedup = Deduplicator::new();
+/// let body = b"msisdn=14155550100&to=12025550123&messageId=0A0000000123ABCD1&text=Hi";
+///
+/// let event = webhooks::parse("application/x-www-form-urlencoded", body).unwrap();
+/// assert!(!dedup.is_duplicate(&event));
+/// assert!(dedup.is_duplicate(&event));
+/// ```
+#[derive(Clone)]
+pub struct Deduplicator {
+    store: Arc<dyn DedupStore>,
+    ttl: Duration,
+}
+
+impl Deduplicator {
+    /// Creates a new `Deduplicator` backed by a
+    /// [`MemoryDedupStore`](./struct.MemoryDedupStore.html).
+    pub fn new() -> Self {
+        Deduplicator {
+            store: Arc::new(MemoryDedupStore::new()),
+            ttl: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+
+    /// Sets how long fingerprints are remembered.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Stores fingerprints in `store` instead of process memory.
+    pub fn store<S: DedupStore + 'static>(mut self, store: S) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Returns whether `event` was seen before, recording it otherwise.
+    pub fn is_duplicate(&self, event: &Event) -> bool {
+        !self.store.insert(&event.fingerprint(), self.ttl)
+    }
+}
+
+impl Default for Deduplicator {
+    fn default() -> Self {
+        Deduplicator::new()
+    }
+}
+
+impl Debug for Deduplicator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Deduplicator))
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::webhooks::parse;
+
+    #[test]
+    fn fingerprints_distinguish_status_updates() {
+        let status = |status: &str| {
+            let body = format!(
+                r#"{{"message_uuid":"aaaaaaaa-bbbb-cccc-dddd-0123456789ab","to":"14155550100","from":"12025550123","timestamp":"2020-01-01T12:00:00.000Z","status":"{}"}}"#,
+                status
+            );
+            parse("application/json", body.as_bytes()).unwrap()
+        };
+
+        assert_eq!(
+            status("delivered").fingerprint(),
+            status("delivered").fingerprint()
+        );
+        assert_ne!(
+            status("delivered").fingerprint(),
+            status("read").fingerprint()
+        );
+        assert_eq!(status("read").fingerprint().as_ref().len(), 64);
+
+        let dedup = Deduplicator::new();
+        assert!(!dedup.is_duplicate(&status("delivered")));
+        assert!(!dedup.is_duplicate(&status("read")));
+        assert!(dedup.is_duplicate(&status("delivered")));
+
+        let dedup = dedup.ttl(Duration::from_secs(0));
+        assert!(!dedup.is_duplicate(&status("rejected")));
+        assert!(!dedup.is_duplicate(&status("rejected")));
+    }
+}
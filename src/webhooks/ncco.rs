@@ -0,0 +1,101 @@
+//! Responses to Voice answer webhooks.
+
+use bytes::Bytes;
+use http::header::{HeaderValue, CONTENT_TYPE};
+use http::{Response, StatusCode};
+use serde::Serialize;
+
+/// A response to a Voice answer webhook, containing the NCCO (Nexmo Call Control Object) which
+/// controls the call.
+///
+/// When a call is placed to or from a Voice application, Vonage requests its answer URL and
+/// expects a JSON array of NCCO actions in return. This type serializes the actions with the
+/// right content type and status, and converts into an `http::Response` which can be returned
+/// from any web framework built on the [`http`] crate.
+///
+/// [`http`]: https://docs.rs/http/
+///
+/// # Example
+///
+/// ```
+/// use http::Response;
+/// use serde_json::json;
+/// use vonage::transport::Bytes;
+/// use vonage::webhooks::NccoResponse;
+///
+/// let ncco = json!([{ "action": "talk", "text": "Thank you for calling" }]);
+/// let response: Response<Bytes> = NccoResponse::new(&ncco).into();
+/// assert_eq!(response.headers()["content-type"], "application/json");
+/// ```
+#[derive(Clone, Debug)]
+pub struct NccoResponse {
+    status: StatusCode,
+    body: Bytes,
+}
+
+impl NccoResponse {
+    /// Creates a new `NccoResponse` containing the given NCCO, which must serialize to a JSON
+    /// array of actions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ncco` cannot be serialized to JSON, e.g. because it contains a map with
+    /// non-string keys.
+    pub fn new<T: Serialize + ?Sized>(ncco: &T) -> Self {
+        let body = serde_json::to_vec(ncco).expect("NCCO must be serializable to JSON");
+        NccoResponse {
+            status: StatusCode::OK,
+            body: body.into(),
+        }
+    }
+
+    /// Sets the HTTP status code of the response.
+    ///
+    /// By default, this is `200 OK`.
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+}
+
+impl From<NccoResponse> for Response<Bytes> {
+    fn from(ncco: NccoResponse) -> Self {
+        let mut response = Response::new(ncco.body);
+        *response.status_mut() = ncco.status;
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        response
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<NccoResponse> for Response<http_body_util::Full<Bytes>> {
+    fn from(ncco: NccoResponse) -> Self {
+        Response::<Bytes>::from(ncco).map(http_body_util::Full::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn serializes_ncco_as_json() {
+        let ncco = json!([{ "action": "talk", "text": "Hello" }]);
+        let response = Response::<Bytes>::from(NccoResponse::new(&ncco));
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+        assert_eq!(
+            response.body(),
+            &Bytes::from(r#"[{"action":"talk","text":"Hello"}]"#)
+        );
+
+        let response =
+            Response::<Bytes>::from(NccoResponse::new(&ncco).status(StatusCode::CREATED));
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+}
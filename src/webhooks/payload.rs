@@ -0,0 +1,248 @@
+//! Payloads of the individual webhook events.
+
+use serde::de::{Error as _, Unexpected};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::phone::NetworkCode;
+use crate::{Metadata, Price};
+
+/// An SMS received by one of your virtual numbers.
+///
+/// Messages longer than a single SMS may be delivered in several parts, in which case the `concat`
+/// fields identify the part, and a [`Reassembler`](./struct.Reassembler.html) combines them.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InboundSms {
+    #[serde(rename = "api-key", default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    pub msisdn: String,
+    pub to: String,
+    #[serde(rename = "messageId")]
+    pub message_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyword: Option<String>,
+    #[serde(
+        rename = "message-timestamp",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub message_timestamp: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "scalar_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub concat: Option<String>,
+    #[serde(
+        rename = "concat-ref",
+        default,
+        deserialize_with = "scalar_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub concat_ref: Option<String>,
+    #[serde(
+        rename = "concat-total",
+        default,
+        deserialize_with = "scalar_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub concat_total: Option<String>,
+    #[serde(
+        rename = "concat-part",
+        default,
+        deserialize_with = "scalar_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub concat_part: Option<String>,
+}
+
+/// Deserializes an optional string which JSON callbacks may send as a number or boolean instead.
+fn scalar_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(value @ Value::Number(_)) | Some(value @ Value::Bool(_)) => {
+            Ok(Some(value.to_string()))
+        }
+        Some(other) => Err(D::Error::invalid_type(
+            Unexpected::Other(&other.to_string()),
+            &"a string, number or boolean",
+        )),
+    }
+}
+
+/// A delivery receipt for an SMS sent with the SMS API.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DeliveryReceipt {
+    #[serde(rename = "api-key", default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    pub msisdn: String,
+    pub to: String,
+    #[serde(
+        rename = "network-code",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub network_code: Option<String>,
+    #[serde(rename = "messageId")]
+    pub message_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<Price>,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scts: Option<String>,
+    #[serde(rename = "err-code", default, skip_serializing_if = "Option::is_none")]
+    pub err_code: Option<String>,
+    #[serde(
+        rename = "client-ref",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub client_ref: Option<String>,
+    #[serde(
+        rename = "message-timestamp",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub message_timestamp: Option<String>,
+}
+
+impl DeliveryReceipt {
+    /// Returns the [`Metadata`](../struct.Metadata.html) attached to the SMS as its `client-ref`, if any.
+    pub fn metadata(&self) -> Option<Metadata> {
+        self.client_ref.as_ref()?.parse().ok()
+    }
+
+    /// Returns the [`NetworkCode`](../phone/struct.NetworkCode.html) of the network the SMS was
+    /// delivered to, if it was reported and is valid.
+    pub fn network(&self) -> Option<NetworkCode> {
+        self.network_code.as_ref()?.parse().ok()
+    }
+}
+
+/// A change in the status of a voice call, sent to the event URL of a Voice application.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct VoiceEvent {
+    pub uuid: String,
+    pub conversation_uuid: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<Price>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub_state: Option<String>,
+}
+
+/// A request to the answer URL of a Voice application, sent when a call is answered.
+///
+/// This is not an [`Event`](./enum.Event.html); it is parsed with
+/// [`answer_from_http_parts()`](./fn.answer_from_http_parts.html), and must be responded to with
+/// an [`NccoResponse`](./struct.NccoResponse.html).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AnswerRequest {
+    pub uuid: String,
+    pub conversation_uuid: String,
+    pub from: String,
+    pub to: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region_url: Option<String>,
+}
+
+/// The result of transcribing a call recording, sent to the event URL of the transcription.
+///
+/// Once `status` is `completed`, the transcript can be retrieved from `transcription_url` with
+/// [`VoiceApi::transcript()`](../voice/struct.VoiceApi.html#method.transcript).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TranscriptionEvent {
+    pub conversation_uuid: String,
+    pub recording_uuid: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcription_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+}
+
+/// A change in the status of a message sent with the Messages API.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MessageStatus {
+    pub message_uuid: String,
+    pub to: String,
+    pub from: String,
+    pub timestamp: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<Value>,
+}
+
+impl MessageStatus {
+    /// Returns the [`Metadata`](../struct.Metadata.html) attached to the message as its `client_ref`, if any.
+    pub fn metadata(&self) -> Option<Metadata> {
+        self.client_ref.as_ref()?.parse().ok()
+    }
+}
+
+/// A change in the status of a Verify request.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct VerifyEvent {
+    pub request_id: String,
+    pub status: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub triggered_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finalized_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_ref: Option<String>,
+}
+
+impl VerifyEvent {
+    /// Returns the [`Metadata`](../struct.Metadata.html) attached to the request as its `client_ref`, if any.
+    pub fn metadata(&self) -> Option<Metadata> {
+        self.client_ref.as_ref()?.parse().ok()
+    }
+}
+
+/// An event which occurred in a Conversation, sent to the RTC event URL of an application.
+///
+/// The contents of `body` depend on the `kind` of event, e.g. `member:joined` or `text`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RtcEvent {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub application_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub body: Value,
+}
@@ -0,0 +1,192 @@
+//! A `tower` service which dispatches webhooks.
+
+use std::convert::Infallible;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use http::{Request, Response, StatusCode};
+use http_body::Body;
+use http_body_util::{BodyExt, Full};
+use tower_service::Service;
+
+use super::{Auth, Deduplicator, Event, Parser};
+use crate::ErrorKind;
+
+/// A [`tower::Service`] which verifies and parses webhook requests, then dispatches the
+/// resulting events to a handler.
+///
+/// This can be mounted inside any `tower`-compatible stack, such as a `hyper` server or a
+/// serverless function runtime, and wrapped with `tower` middleware for logging, timeouts, or
+/// additional authentication.
+///
+/// Every request is verified with the configured [`Auth`] and parsed with [`from_http_parts()`],
+/// or the configured [`Parser`](./struct.Parser.html), regardless of its path. The service responds with `200 OK` once the handler has completed,
+/// `401 Unauthorized` if verification failed, or `400 Bad Request` if the payload was not
+/// recognized.
+///
+/// [`tower::Service`]: https://docs.rs/tower-service/
+/// [`Auth`]: ./enum.Auth.html
+/// [`from_http_parts()`]: ./fn.from_http_parts.html
+///
+/// # Example
+///
+/// ```
+/// use vonage::webhooks::{Auth, Event, Router};
+///
+/// let router = Router::new(Auth::Jwt("<signature_secret>".into()), |event| async move {
+///     match event {
+///         Event::Voice(call) => println!("call {} is {}", call.uuid, call.status),
+///         other => println!("ignoring {:?}", other),
+///     }
+/// });
+/// ```
+pub struct Router<F> {
+    auth: Arc<Auth>,
+    handler: Arc<F>,
+    dedup: Option<Deduplicator>,
+    parser: Parser,
+}
+
+impl<F> Router<F> {
+    /// Creates a new `Router` which verifies requests with `auth` and passes every event to
+    /// `handler`.
+    pub fn new(auth: Auth, handler: F) -> Self {
+        Router {
+            auth: Arc::new(auth),
+            handler: Arc::new(handler),
+            dedup: None,
+            parser: Parser::new(),
+        }
+    }
+
+    /// Parses requests with `parser`, e.g. to reject payloads with unknown fields in tests.
+    ///
+    /// Fields collected by lenient parsers are discarded.
+    pub fn parser(mut self, parser: Parser) -> Self {
+        self.parser = parser;
+        self
+    }
+
+    /// Skips the handler for redeliveries of events which were already dispatched.
+    ///
+    /// Redeliveries are still acknowledged with `200 OK`, so that Vonage stops retrying them.
+    pub fn deduplicate(mut self, dedup: Deduplicator) -> Self {
+        self.dedup = Some(dedup);
+        self
+    }
+}
+
+impl<F, Fut, B> Service<Request<B>> for Router<F>
+where
+    F: Fn(Event) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+    B: Body + Send + 'static,
+    B::Data: Send,
+{
+    type Response = Response<Full<Bytes>>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let auth = self.auth.clone();
+        let handler = self.handler.clone();
+        let dedup = self.dedup.clone();
+        let parser = self.parser.clone();
+
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let body = body.collect().await.ok().map(|body| body.to_bytes());
+            let status = match body {
+                Some(body) => match parser.from_http_parts(&parts, &body, &auth) {
+                    Ok(parsed) => {
+                        let event = parsed.into_event();
+                        if !dedup.is_some_and(|dedup| dedup.is_duplicate(&event)) {
+                            handler(event).await;
+                        }
+                        StatusCode::OK
+                    }
+                    Err(e) if matches!(e.kind(), ErrorKind::Auth) => StatusCode::UNAUTHORIZED,
+                    Err(_) => StatusCode::BAD_REQUEST,
+                },
+                None => StatusCode::BAD_REQUEST,
+            };
+
+            let mut response = Response::new(Full::default());
+            *response.status_mut() = status;
+            Ok(response)
+        })
+    }
+}
+
+impl<F> Clone for Router<F> {
+    fn clone(&self) -> Self {
+        Router {
+            auth: self.auth.clone(),
+            handler: self.handler.clone(),
+            dedup: self.dedup.clone(),
+            parser: self.parser.clone(),
+        }
+    }
+}
+
+impl<F> Debug for Router<F> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct(stringify!(Router))
+            .field("auth", &self.auth)
+            .field("dedup", &self.dedup)
+            .field("parser", &self.parser)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn responds_with_dispatch_outcome() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let router = Router::new(Auth::None, {
+            let received = received.clone();
+            move |event| {
+                received.lock().unwrap().push(event);
+                async {}
+            }
+        })
+        .deduplicate(Deduplicator::new());
+
+        for _ in 0..2 {
+            let body = "msisdn=14155550100&to=12025550123&messageId=0A0000000123ABCD1&text=Hi";
+            let request = Request::post("/webhooks/inbound-sms")
+                .header("Content-Type", "application/x-www-form-urlencoded")
+                .body(Full::new(Bytes::from(body)))
+                .unwrap();
+            let response = router.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        assert!(matches!(
+            received.lock().unwrap()[..],
+            [Event::InboundSms(_)]
+        ));
+
+        let request = Request::post("/webhooks/unknown")
+            .header("Content-Type", "application/json")
+            .body(Full::new(Bytes::from(r#"{"hello":"world"}"#)))
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+}
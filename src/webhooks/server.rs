@@ -0,0 +1,119 @@
+//! A minimal HTTP server which receives webhooks.
+
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+
+use http::Request;
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+use tower_service::Service;
+
+use super::{Auth, Event, Router};
+
+/// Listens for webhooks on `addr` and invokes `handler` with every event received.
+///
+/// Requests are handled by a [`Router`], so every request is verified with `auth` and parsed with
+/// [`from_http_parts()`], regardless of its path.
+///
+/// This is intended for prototypes and examples. Production services will usually want to mount
+/// webhook handling inside an existing web framework instead.
+///
+/// Runs until accepting a connection fails, in which case the error is returned.
+///
+/// [`Router`]: ./struct.Router.html
+/// [`from_http_parts()`]: ./fn.from_http_parts.html
+///
+/// # Example
+///
+/// ```no_run
+/// use vonage::webhooks::{self, Auth, Event};
+///
+/// # async fn run() -> std::io::Result<()> {
+/// let auth = Auth::Jwt("<signature_secret>".into());
+/// webhooks::serve(([0, 0, 0, 0], 3000), auth, |event| async move {
+///     if let Event::InboundSms(sms) = event {
+///         println!("{}: {:?}", sms.msisdn, sms.text);
+///     }
+/// })
+/// .await
+/// # }
+/// ```
+pub async fn serve<A, F, Fut>(addr: A, auth: Auth, handler: F) -> io::Result<()>
+where
+    A: Into<SocketAddr>,
+    F: Fn(Event) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let listener = TcpListener::bind(addr.into()).await?;
+    serve_listener(listener, auth, handler).await
+}
+
+async fn serve_listener<F, Fut>(listener: TcpListener, auth: Auth, handler: F) -> io::Result<()>
+where
+    F: Fn(Event) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let router = Router::new(auth, handler);
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let router = router.clone();
+        tokio::spawn(async move {
+            let service =
+                service_fn(move |request: Request<Incoming>| router.clone().call(request));
+
+            // Connection errors only affect a single client, so they are deliberately ignored.
+            let _ = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+
+    use bytes::Bytes;
+    use http::StatusCode;
+    use http_body_util::Full;
+    use hyper_util::client::legacy::Client;
+    use hyper_util::rt::TokioExecutor;
+    use tokio::sync::mpsc;
+
+    use crate::testing::Webhook;
+
+    #[tokio::test]
+    async fn dispatches_verified_events() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let auth = Auth::Jwt("secret".into());
+        tokio::spawn(serve_listener(listener, auth.clone(), move |event| {
+            let sender = sender.clone();
+            async move { sender.send(event).unwrap() }
+        }));
+
+        let client = Client::builder(TokioExecutor::new()).build_http::<Full<Bytes>>();
+        let send = |webhook: Webhook| {
+            let (mut parts, body) = webhook.into_request().into_parts();
+            parts.uri = format!("http://{}{}", addr, parts.uri).parse().unwrap();
+            client.request(Request::from_parts(parts, Full::new(body)))
+        };
+
+        let call = Webhook::call_answered("14155550100", "12025550123").sign(&auth);
+        let response = send(call).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(matches!(receiver.recv().await, Some(Event::Voice(_))));
+
+        let forged =
+            Webhook::call_answered("14155550100", "12025550123").sign(&Auth::Jwt("forged".into()));
+        let response = send(forged).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(receiver.try_recv().is_err());
+    }
+}